@@ -11,6 +11,11 @@ pub enum TokenType {
     Identifier,
     // Keywords
     Let,
+    Mut,
+    Const,
+    Static,
+    Type,
+    Newtype,
     Fn,
     True,
     False,
@@ -29,6 +34,7 @@ pub enum TokenType {
     Spawn,
     Enum,
     Match,
+    As,
     // Operators
     Assign,       // =
     Plus,         // +
@@ -45,6 +51,8 @@ pub enum TokenType {
     Bang,         // !
     And,          // &&
     Or,           // ||
+    Pipe,         // |
+    Hash,         // #
     // Delimiters
     Comma,        // ,
     Semicolon,    // ;
@@ -57,6 +65,7 @@ pub enum TokenType {
     LeftBracket,  // [
     RightBracket, // ]
     DotDot,       // ..
+    DotDotEq,     // ..=
     Dot,          // .
     Arrow,        // ->
     FatArrow,     // =>
@@ -102,14 +111,18 @@ pub enum Expression {
     Function(FunctionLiteral),
     Call(CallExpression),
     Array(ArrayLiteral),
+    Tuple(TupleLiteral),
     Index(IndexExpression),
     Range(RangeExpression),
     StructLiteral(StructLiteral),
     FieldAccess(FieldAccess),
     ModuleAccess(ModuleAccess),
+    Cast(CastExpression),
     Spawn(SpawnExpression),
     Assignment(AssignmentExpression),
     Match(MatchExpression),
+    IfLet(IfLetExpression),
+    WhileLet(WhileLetExpression),
     Break,
     Continue,
 }
@@ -119,6 +132,8 @@ pub enum Expression {
 pub struct AssignmentExpression {
     pub target: Box<Expression>,
     pub value: Box<Expression>,
+    /// Source line of the target, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -145,6 +160,8 @@ pub struct StringLiteral {
 pub struct PrefixExpression {
     pub operator: String,
     pub right: Box<Expression>,
+    /// Source line of the operator, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -152,6 +169,8 @@ pub struct InfixExpression {
     pub left: Box<Expression>,
     pub operator: String,
     pub right: Box<Expression>,
+    /// Source line of the operator, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -161,6 +180,23 @@ pub struct IfExpression {
     pub alternative: Option<BlockStatement>,
 }
 
+/// `if let Pattern = value { ... } else { ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfLetExpression {
+    pub pattern: Pattern,
+    pub value: Box<Expression>,
+    pub consequence: BlockStatement,
+    pub alternative: Option<BlockStatement>,
+}
+
+/// `while let Pattern = value { ... }` — loops until the pattern fails
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhileLetExpression {
+    pub pattern: Pattern,
+    pub value: Box<Expression>,
+    pub body: BlockStatement,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WhileExpression {
     pub condition: Box<Expression>,
@@ -169,9 +205,13 @@ pub struct WhileExpression {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ForExpression {
+    /// `for (k, v) in m` binds a hidden `__for_item` and opens the body
+    /// with `let (k, v) = __for_item`.
     pub variable: Identifier,
     pub iterable: Box<Expression>,
     pub body: BlockStatement,
+    /// Source line of the `for` keyword, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -185,16 +225,26 @@ pub struct ArrayLiteral {
     pub elements: Vec<Expression>,
 }
 
+/// `(a, b, ...)` — fixed-size tuple; `(a,)` is a one-element tuple.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleLiteral {
+    pub elements: Vec<Expression>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IndexExpression {
     pub left: Box<Expression>,
     pub index: Box<Expression>,
+    /// Source line of `[`, for bounds-check failures.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionLiteral {
     pub name: Option<Identifier>,
     pub parameters: Vec<Identifier>,
+    /// Per-parameter `mut` flags: `fn f(mut n)` → [true]
+    pub mutable_params: Vec<bool>,
     pub is_pub: bool,
     /// Generic type parameters: `fn max<T>(...)` → ["T"]
     pub type_params: Vec<String>,
@@ -203,12 +253,30 @@ pub struct FunctionLiteral {
     /// Optional return type annotation: `fn f(...) -> T`
     pub return_type_hint: Option<String>,
     pub body: BlockStatement,
+    /// Source line of the `fn` keyword, for diagnostics.
+    pub line: usize,
+    /// `test fn` and `bench fn` functions are only run by `aha test` and
+    /// `aha bench`.
+    pub kind: FunctionKind,
+}
+
+/// What a function declaration is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FunctionKind {
+    #[default]
+    Plain,
+    /// `test fn name() { ... }`
+    Test,
+    /// `bench fn name() { ... }`
+    Bench,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallExpression {
     pub function: Box<Expression>,
     pub arguments: Vec<Expression>,
+    /// Source line of the callee, for diagnostics.
+    pub line: usize,
 }
 
 // --- Statement Nodes ---
@@ -221,6 +289,20 @@ pub enum Statement {
     Actor(ActorDefinition),
     Enum(EnumDefinition),
     Import(ImportStatement),
+    LetPattern(LetPatternStatement),
+    Const(ConstStatement),
+    Static(StaticStatement),
+    TypeAlias(TypeAlias),
+    Newtype(NewtypeDefinition),
+    UseVariants(UseVariants),
+}
+
+/// Destructuring let: `let Point { x, y } = p`. The pattern must be
+/// irrefutable (structs, bindings, `_`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetPatternStatement {
+    pub pattern: Pattern,
+    pub value: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -230,11 +312,44 @@ pub struct LetStatement {
     /// Optional explicit type annotation: `let x: int = 5`.
     /// Stored as the raw hint string ("int", "string", "bool", struct name).
     pub type_annotation: Option<String>,
+    /// `let mut x = ...`: the binding may be reassigned.
+    pub mutable: bool,
+    /// Source line of the `let` keyword, for diagnostics.
+    pub line: usize,
+}
+
+/// Module-level constant: `const MAX: int = 64 * 1024`. The value is
+/// evaluated at compile time (see `consteval`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstStatement {
+    pub name: Identifier,
+    pub value: Expression,
+    /// Optional type annotation, as for `let`.
+    pub type_annotation: Option<String>,
+    pub is_pub: bool,
+    /// Source line of the `const` keyword, for diagnostics.
+    pub line: usize,
+}
+
+/// Module-level global: `static mut COUNT: int = 0`. Emitted as an LLVM
+/// global whose initializer is evaluated at compile time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticStatement {
+    pub name: Identifier,
+    pub value: Expression,
+    pub type_annotation: Option<String>,
+    /// `static mut`: functions may assign to it.
+    pub mutable: bool,
+    pub is_pub: bool,
+    /// Source line of the `static` keyword, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReturnStatement {
     pub return_value: Expression,
+    /// Source line of the `return` keyword, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -265,19 +380,63 @@ pub struct Program {
 pub struct StructDefinition {
     pub name: Identifier,
     pub is_pub: bool,
+    /// Generic type parameters: `struct Pair<A, B> { ... }`
+    pub type_params: Vec<String>,
     pub fields: Vec<StructField>,
+    /// A desugared `newtype`: one field named `0` holding the base value.
+    pub is_newtype: bool,
+}
+
+/// `type Index = Map<string, List<int>>` — another name for a type,
+/// expanded in every type hint before type checking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub name: Identifier,
+    /// The aliased type hint as written.
+    pub target: String,
+    pub is_pub: bool,
+    /// Source line of the `type` keyword, for diagnostics.
+    pub line: usize,
+}
+
+/// `newtype Meters = int` — a distinct type with the base type's layout.
+/// `Meters(5)` wraps a value and `m.0` unwraps it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewtypeDefinition {
+    pub name: Identifier,
+    /// The base type hint as written.
+    pub base: String,
+    pub is_pub: bool,
+    /// Source line of the `newtype` keyword, for diagnostics.
+    pub line: usize,
+}
+
+/// `use Color::*` — a bare variant name that several enums define
+/// resolves to `Color`'s variant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseVariants {
+    pub enum_name: Identifier,
+    /// Source line of the `use` keyword, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructField {
     pub name: Identifier,
     pub type_hint: Option<String>,
+    /// `retries: int = 3` — used when a literal or `spawn` omits the field.
+    /// Evaluated once at compile time, like a `const`.
+    pub default: Option<Expression>,
+    /// Source line of the field name, for diagnostics.
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructLiteral {
     pub name: Identifier,
     pub fields: Vec<(Identifier, Expression)>,
+    /// `..base` — struct update syntax: omitted fields are copied from it.
+    pub base: Option<Box<Expression>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -309,6 +468,20 @@ pub struct SpawnExpression {
 pub struct ModuleAccess {
     pub module: String,
     pub name: String,
+    /// Source line of the module name, for diagnostics.
+    pub line: usize,
+}
+
+// --- Cast ---
+
+/// `value as int`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastExpression {
+    pub value: Box<Expression>,
+    /// Target type as written.
+    pub target: String,
+    /// Source line of `as`, for diagnostics.
+    pub line: usize,
 }
 
 // --- Enum Definition ---
@@ -318,14 +491,20 @@ pub struct ModuleAccess {
 pub struct EnumDefinition {
     pub name: Identifier,
     pub is_pub: bool,
+    /// Generic type parameters: `enum Tree<T> { ... }`
+    pub type_params: Vec<String>,
     pub variants: Vec<EnumVariant>,
+    /// Tag width from `#[repr(u8)]`; None = i64.
+    pub repr: Option<String>,
 }
 
-/// A single enum variant: `Name` or `Name(Type, Type, ...)`
+/// A single enum variant: `Name`, `Name(Type, Type, ...)` or `Name = 0x10`
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnumVariant {
     pub name: Identifier,
-    pub payload_types: Vec<String>, // empty = unit variant, e.g. `Red`
+    pub payload_types: Vec<String>, // type hints; empty = unit variant, e.g. `Red`
+    /// Explicit discriminant; None = previous + 1 (0 for the first).
+    pub discriminant: Option<i64>,
 }
 
 // --- Match Expression ---
@@ -335,22 +514,43 @@ pub struct EnumVariant {
 pub struct MatchExpression {
     pub value: Box<Expression>,
     pub arms: Vec<MatchArm>,
+    pub line: usize,
 }
 
-/// A single match arm: `Pattern => body`
+/// A single match arm: `Pattern => body` or `Pattern if guard => body`
 #[derive(Debug, Clone, PartialEq)]
 pub struct MatchArm {
     pub pattern: Pattern,
+    pub guard: Option<Expression>,
     pub body: Expression,
 }
 
-/// Patterns: `EnumVariant`, `EnumVariant(a, b, ...)`, or `_` (wildcard)
+/// Patterns: enum variants, structs, literals, ranges, alternatives,
+/// bindings or `_`. Sub-patterns nest arbitrarily.
+/// Variant names may be qualified (`Outer::X`); the path is kept as written.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pattern {
     /// `_` wildcard — matches anything
     Wildcard,
     /// `Variant` — unit enum variant
     EnumUnit(String),
-    /// `Variant(a, b, ...)` — enum variant with destructured bindings
-    EnumTuple(String, Vec<String>),
+    /// `Variant(p1, p2, ...)` — enum variant with a sub-pattern per payload
+    EnumTuple(String, Vec<Pattern>),
+    /// `Point { x, y: 0 }` — struct fields; `x` alone binds field `x`.
+    /// Fields not listed are ignored.
+    Struct(String, Vec<(String, Pattern)>),
+    /// `(p1, p2, ...)` — tuple elements, one sub-pattern per position
+    Tuple(Vec<Pattern>),
+    /// `42`, `-1` — integer literal
+    Integer(i64),
+    /// `"text"` — string literal
+    Str(String),
+    /// `lo..=hi` — inclusive integer range
+    Range(i64, i64),
+    /// `a | b | ...` — matches when any alternative matches
+    Or(Vec<Pattern>),
+    /// `name` — matches anything and binds the value to `name`
+    Binding(String),
+    /// `mut name` — like `Binding`, but the variable can be reassigned
+    MutBinding(String),
 }
\ No newline at end of file
@@ -0,0 +1,227 @@
+// src/bench.rs
+//
+// `aha bench` — times the `bench fn` functions of a module graph.
+// Each file in the graph is compiled as its own root and JIT-compiled
+// once; its benchmarks then run against that one engine, so LLVM compile
+// time never shows up in a measurement. Each benchmark is warmed up,
+// sized so a sample takes a fixed slice of the measurement time, and
+// summarised as per-iteration mean, median and standard deviation.
+
+use crate::codegen::{BenchRunner, CodeGenerator};
+use crate::compiler::CompileError;
+use crate::testing::{module_units, selected};
+use inkwell::context::Context;
+use std::time::{Duration, Instant};
+
+/// How long to warm up and measure each benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Run the benchmark for at least this long before measuring. The
+    /// warm-up also estimates how long one iteration takes.
+    pub warm_up: Duration,
+    /// Rough total time of the measured samples.
+    pub measurement: Duration,
+    /// Number of timed samples; each runs the same number of iterations.
+    pub samples: usize,
+    /// Benchmarks are built like `--release`: Int overflow wraps unless
+    /// this is set.
+    pub overflow_checks: bool,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warm_up: Duration::from_millis(200),
+            measurement: Duration::from_secs(1),
+            samples: 50,
+            overflow_checks: false,
+        }
+    }
+}
+
+/// Per-iteration timings of one benchmark, in nanoseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchStats {
+    pub samples: usize,
+    /// Iterations in each sample.
+    pub iterations: u64,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    /// Sample standard deviation across the samples.
+    pub stddev_ns: f64,
+    pub min_ns: f64,
+    pub max_ns: f64,
+}
+
+impl BenchStats {
+    /// Summarise per-iteration sample times. `per_iteration_ns` must not
+    /// be empty.
+    pub fn from_samples(iterations: u64, per_iteration_ns: &[f64]) -> BenchStats {
+        let n = per_iteration_ns.len();
+        let mut sorted = per_iteration_ns.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let median = if n % 2 == 1 { sorted[n / 2] } else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 };
+        let variance = if n > 1 {
+            sorted.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        BenchStats {
+            samples: n,
+            iterations,
+            mean_ns: mean,
+            median_ns: median,
+            stddev_ns: variance.sqrt(),
+            min_ns: sorted[0],
+            max_ns: sorted[n - 1],
+        }
+    }
+}
+
+/// The result of one benchmark.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOutcome {
+    /// `<file stem>::<bench name>`, e.g. `sort::insertion_100`.
+    pub name: String,
+    /// `Err` holds the panic message.
+    pub result: Result<BenchStats, String>,
+}
+
+/// All benchmarks of a run, in discovery order.
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    pub outcomes: Vec<BenchOutcome>,
+    /// Benchmarks skipped because no filter matched their name.
+    pub filtered_out: usize,
+}
+
+impl BenchReport {
+    pub fn failed(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.result.is_err()).count()
+    }
+
+    pub fn success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// The report as a JSON document, for saving and comparing runs.
+    pub fn to_json(&self) -> String {
+        let mut benchmarks = Vec::new();
+        let mut failures = Vec::new();
+        for outcome in &self.outcomes {
+            match &outcome.result {
+                Ok(stats) => benchmarks.push(format!(
+                    "    {{\"name\": {}, \"samples\": {}, \"iterations\": {}, \"mean_ns\": {}, \"median_ns\": {}, \"stddev_ns\": {}, \"min_ns\": {}, \"max_ns\": {}}}",
+                    json_string(&outcome.name), stats.samples, stats.iterations,
+                    stats.mean_ns, stats.median_ns, stats.stddev_ns, stats.min_ns, stats.max_ns
+                )),
+                Err(message) => failures.push(format!(
+                    "    {{\"name\": {}, \"message\": {}}}",
+                    json_string(&outcome.name), json_string(message)
+                )),
+            }
+        }
+        let list = |items: &[String]| {
+            if items.is_empty() { "[]".to_string() } else { format!("[\n{}\n  ]", items.join(",\n")) }
+        };
+        format!(
+            "{{\n  \"benchmarks\": {},\n  \"failures\": {},\n  \"filtered_out\": {}\n}}",
+            list(&benchmarks), list(&failures), self.filtered_out
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Format a duration in nanoseconds with a readable unit, e.g.
+/// `812.0 ns`, `1.234 µs`, `56.700 ms`.
+pub fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{:.1} ns", ns)
+    } else if ns < 1e6 {
+        format!("{:.3} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.3} ms", ns / 1e6)
+    } else {
+        format!("{:.3} s", ns / 1e9)
+    }
+}
+
+/// Discover and run the benchmarks of `main_path` and every module it
+/// imports. With `filters`, only benchmarks whose full name contains
+/// one of them run. `on_result` sees each outcome as soon as it is known.
+pub fn run_benches(
+    main_path: &str,
+    filters: &[String],
+    config: &BenchConfig,
+    mut on_result: impl FnMut(&BenchOutcome),
+) -> Result<BenchReport, Vec<CompileError>> {
+    let mut report = BenchReport::default();
+    for unit in module_units(main_path)? {
+        let context = Context::create();
+        let mut codegen = CodeGenerator::new(&context);
+        codegen.set_overflow_checks(config.overflow_checks);
+        let compile_error = |message| vec![CompileError { message, file: unit.file.clone() }];
+        codegen.compile(&unit.program).map_err(compile_error)?;
+        if codegen.bench_functions().is_empty() {
+            continue;
+        }
+        let runner = codegen.bench_runner().map_err(compile_error)?;
+
+        for bench in codegen.bench_functions() {
+            let name = format!("{}::{}", unit.module, bench);
+            if !selected(&name, filters) {
+                report.filtered_out += 1;
+                continue;
+            }
+            let outcome = BenchOutcome { name, result: measure(&runner, bench, config) };
+            on_result(&outcome);
+            report.outcomes.push(outcome);
+        }
+    }
+    Ok(report)
+}
+
+/// Warm up one benchmark, pick an iteration count so `config.samples`
+/// samples fill `config.measurement`, then time the samples.
+pub fn measure(runner: &BenchRunner, bench: &str, config: &BenchConfig) -> Result<BenchStats, String> {
+    // Warm up with doubling batches; the total gives a per-iteration estimate.
+    let started = Instant::now();
+    let mut batch: u64 = 1;
+    let mut warm_up_iterations: u64 = 0;
+    let mut warm_up_time = Duration::ZERO;
+    loop {
+        warm_up_time += runner.run(bench, batch)?;
+        warm_up_iterations += batch;
+        if started.elapsed() >= config.warm_up {
+            break;
+        }
+        batch = batch.saturating_mul(2);
+    }
+    let estimate_ns = (warm_up_time.as_nanos() as f64 / warm_up_iterations as f64).max(1.0);
+
+    let samples = config.samples.max(1);
+    let sample_ns = config.measurement.as_nanos() as f64 / samples as f64;
+    let iterations = ((sample_ns / estimate_ns).round() as u64).max(1);
+    let mut per_iteration_ns = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let elapsed = runner.run(bench, iterations)?;
+        per_iteration_ns.push(elapsed.as_nanos() as f64 / iterations as f64);
+    }
+    Ok(BenchStats::from_samples(iterations, &per_iteration_ns))
+}
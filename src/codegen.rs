@@ -2,13 +2,87 @@
 
 use crate::ast;
 use crate::ast::{ActorDefinition, SpawnExpression};
-use crate::types::{AhaType, TypedValue};
+use crate::consteval::{self, ConstValue};
+use crate::runtime::{PanicRecord, PANIC_DEAD_ACTOR, PANIC_RAISED};
+use crate::typedefs;
+use crate::typeck::{self, TypeTable};
+use crate::units;
+use crate::types::{count_of, generic_fn_name, generic_type_name, split_array_hint, split_type_list, AhaType, TypedValue};
 use inkwell::context::Context;
+use inkwell::execution_engine::ExecutionEngine;
 use inkwell::module::Module;
 use inkwell::builder::Builder;
 use inkwell::values::{PointerValue, BasicValueEnum, FunctionValue, BasicMetadataValueEnum};
-use inkwell::types::{IntType, StructType};
-use std::collections::HashMap;
+use inkwell::types::{BasicType, IntType, StructType};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::intrinsics::Intrinsic;
+use std::collections::{HashMap, HashSet};
+
+/// Exit status of a program that panicked.
+pub const PANIC_EXIT_CODE: i32 = 101;
+
+/// Frames of the shadow stack a panic backtrace can show.
+const PANIC_FRAMES: usize = 256;
+
+/// Why `run_jit` returned no value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    /// The program panicked. The panic runtime has already printed the
+    /// message and a backtrace to stderr.
+    Panic { line: i64, message: String },
+    /// The program could not be JIT-compiled or started.
+    Jit(String),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Panic { line, message } => write!(f, "panicked at line {}: {}", line, message),
+            RunError::Jit(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl PanicRecord {
+    fn into_result(self) -> Result<(), RunError> {
+        if self.panicked == 0 {
+            return Ok(());
+        }
+        let message = unsafe { std::slice::from_raw_parts(self.message, self.len as usize) };
+        Err(RunError::Panic { line: self.line, message: String::from_utf8_lossy(message).into_owned() })
+    }
+}
+
+/// `aha_run(out, entry, quiet)`: call the `i64 ()` function at `entry`,
+/// recording a panic in `out` instead of exiting. With `quiet` set the
+/// panic is not printed, for callers that report it themselves.
+type AhaRunFn = unsafe extern "C" fn(*mut PanicRecord, usize, i64) -> i64;
+
+/// A program JIT-compiled once by `CodeGenerator::bench_runner`. Each
+/// `run` calls one `bench fn` in a tight loop.
+pub struct BenchRunner<'ctx> {
+    _engine: ExecutionEngine<'ctx>,
+    run: AhaRunFn,
+    iterations: *mut i64,
+    entries: HashMap<String, usize>,
+}
+
+impl BenchRunner<'_> {
+    /// Call `bench fn name` `iterations` times in a row and return the
+    /// wall time the loop took. A panic comes back as `Err`.
+    pub fn run(&self, name: &str, iterations: u64) -> Result<std::time::Duration, String> {
+        let entry = *self.entries.get(name)
+            .ok_or_else(|| format!("No bench function named '{}'", name))?;
+        let mut outcome = PanicRecord::none();
+        let started = std::time::Instant::now();
+        unsafe {
+            *self.iterations = iterations as i64;
+            (self.run)(&mut outcome, entry, 1);
+        }
+        let elapsed = started.elapsed();
+        outcome.into_result().map(|()| elapsed).map_err(|e| e.to_string())
+    }
+}
 
 /// Variable info stored in scope: LLVM pointer + AHA! type
 #[derive(Clone, Debug)]
@@ -42,12 +116,6 @@ pub struct CodeGenerator<'ctx> {
     /// Registered struct definitions: struct name → ordered (field name,
     /// declared AhaType) pairs. Field order defines the LLVM layout.
     struct_defs: HashMap<String, Vec<(String, AhaType)>>,
-    /// Pre-scanned variable bindings: var_name → AhaType (for struct
-    /// variables, so scan_expr_for_calls can resolve param types).
-    struct_var_types: HashMap<String, AhaType>,
-    /// Synthetic param scope used while scanning a function body, so
-    /// calls inside resolve their args against the function's own params.
-    scan_scope: Vec<HashMap<String, AhaType>>,
     /// Generic function definitions: name → cloned FunctionLiteral AST.
     /// Populated during predeclare; bodies are compiled lazily per
     /// concrete type at each call site (monomorphization).
@@ -58,6 +126,51 @@ pub struct CodeGenerator<'ctx> {
     /// Registered enum definitions: enum name → variants with payload types.
     /// Each variant is (name, Vec<AhaType>) — empty vec = unit variant.
     enum_defs: HashMap<String, Vec<(String, Vec<AhaType>)>>,
+    /// Generic struct definitions: name → cloned StructDefinition AST.
+    /// Each concrete instantiation (`Pair<Int, String>`) is registered in
+    /// struct_defs under its full name on first use (monomorphization).
+    generic_struct_defs: HashMap<String, ast::StructDefinition>,
+    /// Declared field defaults of structs and actors, evaluated by
+    /// `consteval`.
+    struct_defaults: HashMap<String, HashMap<String, ConstValue>>,
+    /// Instantiated generic structs: full name → (generic name, type args),
+    /// so type params can be bound back out of a concrete struct type.
+    struct_instances: HashMap<String, (String, Vec<AhaType>)>,
+    /// Generic enum definitions: name → cloned EnumDefinition AST. Each
+    /// instantiation (`Tree<Int>`) is registered in enum_defs under its
+    /// full name on first use, like generic structs.
+    generic_enum_defs: HashMap<String, ast::EnumDefinition>,
+    /// Instantiated generic enums: full name → (generic name, type args).
+    enum_instances: HashMap<String, (String, Vec<AhaType>)>,
+    /// Type the expression being compiled must produce, when the context
+    /// pins it down (a `let` annotation or the function's return type).
+    /// Lets `None()` pick `Option<Bool>` instead of defaulting to Int.
+    expected_type: Option<AhaType>,
+    /// Values of module-level constants, inlined at each use.
+    consts: HashMap<String, ConstValue>,
+    /// Module-level statics: the LLVM global and the value's type.
+    statics: HashMap<String, (PointerValue<'ctx>, AhaType)>,
+    /// Structs lowered from `newtype` declarations (single field `0`).
+    newtypes: HashSet<String>,
+    /// Enums opened with `use Color::*`: they win a bare variant name
+    /// that several enums declare.
+    open_enums: HashSet<String>,
+    /// Non-generic enum name → each variant's discriminant (its tag), in
+    /// declaration order. Generic instances use declaration order.
+    enum_discriminants: HashMap<String, Vec<i64>>,
+    /// Tag width and signedness from `#[repr(u8)]`; absent = i64.
+    enum_reprs: HashMap<String, (u32, bool)>,
+    /// User functions and their source names, given backtrace frames
+    /// by `instrument_backtraces` once the module is complete.
+    traced_functions: Vec<(FunctionValue<'ctx>, String)>,
+    /// Lower Int `+`, `-`, `*` and negation to overflow intrinsics and
+    /// panic on overflow. On unless turned off with `set_overflow_checks`.
+    overflow_checks: bool,
+    /// `test fn` names, given `aha_test.<name>` entry points for `run_test`.
+    test_functions: Vec<String>,
+    /// `bench fn` names, given `aha_bench.<name>` entry points for
+    /// `BenchRunner`.
+    bench_functions: Vec<String>,
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -94,14 +207,33 @@ impl<'ctx> CodeGenerator<'ctx> {
             loop_stack: Vec::new(),
             param_type_map: HashMap::new(),
             struct_defs: HashMap::new(),
-            struct_var_types: HashMap::new(),
-            scan_scope: Vec::new(),
             generic_defs: HashMap::new(),
             type_param_map: HashMap::new(),
             enum_defs: HashMap::new(),
+            generic_struct_defs: HashMap::new(),
+            struct_defaults: HashMap::new(),
+            struct_instances: HashMap::new(),
+            generic_enum_defs: HashMap::new(),
+            enum_instances: HashMap::new(),
+            expected_type: None,
+            consts: HashMap::new(),
+            statics: HashMap::new(),
+            newtypes: HashSet::new(),
+            open_enums: HashSet::new(),
+            enum_discriminants: HashMap::new(),
+            enum_reprs: HashMap::new(),
+            traced_functions: Vec::new(),
+            overflow_checks: true,
+            test_functions: Vec::new(),
+            bench_functions: Vec::new(),
         }
     }
 
+    /// Turn checked Int arithmetic on or off; call before `compile`.
+    pub fn set_overflow_checks(&mut self, enabled: bool) {
+        self.overflow_checks = enabled;
+    }
+
     // --- Scope Management ---
 
     fn enter_scope(&mut self) {
@@ -151,10 +283,10 @@ impl<'ctx> CodeGenerator<'ctx> {
     /// Excludes function parameters — they're owned by the caller.
     fn has_heap_locals(&self) -> bool {
         if let Some(scope) = self.scopes.last() {
-            scope.values().any(|v| !v.is_param && !v.freed && matches!(
+            scope.values().any(|v| !v.is_param && !v.freed && (matches!(
                 v.var_type,
                 AhaType::Map(_, _) | AhaType::List(_) | AhaType::String
-            ))
+            ) || self.needs_drop(&v.var_type)))
         } else {
             false
         }
@@ -165,6 +297,19 @@ impl<'ctx> CodeGenerator<'ctx> {
     /// Must be called BEFORE the return terminator is built.
     /// `exclude` — variable names to skip (escaped via return).
     fn insert_cleanup_inline(&mut self, exclude: &std::collections::HashSet<String>) {
+        // Tuple boxes go before the Lists and Maps holding them.
+        let containers: Vec<(PointerValue<'ctx>, AhaType)> = self.scopes.last()
+            .map(|scope| scope.iter()
+                .filter(|(name, v)| !v.is_param && !v.freed && !exclude.contains(*name)
+                    && Self::holds_boxed_tuples(&v.var_type))
+                .map(|(_, v)| (v.ptr, v.var_type.clone()))
+                .collect())
+            .unwrap_or_default();
+        for (ptr, var_type) in containers {
+            if let Ok(handle) = self.builder.build_load(ptr, "tuples_handle") {
+                let _ = self.free_boxed_tuples(handle, &var_type);
+            }
+        }
         if let Some(scope) = self.scopes.last() {
             for (name, var_info) in scope {
                 if var_info.is_param || var_info.freed || exclude.contains(name) { continue; }
@@ -189,10 +334,42 @@ impl<'ctx> CodeGenerator<'ctx> {
                 }
             }
         }
+        // Boxes and enums holding them: collected first, since emitting
+        // their drop functions needs &mut self. They can only escape
+        // through a return value whose type can hold a box.
+        let may_escape = self.returns_droppable();
+        let owned: Vec<(PointerValue<'ctx>, AhaType)> = self.scopes.last()
+            .map(|scope| scope.iter()
+                .filter(|(name, v)| !v.is_param && !v.freed && self.needs_drop(&v.var_type)
+                    && (!may_escape || !exclude.contains(*name)))
+                .map(|(_, v)| (v.ptr, v.var_type.clone()))
+                .collect())
+            .unwrap_or_default();
+        for (ptr, var_type) in owned {
+            let _ = self.build_drop(ptr, &var_type);
+        }
     }
 
     /// Insert a free call for a specific variable.
     fn insert_free_for_var(&mut self, name: &str) {
+        let owned = self.scopes.last()
+            .and_then(|scope| scope.get(name))
+            .filter(|v| !v.is_param && !v.freed && self.needs_drop(&v.var_type))
+            .map(|v| (v.ptr, v.var_type.clone()));
+        if let Some((ptr, var_type)) = owned {
+            let _ = self.build_drop(ptr, &var_type);
+            self.mark_freed(name);
+            return;
+        }
+        let container = self.scopes.last()
+            .and_then(|scope| scope.get(name))
+            .filter(|v| !v.is_param && !v.freed && Self::holds_boxed_tuples(&v.var_type))
+            .map(|v| (v.ptr, v.var_type.clone()));
+        if let Some((ptr, var_type)) = container {
+            if let Ok(handle) = self.builder.build_load(ptr, "tuples_cleanup") {
+                let _ = self.free_boxed_tuples(handle, &var_type);
+            }
+        }
         if let Some(scope) = self.scopes.last() {
             if let Some(var_info) = scope.get(name) {
                 if var_info.is_param || var_info.freed { return; }
@@ -219,6 +396,140 @@ impl<'ctx> CodeGenerator<'ctx> {
         }
     }
 
+    /// Does a value of this type own heap memory that must be dropped
+    /// recursively? A Box owns its pointee; an enum owns the boxes in
+    /// its payloads (including those of nested enums).
+    fn needs_drop(&self, t: &AhaType) -> bool {
+        match t {
+            AhaType::Box(_) => true,
+            AhaType::Enum(name) => self.enum_defs.get(name).is_some_and(|variants| {
+                variants.iter().flat_map(|(_, ts)| ts).any(|t| self.needs_drop(t))
+            }),
+            _ => false,
+        }
+    }
+
+    /// Can the current function's return value own boxes? Unknown
+    /// return types are treated as owning.
+    fn returns_droppable(&self) -> bool {
+        self.current_function
+            .and_then(|f| f.get_name().to_str().ok().and_then(|n| self.fn_types.get(n)))
+            .is_none_or(|t| self.needs_drop(t))
+    }
+
+    /// A local bound straight from another local (`let b = a`,
+    /// `Some(a)`, `box(a)`) takes over its boxes, so the source must not
+    /// be dropped as well.
+    fn mark_moved(&mut self, source: &ast::Expression, t: &AhaType) {
+        if let ast::Expression::Identifier(id) = source {
+            if self.needs_drop(t) {
+                self.mark_freed(&id.value);
+            }
+        }
+    }
+
+    /// Does `expr` mention a borrowed value (a parameter or pattern
+    /// binding) that owns boxes? A local built from one shares those
+    /// boxes with their owner and must not drop them.
+    fn mentions_borrowed_boxes(&self, expr: &ast::Expression) -> bool {
+        Self::find_heap_vars_in_expr(expr).iter().any(|name| {
+            self.scopes.iter().rev()
+                .find_map(|scope| scope.get(name))
+                .is_some_and(|v| v.is_param && self.needs_drop(&v.var_type))
+        })
+    }
+
+    /// Drop the value stored at `ptr`: load it and call its drop function.
+    fn build_drop(&mut self, ptr: PointerValue<'ctx>, t: &AhaType) -> Result<(), String> {
+        let drop_fn = self.drop_function(t)?;
+        let value = self.builder.build_load(ptr, "drop_val").map_err(|e| e.to_string())?;
+        self.builder.build_call(drop_fn, &[value.into()], "").map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// `drop.<T>(value)`, emitted once per type: a Box drops its pointee
+    /// and then frees it (a null handle is skipped); an enum switches on
+    /// its tag and drops the boxes of the variant it holds. Recursive
+    /// types call back into their own drop function.
+    fn drop_function(&mut self, t: &AhaType) -> Result<FunctionValue<'ctx>, String> {
+        let name = format!("drop.{}", t);
+        if let Some(f) = self.module.get_function(&name) {
+            return Ok(f);
+        }
+        let llvm_type = self.aha_type_to_llvm_type(t)?;
+        let fn_type = self.context.void_type().fn_type(&[llvm_type.into()], false);
+        let function = self.module.add_function(&name, fn_type, None);
+        let saved_block = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(function, "entry");
+        let done = self.context.append_basic_block(function, "done");
+        self.builder.position_at_end(entry);
+        let param = function.get_nth_param(0).ok_or("Failed to get parameter")?;
+        let value = TypedValue::new(param, t.clone());
+
+        let result = (|| -> Result<(), String> {
+            match t {
+                AhaType::Box(inner) => {
+                    let handle = param.into_int_value();
+                    let is_null = self.builder.build_int_compare(
+                        inkwell::IntPredicate::EQ, handle, self.i64_type.const_int(0, false), "is_null",
+                    ).map_err(|e| e.to_string())?;
+                    let free_block = self.context.append_basic_block(function, "free");
+                    self.builder.build_conditional_branch(is_null, done, free_block).map_err(|e| e.to_string())?;
+                    self.builder.position_at_end(free_block);
+                    if self.needs_drop(inner) {
+                        let pointee = self.unbox_value(&value)?;
+                        let inner_drop = self.drop_function(inner)?;
+                        self.builder.build_call(inner_drop, &[pointee.value.into()], "")
+                            .map_err(|e| e.to_string())?;
+                    }
+                    let i8_ptr = self.context.i8_type().ptr_type(inkwell::AddressSpace::default());
+                    let raw_ptr = self.builder.build_int_to_ptr(handle, i8_ptr, "box_raw")
+                        .map_err(|e| e.to_string())?;
+                    let free_fn = *self.functions.get("free").expect("free not declared");
+                    self.builder.build_call(free_fn, &[raw_ptr.into()], "").map_err(|e| e.to_string())?;
+                    self.builder.build_unconditional_branch(done).map_err(|e| e.to_string())?;
+                }
+                AhaType::Enum(enum_name) => {
+                    let variants = self.enum_defs.get(enum_name).cloned().unwrap_or_default();
+                    let tag = self.load_enum_tag(enum_name, param.into_struct_value())?;
+                    let mut cases = Vec::new();
+                    for (variant, types) in &variants {
+                        if !types.iter().any(|t| self.needs_drop(t)) {
+                            continue;
+                        }
+                        let block = self.context.append_basic_block(function, &format!("drop.{}", variant));
+                        let key = self.variant_tag(enum_name, variant)? as u64;
+                        cases.push((self.i64_type.const_int(key, false), block, variant.clone(), types.len()));
+                    }
+                    let switch_cases: Vec<_> = cases.iter().map(|(v, b, _, _)| (*v, *b)).collect();
+                    self.builder.build_switch(tag, done, &switch_cases).map_err(|e| e.to_string())?;
+                    for (_, block, variant, arity) in cases {
+                        self.builder.position_at_end(block);
+                        let payload = self.enum_payload_values(enum_name, &variant, arity, &value)?;
+                        for field in payload {
+                            if self.needs_drop(&field.aha_type) {
+                                let field_drop = self.drop_function(&field.aha_type)?;
+                                self.builder.build_call(field_drop, &[field.value.into()], "")
+                                    .map_err(|e| e.to_string())?;
+                            }
+                        }
+                        self.builder.build_unconditional_branch(done).map_err(|e| e.to_string())?;
+                    }
+                }
+                other => return Err(format!("Type {} has nothing to drop", other)),
+            }
+            self.builder.position_at_end(done);
+            self.builder.build_return(None).map_err(|e| e.to_string())?;
+            Ok(())
+        })();
+
+        if let Some(block) = saved_block {
+            self.builder.position_at_end(block);
+        }
+        result?;
+        Ok(function)
+    }
+
     /// Pre-scan: find the last statement index where each heap variable is used.
     /// Returns a map of variable name → last-use statement index.
     fn find_last_uses(body: &[ast::Statement]) -> std::collections::HashMap<String, usize> {
@@ -245,11 +556,21 @@ impl<'ctx> CodeGenerator<'ctx> {
                 Self::collect_var_names(&infix.right, vars);
             }
             ast::Expression::Prefix(prefix) => { Self::collect_var_names(&prefix.right, vars); }
+            ast::Expression::Cast(cast) => { Self::collect_var_names(&cast.value, vars); }
             ast::Expression::If(if_expr) => {
                 Self::collect_var_names(&if_expr.condition, vars);
                 Self::collect_block_vars(&if_expr.consequence, vars);
                 if let Some(ref alt) = if_expr.alternative { Self::collect_block_vars(alt, vars); }
             }
+            ast::Expression::IfLet(if_let) => {
+                Self::collect_var_names(&if_let.value, vars);
+                Self::collect_block_vars(&if_let.consequence, vars);
+                if let Some(ref alt) = if_let.alternative { Self::collect_block_vars(alt, vars); }
+            }
+            ast::Expression::WhileLet(while_let) => {
+                Self::collect_var_names(&while_let.value, vars);
+                Self::collect_block_vars(&while_let.body, vars);
+            }
             ast::Expression::Call(call) => {
                 Self::collect_var_names(&call.function, vars);
                 for arg in &call.arguments { Self::collect_var_names(arg, vars); }
@@ -262,13 +583,20 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Expression::Assignment(assign) => { Self::collect_var_names(&assign.value, vars); }
             ast::Expression::StructLiteral(sl) => {
                 for (_, val) in &sl.fields { Self::collect_var_names(val, vars); }
+                if let Some(base) = &sl.base { Self::collect_var_names(base, vars); }
             }
             ast::Expression::Array(arr) => {
                 for elem in &arr.elements { Self::collect_var_names(elem, vars); }
             }
+            ast::Expression::Tuple(t) => {
+                for elem in &t.elements { Self::collect_var_names(elem, vars); }
+            }
             ast::Expression::Match(m) => {
                 Self::collect_var_names(&m.value, vars);
-                for arm in &m.arms { Self::collect_var_names(&arm.body, vars); }
+                for arm in &m.arms {
+                    if let Some(guard) = &arm.guard { Self::collect_var_names(guard, vars); }
+                    Self::collect_var_names(&arm.body, vars);
+                }
             }
             _ => {}
         }
@@ -279,6 +607,7 @@ impl<'ctx> CodeGenerator<'ctx> {
             match stmt {
                 ast::Statement::Expression(es) => Self::collect_var_names(&es.expression, vars),
                 ast::Statement::Let(ls) => Self::collect_var_names(&ls.value, vars),
+                ast::Statement::LetPattern(lp) => Self::collect_var_names(&lp.value, vars),
                 ast::Statement::Return(ret) => Self::collect_var_names(&ret.return_value, vars),
                 _ => {}
             }
@@ -293,6 +622,9 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Statement::Let(let_stmt) => {
                 Self::scan_expr_uses(&let_stmt.value, last_uses, idx);
             }
+            ast::Statement::LetPattern(let_pat) => {
+                Self::scan_expr_uses(&let_pat.value, last_uses, idx);
+            }
             ast::Statement::Return(ret) => {
                 Self::scan_expr_uses(&ret.return_value, last_uses, idx);
             }
@@ -312,6 +644,9 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Expression::Prefix(prefix) => {
                 Self::scan_expr_uses(&prefix.right, last_uses, idx);
             }
+            ast::Expression::Cast(cast) => {
+                Self::scan_expr_uses(&cast.value, last_uses, idx);
+            }
             ast::Expression::If(if_expr) => {
                 Self::scan_expr_uses(&if_expr.condition, last_uses, idx);
                 Self::scan_block_uses(&if_expr.consequence, last_uses, idx);
@@ -323,6 +658,17 @@ impl<'ctx> CodeGenerator<'ctx> {
                 Self::scan_expr_uses(&while_expr.condition, last_uses, idx);
                 Self::scan_block_uses(&while_expr.body, last_uses, idx);
             }
+            ast::Expression::IfLet(if_let) => {
+                Self::scan_expr_uses(&if_let.value, last_uses, idx);
+                Self::scan_block_uses(&if_let.consequence, last_uses, idx);
+                if let Some(ref alt) = if_let.alternative {
+                    Self::scan_block_uses(alt, last_uses, idx);
+                }
+            }
+            ast::Expression::WhileLet(while_let) => {
+                Self::scan_expr_uses(&while_let.value, last_uses, idx);
+                Self::scan_block_uses(&while_let.body, last_uses, idx);
+            }
             ast::Expression::For(for_expr) => {
                 Self::scan_expr_uses(&for_expr.iterable, last_uses, idx);
                 Self::scan_block_uses(&for_expr.body, last_uses, idx);
@@ -344,18 +690,29 @@ impl<'ctx> CodeGenerator<'ctx> {
                 for (_, val) in &sl.fields {
                     Self::scan_expr_uses(val, last_uses, idx);
                 }
+                if let Some(base) = &sl.base {
+                    Self::scan_expr_uses(base, last_uses, idx);
+                }
             }
             ast::Expression::Array(arr) => {
                 for elem in &arr.elements {
                     Self::scan_expr_uses(elem, last_uses, idx);
                 }
             }
+            ast::Expression::Tuple(t) => {
+                for elem in &t.elements {
+                    Self::scan_expr_uses(elem, last_uses, idx);
+                }
+            }
             ast::Expression::Assignment(assign) => {
                 Self::scan_expr_uses(&assign.value, last_uses, idx);
             }
             ast::Expression::Match(m) => {
                 Self::scan_expr_uses(&m.value, last_uses, idx);
                 for arm in &m.arms {
+                    if let Some(guard) = &arm.guard {
+                        Self::scan_expr_uses(guard, last_uses, idx);
+                    }
                     Self::scan_expr_uses(&arm.body, last_uses, idx);
                 }
             }
@@ -369,199 +726,14 @@ impl<'ctx> CodeGenerator<'ctx> {
         }
     }
 
-    /// Pre-pass: walk all statements to find call expressions and infer
-    /// parameter types. If a string literal is passed as argument N,
-    /// param N is marked as String.
-    fn scan_call_sites(&mut self, statements: &[ast::Statement]) {
-        for stmt in statements {
-            match stmt {
-                ast::Statement::Expression(expr_stmt) => {
-                    self.scan_expr_for_calls(&expr_stmt.expression);
-                }
-                ast::Statement::Let(let_stmt) => {
-                    // Track struct variable bindings so infer_expr_type
-                    // can resolve them when the variable is passed as a
-                    // function argument. Prefer the explicit annotation
-                    // when present (e.g. `let p: Point = ...`).
-                    if let Some(ref hint) = let_stmt.type_annotation {
-                        if self.struct_defs.contains_key(hint) {
-                            self.struct_var_types.insert(
-                                let_stmt.name.value.clone(),
-                                AhaType::Struct(hint.clone()),
-                            );
-                        }
-                    } else if let ast::Expression::StructLiteral(sl) = &let_stmt.value {
-                        self.struct_var_types.insert(
-                            let_stmt.name.value.clone(),
-                            AhaType::Struct(sl.name.value.clone()),
-                        );
-                    } else if let ast::Expression::Call(call) = &let_stmt.value {
-                        // Track list bindings so a later call site can infer
-                        // the param type: `let xs = list_new(); f(xs)` passes
-                        // xs as List<Int>, not Int.
-                        if let ast::Expression::Identifier(id) = call.function.as_ref() {
-                            if id.value == "list_new" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::List(Box::new(AhaType::Int)),
-                                );
-                            } else if id.value == "list_new_string" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::List(Box::new(AhaType::String)),
-                                );
-                            } else if id.value == "map_new" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::Int)),
-                                );
-                            } else if id.value == "map_new_string_key" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::Map(Box::new(AhaType::String), Box::new(AhaType::Int)),
-                                );
-                            } else if id.value == "map_new_string_val" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::String)),
-                                );
-                            } else if id.value == "map_new_strings" {
-                                self.struct_var_types.insert(
-                                    let_stmt.name.value.clone(),
-                                    AhaType::Map(Box::new(AhaType::String), Box::new(AhaType::String)),
-                                );
-                            }
-                        }
-                    }
-                    self.scan_expr_for_calls(&let_stmt.value);
-                }
-                ast::Statement::Return(ret_stmt) => {
-                    self.scan_expr_for_calls(&ret_stmt.return_value);
-                }
-                ast::Statement::Struct(_) => {}
-                ast::Statement::Actor(_) => {}
-                ast::Statement::Enum(_) => {}
-                ast::Statement::Import(_) => {}
-            }
-        }
-    }
-
-    fn scan_expr_for_calls(&mut self, expr: &ast::Expression) {
-        match expr {
-            ast::Expression::Call(call) => {
-                if let ast::Expression::Identifier(id) = call.function.as_ref() {
-                    let types: Vec<AhaType> = call.arguments.iter()
-                        .map(|arg| self.infer_expr_type(arg))
-                        .collect();
-                    self.param_type_map.entry(id.value.clone())
-                        .and_modify(|existing| {
-                            for (i, t) in types.iter().enumerate() {
-                                if i < existing.len() {
-                                    existing[i] = existing[i].unify_with(t);
-                                }
-                            }
-                        })
-                        .or_insert_with(|| types.clone());
-                } else if let ast::Expression::ModuleAccess(ma) = call.function.as_ref() {
-                    // module::name(args) — same as Identifier scan
-                    let types: Vec<AhaType> = call.arguments.iter()
-                        .map(|arg| self.infer_expr_type(arg))
-                        .collect();
-                    self.param_type_map.entry(ma.name.clone())
-                        .and_modify(|existing| {
-                            for (i, t) in types.iter().enumerate() {
-                                if i < existing.len() {
-                                    existing[i] = existing[i].unify_with(t);
-                                }
-                            }
-                        })
-                        .or_insert_with(|| types.clone());
-                }
-                for arg in &call.arguments {
-                    self.scan_expr_for_calls(arg);
-                }
-            }
-            ast::Expression::Infix(infix) => {
-                self.scan_expr_for_calls(&infix.left);
-                self.scan_expr_for_calls(&infix.right);
-            }
-            ast::Expression::Prefix(prefix) => {
-                self.scan_expr_for_calls(&prefix.right);
-            }
-            ast::Expression::If(if_expr) => {
-                self.scan_expr_for_calls(&if_expr.condition);
-                self.scan_block_for_calls(&if_expr.consequence);
-                if let Some(alt) = &if_expr.alternative {
-                    self.scan_block_for_calls(alt);
-                }
-            }
-            ast::Expression::While(while_expr) => {
-                self.scan_expr_for_calls(&while_expr.condition);
-                self.scan_block_for_calls(&while_expr.body);
-            }
-            ast::Expression::For(for_expr) => {
-                self.scan_expr_for_calls(&for_expr.iterable);
-                self.scan_block_for_calls(&for_expr.body);
-            }
-            ast::Expression::Assignment(assign) => {
-                self.scan_expr_for_calls(&assign.target);
-                self.scan_expr_for_calls(&assign.value);
-            }
-            ast::Expression::Function(func) => {
-                // Push function params into scan_scope so body calls can
-                // resolve param types (e.g., `x2(p)` inside `total(p) { ... }`).
-                if let Some(name) = &func.name {
-                    let params: HashMap<String, AhaType> = func.parameters.iter().enumerate()
-                        .map(|(i, p)| {
-                            let t = self.param_type_map.get(&name.value)
-                                .and_then(|types| types.get(i).cloned())
-                                .unwrap_or(AhaType::Int);
-                            (p.value.clone(), t)
-                        })
-                        .collect();
-                    self.scan_scope.push(params);
-                    self.scan_block_for_calls(&func.body);
-                    self.scan_scope.pop();
-                } else {
-                    self.scan_block_for_calls(&func.body);
-                }
-            }
-            ast::Expression::Array(arr) => {
-                for elem in &arr.elements {
-                    self.scan_expr_for_calls(elem);
-                }
-            }
-            ast::Expression::Index(idx) => {
-                self.scan_expr_for_calls(&idx.left);
-                self.scan_expr_for_calls(&idx.index);
-            }
-            ast::Expression::StructLiteral(struct_lit) => {
-                for (_, value_expr) in &struct_lit.fields {
-                    self.scan_expr_for_calls(value_expr);
-                }
-            }
-            ast::Expression::Match(m) => {
-                self.scan_expr_for_calls(&m.value);
-                for arm in &m.arms {
-                    self.scan_expr_for_calls(&arm.body);
-                }
-            }
-            _ => {}
-        }
-    }
-
-    fn scan_block_for_calls(&mut self, block: &ast::BlockStatement) {
-        for stmt in &block.statements {
-            self.scan_call_sites(std::slice::from_ref(stmt));
-        }
-    }
-
     /// LLVM type for an AhaType (function params, returns, allocas).
     fn aha_type_to_llvm_type(&self, t: &AhaType) -> Result<inkwell::types::BasicTypeEnum<'ctx>, String> {
         match t {
             AhaType::String => Ok(self.string_type.into()),
             AhaType::Struct(name) => Ok(self.struct_llvm_type(name)?.into()),
             AhaType::Enum(name) => Ok(self.enum_llvm_type(name)?.into()),
+            AhaType::Tuple(elems) => Ok(self.tuple_llvm_type(elems)?.into()),
+            AhaType::Array(elem, len) => Ok(self.aha_type_to_llvm_type(elem)?.array_type(*len as u32).into()),
             _ => Ok(self.i64_type.into()),
         }
     }
@@ -578,14 +750,43 @@ impl<'ctx> CodeGenerator<'ctx> {
             let inner_type = self.resolve_hint_type(inner);
             return AhaType::List(Box::new(inner_type));
         }
+        // Box<T>: heap handle to a resolved inner type.
+        if let Some(inner) = hint.strip_prefix("Box<").and_then(|s| s.strip_suffix('>')) {
+            return AhaType::Box(Box::new(self.resolve_hint_type(inner)));
+        }
         // Map<K,V> with resolved inner types.
         if let Some(inner) = hint.strip_prefix("Map<").and_then(|s| s.strip_suffix('>')) {
-            if let Some((k, v)) = inner.split_once(',') {
-                let kt = self.resolve_hint_type(k.trim());
-                let vt = self.resolve_hint_type(v.trim());
+            if let [k, v] = split_type_list(inner).as_slice() {
+                let kt = self.resolve_hint_type(k);
+                let vt = self.resolve_hint_type(v);
                 return AhaType::Map(Box::new(kt), Box::new(vt));
             }
         }
+        // Generic struct/enum instance: Pair<int, T> → Struct("Pair<Int, String>"),
+        // Option<T> → Enum("Option<Int>").
+        if let Some((base, inner)) = hint.strip_suffix('>').and_then(|h| h.split_once('<')) {
+            let is_struct = self.generic_struct_defs.contains_key(base);
+            if is_struct || self.generic_enum_defs.contains_key(base) {
+                let args: Vec<AhaType> = split_type_list(inner)
+                    .into_iter()
+                    .map(|a| self.resolve_hint_type(a))
+                    .collect();
+                let name = generic_type_name(base, &args);
+                return if is_struct { AhaType::Struct(name) } else { AhaType::Enum(name) };
+            }
+        }
+        // (T1, T2, ...) — elements may name structs, enums or type params.
+        if let Some(inner) = hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            let elems = split_type_list(inner)
+                .into_iter()
+                .map(|e| self.resolve_hint_type(e))
+                .collect();
+            return AhaType::Tuple(elems);
+        }
+        // [T; N] — the element may name a struct, enum or type param.
+        if let Some((elem, len)) = split_array_hint(hint) {
+            return AhaType::Array(Box::new(self.resolve_hint_type(elem)), len);
+        }
         if let Some(t) = AhaType::from_hint(hint) {
             return t;
         }
@@ -617,6 +818,11 @@ impl<'ctx> CodeGenerator<'ctx> {
                 let et = self.enum_llvm_type(name)?;
                 Ok(et.fn_type(&meta, false))
             }
+            AhaType::Tuple(elems) => {
+                let tt = self.tuple_llvm_type(elems)?;
+                Ok(tt.fn_type(&meta, false))
+            }
+            AhaType::Array(_, _) => Ok(self.aha_type_to_llvm_type(return_type)?.fn_type(&meta, false)),
             _ => Ok(self.i64_type.fn_type(&meta, false)),
         }
     }
@@ -646,12 +852,16 @@ impl<'ctx> CodeGenerator<'ctx> {
                     if func_name == "main" {
                         continue;
                     }
+                    // Errors resurface when compile_function handles the body.
+                    if self.instantiate_fn_hints(func).is_err() {
+                        continue;
+                    }
                     let param_aha = self.infer_param_types(&func_name, &func.parameters, &func.param_type_hints);
                     let param_types: Result<Vec<_>, _> = param_aha.iter()
                         .map(|t| self.aha_type_to_llvm_type(t))
                         .collect();
                     let Ok(param_types) = param_types else { continue; };
-                    let return_type = self.infer_function_return_type(func, &func_name);
+                    let return_type = self.function_return_type(func, &func_name);
                     let Ok(fn_type) = self.build_fn_type(&return_type, &param_types) else { continue; };
                     let function = self.module.add_function(&func_name, fn_type, None);
                     self.functions.insert(func_name.clone(), function);
@@ -661,354 +871,79 @@ impl<'ctx> CodeGenerator<'ctx> {
         }
     }
 
-    /// Infer the AhaType of an expression for the pre-pass.
-    fn infer_expr_type(&self, expr: &ast::Expression) -> AhaType {
-        match expr {
-            ast::Expression::String(_) => AhaType::String,
-            ast::Expression::Integer(_) => AhaType::Int,
-            ast::Expression::Boolean(_) => AhaType::Bool,
-            ast::Expression::Identifier(id) => {
-                // Check scan_scope first (for param types inside function bodies
-                // during the pre-pass scan), then live variables, then struct_var_types.
-                let from_scan = self.scan_scope.last()
-                    .and_then(|scope| scope.get(&id.value).cloned());
-                let from_var = self.lookup_variable(&id.value)
-                    .map(|info| info.var_type.clone());
-                let from_struct = self.struct_var_types.get(&id.value).cloned();
-                from_scan.or(from_var).or(from_struct).unwrap_or(AhaType::Int)
-            }
-            ast::Expression::Infix(infix) => {
-                let lt = self.infer_expr_type(&infix.left);
-                let rt = self.infer_expr_type(&infix.right);
-                match infix.operator.as_str() {
-                    "+" if lt == AhaType::String || rt == AhaType::String => AhaType::String,
-                    "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" => AhaType::Int,
-                    _ => AhaType::Int,
-                }
-            }
-            ast::Expression::Prefix(prefix) => {
-                if prefix.operator == "!" {
-                    AhaType::Bool
-                } else {
-                    AhaType::Int
-                }
-            }
-            ast::Expression::Call(call) => {
-                if let ast::Expression::Identifier(id) = call.function.as_ref() {
-                    // Enum variant constructor: return the enum type.
-                    if let Some(enum_name) = self.find_enum_for_variant(&id.value) {
-                        return AhaType::Enum(enum_name);
-                    }
-                    // List builtins: preserve the element type of the first
-                    // argument so `let xs = list_new(); list_push(xs, ...)`
-                    // keeps xs as List<Int> and list_get(xs, i) is Int.
-                    if id.value == "list_push" || id.value == "list_push_string" {
-                        if let Some(first) = call.arguments.first() {
-                            return self.infer_expr_type(first);
-                        }
-                    }
-                    if id.value == "list_get" || id.value == "list_get_string" {
-                        if let Some(first) = call.arguments.first() {
-                            let list_type = self.infer_expr_type(first);
-                            if let AhaType::List(inner) = list_type {
-                                return *inner;
-                            }
-                        }
-                        return AhaType::Int;
-                    }
-                    // Map get: return the value type of the map.
-                    if id.value == "map_get" || id.value == "map_get_string" {
-                        if let Some(first) = call.arguments.first() {
-                            let map_type = self.infer_expr_type(first);
-                            if let AhaType::Map(_, v) = map_type {
-                                return *v;
-                            }
-                        }
-                        return AhaType::Int;
-                    }
-                    // Prefer a known return type (fn_types), then fall back
-                    // to the builtin len() = Int.
-                    if let Some(rt) = self.fn_types.get(&id.value) {
-                        return rt.clone();
-                    }
-                    if id.value == "len" {
-                        return AhaType::Int;
-                    }
-                }
-                AhaType::Int
-            }
-            ast::Expression::StructLiteral(sl) => {
-                AhaType::Struct(sl.name.value.clone())
-            }
-            ast::Expression::FieldAccess(fa) => {
-                // Infer the struct type from the object, then look up
-                // the field type. For pre-pass, return Int if unknown.
-                let obj_type = self.infer_expr_type(&fa.object);
-                if let AhaType::Struct(name) = &obj_type {
-                    if let Some(fields) = self.struct_defs.get(name) {
-                        for (field_name, ft) in fields {
-                            if field_name == &fa.field.value {
-                                return ft.clone();
-                            }
-                        }
-                    }
-                }
-                AhaType::Int
-            }
-            ast::Expression::Match(m) => {
-                // Return type of first arm body (all arms must agree).
-                if let Some(arm) = m.arms.first() {
-                    self.infer_expr_type(&arm.body)
-                } else {
-                    AhaType::Int
-                }
+    /// Take every signature from typeck: parameter and return types of
+    /// the user functions, return types of the generic function instances
+    /// it saw called, and the generic struct and enum instances those
+    /// types name. Parameters typeck left open are Int.
+    fn apply_type_table(&mut self, table: &TypeTable) -> Result<(), String> {
+        for (base, args) in &table.instances {
+            if self.generic_struct_defs.contains_key(base) {
+                self.instantiate_struct(base, args.clone())?;
+            } else {
+                self.instantiate_enum(base, args.clone())?;
             }
-            _ => AhaType::Int,
         }
+        for (name, params) in &table.params {
+            let types = params.iter().map(|t| t.clone().unwrap_or(AhaType::Int)).collect();
+            self.param_type_map.insert(name.clone(), types);
+        }
+        for (name, ret) in table.returns.iter().chain(&table.instance_returns) {
+            self.fn_types.insert(name.clone(), ret.clone());
+        }
+        Ok(())
     }
 
-    /// Infer a function's return type for the pre-declaration pass.
-    /// Walks the body looking for the last expression value or an
-    /// explicit `return` statement, then types that expression with
-    /// the function's own params in scope (so `a + b` is String when
-    /// a and b are string params).
-    fn infer_function_return_type(&self, func: &ast::FunctionLiteral, func_name: &str) -> AhaType {
-        // Prefer explicit return type annotation (e.g. `fn f() -> int`)
-        if let Some(ref hint) = func.return_type_hint {
-            // Resolve struct/enum names against registries, then fall back to from_hint.
-            let ty = if self.struct_defs.contains_key(hint.as_str()) {
-                AhaType::Struct(hint.clone())
-            } else if self.enum_defs.contains_key(hint.as_str()) {
-                AhaType::Enum(hint.clone())
-            } else {
-                AhaType::from_hint(hint).unwrap_or(AhaType::Int)
-            };
-            return ty;
-        }
+    /// Get i8* pointer type (used frequently for strings)
+    fn i8_ptr_type(&self) -> inkwell::types::PointerType<'ctx> {
+        self.context.i8_type().ptr_type(inkwell::AddressSpace::default())
+    }
 
-        let param_types = self.infer_param_types_immutable(func_name, &func.parameters, &func.param_type_hints);
+    pub fn compile(&mut self, program: &ast::Program) -> Result<(), String> {
+        // Expand type aliases and lower newtypes to one-field structs; every
+        // later pass (and the type table's node keys) sees this program.
+        let program = &typedefs::desugar(program)?;
+        // Type-check the whole program first so every type error is
+        // reported at once, with its line.
+        let type_table = typeck::check_program(program).map_err(|errors| {
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
+        })?;
+        units::check_program(program, &type_table).map_err(|errors| {
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
+        })?;
+        let globals = consteval::evaluate(program).map_err(|errors| {
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
+        })?;
+        self.consts = globals.consts;
+        self.struct_defaults = globals.field_defaults;
+        self.emit_statics(&program.statements, &globals.statics);
 
-        // Build a synthetic scope so infer_expr_type_with_scope can resolve params.
-        let scope: HashMap<String, AhaType> = func.parameters.iter().enumerate()
-            .map(|(i, p)| (p.value.clone(), param_types.get(i).cloned().unwrap_or(AhaType::Int)))
-            .collect();
+        self.declare_printf();
+        self.declare_c_runtime();
+        self.declare_actor_runtime();
+        self.declare_string_and_file_builtins();
+        self.create_list_builtins();
+        self.create_map_builtins();
+        self.create_panic_runtime()?;
+        self.create_run_entry()?;
 
-        for stmt in &func.body.statements {
-            if let ast::Statement::Return(ret) = stmt {
-                return self.infer_expr_type_with_scope(&ret.return_value, &scope);
-            }
-        }
-        // No explicit return — type the last expression statement.
-        for stmt in func.body.statements.iter().rev() {
-            if let ast::Statement::Expression(expr_stmt) = stmt {
-                return self.infer_expr_type_with_scope(&expr_stmt.expression, &scope);
-            }
+        // Verify the module is valid before proceeding.
+        if let Err(e) = self.module.verify() {
+            return Err(format!("LLVM module verification failed: {}", e));
         }
-        AhaType::Int
-    }
 
-    /// Immutable variant of infer_param_types for the pre-pass (when we
-    /// cannot call the &mut self version). Reads from param_type_map.
-    fn infer_param_types_immutable(&self, func_name: &str, params: &[ast::Identifier], hints: &[Option<String>]) -> Vec<AhaType> {
-        let mut types = vec![AhaType::Int; params.len()];
-        for (i, hint) in hints.iter().enumerate() {
-            if i < types.len() {
-                if let Some(h) = hint {
-                    types[i] = if self.enum_defs.contains_key(h.as_str()) {
-                        AhaType::Enum(h.clone())
-                    } else if self.struct_defs.contains_key(h.as_str()) {
-                        AhaType::Struct(h.clone())
-                    } else {
-                        AhaType::from_hint(h).unwrap_or(AhaType::Int)
-                    };
-                }
-            }
-        }
-        if let Some(inferred) = self.param_type_map.get(func_name) {
-            for (i, t) in inferred.iter().enumerate() {
-                if i < types.len() {
-                    if matches!(types[i], AhaType::Int) {
-                        types[i] = t.clone();
-                    }
-                }
-            }
-        }
-        types
-    }
+        // Register struct definitions first so struct literals and field
+        // access can resolve field layout during codegen.
+        self.register_structs(&program.statements);
+        // Register enum definitions so constructors and match can resolve
+        // variant layout during codegen.
+        self.register_enums(&program.statements)?;
+        // Struct fields may name any struct or enum, so resolve them once
+        // every name is known.
+        self.resolve_struct_fields(&program.statements)?;
 
-    /// Like infer_expr_type, but with a synthetic local scope (used by
-    /// the pre-declaration pass to resolve function params).
-    fn infer_expr_type_with_scope(&self, expr: &ast::Expression, scope: &HashMap<String, AhaType>) -> AhaType {
-        match expr {
-            ast::Expression::String(_) => AhaType::String,
-            ast::Expression::Integer(_) => AhaType::Int,
-            ast::Expression::Boolean(_) => AhaType::Bool,
-            ast::Expression::Identifier(id) => {
-                scope.get(&id.value).cloned().unwrap_or(AhaType::Int)
-            }
-            ast::Expression::ModuleAccess(ma) => {
-                scope.get(&ma.name).cloned().unwrap_or(AhaType::Int)
-            }
-            ast::Expression::Infix(infix) => {
-                let lt = self.infer_expr_type_with_scope(&infix.left, scope);
-                let rt = self.infer_expr_type_with_scope(&infix.right, scope);
-                match infix.operator.as_str() {
-                    "+" if lt == AhaType::String || rt == AhaType::String => AhaType::String,
-                    "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" => AhaType::Int,
-                    _ => AhaType::Int,
-                }
-            }
-            ast::Expression::Prefix(prefix) => {
-                if prefix.operator == "!" {
-                    AhaType::Bool
-                } else {
-                    AhaType::Int
-                }
-            }
-            ast::Expression::Call(call) => {
-                let call_name = match call.function.as_ref() {
-                    ast::Expression::Identifier(id) => Some(id.value.as_str()),
-                    ast::Expression::ModuleAccess(ma) => Some(ma.name.as_str()),
-                    _ => None,
-                };
-                if let Some(name) = call_name {
-                    if let Some(enum_name) = self.find_enum_for_variant(name) {
-                        return AhaType::Enum(enum_name);
-                    }
-                    if name == "list_push" || name == "list_push_string" {
-                        if let Some(first) = call.arguments.first() {
-                            return self.infer_expr_type_with_scope(first, scope);
-                        }
-                    }
-                    if name == "list_get" || name == "list_get_string" {
-                        if let Some(first) = call.arguments.first() {
-                            let list_type = self.infer_expr_type_with_scope(first, scope);
-                            if let AhaType::List(inner) = list_type {
-                                return *inner;
-                            }
-                        }
-                        return AhaType::Int;
-                    }
-                    if name == "len" {
-                        return AhaType::Int;
-                    }
-                    if let Some(rt) = self.fn_types.get(name) {
-                        return rt.clone();
-                    }
-                }
-                AhaType::Int
-            }
-            ast::Expression::If(if_expr) => {
-                let cons = self.infer_block_return_type(&if_expr.consequence, scope);
-                if let Some(alt) = &if_expr.alternative {
-                    let alt_t = self.infer_block_return_type(alt, scope);
-                    if alt_t == AhaType::String || cons == AhaType::String {
-                        return AhaType::String;
-                    }
-                }
-                cons
-            }
-            ast::Expression::StructLiteral(sl) => {
-                AhaType::Struct(sl.name.value.clone())
-            }
-            ast::Expression::FieldAccess(fa) => {
-                let obj_type = self.infer_expr_type_with_scope(&fa.object, scope);
-                if let AhaType::Struct(name) = &obj_type {
-                    if let Some(fields) = self.struct_defs.get(name) {
-                        for (field_name, ft) in fields {
-                            if field_name == &fa.field.value {
-                                return ft.clone();
-                            }
-                        }
-                    }
-                }
-                AhaType::Int
-            }
-            ast::Expression::Assignment(assign) => {
-                self.infer_expr_type_with_scope(&assign.value, scope)
-            }
-            ast::Expression::Match(m) => {
-                if let Some(arm) = m.arms.first() {
-                    self.infer_expr_type_with_scope(&arm.body, scope)
-                } else {
-                    AhaType::Int
-                }
-            }
-            _ => AhaType::Int,
-        }
-    }
-
-    fn infer_block_return_type(&self, block: &ast::BlockStatement, scope: &HashMap<String, AhaType>) -> AhaType {
-        for stmt in &block.statements {
-            if let ast::Statement::Return(ret) = stmt {
-                return self.infer_expr_type_with_scope(&ret.return_value, scope);
-            }
-        }
-        for stmt in block.statements.iter().rev() {
-            if let ast::Statement::Expression(expr_stmt) = stmt {
-                return self.infer_expr_type_with_scope(&expr_stmt.expression, scope);
-            }
-        }
-        AhaType::Int
-    }
-
-    /// Get i8* pointer type (used frequently for strings)
-    fn i8_ptr_type(&self) -> inkwell::types::PointerType<'ctx> {
-        self.context.i8_type().ptr_type(inkwell::AddressSpace::default())
-    }
-
-    pub fn compile(&mut self, program: &ast::Program) -> Result<(), String> {
-        self.declare_printf();
-        self.declare_c_runtime();
-        self.declare_actor_runtime();
-        self.declare_string_and_file_builtins();
-        self.create_list_builtins();
-        self.create_map_builtins();
-
-        // Verify the module is valid before proceeding.
-        if let Err(e) = self.module.verify() {
-            return Err(format!("LLVM module verification failed: {}", e));
-        }
-
-        // Register struct definitions first so struct literals and field
-        // access can resolve field layout during codegen.
-        self.register_structs(&program.statements);
-        // Register enum definitions so constructors and match can resolve
-        // variant layout during codegen.
-        self.register_enums(&program.statements);
-
-        // Pre-pass: iterate scanning until param types and return types
-        // stabilize. A single pass is insufficient: a struct param's
-        // type is only known after its call site is scanned, and chained
-        // calls like sum(make(20, 22)) need return types to type the
-        // inner call's argument. Each iteration only upgrades types
-        // (Int → String/Struct), so this terminates quickly.
-        for _ in 0..32 {
-            let before_params = self.param_type_map.clone();
-            let before_fns = self.fn_types.clone();
-            self.scan_call_sites(&program.statements);
-            // Recompute return types now that param types may have changed.
-            for stmt in &program.statements {
-                if let ast::Statement::Expression(ast::ExpressionStatement {
-                    expression: ast::Expression::Function(func),
-                }) = stmt
-                {
-                    if let Some(name) = &func.name {
-                        // Generic functions are monomorphized lazily at call
-                        // sites; their "return type" only exists per
-                        // instantiation, so skip them here.
-                        if !func.type_params.is_empty() {
-                            continue;
-                        }
-                        let rt = self.infer_function_return_type(func, &name.value);
-                        self.fn_types.insert(name.value.clone(), rt);
-                    }
-                }
-            }
-            if self.param_type_map == before_params && self.fn_types == before_fns {
-                break;
-            }
-        }
+        // Signatures come from typeck; generic instances it saw are
+        // registered now so their layouts exist before predeclaration.
+        self.apply_type_table(&type_table)?;
 
         // Pre-declare all user functions so mutual recursion works:
         // is_even can call is_odd before is_odd's body is compiled.
@@ -1031,6 +966,7 @@ impl<'ctx> CodeGenerator<'ctx> {
             let fn_type = self.i64_type.fn_type(&[], false);
             let function = self.module.add_function("main", fn_type, None);
             self.functions.insert("main".to_string(), function);
+            self.traced_functions.push((function, "main".to_string()));
             let bb = self.context.append_basic_block(function, "entry");
             self.builder.position_at_end(bb);
             Some(bb)
@@ -1072,9 +1008,15 @@ impl<'ctx> CodeGenerator<'ctx> {
                 },
                 None => self.i64_type.const_int(0, false).into(),
             };
-            let _ = self.builder.build_return(Some(&return_val));
+            if !self.block_terminated() {
+                let _ = self.builder.build_return(Some(&return_val));
+            }
         }
 
+        self.instrument_backtraces()?;
+        self.create_test_entries()?;
+        self.create_bench_entries()?;
+
         // Final module verification.
         if let Err(e) = self.module.verify() {
             return Err(format!("LLVM module verification failed: {}", e));
@@ -2355,10 +2297,13 @@ impl<'ctx> CodeGenerator<'ctx> {
             let no_cap = self.builder.build_int_compare(inkwell::IntPredicate::EQ, cap, zero, "no_cap").unwrap();
             let needs_grow = self.builder.build_int_compare(inkwell::IntPredicate::SGE, len, cap, "needs_grow").unwrap();
             let should_grow = self.builder.build_or(no_cap, needs_grow, "should_grow").unwrap();
+            let alloc_route = self.context.append_basic_block(function, "alloc_route");
             let init_alloc = self.context.append_basic_block(function, "init_alloc");
             let grow_rehash = self.context.append_basic_block(function, "grow_rehash");
             let probe_block = self.context.append_basic_block(function, "probe");
-            self.builder.build_conditional_branch(should_grow, init_alloc, probe_block).unwrap();
+            self.builder.build_conditional_branch(should_grow, alloc_route, probe_block).unwrap();
+            self.builder.position_at_end(alloc_route);
+            self.builder.build_conditional_branch(no_cap, init_alloc, grow_rehash).unwrap();
 
             // === init_alloc: cap==0, allocate initial4 slots, zero occupied ===
             self.builder.position_at_end(init_alloc);
@@ -2465,7 +2410,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                 // Find empty slot in new buffer via linear probe.
                 let rh_idx = self.builder.build_int_unsigned_rem(rh_hash_val, new_cap_grow, "rh_idx").unwrap();
                 let rh_p = self.builder.build_alloca(i64_type, "rh_p").unwrap();
-                self.builder.build_store(rh_p, one).unwrap();
+                self.builder.build_store(rh_p, zero).unwrap();
                 let rp_cond = self.context.append_basic_block(function, "rp_cond");
                 let rp_body = self.context.append_basic_block(function, "rp_body");
                 let rp_found = self.context.append_basic_block(function, "rp_found");
@@ -2498,9 +2443,8 @@ impl<'ctx> CodeGenerator<'ctx> {
                 self.builder.build_store(rh_p, rh_pn).unwrap();
                 self.builder.build_unconditional_branch(rp_cond).unwrap();
                 self.builder.position_at_end(rp_found);
-                let rp_sum = self.builder.build_load(rh_p, "rp_sum").unwrap().into_int_value();
-                // Probe counter minus1 gives the offset from initial index.
-                let rp_idx = self.builder.build_int_sub(rp_sum, one, "rp_idx").unwrap();
+                // The probe counter is the offset from the initial index.
+                let rp_idx = self.builder.build_load(rh_p, "rp_idx").unwrap().into_int_value();
                 let rp_final = self.builder.build_int_unsigned_rem(
                     self.builder.build_int_add(rh_idx, rp_idx, "rp_final_sum").unwrap(),
                     new_cap_grow, "rp_final"
@@ -2558,7 +2502,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                 let rh_hash_val = splitmix64(&self.builder, rk_val);
                 let rh_idx = self.builder.build_int_unsigned_rem(rh_hash_val, new_cap_grow, "rh_idx").unwrap();
                 let rh_p = self.builder.build_alloca(i64_type, "rh_p").unwrap();
-                self.builder.build_store(rh_p, one).unwrap();
+                self.builder.build_store(rh_p, zero).unwrap();
                 let rp_cond = self.context.append_basic_block(function, "rp_cond");
                 let rp_body = self.context.append_basic_block(function, "rp_body");
                 let rp_found = self.context.append_basic_block(function, "rp_found");
@@ -2590,8 +2534,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                 self.builder.build_store(rh_p, rh_pn).unwrap();
                 self.builder.build_unconditional_branch(rp_cond).unwrap();
                 self.builder.position_at_end(rp_found);
-                let rp_sum = self.builder.build_load(rh_p, "rp_sum").unwrap().into_int_value();
-                let rp_idx = self.builder.build_int_sub(rp_sum, one, "rp_idx").unwrap();
+                let rp_idx = self.builder.build_load(rh_p, "rp_idx").unwrap().into_int_value();
                 let rp_final = self.builder.build_int_unsigned_rem(
                     self.builder.build_int_add(rh_idx, rp_idx, "rp_final_sum").unwrap(),
                     new_cap_grow, "rp_final"
@@ -3155,28 +3098,487 @@ impl<'ctx> CodeGenerator<'ctx> {
 
     }
 
+    // --- Panic runtime ---
+
+    /// Emit the panic runtime. `aha_panic(msg, len, line)` prints the
+    /// message, its source line and a backtrace of AHA! functions to
+    /// stderr, unless a quiet `aha_run` armed this thread, and then calls
+    /// `aha_unwind(msg, len, line)`. That unwinds with `longjmp` to the
+    /// `aha_run` or `aha_actor_run` that armed this thread; in a native
+    /// build, or on a thread nothing armed, it exits with
+    /// `PANIC_EXIT_CODE`. `aha_bounds_fail(index, len, line)` formats an
+    /// index-out-of-bounds message and panics with it.
+    fn create_panic_runtime(&mut self) -> Result<(), String> {
+        let i64_t = self.i64_type;
+        let i32_t = self.context.i32_type();
+        let i8_ptr = self.i8_ptr_type();
+        let void_t = self.context.void_type();
+        let noreturn = self.context.create_enum_attribute(Attribute::get_named_enum_kind_id("noreturn"), 0);
+
+        let global = |cg: &Self, ty: inkwell::types::BasicTypeEnum<'ctx>, name: &str| {
+            let g = cg.module.add_global(ty, None, name);
+            g.set_initializer(&ty.const_zero());
+            g.set_linkage(inkwell::module::Linkage::Internal);
+            g
+        };
+        global(self, i8_ptr.array_type(PANIC_FRAMES as u32).into(), "aha_frames").set_thread_local(true);
+        global(self, i64_t.into(), "aha_depth").set_thread_local(true);
+        // Each thread unwinds to its own `aha_run` or `aha_actor_run`.
+        let jmp_buf = global(self, i64_t.array_type(64).into(), "aha_jmp_buf");
+        jmp_buf.set_alignment(16);
+        jmp_buf.set_thread_local(true);
+        global(self, i64_t.into(), "aha_jmp_armed").set_thread_local(true);
+        global(self, self.string_type.into(), "aha_panic_message").set_thread_local(true);
+        global(self, i64_t.into(), "aha_panic_line").set_thread_local(true);
+        global(self, i64_t.into(), "aha_panic_quiet");
+
+        let dprintf_fn = self.module.add_function("dprintf", i32_t.fn_type(&[i32_t.into(), i8_ptr.into()], true), None);
+        let exit_fn = self.module.add_function("exit", void_t.fn_type(&[i32_t.into()], false), None);
+        exit_fn.add_attribute(AttributeLoc::Function, noreturn);
+        let longjmp_fn = self.module.add_function("longjmp", void_t.fn_type(&[i8_ptr.into(), i32_t.into()], false), None);
+        longjmp_fn.add_attribute(AttributeLoc::Function, noreturn);
+        let setjmp_fn = self.module.add_function("_setjmp", i32_t.fn_type(&[i8_ptr.into()], false), None);
+        let returns_twice = self.context.create_enum_attribute(Attribute::get_named_enum_kind_id("returns_twice"), 0);
+        setjmp_fn.add_attribute(AttributeLoc::Function, returns_twice);
+        let b = self.context.create_builder();
+
+        // aha_unwind(msg: i8*, len: i64, line: i64) -> void, never returns
+        let fn_type = void_t.fn_type(&[i8_ptr.into(), i64_t.into(), i64_t.into()], false);
+        let unwind_fn = self.module.add_function("aha_unwind", fn_type, None);
+        unwind_fn.add_attribute(AttributeLoc::Function, noreturn);
+        let entry = self.context.append_basic_block(unwind_fn, "entry");
+        let unwind_block = self.context.append_basic_block(unwind_fn, "unwind");
+        let exit_block = self.context.append_basic_block(unwind_fn, "exit");
+        let &[msg, len, line] = unwind_fn.get_params().as_slice() else {
+            return Err("aha_unwind takes three parameters".to_string());
+        };
+        b.position_at_end(entry);
+        let armed_ptr = self.module.get_global("aha_jmp_armed").ok_or("aha_jmp_armed not declared")?.as_pointer_value();
+        let armed = b.build_load(armed_ptr, "armed").map_err(|e| e.to_string())?.into_int_value();
+        let armed = b.build_int_compare(inkwell::IntPredicate::NE, armed, i64_t.const_zero(), "is_armed").map_err(|e| e.to_string())?;
+        b.build_conditional_branch(armed, unwind_block, exit_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(unwind_block);
+        let message_ptr = self.module.get_global("aha_panic_message").ok_or("aha_panic_message not declared")?.as_pointer_value();
+        let message = self.string_type.get_undef();
+        let message = b.build_insert_value(message, msg, 0, "message").map_err(|e| e.to_string())?;
+        let message = b.build_insert_value(message, len, 1, "message").map_err(|e| e.to_string())?;
+        b.build_store(message_ptr, message).map_err(|e| e.to_string())?;
+        let line_ptr = self.module.get_global("aha_panic_line").ok_or("aha_panic_line not declared")?.as_pointer_value();
+        b.build_store(line_ptr, line).map_err(|e| e.to_string())?;
+        let jmp_buf = self.module.get_global("aha_jmp_buf").ok_or("aha_jmp_buf not declared")?.as_pointer_value();
+        let jmp_buf = b.build_pointer_cast(jmp_buf, i8_ptr, "jmp_buf").map_err(|e| e.to_string())?;
+        b.build_call(longjmp_fn, &[jmp_buf.into(), i32_t.const_int(1, false).into()], "").map_err(|e| e.to_string())?;
+        b.build_unreachable().map_err(|e| e.to_string())?;
+
+        b.position_at_end(exit_block);
+        b.build_call(exit_fn, &[i32_t.const_int(PANIC_EXIT_CODE as u64, false).into()], "").map_err(|e| e.to_string())?;
+        b.build_unreachable().map_err(|e| e.to_string())?;
+
+        // aha_panic(msg: i8*, len: i64, line: i64) -> void, never returns
+        let function = self.module.add_function("aha_panic", fn_type, None);
+        function.add_attribute(AttributeLoc::Function, noreturn);
+        let entry = self.context.append_basic_block(function, "entry");
+        let print_block = self.context.append_basic_block(function, "print");
+        let loop_block = self.context.append_basic_block(function, "frames");
+        let frame_block = self.context.append_basic_block(function, "frame");
+        let done_block = self.context.append_basic_block(function, "done");
+        b.position_at_end(entry);
+        let &[msg, len, line] = function.get_params().as_slice() else {
+            return Err("aha_panic takes three parameters".to_string());
+        };
+        let (msg, len, line) = (msg.into_pointer_value(), len.into_int_value(), line.into_int_value());
+        // A quiet aha_run reports the panic itself.
+        let armed = b.build_load(armed_ptr, "armed").map_err(|e| e.to_string())?.into_int_value();
+        let armed = b.build_int_compare(inkwell::IntPredicate::NE, armed, i64_t.const_zero(), "is_armed").map_err(|e| e.to_string())?;
+        let quiet_ptr = self.module.get_global("aha_panic_quiet").ok_or("aha_panic_quiet not declared")?.as_pointer_value();
+        let quiet = b.build_load(quiet_ptr, "quiet").map_err(|e| e.to_string())?.into_int_value();
+        let quiet = b.build_int_compare(inkwell::IntPredicate::NE, quiet, i64_t.const_zero(), "is_quiet").map_err(|e| e.to_string())?;
+        let silent = b.build_and(armed, quiet, "silent").map_err(|e| e.to_string())?;
+        b.build_conditional_branch(silent, done_block, print_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(print_block);
+        let stderr = i32_t.const_int(2, false);
+        let header = b.build_global_string_ptr("panicked at line %lld: %.*s\nbacktrace:\n", "panic_fmt").map_err(|e| e.to_string())?;
+        let len32 = b.build_int_truncate(len, i32_t, "len32").map_err(|e| e.to_string())?;
+        b.build_call(dprintf_fn, &[stderr.into(), header.as_pointer_value().into(), line.into(), len32.into(), msg.into()], "").map_err(|e| e.to_string())?;
+        // Walk the shadow stack innermost first: frame k of n is aha_frames[n - 1 - k].
+        let frames = self.module.get_global("aha_frames").ok_or("aha_frames not declared")?.as_pointer_value();
+        let depth_ptr = self.module.get_global("aha_depth").ok_or("aha_depth not declared")?.as_pointer_value();
+        let depth = b.build_load(depth_ptr, "depth").map_err(|e| e.to_string())?.into_int_value();
+        let cap = i64_t.const_int(PANIC_FRAMES as u64, false);
+        let fits = b.build_int_compare(inkwell::IntPredicate::ULT, depth, cap, "fits").map_err(|e| e.to_string())?;
+        let count = b.build_select(fits, depth, cap, "count").map_err(|e| e.to_string())?.into_int_value();
+        let remaining = b.build_alloca(i64_t, "remaining").map_err(|e| e.to_string())?;
+        b.build_store(remaining, count).map_err(|e| e.to_string())?;
+        b.build_unconditional_branch(loop_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(loop_block);
+        let left = b.build_load(remaining, "left").map_err(|e| e.to_string())?.into_int_value();
+        let more = b.build_int_compare(inkwell::IntPredicate::NE, left, i64_t.const_zero(), "more").map_err(|e| e.to_string())?;
+        b.build_conditional_branch(more, frame_block, done_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(frame_block);
+        let index = b.build_int_sub(left, i64_t.const_int(1, false), "index").map_err(|e| e.to_string())?;
+        b.build_store(remaining, index).map_err(|e| e.to_string())?;
+        let slot = unsafe { b.build_in_bounds_gep(frames, &[i64_t.const_zero(), index], "slot").map_err(|e| e.to_string())? };
+        let name = b.build_load(slot, "name").map_err(|e| e.to_string())?;
+        let number = b.build_int_sub(count, left, "number").map_err(|e| e.to_string())?;
+        let frame_fmt = b.build_global_string_ptr("  %lld: %s\n", "frame_fmt").map_err(|e| e.to_string())?;
+        b.build_call(dprintf_fn, &[stderr.into(), frame_fmt.as_pointer_value().into(), number.into(), name.into()], "").map_err(|e| e.to_string())?;
+        b.build_unconditional_branch(loop_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(done_block);
+        b.build_call(unwind_fn, &[msg.into(), len.into(), line.into()], "").map_err(|e| e.to_string())?;
+        b.build_unreachable().map_err(|e| e.to_string())?;
+
+        // aha_bounds_fail(index: i64, len: i64, line: i64) -> void, never returns
+        let fn_type = void_t.fn_type(&[i64_t.into(), i64_t.into(), i64_t.into()], false);
+        let bounds_fn = self.module.add_function("aha_bounds_fail", fn_type, None);
+        bounds_fn.add_attribute(AttributeLoc::Function, noreturn);
+        let &[index, bound, bounds_line] = bounds_fn.get_params().as_slice() else {
+            return Err("aha_bounds_fail takes three parameters".to_string());
+        };
+        b.position_at_end(self.context.append_basic_block(bounds_fn, "entry"));
+        // The message outlives this frame when the panic unwinds to aha_run.
+        let malloc_fn = *self.functions.get("malloc").expect("malloc not declared");
+        let buf = b.build_call(malloc_fn, &[i64_t.const_int(96, false).into()], "buf").map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("runtime call returned void")?.into_pointer_value();
+        let fmt = b.build_global_string_ptr("index out of bounds: the index is %lld but the length is %lld", "bounds_fmt").map_err(|e| e.to_string())?;
+        let snprintf_fn = *self.functions.get("snprintf").expect("snprintf not declared");
+        let written = b.build_call(
+            snprintf_fn,
+            &[buf.into(), i64_t.const_int(96, false).into(), fmt.as_pointer_value().into(),
+              index.into(), bound.into()],
+            "written",
+        ).map_err(|e| e.to_string())?.try_as_basic_value().left().ok_or("runtime call returned void")?.into_int_value();
+        let written = b.build_int_s_extend(written, i64_t, "len").map_err(|e| e.to_string())?;
+        b.build_call(function, &[buf.into(), written.into(), bounds_line.into()], "").map_err(|e| e.to_string())?;
+        b.build_unreachable().map_err(|e| e.to_string())?;
+
+        self.functions.insert("aha_panic".to_string(), function);
+        self.functions.insert("aha_unwind".to_string(), unwind_fn);
+        Ok(())
+    }
+
+    /// `aha_run(out, entry, quiet) -> i64`, the JIT entry point: arm the
+    /// panic runtime for this thread and call `entry` (`main` or a test)
+    /// under `_setjmp`. When a panic unwinds here, `out` is filled with
+    /// {1, message, length, line}. `quiet` stops `aha_panic` printing.
+    /// `aha_actor_run(out, handler, state, msg) -> i64` does the same for
+    /// one message on an actor's thread.
+    fn create_run_entry(&mut self) -> Result<(), String> {
+        let i64_t = self.i64_type;
+        let record_type = self.panic_record_type();
+        let record_ptr = record_type.ptr_type(inkwell::AddressSpace::default());
+        let entry_type = i64_t.fn_type(&[], false).ptr_type(inkwell::AddressSpace::default());
+        let fn_type = i64_t.fn_type(&[record_ptr.into(), entry_type.into(), i64_t.into()], false);
+        let run = self.module.add_function("aha_run", fn_type, None);
+        self.build_guarded_call(run, |b| {
+            let quiet_ptr = self.module.get_global("aha_panic_quiet").expect("panic runtime not declared").as_pointer_value();
+            b.build_store(quiet_ptr, run.get_nth_param(2).unwrap()).map_err(|e| e.to_string())?;
+            let entry_fn = inkwell::values::CallableValue::try_from(run.get_nth_param(1).unwrap().into_pointer_value())
+                .map_err(|_| "aha_run entry is not a function pointer")?;
+            b.build_call(entry_fn, &[], "result").map_err(|e| e.to_string())?
+                .try_as_basic_value().left().ok_or_else(|| "entry returned void".to_string())
+        })?;
+
+        let fn_type = i64_t.fn_type(&[record_ptr.into(), i64_t.into(), i64_t.into(), i64_t.into()], false);
+        let actor_run = self.module.add_function("aha_actor_run", fn_type, None);
+        self.build_guarded_call(actor_run, |b| {
+            let handler_type = i64_t.fn_type(&[i64_t.into(), i64_t.into()], false).ptr_type(inkwell::AddressSpace::default());
+            let handler = b.build_int_to_ptr(actor_run.get_nth_param(1).unwrap().into_int_value(), handler_type, "handler")
+                .map_err(|e| e.to_string())?;
+            let handler = inkwell::values::CallableValue::try_from(handler)
+                .map_err(|_| "aha_actor_run handler is not a function pointer")?;
+            let args = [actor_run.get_nth_param(2).unwrap().into(), actor_run.get_nth_param(3).unwrap().into()];
+            b.build_call(handler, &args, "result").map_err(|e| e.to_string())?
+                .try_as_basic_value().left().ok_or_else(|| "handler returned void".to_string())
+        })
+    }
+
+    /// `{i64, i8*, i64, i64}`, laid out like `runtime::PanicRecord`.
+    fn panic_record_type(&self) -> StructType<'ctx> {
+        let i64_t = self.i64_type;
+        self.context.struct_type(&[i64_t.into(), self.i8_ptr_type().into(), i64_t.into(), i64_t.into()], false)
+    }
+
+    /// Body of `aha_run` and `aha_actor_run`: arm this thread's panic
+    /// runtime, then emit `call` under `_setjmp` and return its value. A
+    /// panic that unwinds here fills the record the first parameter
+    /// points to and returns 0.
+    fn build_guarded_call(
+        &self,
+        function: FunctionValue<'ctx>,
+        call: impl FnOnce(&Builder<'ctx>) -> Result<BasicValueEnum<'ctx>, String>,
+    ) -> Result<(), String> {
+        let i64_t = self.i64_type;
+        let record_type = self.panic_record_type();
+        let entry = self.context.append_basic_block(function, "entry");
+        let run_block = self.context.append_basic_block(function, "run");
+        let panicked_block = self.context.append_basic_block(function, "panicked");
+        let b = self.context.create_builder();
+        let global = |name: &str| self.module.get_global(name).expect("panic runtime not declared").as_pointer_value();
+        let out = function.get_nth_param(0).unwrap().into_pointer_value();
+
+        b.position_at_end(entry);
+        b.build_store(global("aha_depth"), i64_t.const_zero()).map_err(|e| e.to_string())?;
+        b.build_store(global("aha_jmp_armed"), i64_t.const_int(1, false)).map_err(|e| e.to_string())?;
+        let jmp_buf = b.build_pointer_cast(global("aha_jmp_buf"), self.i8_ptr_type(), "jmp_buf").map_err(|e| e.to_string())?;
+        let jumped = b.build_call(self.module.get_function("_setjmp").unwrap(), &[jmp_buf.into()], "jumped")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("_setjmp returned void")?
+            .into_int_value();
+        let first = b.build_int_compare(inkwell::IntPredicate::EQ, jumped, jumped.get_type().const_zero(), "first")
+            .map_err(|e| e.to_string())?;
+        b.build_conditional_branch(first, run_block, panicked_block).map_err(|e| e.to_string())?;
+
+        b.position_at_end(run_block);
+        let result = call(&b)?;
+        b.build_store(global("aha_jmp_armed"), i64_t.const_zero()).map_err(|e| e.to_string())?;
+        b.build_store(out, record_type.const_zero()).map_err(|e| e.to_string())?;
+        b.build_return(Some(&result)).map_err(|e| e.to_string())?;
+
+        b.position_at_end(panicked_block);
+        b.build_store(global("aha_jmp_armed"), i64_t.const_zero()).map_err(|e| e.to_string())?;
+        let message = b.build_load(global("aha_panic_message"), "message").map_err(|e| e.to_string())?.into_struct_value();
+        let line = b.build_load(global("aha_panic_line"), "line").map_err(|e| e.to_string())?;
+        let mut record = record_type.const_zero();
+        let fields: [BasicValueEnum<'ctx>; 4] = [
+            i64_t.const_int(PANIC_RAISED as u64, false).into(),
+            b.build_extract_value(message, 0, "msg").map_err(|e| e.to_string())?,
+            b.build_extract_value(message, 1, "len").map_err(|e| e.to_string())?,
+            line,
+        ];
+        for (i, field) in fields.into_iter().enumerate() {
+            record = b.build_insert_value(record, field, i as u32, "record")
+                .map_err(|e| e.to_string())?
+                .into_struct_value();
+        }
+        b.build_store(out, record).map_err(|e| e.to_string())?;
+        b.build_return(Some(&i64_t.const_zero())).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Give each test an `i64 ()` entry point, `aha_test.<name>`, that
+    /// `aha_run` can call whatever the test body evaluates to.
+    fn create_test_entries(&mut self) -> Result<(), String> {
+        let b = self.context.create_builder();
+        for name in &self.test_functions {
+            let test_fn = self.module.get_function(name)
+                .ok_or_else(|| format!("test fn '{}' was not compiled", name))?;
+            let entry = self.module.add_function(&format!("aha_test.{}", name), self.i64_type.fn_type(&[], false), None);
+            b.position_at_end(self.context.append_basic_block(entry, "entry"));
+            b.build_call(test_fn, &[], "").map_err(|e| e.to_string())?;
+            b.build_return(Some(&self.i64_type.const_zero())).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Give each benchmark an `i64 ()` entry point, `aha_bench.<name>`,
+    /// that calls it `aha_bench_iterations` times. The count is a global
+    /// the runner sets before each sample (`aha_bench.iterations` returns
+    /// its address), so one JIT serves every sample.
+    fn create_bench_entries(&mut self) -> Result<(), String> {
+        if self.bench_functions.is_empty() {
+            return Ok(());
+        }
+        let i64_t = self.i64_type;
+        let iterations = self.module.add_global(i64_t, None, "aha_bench_iterations");
+        iterations.set_initializer(&i64_t.const_zero());
+        let b = self.context.create_builder();
+        let address_fn = self.module.add_function("aha_bench.iterations", i64_t.ptr_type(inkwell::AddressSpace::default()).fn_type(&[], false), None);
+        b.position_at_end(self.context.append_basic_block(address_fn, "entry"));
+        b.build_return(Some(&iterations.as_pointer_value())).map_err(|e| e.to_string())?;
+        for name in &self.bench_functions {
+            let bench_fn = self.module.get_function(name)
+                .ok_or_else(|| format!("bench fn '{}' was not compiled", name))?;
+            let entry = self.module.add_function(&format!("aha_bench.{}", name), i64_t.fn_type(&[], false), None);
+            let start = self.context.append_basic_block(entry, "entry");
+            let body = self.context.append_basic_block(entry, "bench.body");
+            let done = self.context.append_basic_block(entry, "bench.done");
+            b.position_at_end(start);
+            let count = b.build_load(iterations.as_pointer_value(), "bench.count").map_err(|e| e.to_string())?.into_int_value();
+            let any = b.build_int_compare(inkwell::IntPredicate::SGT, count, i64_t.const_zero(), "bench.any").map_err(|e| e.to_string())?;
+            b.build_conditional_branch(any, body, done).map_err(|e| e.to_string())?;
+            b.position_at_end(body);
+            let i = b.build_phi(i64_t, "bench.i").map_err(|e| e.to_string())?;
+            b.build_call(bench_fn, &[], "").map_err(|e| e.to_string())?;
+            let next = b.build_int_add(i.as_basic_value().into_int_value(), i64_t.const_int(1, false), "bench.next").map_err(|e| e.to_string())?;
+            i.add_incoming(&[(&i64_t.const_zero(), start), (&next, body)]);
+            let more = b.build_int_compare(inkwell::IntPredicate::SLT, next, count, "bench.more").map_err(|e| e.to_string())?;
+            b.build_conditional_branch(more, body, done).map_err(|e| e.to_string())?;
+            b.position_at_end(done);
+            b.build_return(Some(&i64_t.const_zero())).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Keep the shadow stack `aha_panic` prints: each traced function
+    /// stores its name at `aha_frames[depth]` on entry and restores
+    /// `depth` before every `ret`. Frames past the buffer are counted but
+    /// not recorded.
+    fn instrument_backtraces(&mut self) -> Result<(), String> {
+        let frames = self.module.get_global("aha_frames").ok_or("panic runtime not declared")?.as_pointer_value();
+        let depth_ptr = self.module.get_global("aha_depth").ok_or("panic runtime not declared")?.as_pointer_value();
+        let i64_t = self.i64_type;
+        let b = self.context.create_builder();
+        for (function, name) in std::mem::take(&mut self.traced_functions) {
+            let Some(entry) = function.get_first_basic_block() else { continue };
+            match entry.get_first_instruction() {
+                Some(first) => b.position_before(&first),
+                None => b.position_at_end(entry),
+            }
+            let saved = b.build_load(depth_ptr, "frame.depth").map_err(|e| e.to_string())?.into_int_value();
+            let cap = i64_t.const_int(PANIC_FRAMES as u64, false);
+            let fits = b.build_int_compare(inkwell::IntPredicate::ULT, saved, cap, "frame.fits").map_err(|e| e.to_string())?;
+            let last = i64_t.const_int(PANIC_FRAMES as u64 - 1, false);
+            let index = b.build_select(fits, saved, last, "frame.index").map_err(|e| e.to_string())?.into_int_value();
+            let slot = unsafe { b.build_in_bounds_gep(frames, &[i64_t.const_zero(), index], "frame.slot").map_err(|e| e.to_string())? };
+            let name_ptr = b.build_global_string_ptr(&name, "frame.name").map_err(|e| e.to_string())?;
+            b.build_store(slot, name_ptr.as_pointer_value()).map_err(|e| e.to_string())?;
+            let next = b.build_int_add(saved, i64_t.const_int(1, false), "frame.next").map_err(|e| e.to_string())?;
+            b.build_store(depth_ptr, next).map_err(|e| e.to_string())?;
+            for block in function.get_basic_blocks() {
+                let Some(ret) = block.get_terminator() else { continue };
+                if ret.get_opcode() == inkwell::values::InstructionOpcode::Return {
+                    b.position_before(&ret);
+                    b.build_store(depth_ptr, saved).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit a call to `aha_panic` with a constant message, ending the
+    /// current block.
+    fn build_panic(&mut self, message: &str, line: usize) -> Result<(), String> {
+        let msg = self.builder.build_global_string_ptr(message, "panic_msg").map_err(|e| e.to_string())?;
+        let len = self.i64_type.const_int(message.len() as u64, false);
+        self.build_panic_value(msg.as_pointer_value(), len, line)
+    }
+
+    fn build_panic_value(
+        &mut self,
+        msg: PointerValue<'ctx>,
+        len: inkwell::values::IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), String> {
+        let panic_fn = *self.functions.get("aha_panic").expect("panic runtime not declared");
+        let line_val = self.i64_type.const_int(line as u64, false);
+        self.builder.build_call(panic_fn, &[msg.into(), len.into(), line_val.into()], "")
+            .map_err(|e| e.to_string())?;
+        self.builder.build_unreachable().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Panic with `message` unless `ok` holds; code after this runs on
+    /// the success path.
+    fn build_panic_unless(&mut self, ok: inkwell::values::IntValue<'ctx>, message: &str, line: usize) -> Result<(), String> {
+        let function = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("panic check outside function")?;
+        let ok_block = self.context.append_basic_block(function, "check.ok");
+        let fail_block = self.context.append_basic_block(function, "check.fail");
+        self.builder.build_conditional_branch(ok, ok_block, fail_block).map_err(|e| e.to_string())?;
+        self.builder.position_at_end(fail_block);
+        self.build_panic(message, line)?;
+        self.builder.position_at_end(ok_block);
+        Ok(())
+    }
+
+    /// Panic before an integer `/` or `%` (`op`) that would trap: a zero
+    /// divisor, or `Int` minimum divided by -1. A constant divisor other
+    /// than 0 and -1 needs no check.
+    fn build_divisor_check(
+        &mut self,
+        dividend: inkwell::values::IntValue<'ctx>,
+        divisor: inkwell::values::IntValue<'ctx>,
+        op: &str,
+        line: usize,
+    ) -> Result<(), String> {
+        let constant = divisor.get_sign_extended_constant();
+        let (zero_message, overflow_message) = if op == "/" {
+            ("attempt to divide by zero", "attempt to divide with overflow")
+        } else {
+            ("attempt to calculate the remainder with a divisor of zero", "attempt to calculate the remainder with overflow")
+        };
+        if constant.is_none_or(|d| d == 0) {
+            let nonzero = self.builder
+                .build_int_compare(inkwell::IntPredicate::NE, divisor, divisor.get_type().const_zero(), "nonzero")
+                .map_err(|e| e.to_string())?;
+            self.build_panic_unless(nonzero, zero_message, line)?;
+        }
+        if constant.is_none_or(|d| d == -1) {
+            let int_type = divisor.get_type();
+            let is_min = self.builder
+                .build_int_compare(inkwell::IntPredicate::EQ, dividend, int_type.const_int(i64::MIN as u64, false), "is_min")
+                .map_err(|e| e.to_string())?;
+            let is_neg_one = self.builder
+                .build_int_compare(inkwell::IntPredicate::EQ, divisor, int_type.const_all_ones(), "is_neg_one")
+                .map_err(|e| e.to_string())?;
+            let overflows = self.builder.build_and(is_min, is_neg_one, "div_overflow").map_err(|e| e.to_string())?;
+            let ok = self.builder.build_not(overflows, "no_div_overflow").map_err(|e| e.to_string())?;
+            self.build_panic_unless(ok, overflow_message, line)?;
+        }
+        Ok(())
+    }
+
+    /// Call an `llvm.*.with.overflow` intrinsic on two i64s: the wrapped
+    /// result and whether it overflowed.
+    fn build_overflow_op(
+        &mut self,
+        intrinsic: &str,
+        lhs: inkwell::values::IntValue<'ctx>,
+        rhs: inkwell::values::IntValue<'ctx>,
+    ) -> Result<(inkwell::values::IntValue<'ctx>, inkwell::values::IntValue<'ctx>), String> {
+        let function = Intrinsic::find(intrinsic)
+            .and_then(|i| i.get_declaration(&self.module, &[self.i64_type.into()]))
+            .ok_or_else(|| format!("LLVM intrinsic {} not available", intrinsic))?;
+        let pair = self.builder.build_call(function, &[lhs.into(), rhs.into()], "ovf")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value()
+            .left()
+            .ok_or("overflow intrinsic returned void")?
+            .into_struct_value();
+        let value = self.builder.build_extract_value(pair, 0, "ovf.value").map_err(|e| e.to_string())?;
+        let overflowed = self.builder.build_extract_value(pair, 1, "ovf.flag").map_err(|e| e.to_string())?;
+        Ok((value.into_int_value(), overflowed.into_int_value()))
+    }
+
+    /// Has the current block already ended, in a `panic` or `return`?
+    fn block_terminated(&self) -> bool {
+        self.builder.get_insert_block().is_some_and(|b| b.get_terminator().is_some())
+    }
+
     fn compile_statement(&mut self, statement: &ast::Statement) -> Result<(), String> {
         match statement {
             ast::Statement::Let(let_stmt) => {
-                let typed_val = self.compile_expression(&let_stmt.value)?;
+                // The annotation is the value's expected type, so
+                // `let o: Option<bool> = None()` builds an Option<Bool>.
+                let hint_type = match &let_stmt.type_annotation {
+                    Some(hint) => {
+                        self.instantiate_hint_generics(hint)?;
+                        Some(self.resolve_hint_type(hint))
+                    }
+                    None => None,
+                };
+                let mut typed_val = self.compile_owned(&let_stmt.value, hint_type.clone())?;
+                self.mark_moved(&let_stmt.value, &typed_val.aha_type);
+                let borrowed = self.mentions_borrowed_boxes(&let_stmt.value);
                 // Determine allocation type: prefer explicit annotation,
                 // then fall back to inferred type from the expression.
-                let alloc_type = if let Some(ref hint) = let_stmt.type_annotation {
-                    let hint_type = AhaType::from_hint(hint)
-                        .unwrap_or(AhaType::Int);
-                    // If the struct_defs or enum_defs registry has a matching name.
-                    let hint_type = if self.struct_defs.contains_key(hint) {
-                        AhaType::Struct(hint.clone())
-                    } else if self.enum_defs.contains_key(hint) {
-                        AhaType::Enum(hint.clone())
-                    } else {
-                        hint_type
-                    };
+                let alloc_type = if let (Some(hint), Some(hint_type)) = (&let_stmt.type_annotation, hint_type) {
                     // Type-check: annotation must match the inferred type.
                     // Struct("Point") vs Struct("Point") or Enum("Color") vs Enum("Color") is compatible.
                     let compatible = match (&hint_type, &typed_val.aha_type) {
                         (AhaType::Struct(a), AhaType::Struct(b)) => a == b,
                         (AhaType::Enum(a), AhaType::Enum(b)) => a == b,
+                        // A fresh list_new()/map_new() holds 8-byte slots; an
+                        // annotation can type them as boxed tuples.
+                        (AhaType::List(h), AhaType::List(v)) if matches!(**h, AhaType::Tuple(_)) && v.is_int() => true,
+                        (AhaType::Map(hk, hv), AhaType::Map(vk, vv))
+                            if hk == vk && matches!(**hv, AhaType::Tuple(_)) && vv.is_int() => true,
                         _ => hint_type == typed_val.aha_type,
                     };
                     if !compatible {
@@ -3185,7 +3587,11 @@ impl<'ctx> CodeGenerator<'ctx> {
                             let_stmt.name.value, hint, typed_val.aha_type
                         ));
                     }
-                    self.aha_type_to_llvm_type(&hint_type)?
+                    let llvm_type = self.aha_type_to_llvm_type(&hint_type)?;
+                    if matches!(hint_type, AhaType::List(_) | AhaType::Map(_, _)) {
+                        typed_val.aha_type = hint_type;
+                    }
+                    llvm_type
                 } else {
                     self.aha_type_to_llvm_type(&typed_val.aha_type)?
                 };
@@ -3194,12 +3600,21 @@ impl<'ctx> CodeGenerator<'ctx> {
                 self.builder.build_store(pointer, typed_val.value)
                     .map_err(|e| e.to_string())?;
                 self.insert_variable(let_stmt.name.value.clone(), pointer, typed_val.aha_type);
+                if borrowed {
+                    self.mark_param(&let_stmt.name.value);
+                }
             },
             ast::Statement::Expression(expr_stmt) => {
                 self.compile_expression(&expr_stmt.expression)?;
             },
             ast::Statement::Return(ret_stmt) => {
-                let typed_val = self.compile_expression(&ret_stmt.return_value)?;
+                let return_type = self.current_function
+                    .and_then(|f| f.get_name().to_str().ok().and_then(|n| self.fn_types.get(n)).cloned());
+                let typed_val = self.compile_owned(&ret_stmt.return_value, return_type.clone())?;
+                if let (Some(function), Some(return_type)) = (self.current_function, &return_type) {
+                    let name = function.get_name().to_string_lossy().into_owned();
+                    self.check_return_value(&name, return_type, &typed_val, ret_stmt.line)?;
+                }
                 if self.has_heap_locals() {
                     let escaped = Self::find_heap_vars_in_expr(&ret_stmt.return_value);
                     self.insert_cleanup_inline(&escaped);
@@ -3219,10 +3634,53 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Statement::Enum(_) => {
                 // Enum definitions are compile-time metadata
             }
+            ast::Statement::Const(_) | ast::Statement::Static(_) => {
+                // Evaluated up front: constants are inlined at each use,
+                // statics emitted as globals by `emit_statics`
+            }
+            ast::Statement::TypeAlias(_) | ast::Statement::Newtype(_) => {
+                // Desugared by `typedefs::desugar` before compilation
+            }
+            ast::Statement::UseVariants(_) => {
+                // Read by `register_enums` to resolve bare variant names
+            }
+            ast::Statement::LetPattern(let_pat) => {
+                let typed_val = self.compile_expression(&let_pat.value)?;
+                if !self.pattern_is_catch_all(&let_pat.pattern, &typed_val.aha_type) {
+                    return Err(format!(
+                        "Refutable pattern {} in let: use match instead",
+                        Self::describe_pattern(&let_pat.pattern)
+                    ));
+                }
+                self.bind_pattern(&let_pat.pattern, &typed_val)?;
+            }
         }
         Ok(())
     }
 
+    /// A function's value must have its LLVM return type. Typeck reports
+    /// the mismatches it can type; this catches the rest (a `-> string`
+    /// function ending in a loop) before LLVM verification does.
+    fn check_return_value(&self, func_name: &str, return_type: &AhaType, value: &TypedValue<'ctx>, line: usize) -> Result<(), String> {
+        let declared = self.current_function.and_then(|f| f.get_type().get_return_type());
+        if declared.is_none_or(|t| t == value.value.get_type()) {
+            return Ok(());
+        }
+        Err(format!(
+            "line {}: Function '{}' returns {}, but its return type is {}",
+            line, func_name, value.aha_type, return_type
+        ))
+    }
+
+    /// Compile `expression` with `expected` as its contextual type,
+    /// restoring the enclosing one afterwards.
+    fn compile_expecting(&mut self, expression: &ast::Expression, expected: Option<AhaType>) -> Result<TypedValue<'ctx>, String> {
+        let saved = std::mem::replace(&mut self.expected_type, expected);
+        let result = self.compile_expression(expression);
+        self.expected_type = saved;
+        result
+    }
+
     fn compile_expression(&mut self, expression: &ast::Expression) -> Result<TypedValue<'ctx>, String> {
         match expression {
             ast::Expression::Integer(int_lit) => {
@@ -3236,6 +3694,12 @@ impl<'ctx> CodeGenerator<'ctx> {
                     let loaded = self.builder.build_load(ptr, &ident.value)
                         .map_err(|e| e.to_string())?;
                     Ok(TypedValue::new(loaded, var_type))
+                } else if let Some((ptr, var_type)) = self.statics.get(&ident.value).cloned() {
+                    let loaded = self.builder.build_load(ptr, &ident.value)
+                        .map_err(|e| e.to_string())?;
+                    Ok(TypedValue::new(loaded, var_type))
+                } else if let Some(value) = self.consts.get(&ident.value) {
+                    self.compile_expression(&value.to_literal())
                 } else {
                     Err(format!("Variable '{}' not found", ident.value))
                 }
@@ -3243,6 +3707,8 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Expression::Infix(infix) => self.compile_infix(infix),
             ast::Expression::If(if_expr) => self.compile_if_expression(if_expr),
             ast::Expression::While(while_expr) => self.compile_while_expression(while_expr),
+            ast::Expression::IfLet(if_let) => self.compile_if_let_expression(if_let),
+            ast::Expression::WhileLet(while_let) => self.compile_while_let_expression(while_let),
             ast::Expression::For(for_expr) => self.compile_for_expression(for_expr),
             ast::Expression::Boolean(bool_lit) => {
                 let val = if bool_lit.value { 1 } else { 0 };
@@ -3253,12 +3719,23 @@ impl<'ctx> CodeGenerator<'ctx> {
             ast::Expression::Function(func_lit) => self.compile_function(func_lit),
             ast::Expression::Call(call_expr) => self.compile_call(call_expr),
             ast::Expression::Array(arr_lit) => self.compile_array_literal(arr_lit),
+            ast::Expression::Tuple(tuple) => self.compile_tuple_literal(tuple),
             ast::Expression::Index(idx_expr) => self.compile_index_expression(idx_expr),
             ast::Expression::Range(range_expr) => self.compile_range_expression(range_expr),
             ast::Expression::Assignment(assign) => self.compile_assignment(assign),
             ast::Expression::StructLiteral(struct_lit) => self.compile_struct_literal(struct_lit),
             ast::Expression::FieldAccess(field_access) => self.compile_field_access(field_access),
+            ast::Expression::Cast(cast) => self.compile_cast(cast),
             ast::Expression::ModuleAccess(ma) => {
+                // Enum::Variant — a unit variant
+                if let Some((enum_name, variant)) = self.variant_callee(expression) {
+                    let call = ast::CallExpression {
+                        function: Box::new(expression.clone()),
+                        arguments: Vec::new(),
+                        line: ma.line,
+                    };
+                    return self.compile_enum_constructor(&enum_name, &variant, &call);
+                }
                 // module::name — resolve to the flat function/variable name
                 // (compiler already merged pub items into global scope)
                 self.compile_expression(&ast::Expression::Identifier(
@@ -3289,14 +3766,24 @@ impl<'ctx> CodeGenerator<'ctx> {
                     Some(fields) => fields.iter().map(|(n, _)| n.clone()).collect(),
                     None => return Err(format!("Unknown actor type: {}", struct_name)),
                 };
+                let omitted = self.omitted_fields(struct_name, &spawn_expr.fields)?;
+                let missing: Vec<String> = omitted.into_iter()
+                    .filter(|f| self.field_default(struct_name, f).is_none())
+                    .collect();
+                if !missing.is_empty() {
+                    return Err(Self::missing_fields_error(struct_name, &missing));
+                }
 
                 let mut field_values = Vec::new();
                 for field_name in &field_names {
-                    let val = spawn_expr.fields.iter()
-                        .find(|(k, _)| &k.value == field_name)
-                        .map(|(_, v)| self.compile_expression(v))
-                        .transpose()?
-                        .unwrap_or_else(|| TypedValue::int(self.i64_type.const_int(0, false).into()));
+                    let val = match spawn_expr.fields.iter().find(|(k, _)| &k.value == field_name) {
+                        Some((_, v)) => self.compile_expression(v)?,
+                        None => {
+                            let default = self.field_default(struct_name, field_name)
+                                .expect("omitted actor fields have defaults");
+                            self.compile_expression(&default)?
+                        }
+                    };
                     field_values.push(val);
                 }
 
@@ -3337,9 +3824,18 @@ impl<'ctx> CodeGenerator<'ctx> {
                     "handler_ptr",
                 ).expect("ptr_to_int failed");
 
-                // Call actor_spawn(handler_ptr, state_ptr) -> handle.
+                // The actor thread runs each message through aha_actor_run,
+                // which catches a panic in the handler.
+                let run_fn = self.module.get_function("aha_actor_run").ok_or("aha_actor_run not declared")?;
+                let run_ptr = self.builder.build_ptr_to_int(
+                    run_fn.as_global_value().as_pointer_value(),
+                    self.i64_type,
+                    "actor_run_ptr",
+                ).map_err(|e| e.to_string())?;
+
+                // Call actor_spawn(handler_ptr, state_ptr, run_ptr) -> handle.
                 let actor_spawn_fn = *self.functions.get("actor_spawn").expect("actor_spawn not declared");
-                let args_meta: Vec<BasicMetadataValueEnum> = vec![handler_ptr.into(), state_ptr.into()];
+                let args_meta: Vec<BasicMetadataValueEnum> = vec![handler_ptr.into(), state_ptr.into(), run_ptr.into()];
                 let call_result = self.builder.build_call(actor_spawn_fn, &args_meta, "actor_handle")
                     .map_err(|e| e.to_string())?;
                 let handle = call_result.try_as_basic_value()
@@ -3371,71 +3867,272 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::string(str_struct.into()))
     }
 
-    fn compile_array_literal(&mut self, arr: &ast::ArrayLiteral) -> Result<TypedValue<'ctx>, String> {
-        let array_size = arr.elements.len() as u32;
-        let array_type = self.i64_type.array_type(array_size);
-        let array_ptr = self.builder.build_alloca(array_type, "arr")
+    /// Emit each `static` as an LLVM global holding its compile-time
+    /// initial value. Immutable statics are marked constant; `static mut`
+    /// globals are only touched from the main thread (typeck rejects them
+    /// in code reachable from actor handlers). A `String` static starts
+    /// out pointing at the constant `static.<name>.str`; see
+    /// `store_string_static` for the buffers it owns after that.
+    fn emit_statics(&mut self, statements: &[ast::Statement], values: &HashMap<String, ConstValue>) {
+        for stmt in statements {
+            let ast::Statement::Static(def) = stmt else { continue };
+            let name = &def.name.value;
+            let Some(value) = values.get(name) else { continue };
+            let initializer: BasicValueEnum<'ctx> = match value {
+                ConstValue::Int(n) => self.i64_type.const_int(*n as u64, false).into(),
+                ConstValue::Bool(b) => self.i64_type.const_int(*b as u64, false).into(),
+                ConstValue::Str(s) => {
+                    let bytes = self.context.const_string(s.as_bytes(), true);
+                    let data = self.module.add_global(bytes.get_type(), None, &format!("static.{}.str", name));
+                    data.set_initializer(&bytes);
+                    data.set_constant(true);
+                    data.set_linkage(inkwell::module::Linkage::Private);
+                    let ptr = data.as_pointer_value()
+                        .const_cast(self.context.i8_type().ptr_type(inkwell::AddressSpace::default()));
+                    let len = self.i64_type.const_int(s.len() as u64, false);
+                    self.string_type.const_named_struct(&[ptr.into(), len.into()]).into()
+                }
+            };
+            let global = self.module.add_global(initializer.get_type(), None, &format!("static.{}", name));
+            global.set_initializer(&initializer);
+            global.set_constant(!def.mutable);
+            self.statics.insert(name.clone(), (global.as_pointer_value(), value.aha_type()));
+        }
+    }
+
+    /// Assign a `String` static. The static owns its buffer: the new
+    /// value is copied to the heap unless it is a fresh concatenation,
+    /// and the previous buffer is freed unless it is still the constant
+    /// initial value.
+    fn store_string_static(
+        &mut self,
+        name: &str,
+        ptr: PointerValue<'ctx>,
+        value: TypedValue<'ctx>,
+        source: &ast::Expression,
+    ) -> Result<TypedValue<'ctx>, String> {
+        let fresh = matches!(source, ast::Expression::Infix(infix) if infix.operator == "+");
+        let owned = if fresh { value } else { self.copy_string(&value)? };
+        let old = self.builder.build_load(ptr, "static_old").map_err(|e| e.to_string())?;
+        let old_ptr = self.extract_str_ptr(&TypedValue::string(old))?;
+        let initial = self.module.get_global(&format!("static.{}.str", name))
+            .ok_or_else(|| format!("Static '{}' has no initial string", name))?
+            .as_pointer_value()
+            .const_cast(self.i8_ptr_type());
+        let is_initial = self.builder.build_int_compare(inkwell::IntPredicate::EQ, old_ptr, initial, "static_is_initial")
+            .map_err(|e| e.to_string())?;
+        // free(NULL) is a no-op, so the constant is skipped without a branch.
+        let to_free = self.builder.build_select(is_initial, self.i8_ptr_type().const_null(), old_ptr, "static_to_free")
             .map_err(|e| e.to_string())?;
+        let free_fn = *self.functions.get("free").expect("free not declared");
+        self.builder.build_call(free_fn, &[to_free.into()], "").map_err(|e| e.to_string())?;
+        self.builder.build_store(ptr, owned.value).map_err(|e| e.to_string())?;
+        Ok(owned)
+    }
+
+    /// Compile a value for a place that outlives the expression: a
+    /// binding, a return value, a call argument, a field or payload, or a
+    /// container element. A `String` static frees its buffer when
+    /// reassigned, so a plain read of one is copied.
+    fn compile_owned(&mut self, expr: &ast::Expression, expected: Option<AhaType>) -> Result<TypedValue<'ctx>, String> {
+        let value = self.compile_expecting(expr, expected)?;
+        self.own_static_read(expr, value)
+    }
+
+    /// `value`, compiled from `expr`, copied if `expr` reads a `String`
+    /// static; see `compile_owned`.
+    fn own_static_read(&mut self, expr: &ast::Expression, value: TypedValue<'ctx>) -> Result<TypedValue<'ctx>, String> {
+        let ast::Expression::Identifier(id) = expr else { return Ok(value) };
+        if self.lookup_variable(&id.value).is_none() && self.statics.get(&id.value).is_some_and(|(_, t)| t.is_string()) {
+            return self.copy_string(&value);
+        }
+        Ok(value)
+    }
+
+    /// Compile `[a, b, ...]` into a by-value `[N x T]`. The element type
+    /// comes from the expected array type, else from the first element.
+    fn compile_array_literal(&mut self, arr: &ast::ArrayLiteral) -> Result<TypedValue<'ctx>, String> {
+        let mut elem_type = match &self.expected_type {
+            Some(AhaType::Array(elem, _)) => Some((**elem).clone()),
+            _ => None,
+        };
+        let mut values = Vec::with_capacity(arr.elements.len());
         for (i, elem) in arr.elements.iter().enumerate() {
-            let value = self.compile_expression(elem)?;
-            let idx = self.i64_type.const_int(i as u64, false);
-            let zero = self.i64_type.const_int(0, false);
-            let elem_ptr = unsafe {
-                self.builder.build_gep(array_ptr, &[zero, idx], "elem_ptr")
-                    .map_err(|e| e.to_string())?
-            };
-            self.builder.build_store(elem_ptr, value.value)
-                .map_err(|e| e.to_string())?;
+            let tv = self.compile_owned(elem, elem_type.clone())?;
+            self.mark_moved(elem, &tv.aha_type);
+            let expected = elem_type.get_or_insert_with(|| tv.aha_type.clone());
+            if !Self::types_compatible(expected, &tv.aha_type) {
+                return Err(format!(
+                    "Array element {} is {}, but the array holds {}", i, tv.aha_type, expected
+                ));
+            }
+            values.push(tv.value);
+        }
+        let array_type = AhaType::Array(Box::new(elem_type.unwrap_or(AhaType::Int)), values.len());
+        let mut agg = self.aha_type_to_llvm_type(&array_type)?.into_array_type().get_undef();
+        for (i, value) in values.into_iter().enumerate() {
+            agg = self.builder.build_insert_value(agg, value, i as u32, "arr")
+                .map_err(|e| e.to_string())?
+                .into_array_value();
+        }
+        Ok(TypedValue::new(agg.into(), array_type))
+    }
+
+    /// The alloca of the local array variable `expr` names, so its
+    /// elements are addressed in place. `None` for any other expression.
+    fn array_variable_ptr(&self, expr: &ast::Expression) -> Option<PointerValue<'ctx>> {
+        let ast::Expression::Identifier(id) = expr else { return None };
+        let info = self.lookup_variable(&id.value)?;
+        matches!(info.var_type, AhaType::Array(..)).then_some(info.ptr)
+    }
+
+    /// Pointer to the storage of `array`, the value of `expr`: the
+    /// variable's own alloca when `expr` names a local array, otherwise
+    /// a stack slot the rvalue is spilled to.
+    fn array_storage(&mut self, expr: &ast::Expression, array: BasicValueEnum<'ctx>) -> Result<PointerValue<'ctx>, String> {
+        if let Some(ptr) = self.array_variable_ptr(expr) {
+            return Ok(ptr);
         }
-        let ptr_as_int = self.builder.build_ptr_to_int(array_ptr, self.i64_type, "arr_ptr")
+        let slot = self.entry_alloca(array.get_type(), "arr_slot")?;
+        self.builder.build_store(slot, array).map_err(|e| e.to_string())?;
+        Ok(slot)
+    }
+
+    /// Check `index` against an array's length `len` and return a
+    /// pointer to the element in `storage` (see `array_storage`).
+    fn array_element_ptr(
+        &mut self,
+        storage: PointerValue<'ctx>,
+        index: inkwell::values::IntValue<'ctx>,
+        len: usize,
+        line: usize,
+    ) -> Result<PointerValue<'ctx>, String> {
+        self.build_bounds_check(index, self.i64_type.const_int(len as u64, false), line)?;
+        let zero = self.i64_type.const_int(0, false);
+        unsafe { self.builder.build_in_bounds_gep(storage, &[zero, index], "elem_ptr") }
+            .map_err(|e| e.to_string())
+    }
+
+    /// Panic with "index out of bounds" unless `index < len`. The compare
+    /// is unsigned, so a negative index fails too.
+    fn build_bounds_check(
+        &mut self,
+        index: inkwell::values::IntValue<'ctx>,
+        len: inkwell::values::IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), String> {
+        let current_fn = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("index outside function")?;
+        let in_bounds = self.builder.build_int_compare(inkwell::IntPredicate::ULT, index, len, "in_bounds")
+            .map_err(|e| e.to_string())?;
+        let ok_block = self.context.append_basic_block(current_fn, "bounds.ok");
+        let fail_block = self.context.append_basic_block(current_fn, "bounds.fail");
+        self.builder.build_conditional_branch(in_bounds, ok_block, fail_block)
+            .map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(fail_block);
+        let fail_fn = self.module.get_function("aha_bounds_fail").ok_or("panic runtime not declared")?;
+        let line_val = self.i64_type.const_int(line as u64, false);
+        self.builder.build_call(fail_fn, &[index.into(), len.into(), line_val.into()], "")
             .map_err(|e| e.to_string())?;
-        Ok(TypedValue::new(ptr_as_int.into(), AhaType::Array(Box::new(AhaType::Int))))
+        self.builder.build_unreachable().map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(ok_block);
+        Ok(())
+    }
+
+    /// An alloca in the current function's entry block, so a slot used
+    /// inside a loop is allocated once rather than on every iteration.
+    fn entry_alloca(&self, ty: inkwell::types::BasicTypeEnum<'ctx>, name: &str) -> Result<PointerValue<'ctx>, String> {
+        let function = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("alloca outside function")?;
+        let entry = function.get_first_basic_block().ok_or("function has no entry block")?;
+        let builder = self.context.create_builder();
+        match entry.get_first_instruction() {
+            Some(first) => builder.position_before(&first),
+            None => builder.position_at_end(entry),
+        }
+        builder.build_alloca(ty, name).map_err(|e| e.to_string())
+    }
+
+    /// Panic at `line` unless `index` is within the list's current length.
+    fn check_list_index(
+        &mut self,
+        list_handle: inkwell::values::IntValue<'ctx>,
+        index: inkwell::values::IntValue<'ctx>,
+        line: usize,
+    ) -> Result<(), String> {
+        let len_fn = *self.functions.get("list_len").expect("list_len not declared");
+        let len = self.builder.build_call(len_fn, &[list_handle.into()], "list.len")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value()
+            .left()
+            .ok_or("list_len returned void")?
+            .into_int_value();
+        self.build_bounds_check(index, len, line)
     }
 
     fn compile_index_expression(&mut self, idx: &ast::IndexExpression) -> Result<TypedValue<'ctx>, String> {
         let array_val = self.compile_expression(&idx.left)?;
         let index_val = self.compile_expression(&idx.index)?;
 
+        if let AhaType::Array(elem, len) = &array_val.aha_type {
+            let storage = self.array_storage(&idx.left, array_val.value)?;
+            let elem_ptr = self.array_element_ptr(storage, index_val.value.into_int_value(), *len, idx.line)?;
+            let elem_val = self.builder.build_load(elem_ptr, "elem_val")
+                .map_err(|e| e.to_string())?;
+            return Ok(TypedValue::new(elem_val, (**elem).clone()));
+        }
+
         // List<T> indexing: delegate to list_get/list_get_string builtin.
         if let AhaType::List(inner) = &array_val.aha_type {
             let list_handle = array_val.value.into_int_value();
-            let args_meta: Vec<_> = [
-                list_handle.into(),
-                index_val.value.into(),
-            ].iter().map(|a: &inkwell::values::BasicValueEnum| (*a).into()).collect();
-            let builtin = if inner.is_string() { "list_get_string" } else { "list_get" };
-            let function = *self.functions.get(builtin).expect("list builtin not declared");
-            let call_result = self.builder.build_call(function, &args_meta, "listidx")
-                .map_err(|e| e.to_string())?;
-            let val = call_result.try_as_basic_value()
-                .left()
-                .ok_or("list_get returned void")?;
-            let tv = if inner.is_string() {
-                TypedValue::string(val)
-            } else {
-                TypedValue::int(val)
-            };
-            return Ok(tv);
+            let index = index_val.value.into_int_value();
+            self.check_list_index(list_handle, index, idx.line)?;
+            return self.list_element(list_handle, index, inner);
         }
 
-        let array_ptr = self.builder.build_int_to_ptr(
-            array_val.value.into_int_value(),
-            self.i64_type.ptr_type(inkwell::AddressSpace::default()),
-            "arr_ptr_cast"
-        ).map_err(|e| e.to_string())?;
-        let elem_ptr = unsafe {
-            self.builder.build_gep(array_ptr, &[index_val.value.into_int_value()], "elem")
-                .map_err(|e| e.to_string())?
-        };
-        let elem_val = self.builder.build_load(elem_ptr, "elem_val")
-            .map_err(|e| e.to_string())?;
-        Ok(TypedValue::int(elem_val))
+        Err(format!("line {}: Cannot index into a value of type {}", idx.line, array_val.aha_type))
     }
 
-    /// Declare C runtime functions (malloc, strlen, memcpy, strcmp, sprintf, realloc, free)
-    fn declare_c_runtime(&mut self) {
-        let i8_ptr = self.i8_ptr_type();
-        let i64_t = self.i64_type;
+    /// What reading an element of a `List<inner>` (or a map value)
+    /// yields: strings and tuples as themselves, anything else as Int.
+    fn list_element_type(inner: &AhaType) -> AhaType {
+        match inner {
+            AhaType::String => AhaType::String,
+            AhaType::Tuple(_) => inner.clone(),
+            _ => AhaType::Int,
+        }
+    }
+
+    /// Read element `index` of a list through `list_get`/`list_get_string`,
+    /// without a bounds check.
+    fn list_element(
+        &mut self,
+        list_handle: inkwell::values::IntValue<'ctx>,
+        index: inkwell::values::IntValue<'ctx>,
+        inner: &AhaType,
+    ) -> Result<TypedValue<'ctx>, String> {
+        let builtin = if inner.is_string() { "list_get_string" } else { "list_get" };
+        let function = *self.functions.get(builtin).expect("list builtin not declared");
+        let call_result = self.builder.build_call(function, &[list_handle.into(), index.into()], "listidx")
+            .map_err(|e| e.to_string())?;
+        let val = call_result.try_as_basic_value()
+            .left()
+            .ok_or("list_get returned void")?;
+        match Self::list_element_type(inner) {
+            AhaType::String => Ok(TypedValue::string(val)),
+            AhaType::Tuple(_) => self.unbox_tuple(val, inner),
+            _ => Ok(TypedValue::int(val)),
+        }
+    }
+
+    /// Declare C runtime functions (malloc, strlen, memcpy, strcmp, sprintf, realloc, free)
+    fn declare_c_runtime(&mut self) {
+        let i8_ptr = self.i8_ptr_type();
+        let i64_t = self.i64_type;
         // malloc
         let malloc_ty = i8_ptr.fn_type(&[i64_t.into()], false);
         let malloc_fn = self.module.add_function("malloc", malloc_ty, None);
@@ -3504,16 +4201,17 @@ impl<'ctx> CodeGenerator<'ctx> {
     /// for Phase 2 threading. Mapped to actual Rust functions via add_global_mapping in run_jit.
     fn declare_actor_runtime(&mut self) {
         let i64_t = self.i64_type;
-        // actor_spawn(fn_ptr: i64, init_state: i64) -> i64
-        let spawn_ty = i64_t.fn_type(&[i64_t.into(), i64_t.into()], false);
+        // actor_spawn(fn_ptr: i64, init_state: i64, run: i64) -> i64
+        let spawn_ty = i64_t.fn_type(&[i64_t.into(), i64_t.into(), i64_t.into()], false);
         let spawn_fn = self.module.add_function("actor_spawn", spawn_ty, None);
         self.functions.insert("actor_spawn".to_string(), spawn_fn);
         // actor_send(handle: i64, msg: i64) -> void
         let send_ty = self.context.void_type().fn_type(&[i64_t.into(), i64_t.into()], false);
         let send_fn = self.module.add_function("actor_send", send_ty, None);
         self.functions.insert("actor_send".to_string(), send_fn);
-        // actor_call(handle: i64, msg: i64) -> i64
-        let call_ty = i64_t.fn_type(&[i64_t.into(), i64_t.into()], false);
+        // actor_call(handle: i64, msg: i64, out: PanicRecord*) -> i64
+        let record_ptr = self.panic_record_type().ptr_type(inkwell::AddressSpace::default());
+        let call_ty = i64_t.fn_type(&[i64_t.into(), i64_t.into(), record_ptr.into()], false);
         let call_fn = self.module.add_function("actor_call", call_ty, None);
         self.functions.insert("actor_call".to_string(), call_fn);
     }
@@ -3523,12 +4221,73 @@ impl<'ctx> CodeGenerator<'ctx> {
         let left = self.compile_expression(&infix.left)?;
         let right = self.compile_expression(&infix.right)?;
         let op = infix.operator.as_str();
+        if let Some(result) = self.compile_newtype_op(&left, op, &right, infix.line)? {
+            return Ok(result);
+        }
+        self.compile_binary_op(left, op, right, infix.line)
+    }
+
+    /// Operators on two values of the same newtype apply to the wrapped
+    /// values: arithmetic rewraps the result (`Meters + Meters` is
+    /// `Meters`), comparisons yield Int. Anything else (`Meters + int`,
+    /// `Meters + Seconds`) is left to `check_binary_op` to reject.
+    fn compile_newtype_op(
+        &mut self,
+        left: &TypedValue<'ctx>,
+        op: &str,
+        right: &TypedValue<'ctx>,
+        line: usize,
+    ) -> Result<Option<TypedValue<'ctx>>, String> {
+        let name = match (&left.aha_type, &right.aha_type) {
+            (AhaType::Struct(l), AhaType::Struct(r)) if l == r && self.newtypes.contains(l) => l.clone(),
+            _ => return Ok(None),
+        };
+        let base = self.field_type(&name, "0")?;
+        let unwrap = |cg: &mut Self, tv: &TypedValue<'ctx>| -> Result<TypedValue<'ctx>, String> {
+            let v = cg.builder
+                .build_extract_value(tv.value.into_struct_value(), 0, "newtype.val")
+                .map_err(|e| e.to_string())?;
+            Ok(TypedValue::new(v, base.clone()))
+        };
+        let l = unwrap(self, left)?;
+        let r = unwrap(self, right)?;
+        let result = self.compile_binary_op(l, op, r, line)?;
+        if !matches!(op, "+" | "-" | "*" | "/" | "%") {
+            return Ok(Some(result));
+        }
+        let struct_type = self.struct_llvm_type(&name)?;
+        let wrapped = self.builder
+            .build_insert_value(struct_type.const_zero(), result.value, 0, "newtype")
+            .map_err(|e| e.to_string())?
+            .into_struct_value();
+        Ok(Some(TypedValue::new(wrapped.into(), AhaType::Struct(name))))
+    }
 
+    /// Type-check and emit a binary operator on already-evaluated operands.
+    /// Integer `/` and `%` panic at `line` on a zero divisor.
+    fn compile_binary_op(
+        &mut self,
+        left: TypedValue<'ctx>,
+        op: &str,
+        right: TypedValue<'ctx>,
+        line: usize,
+    ) -> Result<TypedValue<'ctx>, String> {
         // Type check
         let result_type = left.aha_type.check_binary_op(op, &right.aha_type)?;
 
         match (&left.aha_type, op, &right.aha_type) {
             // Int arithmetic
+            (AhaType::Int, "+" | "-" | "*", AhaType::Int) if self.overflow_checks => {
+                let (intrinsic, message) = match op {
+                    "+" => ("llvm.sadd.with.overflow", "attempt to add with overflow"),
+                    "-" => ("llvm.ssub.with.overflow", "attempt to subtract with overflow"),
+                    _ => ("llvm.smul.with.overflow", "attempt to multiply with overflow"),
+                };
+                let (r, overflowed) = self.build_overflow_op(intrinsic, left.value.into_int_value(), right.value.into_int_value())?;
+                let ok = self.builder.build_not(overflowed, "no_overflow").map_err(|e| e.to_string())?;
+                self.build_panic_unless(ok, message, line)?;
+                Ok(TypedValue::int(r.into()))
+            },
             (AhaType::Int, "+", AhaType::Int) => {
                 let r = self.builder.build_int_add(left.value.into_int_value(), right.value.into_int_value(), "addtmp")
                     .map_err(|e| e.to_string())?;
@@ -3545,11 +4304,13 @@ impl<'ctx> CodeGenerator<'ctx> {
                 Ok(TypedValue::int(r.into()))
             },
             (AhaType::Int, "/", AhaType::Int) => {
+                self.build_divisor_check(left.value.into_int_value(), right.value.into_int_value(), op, line)?;
                 let r = self.builder.build_int_signed_div(left.value.into_int_value(), right.value.into_int_value(), "divtmp")
                     .map_err(|e| e.to_string())?;
                 Ok(TypedValue::int(r.into()))
             },
             (AhaType::Int, "%", AhaType::Int) => {
+                self.build_divisor_check(left.value.into_int_value(), right.value.into_int_value(), op, line)?;
                 let r = self.builder.build_int_signed_rem(left.value.into_int_value(), right.value.into_int_value(), "modtmp")
                     .map_err(|e| e.to_string())?;
                 Ok(TypedValue::int(r.into()))
@@ -3651,6 +4412,28 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::string(s.into()))
     }
 
+    /// Copy a string into a fresh, null-terminated heap buffer.
+    fn copy_string(&mut self, value: &TypedValue<'ctx>) -> Result<TypedValue<'ctx>, String> {
+        let src = self.extract_str_ptr(value)?;
+        let len = self.extract_str_len(value)?;
+        let alloc_size = self.builder.build_int_add(len, self.i64_type.const_int(1, false), "copy_sz")
+            .map_err(|e| e.to_string())?;
+        let malloc_fn = *self.functions.get("malloc").expect("malloc not declared");
+        let buf = self.builder.build_call(malloc_fn, &[alloc_size.into()], "copybuf")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("malloc returned void")?
+            .into_pointer_value();
+        let memcpy_fn = *self.functions.get("memcpy").expect("memcpy not declared");
+        self.builder.build_call(memcpy_fn, &[buf.into(), src.into(), len.into()], "cp").map_err(|e| e.to_string())?;
+        let null_pos = unsafe { self.builder.build_gep(buf, &[len], "nullpos").map_err(|e| e.to_string())? };
+        self.builder.build_store(null_pos, self.context.i8_type().const_int(0, false)).map_err(|e| e.to_string())?;
+        let s = self.builder.build_insert_value(self.string_type.const_zero(), buf, 0, "cptr")
+            .map_err(|e| e.to_string())?.into_struct_value();
+        let s = self.builder.build_insert_value(s, len, 1, "clen")
+            .map_err(|e| e.to_string())?.into_struct_value();
+        Ok(TypedValue::string(s.into()))
+    }
+
     /// Compile string comparison using strcmp
     fn compile_string_compare(&mut self, left: &TypedValue<'ctx>, right: &TypedValue<'ctx>, op: &str) -> Result<TypedValue<'ctx>, String> {
         let l_ptr = self.extract_str_ptr(left)?;
@@ -3701,21 +4484,15 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::int(ext.into()))
     }
 
-    /// Infer parameter types by scanning call expressions in the program.
-    /// If a call passes a string arg, that param is String. Otherwise Int.
+    /// Parameter types of a user function: its hints, else the types
+    /// typeck settled from its call sites, else Int.
     fn infer_param_types(&mut self, func_name: &str, params: &[ast::Identifier], hints: &[Option<String>]) -> Vec<AhaType> {
         let mut types = vec![AhaType::Int; params.len()];
         // Use type hints first (e.g. `d: Day` → Enum("Day"))
         for (i, hint) in hints.iter().enumerate() {
             if i < types.len() {
                 if let Some(h) = hint {
-                    types[i] = if self.enum_defs.contains_key(h.as_str()) {
-                        AhaType::Enum(h.clone())
-                    } else if self.struct_defs.contains_key(h.as_str()) {
-                        AhaType::Struct(h.clone())
-                    } else {
-                        AhaType::from_hint(h).unwrap_or(AhaType::Int)
-                    };
+                    types[i] = self.resolve_hint_type(h);
                 }
             }
         }
@@ -3733,6 +4510,15 @@ impl<'ctx> CodeGenerator<'ctx> {
         types
     }
 
+    /// Return type of a user function (or generic instance, with its type
+    /// params bound): its `-> T` hint, else what typeck settled, else Int.
+    fn function_return_type(&self, func: &ast::FunctionLiteral, name: &str) -> AhaType {
+        match &func.return_type_hint {
+            Some(hint) => self.resolve_hint_type(hint),
+            None => self.fn_types.get(name).cloned().unwrap_or(AhaType::Int),
+        }
+    }
+
     // Compile function definition — FIX C-05 (double return) and C-06 (variable restore safety)
     // Generic functions are compiled lazily via monomorphization; skip body compilation here.
     fn compile_function(&mut self, func: &ast::FunctionLiteral) -> Result<TypedValue<'ctx>, String> {
@@ -3743,64 +4529,13 @@ impl<'ctx> CodeGenerator<'ctx> {
             return Ok(TypedValue::void(self.i64_type.const_int(0, false).into()));
         }
 
+        self.instantiate_fn_hints(func)?;
+
         // Infer param types from call sites: scan all call expressions
         // in already-compiled code for this function name
         let param_aha_types = self.infer_param_types(&func_name, &func.parameters, &func.param_type_hints);
 
-        // Determine return type — reuse the pre-declared type if present
-        // (set by predeclare_functions), otherwise infer it now.
-        let return_type = self.fn_types.get(&func_name)
-            .cloned()
-            .unwrap_or_else(|| self.infer_function_return_type(func, &func_name));
-
-        // If a return type annotation is present, validate it against the
-        // body's inferred return type to catch mismatches early.
-        if let Some(ref hint) = func.return_type_hint {
-            let param_aha_types_imm = self.infer_param_types_immutable(&func_name, &func.parameters, &func.param_type_hints);
-            let scope: HashMap<String, AhaType> = func.parameters.iter().enumerate()
-                .map(|(i, p)| (p.value.clone(), param_aha_types_imm.get(i).cloned().unwrap_or(AhaType::Int)))
-                .collect();
-            let mut body_type = AhaType::Int;
-            for stmt in &func.body.statements {
-                if let ast::Statement::Return(ret) = stmt {
-                    body_type = self.infer_expr_type_with_scope(&ret.return_value, &scope);
-                    break;
-                }
-            }
-            if body_type == AhaType::Int {
-                for stmt in func.body.statements.iter().rev() {
-                    if let ast::Statement::Expression(expr_stmt) = stmt {
-                        body_type = self.infer_expr_type_with_scope(&expr_stmt.expression, &scope);
-                        break;
-                    }
-                }
-            }
-            let hint_type = if self.struct_defs.contains_key(hint.as_str()) {
-                AhaType::Struct(hint.clone())
-            } else if self.enum_defs.contains_key(hint.as_str()) {
-                AhaType::Enum(hint.clone())
-            } else {
-                AhaType::from_hint(hint).unwrap_or(AhaType::Int)
-            };
-            let compatible = match (&hint_type, &body_type) {
-                (AhaType::Struct(a), AhaType::Struct(b)) => a == b,
-                (AhaType::Enum(a), AhaType::Enum(b)) => a == b,
-                (AhaType::Int, t) if t.is_bool() => true, // Int and Bool are both i64
-                (t, AhaType::Int) if t.is_bool() => true,
-                // Body inferred as Int but hint is complex — trust the hint
-                // (infer_expr_type_with_scope can't track local variable types
-                // for Map/List; String literals ARE inferred correctly).
-                (AhaType::Map(_, _), AhaType::Int) => true,
-                (AhaType::List(_), AhaType::Int) => true,
-                _ => hint_type == body_type,
-            };
-            if !compatible {
-                return Err(format!(
-                    "Return type annotation '{}' does not match actual return type '{}' in function '{}'",
-                    hint, body_type, func_name
-                ));
-            }
-        }
+        let return_type = self.function_return_type(func, &func_name);
 
         // Reuse pre-declared function if it exists (for forward references)
         let function = if let Some(f) = self.functions.get(&func_name) {
@@ -3816,6 +4551,12 @@ impl<'ctx> CodeGenerator<'ctx> {
             function
         };
         self.fn_types.insert(func_name.clone(), return_type.clone());
+        self.traced_functions.push((function, func_name.clone()));
+        match func.kind {
+            ast::FunctionKind::Test => self.test_functions.push(func_name.clone()),
+            ast::FunctionKind::Bench => self.bench_functions.push(func_name.clone()),
+            ast::FunctionKind::Plain => {}
+        }
 
         let saved_block = self.builder.get_insert_block();
         let saved_scopes = std::mem::replace(&mut self.scopes, vec![HashMap::new()]);
@@ -3870,16 +4611,26 @@ impl<'ctx> CodeGenerator<'ctx> {
                 AhaType::Enum(name) => {
                     self.enum_llvm_type(name)?.const_zero().into()
                 }
+                AhaType::Tuple(elems) => {
+                    self.tuple_llvm_type(elems)?.const_zero().into()
+                }
+                AhaType::Array(_, _) => self.aha_type_to_llvm_type(&return_type)?.const_zero(),
                 _ => self.i64_type.const_int(0, false).into(),
             };
 
+            let tail_idx = func.body.statements.len().saturating_sub(1);
             for (stmt_idx, stmt) in func.body.statements.iter().enumerate() {
                 if let ast::Statement::Return(_) = stmt {
                     self.compile_statement(stmt)?;
                     has_return = true;
                     break;
                 } else if let ast::Statement::Expression(expr_stmt) = stmt {
-                    let tv = self.compile_expression(&expr_stmt.expression)?;
+                    // The tail expression is the return value.
+                    let expected = (stmt_idx == tail_idx).then(|| return_type.clone());
+                    let tv = self.compile_owned(&expr_stmt.expression, expected)?;
+                    if stmt_idx == tail_idx {
+                        self.check_return_value(&func_name, &return_type, &tv, func.line)?;
+                    }
                     last_value = tv.value;
                 } else {
                     self.compile_statement(stmt)?;
@@ -3892,7 +4643,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                 }
             }
 
-            if !has_return && self.has_heap_locals() {
+            if !has_return && self.block_terminated() {
+                // The body ends in a panic: nothing to return.
+            } else if !has_return && self.has_heap_locals() {
                 self.insert_cleanup_inline(&escaped);
                 self.builder.build_return(Some(&last_value))
                     .map_err(|e| e.to_string())?;
@@ -3917,6 +4670,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                 AhaType::String => self.string_type.const_zero().into(),
                 AhaType::Struct(name) => self.struct_llvm_type(name)?.const_zero().into(),
                 AhaType::Enum(name) => self.enum_llvm_type(name)?.const_zero().into(),
+                AhaType::Tuple(_) | AhaType::Array(_, _) => self.aha_type_to_llvm_type(&return_type)?.const_zero(),
                 _ => self.i64_type.const_int(0, false).into(),
             };
             self.builder.build_return(Some(&default_val)).map_err(|e| e.to_string())?;
@@ -3957,13 +4711,48 @@ impl<'ctx> CodeGenerator<'ctx> {
         if func_name == "send" || func_name == "call" {
             return self.compile_actor_call(&func_name, call);
         }
-        // Enum variant constructor: Variant(args...) or Variant
-        if let Some(enum_name) = self.find_enum_for_variant(&func_name) {
-            return self.compile_enum_constructor(&enum_name, &func_name, call);
+        // box(value): move a value to the heap → Box<T>
+        if func_name == "box" {
+            if call.arguments.len() != 1 {
+                return Err(format!("box() expects 1 argument, got {}", call.arguments.len()));
+            }
+            let expected = match &self.expected_type {
+                Some(AhaType::Box(inner)) => Some((**inner).clone()),
+                _ => None,
+            };
+            let value = self.compile_expecting(&call.arguments[0], expected)?;
+            self.mark_moved(&call.arguments[0], &value.aha_type);
+            return self.box_value(value);
+        }
+        // Enum variant constructor: Variant(args...) or Enum::Variant(args...)
+        if let Some((enum_name, variant)) = self.variant_callee(&call.function) {
+            return self.compile_enum_constructor(&enum_name, &variant, call);
+        }
+        if let Some(enum_name) = self.enum_from_int_callee(&call.function) {
+            return self.compile_enum_from_int(&enum_name, call);
         }
+        if func_name == "panic" || func_name == "unwrap" {
+            return self.compile_panic_call(&func_name, call);
+        }
+        if matches!(func_name.as_str(), "wrapping_add" | "saturating_add" | "checked_add") {
+            return self.compile_explicit_add(&func_name, call);
+        }
+        if matches!(func_name.as_str(), "assert" | "assert_eq" | "assert_ne") {
+            return self.compile_assert_call(&func_name, call);
+        }
+        // A user function may keep its arguments; builtins only read them.
+        let user_fn = self.param_type_map.contains_key(&func_name);
         let mut args: Vec<BasicValueEnum> = Vec::new();
         for arg in &call.arguments {
-            args.push(self.compile_expression(arg)?.value);
+            let mut tv = self.compile_expression(arg)?;
+            // `len(arr)` is the array's static length.
+            if let ("len", AhaType::Array(_, len)) = (func_name.as_str(), &tv.aha_type) {
+                return Ok(TypedValue::int(self.i64_type.const_int(*len as u64, false).into()));
+            }
+            if user_fn {
+                tv = self.own_static_read(arg, tv)?;
+            }
+            args.push(tv.value);
         }
         let args_meta: Vec<_> = args.iter().map(|a| (*a).into()).collect();
 
@@ -3983,9 +4772,510 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::new(val, ret_type))
     }
 
+    /// The variant `unwrap` accepts, and the type it yields, for an enum
+    /// with a one-field `Some` or `Ok` variant.
+    fn unwrap_variant(&self, wrapped: &AhaType) -> Option<(String, AhaType)> {
+        let AhaType::Enum(name) = wrapped else { return None };
+        self.enum_defs.get(name)?.iter()
+            .find(|(variant, payload)| (variant == "Some" || variant == "Ok") && payload.len() == 1)
+            .map(|(variant, payload)| (variant.clone(), payload[0].clone()))
+    }
+
+    /// `panic(message)` aborts with a String message. `unwrap(value)`
+    /// returns the payload of `Some(x)` / `Ok(x)` and panics on any other
+    /// variant.
+    fn compile_panic_call(&mut self, func_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
+        if call.arguments.len() != 1 {
+            return Err(format!("{}() expects 1 argument, got {}", func_name, call.arguments.len()));
+        }
+        let value = self.compile_expression(&call.arguments[0])?;
+        if func_name == "panic" {
+            if !value.aha_type.is_string() {
+                return Err(format!("line {}: `panic` needs a String message, got {}", call.line, value.aha_type));
+            }
+            let msg = self.extract_str_ptr(&value)?;
+            let len = self.extract_str_len(&value)?;
+            self.build_panic_value(msg, len, call.line)?;
+            // Never used: the block already ends in `unreachable`.
+            return Ok(TypedValue::int(self.i64_type.const_zero().into()));
+        }
+        let (AhaType::Enum(enum_name), Some((variant, _))) = (&value.aha_type, self.unwrap_variant(&value.aha_type)) else {
+            return Err(format!("line {}: `unwrap` needs an Option or Result, got {}", call.line, value.aha_type));
+        };
+        let (enum_name, variant) = (enum_name.clone(), variant.as_str());
+        let ok = self.compile_tag_test(&enum_name, variant, &value)?;
+        let message = if variant == "Some" {
+            "called `unwrap` on a `None` value"
+        } else {
+            "called `unwrap` on an `Err` value"
+        };
+        self.build_panic_unless(ok, message, call.line)?;
+        let mut payload = self.enum_payload_values(&enum_name, variant, 1, &value)?;
+        Ok(payload.remove(0))
+    }
+
+    /// `assert(cond)` panics unless `cond` holds. `assert_eq(left, right)`
+    /// and `assert_ne(left, right)` compare with `==` / `!=` (tuples and
+    /// enums element by element) and, on failure, panic with both values:
+    ///
+    /// ```text
+    /// assertion `left == right` failed
+    ///   left: 3
+    ///  right: 4
+    /// ```
+    fn compile_assert_call(&mut self, func_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
+        let arity = if func_name == "assert" { 1 } else { 2 };
+        if call.arguments.len() != arity {
+            return Err(format!("{}() expects {}, got {}", func_name, count_of(arity, "argument"), call.arguments.len()));
+        }
+        let done = TypedValue::void(self.i64_type.const_zero().into());
+        if func_name == "assert" {
+            let cond = self.compile_expression(&call.arguments[0])?;
+            if !matches!(cond.aha_type, AhaType::Int | AhaType::Bool) {
+                return Err(format!("line {}: `assert` needs an Int or Bool condition, got {}", call.line, cond.aha_type));
+            }
+            let ok = self.builder
+                .build_int_compare(inkwell::IntPredicate::NE, cond.value.into_int_value(), self.i64_type.const_zero(), "assert")
+                .map_err(|e| e.to_string())?;
+            self.build_panic_unless(ok, "assertion failed", call.line)?;
+            return Ok(done);
+        }
+
+        let left = self.compile_expression(&call.arguments[0])?;
+        let right = self.compile_expression(&call.arguments[1])?;
+        let op = if func_name == "assert_eq" { "==" } else { "!=" };
+        let structural = |t: &AhaType| match t {
+            AhaType::Struct(name) => !self.newtypes.contains(name),
+            AhaType::Tuple(_) | AhaType::Enum(_) | AhaType::List(_) | AhaType::Array(..) => true,
+            _ => false,
+        };
+        let ok = if structural(&left.aha_type) || structural(&right.aha_type) {
+            if left.aha_type != right.aha_type {
+                return Err(format!(
+                    "line {}: `{}`: Cannot apply operator '{}' to types {} and {}",
+                    call.line, func_name, op, left.aha_type, right.aha_type
+                ));
+            }
+            let equal = self.compile_values_equal(&left, &right, call.line)
+                .map_err(|e| format!("line {}: `{}`: {}", call.line, func_name, e))?;
+            if op == "==" { equal } else { self.builder.build_not(equal, "assert").map_err(|e| e.to_string())? }
+        } else {
+            let holds = match self.compile_newtype_op(&left, op, &right, call.line)? {
+                Some(result) => result,
+                None => self.compile_binary_op(left.clone(), op, right.clone(), call.line)
+                    .map_err(|e| format!("line {}: `{}`: {}", call.line, func_name, e))?,
+            };
+            self.builder
+                .build_int_compare(inkwell::IntPredicate::NE, holds.value.into_int_value(), self.i64_type.const_zero(), "assert")
+                .map_err(|e| e.to_string())?
+        };
+
+        let function = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("assertion outside function")?;
+        let ok_block = self.context.append_basic_block(function, "assert.ok");
+        let fail_block = self.context.append_basic_block(function, "assert.fail");
+        self.builder.build_conditional_branch(ok, ok_block, fail_block).map_err(|e| e.to_string())?;
+
+        // The message is only built when the assertion fails.
+        self.builder.position_at_end(fail_block);
+        let mut message = self.compile_string_literal(&format!("assertion `left {} right` failed\n  left: ", op))?;
+        for (value, label) in [(&left, "\n right: "), (&right, "")] {
+            let shown = self.debug_string(value, call.line)?;
+            message = self.compile_string_concat(&message, &shown)?;
+            if !label.is_empty() {
+                let label = self.compile_string_literal(label)?;
+                message = self.compile_string_concat(&message, &label)?;
+            }
+        }
+        let msg = self.extract_str_ptr(&message)?;
+        let len = self.extract_str_len(&message)?;
+        self.build_panic_value(msg, len, call.line)?;
+
+        self.builder.position_at_end(ok_block);
+        Ok(done)
+    }
+
+    /// `left == right` as an i1 for two values of the same type. Tuples,
+    /// arrays and lists compare element by element (lists of different
+    /// lengths are unequal) and structs field by field; enums compare
+    /// tags, then the payload of the variant both hold. Anything else
+    /// goes through `==`.
+    fn compile_values_equal(&mut self, left: &TypedValue<'ctx>, right: &TypedValue<'ctx>, line: usize) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        match &left.aha_type {
+            AhaType::Tuple(elems) => {
+                let mut equal = self.context.bool_type().const_int(1, false);
+                for i in 0..elems.len() {
+                    let l = self.tuple_element_value(left, i)?;
+                    let r = self.tuple_element_value(right, i)?;
+                    let element_equal = self.compile_values_equal(&l, &r, line)?;
+                    equal = self.builder.build_and(equal, element_equal, "tuple_eq").map_err(|e| e.to_string())?;
+                }
+                Ok(equal)
+            }
+            AhaType::Enum(name) => {
+                let name = name.clone();
+                let left_tag = self.load_enum_tag(&name, left.value.into_struct_value())?;
+                let right_tag = self.load_enum_tag(&name, right.value.into_struct_value())?;
+                let same_tag = self.builder.build_int_compare(inkwell::IntPredicate::EQ, left_tag, right_tag, "same_tag")
+                    .map_err(|e| e.to_string())?;
+                let variants = self.enum_defs.get(&name).cloned().unwrap_or_default();
+                if variants.iter().all(|(_, payload)| payload.is_empty()) {
+                    return Ok(same_tag);
+                }
+                // Payloads are only read once both tags name the same variant.
+                let function = self.builder.get_insert_block()
+                    .and_then(|b| b.get_parent())
+                    .ok_or("comparison outside function")?;
+                let tag_block = self.builder.get_insert_block().ok_or("comparison outside a block")?;
+                let payload_block = self.context.append_basic_block(function, "eq.payload");
+                let done_block = self.context.append_basic_block(function, "eq.done");
+                self.builder.build_conditional_branch(same_tag, payload_block, done_block).map_err(|e| e.to_string())?;
+
+                let mut incoming = Vec::new();
+                let mut cases = Vec::new();
+                for (variant, payload) in variants.iter().filter(|(_, payload)| !payload.is_empty()) {
+                    let block = self.context.append_basic_block(function, "eq.variant");
+                    let tag = self.variant_tag(&name, variant)?;
+                    cases.push((self.i64_type.const_int(tag as u64, true), block));
+                    self.builder.position_at_end(block);
+                    let l = self.enum_payload_values(&name, variant, payload.len(), left)?;
+                    let r = self.enum_payload_values(&name, variant, payload.len(), right)?;
+                    let mut equal = self.context.bool_type().const_int(1, false);
+                    for (l, r) in l.iter().zip(r.iter()) {
+                        let field_equal = self.compile_values_equal(l, r, line)?;
+                        equal = self.builder.build_and(equal, field_equal, "payload_eq").map_err(|e| e.to_string())?;
+                    }
+                    let end = self.builder.get_insert_block().ok_or("comparison outside a block")?;
+                    self.builder.build_unconditional_branch(done_block).map_err(|e| e.to_string())?;
+                    incoming.push((equal, end));
+                }
+                // Variants without a payload are equal once their tags are.
+                self.builder.position_at_end(payload_block);
+                self.builder.build_switch(left_tag, done_block, &cases).map_err(|e| e.to_string())?;
+
+                self.builder.position_at_end(done_block);
+                let bool_type = self.context.bool_type();
+                let phi = self.builder.build_phi(bool_type, "enum_eq").map_err(|e| e.to_string())?;
+                phi.add_incoming(&[(&bool_type.const_zero(), tag_block), (&bool_type.const_int(1, false), payload_block)]);
+                for (equal, end) in &incoming {
+                    phi.add_incoming(&[(equal, *end)]);
+                }
+                Ok(phi.as_basic_value().into_int_value())
+            }
+            AhaType::Struct(name) if !self.newtypes.contains(name) => {
+                let name = name.clone();
+                let fields = self.struct_defs.get(&name).cloned()
+                    .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
+                let mut equal = self.context.bool_type().const_int(1, false);
+                for (field, _) in &fields {
+                    let l = self.struct_field_value(&name, field, left)?;
+                    let r = self.struct_field_value(&name, field, right)?;
+                    let field_equal = self.compile_values_equal(&l, &r, line)?;
+                    equal = self.builder.build_and(equal, field_equal, "struct_eq").map_err(|e| e.to_string())?;
+                }
+                Ok(equal)
+            }
+            AhaType::Array(..) | AhaType::List(_) => {
+                let (left_len, left_slot) = self.sequence_parts(left)?;
+                let (right_len, right_slot) = self.sequence_parts(right)?;
+                let same_len = self.builder.build_int_compare(inkwell::IntPredicate::EQ, left_len, right_len, "same_len")
+                    .map_err(|e| e.to_string())?;
+                let equal_ptr = self.entry_alloca(self.context.bool_type().into(), "seq_eq")?;
+                self.builder.build_store(equal_ptr, same_len).map_err(|e| e.to_string())?;
+                // Elements are only compared when the lengths agree.
+                let count = self.builder.build_select(same_len, left_len, self.i64_type.const_zero(), "seq_count")
+                    .map_err(|e| e.to_string())?
+                    .into_int_value();
+                self.build_count_loop(count, |cg, index| {
+                    let l = cg.sequence_element(left, left_slot, index)?;
+                    let r = cg.sequence_element(right, right_slot, index)?;
+                    let element_equal = cg.compile_values_equal(&l, &r, line)?;
+                    let so_far = cg.builder.build_load(equal_ptr, "seq_eq").map_err(|e| e.to_string())?.into_int_value();
+                    let equal = cg.builder.build_and(so_far, element_equal, "seq_eq").map_err(|e| e.to_string())?;
+                    cg.builder.build_store(equal_ptr, equal).map_err(|e| e.to_string())?;
+                    Ok(())
+                })?;
+                Ok(self.builder.build_load(equal_ptr, "seq_eq").map_err(|e| e.to_string())?.into_int_value())
+            }
+            _ => {
+                let holds = match self.compile_newtype_op(left, "==", right, line)? {
+                    Some(result) => result,
+                    None => self.compile_binary_op(left.clone(), "==", right.clone(), line)?,
+                };
+                self.builder
+                    .build_int_compare(inkwell::IntPredicate::NE, holds.value.into_int_value(), self.i64_type.const_zero(), "eq")
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// A value as `assert_eq` shows it: Int and Bool as literals, String
+    /// quoted, a newtype as its wrapped value, a tuple as `(a, b)`, a
+    /// struct as `Point { x: 1, y: 2 }`, an array or list as `[a, b]` and
+    /// an enum as `Variant` or `Variant(a, b)`.
+    fn debug_string(&mut self, value: &TypedValue<'ctx>, line: usize) -> Result<TypedValue<'ctx>, String> {
+        match &value.aha_type {
+            AhaType::String => {
+                let quote = self.compile_string_literal("\"")?;
+                let opened = self.compile_string_concat(&quote, value)?;
+                self.compile_string_concat(&opened, &quote)
+            }
+            AhaType::Bool => {
+                let yes = self.compile_string_literal("true")?;
+                let no = self.compile_string_literal("false")?;
+                let is_true = self.builder
+                    .build_int_compare(inkwell::IntPredicate::NE, value.value.into_int_value(), self.i64_type.const_zero(), "is_true")
+                    .map_err(|e| e.to_string())?;
+                let shown = self.builder.build_select(is_true, yes.value, no.value, "bool_str").map_err(|e| e.to_string())?;
+                Ok(TypedValue::string(shown))
+            }
+            AhaType::Struct(name) if self.newtypes.contains(name) => {
+                let inner = self.builder.build_extract_value(value.value.into_struct_value(), 0, "newtype.val")
+                    .map_err(|e| e.to_string())?;
+                let base = self.field_type(name, "0")?;
+                self.debug_string(&TypedValue::new(inner, base), line)
+            }
+            AhaType::Struct(name) => {
+                let name = name.clone();
+                let fields = self.struct_defs.get(&name).cloned()
+                    .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
+                if fields.is_empty() {
+                    return self.compile_string_literal(&format!("{} {{}}", name));
+                }
+                let mut shown = self.compile_string_literal(&format!("{} {{ ", name))?;
+                for (i, (field, _)) in fields.iter().enumerate() {
+                    let label = self.compile_string_literal(&format!("{}{}: ", if i > 0 { ", " } else { "" }, field))?;
+                    shown = self.compile_string_concat(&shown, &label)?;
+                    let field_value = self.struct_field_value(&name, field, value)?;
+                    let field_shown = self.debug_string(&field_value, line)?;
+                    shown = self.compile_string_concat(&shown, &field_shown)?;
+                }
+                let close = self.compile_string_literal(" }")?;
+                self.compile_string_concat(&shown, &close)
+            }
+            AhaType::Array(..) | AhaType::List(_) => {
+                let (len, slot) = self.sequence_parts(value)?;
+                let none = self.compile_string_literal("")?;
+                let comma = self.compile_string_literal(", ")?;
+                let open = self.compile_string_literal("[")?;
+                let shown_ptr = self.entry_alloca(self.string_type.into(), "seq_str")?;
+                self.builder.build_store(shown_ptr, open.value).map_err(|e| e.to_string())?;
+                self.build_count_loop(len, |cg, index| {
+                    let first = cg.builder.build_int_compare(inkwell::IntPredicate::EQ, index, cg.i64_type.const_zero(), "first")
+                        .map_err(|e| e.to_string())?;
+                    let separator = cg.builder.build_select(first, none.value, comma.value, "sep").map_err(|e| e.to_string())?;
+                    let element = cg.sequence_element(value, slot, index)?;
+                    let element = cg.debug_string(&element, line)?;
+                    let so_far = TypedValue::string(cg.builder.build_load(shown_ptr, "seq_str").map_err(|e| e.to_string())?);
+                    let shown = cg.compile_string_concat(&so_far, &TypedValue::string(separator))?;
+                    let shown = cg.compile_string_concat(&shown, &element)?;
+                    cg.builder.build_store(shown_ptr, shown.value).map_err(|e| e.to_string())?;
+                    Ok(())
+                })?;
+                let shown = TypedValue::string(self.builder.build_load(shown_ptr, "seq_str").map_err(|e| e.to_string())?);
+                let close = self.compile_string_literal("]")?;
+                self.compile_string_concat(&shown, &close)
+            }
+            AhaType::Tuple(elems) => {
+                let elems = (0..elems.len())
+                    .map(|i| self.tuple_element_value(value, i))
+                    .collect::<Result<Vec<_>, String>>()?;
+                self.debug_parenthesized("", &elems, elems.len() == 1, line)
+            }
+            AhaType::Enum(name) => {
+                let name = name.clone();
+                let variants = self.enum_defs.get(&name).cloned().unwrap_or_default();
+                let tag = self.load_enum_tag(&name, value.value.into_struct_value())?;
+                let function = self.builder.get_insert_block()
+                    .and_then(|b| b.get_parent())
+                    .ok_or("assertion outside function")?;
+                let switch_block = self.builder.get_insert_block().ok_or("assertion outside a block")?;
+                let done_block = self.context.append_basic_block(function, "show.done");
+                let mut cases = Vec::new();
+                let mut incoming = Vec::new();
+                for (variant, payload) in &variants {
+                    let block = self.context.append_basic_block(function, "show.variant");
+                    let variant_tag = self.variant_tag(&name, variant)?;
+                    cases.push((self.i64_type.const_int(variant_tag as u64, true), block));
+                    self.builder.position_at_end(block);
+                    let shown = if payload.is_empty() {
+                        self.compile_string_literal(variant)?
+                    } else {
+                        let fields = self.enum_payload_values(&name, variant, payload.len(), value)?;
+                        self.debug_parenthesized(variant, &fields, false, line)?
+                    };
+                    let end = self.builder.get_insert_block().ok_or("assertion outside a block")?;
+                    self.builder.build_unconditional_branch(done_block).map_err(|e| e.to_string())?;
+                    incoming.push((shown.value, end));
+                }
+                // A tag is always one of the variants; the last one doubles
+                // as the switch default.
+                let (_, default_block) = cases.pop().ok_or_else(|| format!("Enum '{}' has no variants", name))?;
+                self.builder.position_at_end(switch_block);
+                self.builder.build_switch(tag, default_block, &cases).map_err(|e| e.to_string())?;
+
+                self.builder.position_at_end(done_block);
+                let phi = self.builder.build_phi(self.string_type, "enum_str").map_err(|e| e.to_string())?;
+                for (shown, end) in &incoming {
+                    phi.add_incoming(&[(shown, *end)]);
+                }
+                Ok(TypedValue::string(phi.as_basic_value()))
+            }
+            AhaType::Int => {
+                let to_string = *self.functions.get("int_to_string").expect("int_to_string not declared");
+                let shown = self.builder.build_call(to_string, &[value.value.into()], "int_str")
+                    .map_err(|e| e.to_string())?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or("int_to_string returned void")?;
+                Ok(TypedValue::string(shown))
+            }
+            other => Err(format!("line {}: `assert_eq` cannot show a value of type {}", line, other)),
+        }
+    }
+
+    /// The length of an array or list value, and for an array the stack
+    /// slot it is spilled to so `sequence_element` can index it.
+    fn sequence_parts(&mut self, value: &TypedValue<'ctx>) -> Result<(inkwell::values::IntValue<'ctx>, Option<PointerValue<'ctx>>), String> {
+        match &value.aha_type {
+            AhaType::Array(_, len) => {
+                let slot = self.entry_alloca(value.value.get_type(), "arr_slot")?;
+                self.builder.build_store(slot, value.value).map_err(|e| e.to_string())?;
+                Ok((self.i64_type.const_int(*len as u64, false), Some(slot)))
+            }
+            _ => {
+                let len_fn = *self.functions.get("list_len").expect("list_len not declared");
+                let len = self.builder.build_call(len_fn, &[value.value.into()], "list.len")
+                    .map_err(|e| e.to_string())?
+                    .try_as_basic_value().left().ok_or("list_len returned void")?
+                    .into_int_value();
+                Ok((len, None))
+            }
+        }
+    }
+
+    /// Element `index` of an array or list, which must be in bounds.
+    fn sequence_element(
+        &mut self,
+        value: &TypedValue<'ctx>,
+        slot: Option<PointerValue<'ctx>>,
+        index: inkwell::values::IntValue<'ctx>,
+    ) -> Result<TypedValue<'ctx>, String> {
+        match (&value.aha_type, slot) {
+            (AhaType::Array(elem, _), Some(slot)) => {
+                let zero = self.i64_type.const_zero();
+                let elem_ptr = unsafe { self.builder.build_in_bounds_gep(slot, &[zero, index], "elem_ptr") }
+                    .map_err(|e| e.to_string())?;
+                let elem_val = self.builder.build_load(elem_ptr, "elem_val").map_err(|e| e.to_string())?;
+                Ok(TypedValue::new(elem_val, (**elem).clone()))
+            }
+            (AhaType::List(inner), _) => {
+                let inner = (**inner).clone();
+                self.list_element(value.value.into_int_value(), index, &inner)
+            }
+            (other, _) => Err(format!("Cannot index into a value of type {}", other)),
+        }
+    }
+
+    /// Emit `body(i)` for each `i` in `0..count`, leaving the builder
+    /// after the loop.
+    fn build_count_loop(
+        &mut self,
+        count: inkwell::values::IntValue<'ctx>,
+        mut body: impl FnMut(&mut Self, inkwell::values::IntValue<'ctx>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let function = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("loop outside function")?;
+        let index_ptr = self.entry_alloca(self.i64_type.into(), "loop.index")?;
+        self.builder.build_store(index_ptr, self.i64_type.const_zero()).map_err(|e| e.to_string())?;
+        let cond_block = self.context.append_basic_block(function, "loop.cond");
+        let body_block = self.context.append_basic_block(function, "loop.body");
+        let after_block = self.context.append_basic_block(function, "loop.after");
+        self.builder.build_unconditional_branch(cond_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(cond_block);
+        let index = self.builder.build_load(index_ptr, "loop.i").map_err(|e| e.to_string())?.into_int_value();
+        let more = self.builder.build_int_compare(inkwell::IntPredicate::SLT, index, count, "loop.more")
+            .map_err(|e| e.to_string())?;
+        self.builder.build_conditional_branch(more, body_block, after_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(body_block);
+        body(self, index)?;
+        let next = self.builder.build_int_add(index, self.i64_type.const_int(1, false), "loop.next")
+            .map_err(|e| e.to_string())?;
+        self.builder.build_store(index_ptr, next).map_err(|e| e.to_string())?;
+        self.builder.build_unconditional_branch(cond_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(after_block);
+        Ok(())
+    }
+
+    /// `prefix(a, b)` with each value shown by `debug_string`; a
+    /// `trailing_comma` gives `(a,)` for a one-element tuple.
+    fn debug_parenthesized(&mut self, prefix: &str, values: &[TypedValue<'ctx>], trailing_comma: bool, line: usize) -> Result<TypedValue<'ctx>, String> {
+        let mut shown = self.compile_string_literal(&format!("{}(", prefix))?;
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                let comma = self.compile_string_literal(", ")?;
+                shown = self.compile_string_concat(&shown, &comma)?;
+            }
+            let value = self.debug_string(value, line)?;
+            shown = self.compile_string_concat(&shown, &value)?;
+        }
+        let close = self.compile_string_literal(if trailing_comma { ",)" } else { ")" })?;
+        self.compile_string_concat(&shown, &close)
+    }
+
+    /// `wrapping_add(a, b)` wraps on overflow, `saturating_add(a, b)`
+    /// clamps to the Int range and `checked_add(a, b)` is `None` on
+    /// overflow, whatever `--overflow-checks` says.
+    fn compile_explicit_add(&mut self, func_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
+        if call.arguments.len() != 2 {
+            return Err(format!("{}() expects 2 arguments, got {}", func_name, call.arguments.len()));
+        }
+        let mut operands = Vec::new();
+        for arg in &call.arguments {
+            let value = self.compile_expression(arg)?;
+            if !Self::types_compatible(&AhaType::Int, &value.aha_type) {
+                return Err(format!("line {}: `{}` expects Int arguments, got {}", call.line, func_name, value.aha_type));
+            }
+            operands.push(value.value.into_int_value());
+        }
+        let (a, b) = (operands[0], operands[1]);
+        match func_name {
+            "wrapping_add" => {
+                let sum = self.builder.build_int_add(a, b, "wrapping_add").map_err(|e| e.to_string())?;
+                Ok(TypedValue::int(sum.into()))
+            }
+            "saturating_add" => {
+                let function = Intrinsic::find("llvm.sadd.sat")
+                    .and_then(|i| i.get_declaration(&self.module, &[self.i64_type.into()]))
+                    .ok_or("LLVM intrinsic llvm.sadd.sat not available")?;
+                let sum = self.builder.build_call(function, &[a.into(), b.into()], "saturating_add")
+                    .map_err(|e| e.to_string())?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or("llvm.sadd.sat returned void")?;
+                Ok(TypedValue::int(sum))
+            }
+            _ => {
+                let option = self.option_instance(AhaType::Int, "`checked_add`")?;
+                let (sum, overflowed) = self.build_overflow_op("llvm.sadd.with.overflow", a, b)?;
+                let some = self.build_enum_value(&option, "Some", &[sum.into()])?;
+                let none = self.build_enum_value(&option, "None", &[])?;
+                let result = self.builder.build_select(overflowed, none, some, "checked_add").map_err(|e| e.to_string())?;
+                Ok(TypedValue::new(result, AhaType::Enum(option)))
+            }
+        }
+    }
+
     /// Compile actor_send / actor_call builtin calls via the threaded runtime.
-    /// call(a, msg) -> actor_call(handle, msg) -> blocking request-response.
+    /// call(a, msg) -> actor_call(handle, msg, out) -> blocking request-response.
     /// send(a, msg) -> actor_send(handle, msg) -> fire-and-forget.
+    /// A panic in the handler is raised again on this thread, and a call
+    /// on an actor that is not running panics.
     fn compile_actor_call(&mut self, func_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
         if call.arguments.len() != 2 {
             return Err(format!("{} expects 2 arguments (handle, msg)", func_name));
@@ -3993,22 +5283,65 @@ impl<'ctx> CodeGenerator<'ctx> {
         let handle = self.compile_expression(&call.arguments[0])?.value;
         let msg = self.compile_expression(&call.arguments[1])?.value;
 
-        let runtime_fn_name = if func_name == "send" { "actor_send" } else { "actor_call" };
-        let function = *self.functions.get(runtime_fn_name)
-            .ok_or_else(|| format!("{} not declared", runtime_fn_name))?;
+        if func_name == "send" {
+            let function = *self.functions.get("actor_send").expect("actor_send not declared");
+            self.builder.build_call(function, &[handle.into(), msg.into()], "")
+                .map_err(|e| e.to_string())?;
+            return Ok(TypedValue::void(self.i64_type.const_int(0, false).into()));
+        }
 
-        let args_meta: Vec<BasicMetadataValueEnum> = vec![handle.into(), msg.into()];
-        let call_result = self.builder.build_call(function, &args_meta, "actor_tmp")
-            .map_err(|e| e.to_string())?;
+        let record_type = self.panic_record_type();
+        let out = self.entry_alloca(record_type.into(), "actor_outcome")?;
+        let function = *self.functions.get("actor_call").expect("actor_call not declared");
+        let val = self.builder.build_call(function, &[handle.into(), msg.into(), out.into()], "actor_tmp")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| "actor_call did not return a value".to_string())?;
+
+        // out = {0, ..} unless the call panicked.
+        let current = self.builder.get_insert_block()
+            .and_then(|b| b.get_parent())
+            .ok_or("actor call outside function")?;
+        let raised_block = self.context.append_basic_block(current, "actor.raised");
+        let check_dead_block = self.context.append_basic_block(current, "actor.check_dead");
+        let dead_block = self.context.append_basic_block(current, "actor.dead");
+        let ok_block = self.context.append_basic_block(current, "actor.ok");
+        let record = self.builder.build_load(out, "actor_record").map_err(|e| e.to_string())?.into_struct_value();
+        let field = |cg: &Self, i: u32, name: &str| cg.builder.build_extract_value(record, i, name).map_err(|e| e.to_string());
+        let panicked = field(self, 0, "panicked")?.into_int_value();
+        let args: Vec<BasicMetadataValueEnum> = vec![
+            field(self, 1, "panic_msg")?.into(),
+            field(self, 2, "panic_len")?.into(),
+        ];
+        let raised = self.builder.build_int_compare(
+            inkwell::IntPredicate::EQ, panicked, self.i64_type.const_int(PANIC_RAISED as u64, false), "actor_raised",
+        ).map_err(|e| e.to_string())?;
+        self.builder.build_conditional_branch(raised, raised_block, check_dead_block).map_err(|e| e.to_string())?;
+
+        // The handler panicked: it has printed the panic on its own
+        // thread, so unwind this one with the same message and line.
+        self.builder.position_at_end(raised_block);
+        let unwind_fn = *self.functions.get("aha_unwind").expect("panic runtime not declared");
+        let line = field(self, 3, "panic_line")?;
+        let unwind_args = [args[0], args[1], line.into()];
+        self.builder.build_call(unwind_fn, &unwind_args, "").map_err(|e| e.to_string())?;
+        self.builder.build_unreachable().map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(check_dead_block);
+        let dead = self.builder.build_int_compare(
+            inkwell::IntPredicate::EQ, panicked, self.i64_type.const_int(PANIC_DEAD_ACTOR as u64, false), "actor_dead",
+        ).map_err(|e| e.to_string())?;
+        self.builder.build_conditional_branch(dead, dead_block, ok_block).map_err(|e| e.to_string())?;
 
-        if func_name == "call" {
-            let val = call_result.try_as_basic_value()
-                .left()
-                .ok_or_else(|| "actor_call did not return a value".to_string())?;
-            Ok(TypedValue::int(val))
-        } else {
-            Ok(TypedValue::void(self.i64_type.const_int(0, false).into()))
-        }
+        self.builder.position_at_end(dead_block);
+        let panic_fn = *self.functions.get("aha_panic").expect("panic runtime not declared");
+        let line = self.i64_type.const_int(call.line as u64, false);
+        self.builder.build_call(panic_fn, &[args[0], args[1], line.into()], "").map_err(|e| e.to_string())?;
+        self.builder.build_unreachable().map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(ok_block);
+        Ok(TypedValue::int(val))
     }
 
     /// Compile a list_* builtin call. The LLVM-level dispatch depends on
@@ -4037,6 +5370,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                     if let ast::Expression::Identifier(id) = &call.arguments[0] {
                         self.mark_freed(&id.value);
                     }
+                    self.free_boxed_tuples(list_tv.value, &list_tv.aha_type)?;
                 }
                 let args_meta: Vec<_> = [list_handle.into()].iter().map(|a: &inkwell::values::BasicValueEnum| (*a).into()).collect();
                 let function = *self.functions.get(func_name).expect("list builtin not declared");
@@ -4052,6 +5386,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                 // Compile the value argument. For String lists, split the
                 // string struct and call list_push_string(list, ptr, len).
                 let value_tv = self.compile_expression(&call.arguments[1])?;
+                let value_tv = self.own_static_read(&call.arguments[1], value_tv)?;
                 if elem_type.is_string() {
                     if !value_tv.aha_type.is_string() {
                         return Err(format!(
@@ -4070,6 +5405,22 @@ impl<'ctx> CodeGenerator<'ctx> {
                     self.builder.build_call(function, &args_meta, "calltmp")
                         .map_err(|e| e.to_string())?;
                     Ok(list_tv)
+                } else if let AhaType::Tuple(_) = elem_type {
+                    if value_tv.aha_type != elem_type {
+                        return Err(format!(
+                            "list_push on List<{}> requires a {} value, got {}",
+                            elem_type, elem_type, value_tv.aha_type
+                        ));
+                    }
+                    let handle = self.box_tuple(&value_tv)?;
+                    let args_meta: Vec<_> = [
+                        list_handle.into(),
+                        handle.into(),
+                    ].iter().map(|a: &inkwell::values::BasicValueEnum| (*a).into()).collect();
+                    let function = *self.functions.get("list_push").expect("list_push not declared");
+                    self.builder.build_call(function, &args_meta, "calltmp")
+                        .map_err(|e| e.to_string())?;
+                    Ok(list_tv)
                 } else {
                     if value_tv.aha_type.is_string() {
                         return Err(format!(
@@ -4088,6 +5439,7 @@ impl<'ctx> CodeGenerator<'ctx> {
             }
             "list_get" => {
                 let index_tv = self.compile_expression(&call.arguments[1])?;
+                self.check_list_index(list_handle, index_tv.value.into_int_value(), call.line)?;
                 if elem_type.is_string() {
                     let args_meta: Vec<_> = [
                         list_handle.into(),
@@ -4111,6 +5463,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                     let val = call_result.try_as_basic_value()
                         .left()
                         .ok_or("list_get returned void")?;
+                    if let AhaType::Tuple(_) = elem_type {
+                        return self.unbox_tuple(val, &elem_type);
+                    }
                     Ok(TypedValue::int(val))
                 }
             }
@@ -4150,6 +5505,7 @@ impl<'ctx> CodeGenerator<'ctx> {
                     if let ast::Expression::Identifier(id) = &call.arguments[0] {
                         self.mark_freed(&id.value);
                     }
+                    self.free_boxed_tuples(map_tv.value, &map_tv.aha_type)?;
                 }
                 let args_meta: Vec<_> = [map_handle.into()]
                     .iter().map(|a: &inkwell::values::BasicValueEnum| (*a).into()).collect();
@@ -4164,7 +5520,9 @@ impl<'ctx> CodeGenerator<'ctx> {
 
             "map_set" | "map_string_key_set" | "map_string_val_set" | "map_strings_set" => {
                 let key_tv = self.compile_expression(&call.arguments[1])?;
+                let key_tv = self.own_static_read(&call.arguments[1], key_tv)?;
                 let val_tv = self.compile_expression(&call.arguments[2])?;
+                let val_tv = self.own_static_read(&call.arguments[2], val_tv)?;
                 let mut args: Vec<BasicValueEnum> = vec![map_handle.into()];
                 // Key arg(s)
                 if key_type.is_string() {
@@ -4177,6 +5535,15 @@ impl<'ctx> CodeGenerator<'ctx> {
                 if val_type.is_string() {
                     args.push(self.extract_str_ptr(&val_tv)? .into());
                     args.push(self.extract_str_len(&val_tv)? .into());
+                } else if let AhaType::Tuple(_) = val_type {
+                    if val_tv.aha_type != val_type {
+                        return Err(format!(
+                            "map_set on Map<{}, {}> requires a {} value, got {}",
+                            key_type, val_type, val_type, val_tv.aha_type
+                        ));
+                    }
+                    self.free_map_tuple(func_name.trim_end_matches("_set"), &args)?;
+                    args.push(self.box_tuple(&val_tv)?.into());
                 } else {
                     args.push(val_tv.value);
                 }
@@ -4204,6 +5571,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                     .map_err(|e| e.to_string())?;
                 let val = call_result.try_as_basic_value()
                     .left().ok_or("map_get returned void")?;
+                if let AhaType::Tuple(_) = val_type {
+                    return self.unbox_tuple(val, &val_type);
+                }
                 let ret_type = if val_type.is_string() { AhaType::String } else { AhaType::Int };
                 Ok(TypedValue::new(val, ret_type))
             }
@@ -4236,6 +5606,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                     args.push(key_tv.value);
                 }
                 let args_meta: Vec<_> = args.iter().map(|a: &BasicValueEnum| (*a).into()).collect();
+                if let AhaType::Tuple(_) = val_type {
+                    self.free_map_tuple(func_name.trim_end_matches("_remove"), &args)?;
+                }
                 let function = *self.functions.get(func_name).expect("map_remove not declared");
                 let call_result = self.builder.build_call(function, &args_meta, "calltmp")
                     .map_err(|e| e.to_string())?;
@@ -4271,26 +5644,6 @@ impl<'ctx> CodeGenerator<'ctx> {
             .ok_or_else(|| "Function call did not return a value".to_string())?;
         Ok(TypedValue::new(val, ret_type))
     }
-    /// Infer a generic function's return type with concrete type
-    /// parameters bound. Param types come from the resolved hints,
-    /// so `fn id<T>(x: T) -> T { x }` returns the concrete arg type.
-    fn infer_generic_return_type(&self, func: &ast::FunctionLiteral, param_types: &[AhaType]) -> AhaType {
-        let scope: HashMap<String, AhaType> = func.parameters.iter().enumerate()
-            .map(|(i, p)| (p.value.clone(), param_types.get(i).cloned().unwrap_or(AhaType::Int)))
-            .collect();
-        for stmt in &func.body.statements {
-            if let ast::Statement::Return(ret) = stmt {
-                return self.infer_expr_type_with_scope(&ret.return_value, &scope);
-            }
-        }
-        for stmt in func.body.statements.iter().rev() {
-            if let ast::Statement::Expression(expr_stmt) = stmt {
-                return self.infer_expr_type_with_scope(&expr_stmt.expression, &scope);
-            }
-        }
-        AhaType::Int
-    }
-
     /// Monomorphize and call a generic function.
     /// Each unique (generic name, concrete type params) combination gets
     /// its own LLVM function (`max_Int`, `max_String`, ...), compiled
@@ -4304,6 +5657,7 @@ impl<'ctx> CodeGenerator<'ctx> {
         let mut arg_types: Vec<AhaType> = Vec::new();
         for arg in &call.arguments {
             let tv = self.compile_expression(arg)?;
+            let tv = self.own_static_read(arg, tv)?;
             arg_types.push(tv.aha_type.clone());
             args.push(tv.value);
         }
@@ -4312,28 +5666,13 @@ impl<'ctx> CodeGenerator<'ctx> {
         // concrete argument types at matching positions.
         // Handles both direct hints (T) and container hints (List<T>).
         let mut type_params: HashMap<String, AhaType> = HashMap::new();
-        for (i, hint) in generic.param_type_hints.iter().enumerate() {
+        for (hint, actual) in generic.param_type_hints.iter().zip(&arg_types) {
             if let Some(h) = hint {
-                if generic.type_params.contains(h) && !type_params.contains_key(h) {
-                    let t = arg_types.get(i).cloned().unwrap_or(AhaType::Int);
-                    type_params.insert(h.clone(), t);
-                } else if let Some(inner) = h.strip_prefix("List<").and_then(|s| s.strip_suffix('>')) {
-                    if generic.type_params.iter().any(|tp| tp == inner) && !type_params.contains_key(inner) {
-                        if let Some(AhaType::List(inner_type)) = arg_types.get(i) {
-                            type_params.insert(inner.to_string(), *inner_type.clone());
-                        }
-                    }
-                }
+                self.bind_type_params(&generic.type_params, h, actual, &mut type_params);
             }
         }
 
-        // Mangled name: deterministic order by type-param name.
-        let mut keyed: Vec<(String, AhaType)> = type_params.iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        keyed.sort_by(|a, b| a.0.cmp(&b.0));
-        let suffix: String = keyed.iter().map(|(_, t)| format!("_{}", t)).collect();
-        let mangled = format!("{}{}", func_name, suffix);
+        let mangled = generic_fn_name(func_name, &type_params);
 
         // Cache hit — just call the already-instantiated function.
         if let Some(f) = self.functions.get(&mangled) {
@@ -4349,6 +5688,10 @@ impl<'ctx> CodeGenerator<'ctx> {
 
         // Activate type-param bindings for hint resolution & body compile.
         let saved_tpm = std::mem::replace(&mut self.type_param_map, type_params);
+        if let Err(e) = self.instantiate_fn_hints(&generic) {
+            self.type_param_map = saved_tpm;
+            return Err(e);
+        }
 
         let mut param_aha_types: Vec<AhaType> = Vec::new();
         for (i, _p) in generic.parameters.iter().enumerate() {
@@ -4359,10 +5702,7 @@ impl<'ctx> CodeGenerator<'ctx> {
             param_aha_types.push(t);
         }
 
-        let return_type = match &generic.return_type_hint {
-            Some(h) => self.resolve_hint_type(h.as_str()),
-            None => self.infer_generic_return_type(&generic, &param_aha_types),
-        };
+        let return_type = self.function_return_type(&generic, &mangled);
 
         let param_types: Result<Vec<_>, _> = param_aha_types.iter()
             .map(|t| self.aha_type_to_llvm_type(t))
@@ -4372,6 +5712,7 @@ impl<'ctx> CodeGenerator<'ctx> {
         let function = self.module.add_function(&mangled, fn_type, None);
         self.functions.insert(mangled.clone(), function);
         self.fn_types.insert(mangled.clone(), return_type.clone());
+        self.traced_functions.push((function, mangled.clone()));
 
         // Compile the body with the concrete type params bound.
         let saved_block = self.builder.get_insert_block();
@@ -4424,16 +5765,26 @@ impl<'ctx> CodeGenerator<'ctx> {
                 AhaType::Enum(name) => {
                     self.enum_llvm_type(name)?.const_zero().into()
                 }
+                AhaType::Tuple(elems) => {
+                    self.tuple_llvm_type(elems)?.const_zero().into()
+                }
+                AhaType::Array(_, _) => self.aha_type_to_llvm_type(&return_type)?.const_zero(),
                 _ => self.i64_type.const_int(0, false).into(),
             };
 
+            let tail_idx = generic.body.statements.len().saturating_sub(1);
             for (stmt_idx, stmt) in generic.body.statements.iter().enumerate() {
                 if let ast::Statement::Return(_) = stmt {
                     self.compile_statement(stmt)?;
                     has_return = true;
                     break;
                 } else if let ast::Statement::Expression(expr_stmt) = stmt {
-                    let tv = self.compile_expression(&expr_stmt.expression)?;
+                    // The tail expression is the return value.
+                    let expected = (stmt_idx == tail_idx).then(|| return_type.clone());
+                    let tv = self.compile_owned(&expr_stmt.expression, expected)?;
+                    if stmt_idx == tail_idx {
+                        self.check_return_value(func_name, &return_type, &tv, generic.line)?;
+                    }
                     last_value = tv.value;
                 } else {
                     self.compile_statement(stmt)?;
@@ -4446,7 +5797,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                 }
             }
 
-            if !has_return && self.has_heap_locals() {
+            if !has_return && self.block_terminated() {
+                // The body ends in a panic: nothing to return.
+            } else if !has_return && self.has_heap_locals() {
                 self.insert_cleanup_inline(&escaped);
                 self.builder.build_return(Some(&last_value))
                     .map_err(|e| e.to_string())?;
@@ -4508,6 +5861,40 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::void(self.i64_type.const_int(0, false).into()))
     }
 
+    /// Compile: while let Pattern = value { ... }
+    /// Re-evaluates `value` each iteration and exits when the pattern fails.
+    fn compile_while_let_expression(&mut self, while_let: &ast::WhileLetExpression) -> Result<TypedValue<'ctx>, String> {
+        let function = self.builder.get_insert_block()
+            .expect("Builder not in a block!")
+            .get_parent()
+            .unwrap();
+        let condition_block = self.context.append_basic_block(function, "while_let_cond");
+        let body_block = self.context.append_basic_block(function, "while_let_body");
+        let after_block = self.context.append_basic_block(function, "while_let_after");
+        self.builder.build_unconditional_branch(condition_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(condition_block);
+        let scrutinee = self.compile_expression(&while_let.value)?;
+        let hit = self.compile_pattern_test(&while_let.pattern, &scrutinee)?;
+        self.builder.build_conditional_branch(hit, body_block, after_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(body_block);
+        self.enter_scope();
+        self.bind_pattern(&while_let.pattern, &scrutinee)?;
+        self.loop_stack.push((condition_block, after_block));
+        self.compile_block_statement(&while_let.body)?;
+        self.loop_stack.pop();
+        self.exit_scope();
+
+        let body_end = self.builder.get_insert_block().unwrap();
+        if body_end.get_terminator().is_none() {
+            self.builder.build_unconditional_branch(condition_block).map_err(|e| e.to_string())?;
+        }
+
+        self.builder.position_at_end(after_block);
+        Ok(TypedValue::void(self.i64_type.const_int(0, false).into()))
+    }
+
     fn compile_if_expression(&mut self, if_expr: &ast::IfExpression) -> Result<TypedValue<'ctx>, String> {
         let condition_val = self.compile_expression(&if_expr.condition)?;
         let condition_bool = self.builder.build_int_compare(
@@ -4516,16 +5903,44 @@ impl<'ctx> CodeGenerator<'ctx> {
             self.i64_type.const_int(0, false),
             "if_cond_bool"
         ).map_err(|e| e.to_string())?;
-        
-        let function = self.builder.get_insert_block().expect("Builder not in block").get_parent().unwrap();
-        let consequence_block = self.context.append_basic_block(function, "consequence");
+        self.compile_if_branches(condition_bool, &if_expr.consequence, if_expr.alternative.as_ref(), None)
+    }
+
+    /// Compile: if let Pattern = value { ... } else { ... }
+    /// Reuses the match pattern test; bindings are scoped to the consequence.
+    fn compile_if_let_expression(&mut self, if_let: &ast::IfLetExpression) -> Result<TypedValue<'ctx>, String> {
+        let scrutinee = self.compile_expression(&if_let.value)?;
+        let hit = self.compile_pattern_test(&if_let.pattern, &scrutinee)?;
+        self.compile_if_branches(hit, &if_let.consequence, if_let.alternative.as_ref(), Some((&if_let.pattern, &scrutinee)))
+    }
+
+    /// Shared tail of `if` / `if let`: branch on `condition_bool`, compile
+    /// both arms and merge their values with a phi. `bindings` are brought
+    /// into scope at the start of the consequence.
+    fn compile_if_branches(
+        &mut self,
+        condition_bool: inkwell::values::IntValue<'ctx>,
+        consequence: &ast::BlockStatement,
+        alternative: Option<&ast::BlockStatement>,
+        bindings: Option<(&ast::Pattern, &TypedValue<'ctx>)>,
+    ) -> Result<TypedValue<'ctx>, String> {
+        let function = self.builder.get_insert_block().expect("Builder not in block").get_parent().unwrap();
+        let consequence_block = self.context.append_basic_block(function, "consequence");
         let alternative_block = self.context.append_basic_block(function, "alternative");
         let merge_block = self.context.append_basic_block(function, "merge");
         self.builder.build_conditional_branch(condition_bool, consequence_block, alternative_block)
             .map_err(|e| e.to_string())?;
 
         self.builder.position_at_end(consequence_block);
-        let consequence_tv = self.compile_block_statement(&if_expr.consequence)?;
+        let consequence_tv = if let Some((pattern, scrutinee)) = bindings {
+            self.enter_scope();
+            self.bind_pattern(pattern, scrutinee)?;
+            let tv = self.compile_block_statement(consequence)?;
+            self.exit_scope();
+            tv
+        } else {
+            self.compile_block_statement(consequence)?
+        };
         let consequence_end_block = self.builder.get_insert_block().unwrap();
         let consequence_terminated = consequence_end_block.get_terminator().is_some();
         if !consequence_terminated {
@@ -4533,7 +5948,7 @@ impl<'ctx> CodeGenerator<'ctx> {
         }
 
         self.builder.position_at_end(alternative_block);
-        let alternative_tv = if let Some(alt_block) = &if_expr.alternative {
+        let alternative_tv = if let Some(alt_block) = alternative {
             self.compile_block_statement(alt_block)?
         } else {
             TypedValue::int(self.i64_type.const_int(0, false).into())
@@ -4558,6 +5973,8 @@ impl<'ctx> CodeGenerator<'ctx> {
             (_, AhaType::Struct(name)) => self.struct_llvm_type(name)?.into(),
             (AhaType::Enum(name), _) => self.enum_llvm_type(name)?.into(),
             (_, AhaType::Enum(name)) => self.enum_llvm_type(name)?.into(),
+            (AhaType::Tuple(elems), _) | (_, AhaType::Tuple(elems)) => self.tuple_llvm_type(elems)?.into(),
+            (t @ AhaType::Array(_, _), _) | (_, t @ AhaType::Array(_, _)) => self.aha_type_to_llvm_type(t)?,
             _ => self.i64_type.into(),
         };
         let phi_node = self.builder.build_phi(phi_type, "iftmp")
@@ -4575,6 +5992,10 @@ impl<'ctx> CodeGenerator<'ctx> {
         self.enter_scope();
         let mut last = TypedValue::int(self.i64_type.const_int(0, false).into());
         for statement in &block.statements {
+            // Code after a `panic` or `return` is unreachable.
+            if self.block_terminated() {
+                break;
+            }
             if let ast::Statement::Expression(expr_stmt) = statement {
                 last = self.compile_expression(&expr_stmt.expression)?;
             } else {
@@ -4589,6 +6010,13 @@ impl<'ctx> CodeGenerator<'ctx> {
         let right = self.compile_expression(&prefix.right)?;
         let result_type = right.aha_type.check_prefix_op(&prefix.operator)?;
         match prefix.operator.as_str() {
+            "-" if self.overflow_checks => {
+                let zero = self.i64_type.const_zero();
+                let (neg, overflowed) = self.build_overflow_op("llvm.ssub.with.overflow", zero, right.value.into_int_value())?;
+                let ok = self.builder.build_not(overflowed, "no_overflow").map_err(|e| e.to_string())?;
+                self.build_panic_unless(ok, "attempt to negate with overflow", prefix.line)?;
+                Ok(TypedValue::new(neg.into(), result_type))
+            },
             "-" => {
                 let neg = self.builder.build_int_neg(right.value.into_int_value(), "negtmp")
                     .map_err(|e| e.to_string())?;
@@ -4609,11 +6037,30 @@ impl<'ctx> CodeGenerator<'ctx> {
 
     fn compile_assignment(&mut self, assign: &ast::AssignmentExpression) -> Result<TypedValue<'ctx>, String> {
         let typed_val = self.compile_expression(&assign.value)?;
+        // A `String` static target copies the value in `store_string_static`.
+        let to_static = matches!(&*assign.target, ast::Expression::Identifier(id) if self.lookup_variable(&id.value).is_none());
+        let typed_val = if to_static { typed_val } else { self.own_static_read(&assign.value, typed_val)? };
 
         // Handle field access: p.x = value
         if let ast::Expression::FieldAccess(fa) = &*assign.target {
             // Compile the object expression to get the struct value
             let object = self.compile_expression(&fa.object)?;
+            // Tuple element: pair.0 = value
+            if let AhaType::Tuple(elems) = &object.aha_type {
+                let index = self.tuple_field_index(&object.aha_type, &fa.field.value)?;
+                if !Self::types_compatible(&elems[index as usize], &typed_val.aha_type) {
+                    return Err(format!(
+                        "Tuple element .{} expects {}, got {}", index, elems[index as usize], typed_val.aha_type
+                    ));
+                }
+                self.mark_stored(&assign.value, &typed_val.aha_type);
+                let new_tuple = self.builder
+                    .build_insert_value(object.value.into_struct_value(), typed_val.value, index, "mutelem")
+                    .map_err(|e| e.to_string())?
+                    .into_struct_value();
+                self.store_place(&fa.object, new_tuple.into(), assign.line)?;
+                return Ok(TypedValue::new(new_tuple.into(), object.aha_type));
+            }
             let struct_name = match &object.aha_type {
                 AhaType::Struct(name) => name.clone(),
                 other => return Err(format!(
@@ -4623,19 +6070,8 @@ impl<'ctx> CodeGenerator<'ctx> {
             let idx = self.field_index(&struct_name, &fa.field.value)?;
             let declared = self.field_type(&struct_name, &fa.field.value)?;
 
-            // Type-check: the assigned value must match the field's declared type
-            if declared == AhaType::String && !typed_val.aha_type.is_string() {
-                return Err(format!(
-                    "Field '{}' of '{}' expects a string, got {}",
-                    fa.field.value, struct_name, typed_val.aha_type
-                ));
-            }
-            if declared != AhaType::String && typed_val.aha_type.is_string() {
-                return Err(format!(
-                    "Field '{}' of '{}' expects {}, got string",
-                    fa.field.value, struct_name, declared
-                ));
-            }
+            self.check_field_value(&struct_name, &fa.field.value, &declared, &typed_val.aha_type)?;
+            self.mark_stored(&assign.value, &typed_val.aha_type);
 
             // Load the struct from the variable, update the field, store back
             let struct_name_for_var = struct_name.clone();
@@ -4645,14 +6081,9 @@ impl<'ctx> CodeGenerator<'ctx> {
                 .map_err(|e| e.to_string())?
                 .into_struct_value();
 
-            // If the object expression is a variable, store the updated struct back
-            if let ast::Expression::Identifier(id) = &*fa.object {
-                if let Some(info) = self.lookup_variable(&id.value) {
-                    self.builder.build_store(info.ptr, new_struct)
-                        .map_err(|e| e.to_string())?;
-                }
-                // else: it's a temporary, no store needed
-            }
+            // Write the updated struct back through `fa.object`, which may
+            // itself be a field (`line.start.x = 3`).
+            self.store_place(&fa.object, new_struct.into(), assign.line)?;
 
             return Ok(TypedValue::struct_val(new_struct.into(), struct_name_for_var));
         }
@@ -4660,13 +6091,24 @@ impl<'ctx> CodeGenerator<'ctx> {
         // Handle list indexing: xs[i] = value
         if let ast::Expression::Index(index_expr) = &*assign.target {
             let list_tv = self.compile_expression(&index_expr.left)?;
+            // Array element: arr[i] = value, also through fields and
+            // nested arrays (`grid[i][j] = 0`).
+            if let AhaType::Array(elem, _) = &list_tv.aha_type {
+                if !Self::types_compatible(elem, &typed_val.aha_type) {
+                    return Err(format!("Array element expects {}, got {}", elem, typed_val.aha_type));
+                }
+                self.mark_stored(&assign.value, &typed_val.aha_type);
+                self.store_place(&assign.target, typed_val.value, assign.line)?;
+                return Ok(typed_val);
+            }
             let elem_type = match &list_tv.aha_type {
                 AhaType::List(inner) => (**inner).clone(),
                 other => return Err(format!(
-                    "Index assignment target is not a List, got {}", other
+                    "Index assignment target is not an array or List, got {}", other
                 )),
             };
             let index_tv = self.compile_expression(&index_expr.index)?;
+            self.check_list_index(list_tv.value.into_int_value(), index_tv.value.into_int_value(), index_expr.line)?;
 
             // For String lists, the value must be a string; store the full
             // {i8*, i64} struct at data[index*elem_size].
@@ -4737,6 +6179,20 @@ impl<'ctx> CodeGenerator<'ctx> {
                     .map_err(|e| e.to_string())?;
                 return Ok(typed_val);
             }
+            if let Some((ptr, static_type)) = self.statics.get(&id.value).cloned() {
+                if static_type.is_string() != typed_val.aha_type.is_string() {
+                    return Err(format!(
+                        "Static '{}' has type {}, cannot assign {}",
+                        id.value, static_type, typed_val.aha_type
+                    ));
+                }
+                if static_type.is_string() {
+                    return self.store_string_static(&id.value, ptr, typed_val, &assign.value);
+                }
+                self.builder.build_store(ptr, typed_val.value)
+                    .map_err(|e| e.to_string())?;
+                return Ok(typed_val);
+            }
             return Err(format!("Cannot assign to undefined variable: '{}'", id.value));
         }
 
@@ -4747,15 +6203,16 @@ impl<'ctx> CodeGenerator<'ctx> {
     }
 
     fn compile_for_expression(&mut self, for_expr: &ast::ForExpression) -> Result<TypedValue<'ctx>, String> {
+        match &*for_expr.iterable {
+            ast::Expression::Range(range) => self.compile_for_range(for_expr, range),
+            _ => self.compile_for_collection(for_expr),
+        }
+    }
+
+    fn compile_for_range(&mut self, for_expr: &ast::ForExpression, range: &ast::RangeExpression) -> Result<TypedValue<'ctx>, String> {
         let function = self.builder.get_insert_block().expect("Builder not in a block!").get_parent().unwrap();
-        let (start_val, end_val) = match &*for_expr.iterable {
-            ast::Expression::Range(range) => {
-                let start = self.compile_expression(&range.start)?;
-                let end = self.compile_expression(&range.end)?;
-                (start, end)
-            },
-            _ => return Err("for loop currently only supports range expressions (start..end)".to_string()),
-        };
+        let start_val = self.compile_expression(&range.start)?;
+        let end_val = self.compile_expression(&range.end)?;
         let loop_var_ptr = self.builder.build_alloca(self.i64_type, &for_expr.variable.value)
             .map_err(|e| e.to_string())?;
         self.builder.build_store(loop_var_ptr, start_val.value).map_err(|e| e.to_string())?;
@@ -4798,6 +6255,195 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::void(self.i64_type.const_int(0, false).into()))
     }
 
+    /// `for x in xs` over a list, an array, a string or a map: a hidden
+    /// index walks from 0 and each pass copies the element into `x`.
+    /// Lists re-read their length and maps their capacity every pass; a
+    /// map is walked slot by slot, skipping empty slots, and yields
+    /// `(key, value)` tuples. A string yields its bytes as Int, like
+    /// `char_at`.
+    fn compile_for_collection(&mut self, for_expr: &ast::ForExpression) -> Result<TypedValue<'ctx>, String> {
+        let function = self.builder.get_insert_block().expect("Builder not in a block!").get_parent().unwrap();
+        let iterable = self.compile_expression(&for_expr.iterable)?;
+        let i64_t = self.i64_type;
+        let elem_type = match &iterable.aha_type {
+            AhaType::List(inner) => Self::list_element_type(inner),
+            AhaType::Array(elem, _) => (**elem).clone(),
+            AhaType::String => AhaType::Int,
+            AhaType::Map(key, value) => AhaType::Tuple(vec![
+                if key.is_string() { AhaType::String } else { AhaType::Int },
+                Self::list_element_type(value),
+            ]),
+            other => return Err(format!(
+                "line {}: Cannot iterate over a value of type {}: for loops take a range, List, array, String or Map",
+                for_expr.line, other
+            )),
+        };
+
+        let index_ptr = self.entry_alloca(i64_t.into(), "for.index")?;
+        self.builder.build_store(index_ptr, i64_t.const_zero()).map_err(|e| e.to_string())?;
+        let var_ptr = self.entry_alloca(self.aha_type_to_llvm_type(&elem_type)?, &for_expr.variable.value)?;
+        let array_slot = match &iterable.aha_type {
+            AhaType::Array(..) => Some(self.array_storage(&for_expr.iterable, iterable.value)?),
+            _ => None,
+        };
+        let string_len = match &iterable.aha_type {
+            AhaType::String => Some(self.extract_str_len(&iterable)?),
+            _ => None,
+        };
+
+        let cond_block = self.context.append_basic_block(function, "for_cond");
+        let body_block = self.context.append_basic_block(function, "for_body");
+        let increment_block = self.context.append_basic_block(function, "for_incr");
+        let after_block = self.context.append_basic_block(function, "for_after");
+        self.builder.build_unconditional_branch(cond_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(cond_block);
+        let index = self.builder.build_load(index_ptr, "for.i").map_err(|e| e.to_string())?.into_int_value();
+        let bound = match &iterable.aha_type {
+            AhaType::List(_) => {
+                let len_fn = *self.functions.get("list_len").expect("list_len not declared");
+                self.builder.build_call(len_fn, &[iterable.value.into()], "for.len")
+                    .map_err(|e| e.to_string())?
+                    .try_as_basic_value().left().ok_or("list_len returned void")?
+                    .into_int_value()
+            }
+            AhaType::Array(_, len) => i64_t.const_int(*len as u64, false),
+            AhaType::Map(..) => {
+                let header = self.map_header_ptr(iterable.value.into_int_value())?;
+                let cap_ptr = self.builder.build_struct_gep(header, 2, "for.cap_ptr").map_err(|_| "bad map header")?;
+                self.builder.build_load(cap_ptr, "for.cap").map_err(|e| e.to_string())?.into_int_value()
+            }
+            _ => string_len.expect("string length computed before the loop"),
+        };
+        let more = self.builder.build_int_compare(inkwell::IntPredicate::SLT, index, bound, "for_cond")
+            .map_err(|e| e.to_string())?;
+        self.builder.build_conditional_branch(more, body_block, after_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(body_block);
+        let index = self.builder.build_load(index_ptr, "for.i").map_err(|e| e.to_string())?.into_int_value();
+        let element: BasicValueEnum<'ctx> = match &iterable.aha_type {
+            AhaType::List(inner) => self.list_element(iterable.value.into_int_value(), index, inner)?.value,
+            AhaType::Array(..) => {
+                let zero = i64_t.const_zero();
+                let slot = array_slot.expect("array storage found before the loop");
+                let elem_ptr = unsafe { self.builder.build_in_bounds_gep(slot, &[zero, index], "for.elem_ptr") }
+                    .map_err(|e| e.to_string())?;
+                self.builder.build_load(elem_ptr, "for.elem").map_err(|e| e.to_string())?
+            }
+            AhaType::Map(key, value) => {
+                let (key, value) = ((**key).clone(), (**value).clone());
+                self.map_slot_entry(iterable.value.into_int_value(), index, &key, &value, &elem_type, increment_block)?
+            }
+            _ => {
+                let char_at = *self.functions.get("char_at").expect("char_at not declared");
+                self.builder.build_call(char_at, &[iterable.value.into(), index.into()], "for.char")
+                    .map_err(|e| e.to_string())?
+                    .try_as_basic_value().left().ok_or("char_at returned void")?
+            }
+        };
+        self.builder.build_store(var_ptr, element).map_err(|e| e.to_string())?;
+        // `for (k, v) in ...` opens the body with `let (k, v) = __for_item`.
+        if let Some(ast::Statement::LetPattern(let_pat)) = for_expr.body.statements.first() {
+            if for_expr.variable.value == "__for_item" && !self.pattern_is_catch_all(&let_pat.pattern, &elem_type) {
+                return Err(format!(
+                    "line {}: Pattern {} does not match the elements of {}",
+                    for_expr.line, Self::describe_pattern(&let_pat.pattern), iterable.aha_type
+                ));
+            }
+        }
+        self.insert_variable(for_expr.variable.value.clone(), var_ptr, elem_type);
+
+        self.loop_stack.push((increment_block, after_block));
+        self.compile_block_statement(&for_expr.body)?;
+        self.loop_stack.pop();
+        if !self.block_terminated() {
+            self.builder.build_unconditional_branch(increment_block).map_err(|e| e.to_string())?;
+        }
+
+        self.builder.position_at_end(increment_block);
+        let current = self.builder.build_load(index_ptr, "for.cur").map_err(|e| e.to_string())?.into_int_value();
+        let next = self.builder.build_int_add(current, i64_t.const_int(1, false), "for.next")
+            .map_err(|e| e.to_string())?;
+        self.builder.build_store(index_ptr, next).map_err(|e| e.to_string())?;
+        self.builder.build_unconditional_branch(cond_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(after_block);
+        Ok(TypedValue::void(i64_t.const_zero().into()))
+    }
+
+    /// The header of a map handle.
+    fn map_header_ptr(&self, handle: inkwell::values::IntValue<'ctx>) -> Result<PointerValue<'ctx>, String> {
+        let header_ptr = self.map_header_type.ptr_type(inkwell::AddressSpace::default());
+        self.builder.build_int_to_ptr(handle, header_ptr, "map_hdr").map_err(|e| e.to_string())
+    }
+
+    /// Read slot `index` of a map as a `(key, value)` tuple. An empty
+    /// slot branches to `skip`; the builder is left where the slot is
+    /// known to be occupied. Slots are laid out as in `emit_map_combo`.
+    fn map_slot_entry(
+        &mut self,
+        handle: inkwell::values::IntValue<'ctx>,
+        index: inkwell::values::IntValue<'ctx>,
+        key_type: &AhaType,
+        value_type: &AhaType,
+        entry_type: &AhaType,
+        skip: inkwell::basic_block::BasicBlock<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, String> {
+        let i64_t = self.i64_type;
+        let i64_ptr = i64_t.ptr_type(inkwell::AddressSpace::default());
+        let key_sz: u64 = if key_type.is_string() { 16 } else { 8 };
+        let val_sz: u64 = if value_type.is_string() { 16 } else { 8 };
+        let header = self.map_header_ptr(handle)?;
+        let data_ptr = self.builder.build_struct_gep(header, 0, "for.data_ptr").map_err(|_| "bad map header")?;
+        let data = self.builder.build_load(data_ptr, "for.data").map_err(|e| e.to_string())?.into_pointer_value();
+        let offset = self.builder.build_int_mul(index, i64_t.const_int(key_sz + val_sz + 8, false), "for.slot_off")
+            .map_err(|e| e.to_string())?;
+        // The slot's i64 words: key, then value, then the occupied flag.
+        let word = |cg: &Self, at: u64, name: &str| -> Result<PointerValue<'ctx>, String> {
+            let byte_off = cg.builder.build_int_add(offset, i64_t.const_int(at, false), name).map_err(|e| e.to_string())?;
+            let raw = unsafe { cg.builder.build_gep(data, &[byte_off], name) }.map_err(|e| e.to_string())?;
+            cg.builder.build_pointer_cast(raw, i64_ptr, name).map_err(|e| e.to_string())
+        };
+
+        let occupied_ptr = word(self, key_sz + val_sz, "for.occ_ptr")?;
+        let occupied = self.builder.build_load(occupied_ptr, "for.occ").map_err(|e| e.to_string())?.into_int_value();
+        let is_occupied = self.builder.build_int_compare(inkwell::IntPredicate::NE, occupied, i64_t.const_zero(), "for.is_occ")
+            .map_err(|e| e.to_string())?;
+        let function = skip.get_parent().ok_or("loop block outside function")?;
+        let full = self.context.append_basic_block(function, "for.slot");
+        self.builder.build_conditional_branch(is_occupied, full, skip).map_err(|e| e.to_string())?;
+        self.builder.position_at_end(full);
+
+        let mut fields = Vec::with_capacity(2);
+        for (at, field_type) in [(0, key_type), (key_sz, value_type)] {
+            let first = self.builder.build_load(word(self, at, "for.word")?, "for.word").map_err(|e| e.to_string())?;
+            let field = if field_type.is_string() {
+                let len = self.builder.build_load(word(self, at + 8, "for.len_word")?, "for.len").map_err(|e| e.to_string())?;
+                let ptr = self.builder.build_int_to_ptr(first.into_int_value(), self.i8_ptr_type(), "for.str_ptr")
+                    .map_err(|e| e.to_string())?;
+                let string = self.builder.build_insert_value(self.string_type.get_undef(), ptr, 0, "for.str")
+                    .map_err(|e| e.to_string())?;
+                self.builder.build_insert_value(string, len, 1, "for.str")
+                    .map_err(|e| e.to_string())?
+                    .into_struct_value()
+                    .into()
+            } else if let AhaType::Tuple(_) = field_type {
+                self.unbox_tuple(first, field_type)?.value
+            } else {
+                first
+            };
+            fields.push(field);
+        }
+        let AhaType::Tuple(elems) = entry_type else { unreachable!("map entries are tuples") };
+        let mut entry = self.tuple_llvm_type(elems)?.get_undef();
+        for (i, field) in fields.into_iter().enumerate() {
+            entry = self.builder.build_insert_value(entry, field, i as u32, "for.entry")
+                .map_err(|e| e.to_string())?
+                .into_struct_value();
+        }
+        Ok(entry.into())
+    }
+
     fn compile_range_expression(&mut self, range: &ast::RangeExpression) -> Result<TypedValue<'ctx>, String> {
         self.compile_expression(&range.start)
     }
@@ -4809,6 +6455,16 @@ impl<'ctx> CodeGenerator<'ctx> {
     /// resolve layout and check types.
     fn register_structs(&mut self, statements: &[ast::Statement]) {
         for stmt in statements {
+            // Generic structs are instantiated per type-argument list.
+            if let ast::Statement::Struct(def) = stmt {
+                if !def.type_params.is_empty() {
+                    self.generic_struct_defs.insert(def.name.value.clone(), def.clone());
+                    continue;
+                }
+                if def.is_newtype {
+                    self.newtypes.insert(def.name.value.clone());
+                }
+            }
             let fields = match stmt {
                 ast::Statement::Struct(def) => &def.fields,
                 ast::Statement::Actor(def) => &def.fields,
@@ -4831,6 +6487,179 @@ impl<'ctx> CodeGenerator<'ctx> {
         }
     }
 
+    /// Resolve the field types of every non-generic struct with full hint
+    /// resolution (structs, enums, tuples, generic instances, lists and
+    /// maps). `register_structs` only knew the built-in types.
+    fn resolve_struct_fields(&mut self, statements: &[ast::Statement]) -> Result<(), String> {
+        let defs: Vec<&ast::StructDefinition> = statements.iter()
+            .filter_map(|stmt| match stmt {
+                ast::Statement::Struct(def) if def.type_params.is_empty() => Some(def),
+                _ => None,
+            })
+            .collect();
+        for def in &defs {
+            let mut fields = Vec::with_capacity(def.fields.len());
+            for field in &def.fields {
+                let t = match field.type_hint.as_deref() {
+                    Some(hint) => {
+                        let bare = hint.chars().all(|c| c.is_alphanumeric() || c == '_');
+                        if bare && AhaType::from_hint(hint).is_none()
+                            && !self.struct_defs.contains_key(hint)
+                            && !self.enum_defs.contains_key(hint)
+                        {
+                            return Err(format!(
+                                "Unknown type '{}' for field '{}' of '{}'",
+                                hint, field.name.value, def.name.value
+                            ));
+                        }
+                        self.instantiate_hint_generics(hint)?;
+                        self.resolve_hint_type(hint)
+                    }
+                    None => AhaType::Int,
+                };
+                fields.push((field.name.value.clone(), t));
+            }
+            self.struct_defs.insert(def.name.value.clone(), fields);
+        }
+        for def in &defs {
+            let name = &def.name.value;
+            if self.struct_holds_unboxed(&AhaType::Struct(name.clone()), name, &mut Vec::new()) {
+                return Err(format!(
+                    "Recursive struct '{}' has infinite size: wrap the recursive field in Box<{}>",
+                    name, name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Does a value of type `t` hold struct `target` inline, through
+    /// struct fields, tuple elements or enum payloads? Boxes, lists and
+    /// maps don't count.
+    fn struct_holds_unboxed(&self, t: &AhaType, target: &str, seen: &mut Vec<String>) -> bool {
+        match t {
+            AhaType::Struct(name) => {
+                if seen.iter().any(|s| s == name) {
+                    return name == target;
+                }
+                seen.push(name.clone());
+                self.struct_defs.get(name).is_some_and(|fields| {
+                    fields.iter().any(|(_, ft)| {
+                        matches!(ft, AhaType::Struct(n) if n == target)
+                            || self.struct_holds_unboxed(ft, target, seen)
+                    })
+                })
+            }
+            AhaType::Tuple(elems) => elems.iter().any(|e| {
+                matches!(e, AhaType::Struct(n) if n == target) || self.struct_holds_unboxed(e, target, seen)
+            }),
+            AhaType::Enum(name) => {
+                if seen.iter().any(|s| s == name) {
+                    return false;
+                }
+                seen.push(name.clone());
+                self.enum_defs.get(name).is_some_and(|variants| {
+                    variants.iter().flat_map(|(_, ts)| ts).any(|t| {
+                        matches!(t, AhaType::Struct(n) if n == target) || self.struct_holds_unboxed(t, target, seen)
+                    })
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Check a value given for a struct field against its declared type.
+    /// Int and Bool mix freely; list and map element types are settled
+    /// at their first use, as in the type checker.
+    fn check_field_value(&self, struct_name: &str, field: &str, declared: &AhaType, actual: &AhaType) -> Result<(), String> {
+        if *declared == AhaType::String && !actual.is_string() {
+            return Err(format!(
+                "Field '{}' of '{}' expects a string, got {}",
+                field, struct_name, actual
+            ));
+        }
+        if *declared != AhaType::String && actual.is_string() {
+            return Err(format!(
+                "Field '{}' of '{}' expects {}, got string",
+                field, struct_name, declared
+            ));
+        }
+        let fits = Self::types_compatible(declared, actual) || matches!(
+            (declared, actual),
+            (AhaType::List(_), AhaType::List(_)) | (AhaType::Map(_, _), AhaType::Map(_, _))
+        );
+        if !fits {
+            return Err(format!(
+                "Field '{}' of '{}' expects {}, got {}",
+                field, struct_name, declared, actual
+            ));
+        }
+        Ok(())
+    }
+
+    /// A heap local stored into a struct field now lives in the struct,
+    /// so scope cleanup must not free it.
+    fn mark_stored(&mut self, value: &ast::Expression, t: &AhaType) {
+        if let ast::Expression::Identifier(id) = value {
+            if matches!(t, AhaType::List(_) | AhaType::Map(_, _)) || self.needs_drop(t) {
+                self.mark_freed(&id.value);
+            }
+        }
+    }
+
+    /// Store `value` to the place `target` names: a variable, or a field,
+    /// tuple element or array element of a place, rebuilt level by level
+    /// up to its variable. Anything else (a call, a List element) is not
+    /// a place, and assigning through it is an error on `line`.
+    fn store_place(&mut self, target: &ast::Expression, value: BasicValueEnum<'ctx>, line: usize) -> Result<(), String> {
+        match target {
+            ast::Expression::Identifier(id) => {
+                let info = self.lookup_variable(&id.value).ok_or_else(|| format!(
+                    "line {}: Cannot assign through '{}': it is not a local variable", line, id.value
+                ))?;
+                self.builder.build_store(info.ptr, value)
+                    .map_err(|e| e.to_string())?;
+                Ok(())
+            }
+            ast::Expression::FieldAccess(fa) => {
+                let object = self.compile_expression(&fa.object)?;
+                let idx = match &object.aha_type {
+                    AhaType::Struct(name) => self.field_index(name, &fa.field.value)?,
+                    AhaType::Tuple(_) => self.tuple_field_index(&object.aha_type, &fa.field.value)?,
+                    other => return Err(format!(
+                        "line {}: Cannot assign through field '.{}' of non-struct type {}", line, fa.field.value, other
+                    )),
+                };
+                let updated = self.builder
+                    .build_insert_value(object.value.into_struct_value(), value, idx, "mutfield")
+                    .map_err(|e| e.to_string())?
+                    .into_struct_value();
+                self.store_place(&fa.object, updated.into(), line)
+            }
+            ast::Expression::Index(idx) => {
+                let container = self.compile_expression(&idx.left)?;
+                let AhaType::Array(_, len) = container.aha_type else {
+                    return Err(format!(
+                        "line {}: Cannot assign through an element of {}; copy it into a 'let mut', change it and store it back",
+                        line, container.aha_type
+                    ));
+                };
+                let index = self.compile_expression(&idx.index)?;
+                let storage = self.array_storage(&idx.left, container.value)?;
+                let elem_ptr = self.array_element_ptr(storage, index.value.into_int_value(), len, idx.line)?;
+                self.builder.build_store(elem_ptr, value).map_err(|e| e.to_string())?;
+                // A local array was updated in place; an array inside a
+                // field or another array was spilled, so write it back.
+                if self.array_variable_ptr(&idx.left).is_some() {
+                    return Ok(());
+                }
+                let updated = self.builder.build_load(storage, "arr").map_err(|e| e.to_string())?;
+                self.store_place(&idx.left, updated, line)
+            }
+            _ => Err(format!("line {}: Cannot assign through a temporary value; bind it with 'let mut' first", line)),
+        }
+    }
+
     /// Declared type of a struct field.
     fn field_type(&self, struct_name: &str, field: &str) -> Result<AhaType, String> {
         let fields = self.struct_defs.get(struct_name)
@@ -4841,109 +6670,886 @@ impl<'ctx> CodeGenerator<'ctx> {
             .ok_or_else(|| format!("Struct '{}' has no field '{}'", struct_name, field))
     }
 
-    /// LLVM type for a registered struct. Each field uses its declared
-    /// type: String → {i8*, i64}, everything else → i64.
-    fn struct_llvm_type(&self, name: &str) -> Result<inkwell::types::StructType<'ctx>, String> {
-        let fields = self.struct_defs.get(name)
-            .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
-        let field_types: Vec<inkwell::types::BasicTypeEnum<'ctx>> = fields.iter()
-            .map(|(_, t)| match t {
-                AhaType::String => self.string_type.into(),
-                _ => self.i64_type.into(),
-            })
-            .collect();
-        Ok(self.context.struct_type(&field_types, false))
+    /// Register the concrete instance of a generic struct for `args`,
+    /// resolving each field hint with the type params bound. Returns the
+    /// instance's full name; repeated calls reuse the first registration.
+    fn instantiate_struct(&mut self, base: &str, args: Vec<AhaType>) -> Result<String, String> {
+        let def = self.generic_struct_defs.get(base).cloned()
+            .ok_or_else(|| format!("Unknown generic struct '{}'", base))?;
+        if args.len() != def.type_params.len() {
+            return Err(format!(
+                "Struct '{}' expects {}, got {}",
+                base, count_of(def.type_params.len(), "type argument"), args.len()
+            ));
+        }
+        let name = generic_type_name(base, &args);
+        if self.struct_defs.contains_key(&name) {
+            return Ok(name);
+        }
+        // Placeholder first, so a field naming this same instance terminates.
+        self.struct_defs.insert(name.clone(), Vec::new());
+        self.struct_instances.insert(name.clone(), (base.to_string(), args.clone()));
+
+        let bindings: HashMap<String, AhaType> = def.type_params.iter().cloned().zip(args).collect();
+        let saved_tpm = std::mem::replace(&mut self.type_param_map, bindings);
+        let mut fields = Vec::with_capacity(def.fields.len());
+        let mut result = Ok(());
+        for field in &def.fields {
+            let t = match field.type_hint.as_deref() {
+                Some(hint) => {
+                    if let Err(e) = self.instantiate_hint_generics(hint) {
+                        result = Err(e);
+                        break;
+                    }
+                    self.resolve_hint_type(hint)
+                }
+                None => AhaType::Int,
+            };
+            fields.push((field.name.value.clone(), t));
+        }
+        self.type_param_map = saved_tpm;
+        if let Err(e) = result {
+            self.struct_defs.remove(&name);
+            self.struct_instances.remove(&name);
+            return Err(e);
+        }
+        self.struct_defs.insert(name.clone(), fields);
+        Ok(name)
     }
 
-    /// Index of a field within a struct's layout.
-    fn field_index(&self, struct_name: &str, field: &str) -> Result<u32, String> {
-        let fields = self.struct_defs.get(struct_name)
-            .ok_or_else(|| format!("Unknown struct type '{}'", struct_name))?;
-        fields.iter().position(|(f, _)| f == field)
-            .map(|i| i as u32)
-            .ok_or_else(|| format!("Struct '{}' has no field '{}'", struct_name, field))
+    /// Make sure every generic struct or enum named in a type hint
+    /// (`Pair<int, T>`, `List<Option<int>>`, `(Pair<A, B>, int)`) has its
+    /// instance registered, so resolve_hint_type's result has a layout.
+    fn instantiate_hint_generics(&mut self, hint: &str) -> Result<(), String> {
+        if let Some(inner) = hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            for part in split_type_list(inner) {
+                self.instantiate_hint_generics(part)?;
+            }
+            return Ok(());
+        }
+        if let Some((elem, _)) = split_array_hint(hint) {
+            return self.instantiate_hint_generics(elem);
+        }
+        let Some((base, inner)) = hint.strip_suffix('>').and_then(|h| h.split_once('<')) else {
+            if self.generic_struct_defs.contains_key(hint) {
+                return Err(format!(
+                    "Generic struct '{}' needs type arguments, e.g. {}<int>", hint, hint
+                ));
+            }
+            if self.generic_enum_defs.contains_key(hint) {
+                return Err(format!(
+                    "Generic enum '{}' needs type arguments, e.g. {}<int>", hint, hint
+                ));
+            }
+            return Ok(());
+        };
+        let parts = split_type_list(inner);
+        for part in &parts {
+            self.instantiate_hint_generics(part)?;
+        }
+        if self.generic_struct_defs.contains_key(base) {
+            let args = parts.iter().map(|p| self.resolve_hint_type(p)).collect();
+            self.instantiate_struct(base, args)?;
+        } else if self.generic_enum_defs.contains_key(base) {
+            let args = parts.iter().map(|p| self.resolve_hint_type(p)).collect();
+            self.instantiate_enum(base, args)?;
+        }
+        Ok(())
     }
 
-    // --- Enum support ---
+    /// Instantiate the generic structs and enums named in a function's signature.
+    fn instantiate_fn_hints(&mut self, func: &ast::FunctionLiteral) -> Result<(), String> {
+        for hint in func.param_type_hints.iter().flatten() {
+            self.instantiate_hint_generics(hint)?;
+        }
+        if let Some(hint) = &func.return_type_hint {
+            self.instantiate_hint_generics(hint)?;
+        }
+        Ok(())
+    }
 
-    /// Walk top-level statements and record every enum definition's
-    /// variant names + payload types for constructors and match.
-    fn register_enums(&mut self, statements: &[ast::Statement]) {
-        for stmt in statements {
-            if let ast::Statement::Enum(def) = stmt {
-                let variants: Vec<(String, Vec<AhaType>)> = def.variants.iter()
-                    .map(|v| {
-                        let types: Vec<AhaType> = v.payload_types.iter()
-                            .map(|t| {
-                                // Check if t is a known enum name (nested enum)
-                                if self.enum_defs.contains_key(t.as_str()) {
-                                    AhaType::Enum(t.clone())
-                                } else {
-                                    AhaType::from_hint(t).unwrap_or(AhaType::Int)
-                                }
-                            })
-                            .collect();
-                        (v.name.value.clone(), types)
-                    })
-                    .collect();
-                self.enum_defs.insert(def.name.value.clone(), variants);
+    /// Bind type params appearing in `hint` from the matching `actual`
+    /// type: `T` binds directly, and `List<T>`, `Map<K, V>`, tuples and
+    /// generic struct and enum instances are walked structurally. Earlier
+    /// bindings win.
+    fn bind_type_params(&self, params: &[String], hint: &str, actual: &AhaType, bound: &mut HashMap<String, AhaType>) {
+        if params.iter().any(|p| p == hint) {
+            bound.entry(hint.to_string()).or_insert_with(|| actual.clone());
+            return;
+        }
+        if let (Some(inner), AhaType::Tuple(elems)) = (hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')), actual) {
+            for (h, t) in split_type_list(inner).into_iter().zip(elems) {
+                self.bind_type_params(params, h, t, bound);
+            }
+            return;
+        }
+        let Some((base, inner)) = hint.strip_suffix('>').and_then(|h| h.split_once('<')) else { return };
+        let parts = split_type_list(inner);
+        match actual {
+            AhaType::List(elem) if parts.len() == 1 => self.bind_type_params(params, parts[0], elem, bound),
+            AhaType::Box(elem) if base == "Box" => self.bind_type_params(params, inner, elem, bound),
+            // A plain T fills a Box<T> slot (auto-boxed by the constructor).
+            _ if base == "Box" => self.bind_type_params(params, inner, actual, bound),
+            AhaType::Map(k, v) if parts.len() == 2 => {
+                self.bind_type_params(params, parts[0], k, bound);
+                self.bind_type_params(params, parts[1], v, bound);
+            }
+            AhaType::Struct(name) | AhaType::Enum(name) => {
+                let instance = self.struct_instances.get(name).or_else(|| self.enum_instances.get(name));
+                if let Some((generic, args)) = instance {
+                    if generic == base {
+                        for (h, t) in parts.into_iter().zip(args) {
+                            self.bind_type_params(params, h, t, bound);
+                        }
+                    }
+                }
             }
+            _ => {}
         }
     }
 
-    /// Find which enum owns a variant name by scanning all registered enums.
-    fn find_enum_for_variant(&self, variant_name: &str) -> Option<String> {
-        for (enum_name, variants) in &self.enum_defs {
-            if variants.iter().any(|(name, _)| name == variant_name) {
-                return Some(enum_name.clone());
+    /// Type arguments for a generic struct literal, inferred from the
+    /// field values' types. Params no field pins down default to Int.
+    fn infer_struct_type_args(&self, def: &ast::StructDefinition, field_types: &[(String, AhaType)]) -> Vec<AhaType> {
+        let mut bound = HashMap::new();
+        for (field, actual) in field_types {
+            let hint = def.fields.iter()
+                .find(|f| &f.name.value == field)
+                .and_then(|f| f.type_hint.as_deref());
+            if let Some(hint) = hint {
+                self.bind_type_params(&def.type_params, hint, actual, &mut bound);
             }
         }
-        None
+        def.type_params.iter()
+            .map(|p| bound.get(p).cloned().unwrap_or(AhaType::Int))
+            .collect()
     }
 
-    /// LLVM struct type for an enum: {i64 tag, i64, i64, ...} where
-    /// the number of i64 slots after the tag equals the max payload size.
-    fn enum_llvm_type(&self, name: &str) -> Result<inkwell::types::StructType<'ctx>, String> {
-        let variants = self.enum_defs.get(name)
-            .ok_or_else(|| format!("Unknown enum type '{}'", name))?;
-        // Count total payload slots, expanding nested enums recursively.
-        let max_payload: usize = variants.iter()
-            .map(|(_, types)| -> usize {
-                types.iter().map(|t| match t {
-                    AhaType::Enum(inner) => {
-                        // Nested enum: tag + its own payload slots
-                        let inner_variants = self.enum_defs.get(inner).map_or(1, |v| {
-                            1 + v.iter().map(|(_, ts)| ts.len()).max().unwrap_or(0)
-                        });
-                        inner_variants
-                    }
-                    _ => 1,
-                }).sum()
-            })
-            .max()
-            .unwrap_or(0);
-        let mut field_types: Vec<inkwell::types::BasicTypeEnum<'ctx>> =
-            Vec::with_capacity(1 + max_payload);
-        field_types.push(self.i64_type.into()); // tag
-        for _ in 0..max_payload {
-            field_types.push(self.i64_type.into()); // payload slots
-        }
+    /// LLVM type for a registered struct. Each field uses its declared
+    /// type: String → {i8*, i64}, structs/enums/tuples (e.g. a generic
+    /// field bound to `Point`) nest by value, everything else → i64.
+    fn struct_llvm_type(&self, name: &str) -> Result<inkwell::types::StructType<'ctx>, String> {
+        let fields = self.struct_defs.get(name)
+            .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
+        let field_types = fields.iter()
+            .map(|(_, t)| self.aha_type_to_llvm_type(t))
+            .collect::<Result<Vec<_>, String>>()?;
         Ok(self.context.struct_type(&field_types, false))
     }
 
-    /// Variant tag index (0-based, declaration order).
-    fn variant_tag(&self, enum_name: &str, variant_name: &str) -> Result<u64, String> {
-        let variants = self.enum_defs.get(enum_name)
-            .ok_or_else(|| format!("Unknown enum type '{}'", enum_name))?;
-        variants.iter()
-            .position(|(name, _)| name == variant_name)
-            .map(|i| i as u64)
-            .ok_or_else(|| format!("Enum '{}' has no variant '{}'", enum_name, variant_name))
+    /// LLVM layout for a tuple: an anonymous struct with one field per
+    /// element, in order. Unlike named struct fields, elements keep their
+    /// full type, so tuples can hold structs, enums and other tuples.
+    fn tuple_llvm_type(&self, elems: &[AhaType]) -> Result<inkwell::types::StructType<'ctx>, String> {
+        let field_types = elems.iter()
+            .map(|t| self.aha_type_to_llvm_type(t))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(self.context.struct_type(&field_types, false))
     }
 
-    /// Payload types for a variant.
-    fn variant_payload(&self, enum_name: &str, variant_name: &str) -> Result<Vec<AhaType>, String> {
-        let variants = self.enum_defs.get(enum_name)
+    /// Compile `(a, b, ...)` into a tuple value.
+    fn compile_tuple_literal(&mut self, tuple: &ast::TupleLiteral) -> Result<TypedValue<'ctx>, String> {
+        let mut values = Vec::with_capacity(tuple.elements.len());
+        for elem in &tuple.elements {
+            let value = self.compile_expression(elem)?;
+            values.push(self.own_static_read(elem, value)?);
+        }
+        let elem_types: Vec<AhaType> = values.iter().map(|v| v.aha_type.clone()).collect();
+        let mut agg = self.tuple_llvm_type(&elem_types)?.get_undef();
+        for (i, v) in values.iter().enumerate() {
+            agg = self.builder.build_insert_value(agg, v.value, i as u32, "tuple")
+                .map_err(|e| e.to_string())?
+                .into_struct_value();
+        }
+        Ok(TypedValue::new(agg.into(), AhaType::Tuple(elem_types)))
+    }
+
+    /// Read element `index` out of a tuple value.
+    fn tuple_element_value(&mut self, value: &TypedValue<'ctx>, index: usize) -> Result<TypedValue<'ctx>, String> {
+        let elems = match &value.aha_type {
+            AhaType::Tuple(elems) => elems.clone(),
+            other => return Err(format!("Tuple index .{} on non-tuple type {}", index, other)),
+        };
+        let elem_type = elems.get(index).cloned().ok_or_else(|| format!(
+            "Tuple index .{} out of range for {}", index, value.aha_type
+        ))?;
+        let elem = self.builder.build_extract_value(value.value.into_struct_value(), index as u32, "tuple_elem")
+            .map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(elem, elem_type))
+    }
+
+    /// Index of the element `.field` names in a tuple of type `tuple`.
+    fn tuple_field_index(&self, tuple: &AhaType, field: &str) -> Result<u32, String> {
+        let AhaType::Tuple(elems) = tuple else {
+            return Err(format!("Tuple index .{} on non-tuple type {}", field, tuple));
+        };
+        let index = field.parse::<usize>().map_err(|_| format!(
+            "Field access '.{}' on tuple type {}: use .0, .1, ...", field, tuple
+        ))?;
+        if index >= elems.len() {
+            return Err(format!("Tuple index .{} out of range for {}", index, tuple));
+        }
+        Ok(index as u32)
+    }
+
+    /// Lists and maps store 8-byte slots, so a tuple element is copied to
+    /// the heap and its address kept as an i64 handle.
+    fn box_tuple(&mut self, value: &TypedValue<'ctx>) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let tuple_type = match &value.aha_type {
+            AhaType::Tuple(elems) => self.tuple_llvm_type(elems)?,
+            other => return Err(format!("Cannot box non-tuple type {}", other)),
+        };
+        let size = tuple_type.size_of().ok_or("tuple type has no size")?;
+        let malloc_fn = *self.functions.get("malloc").expect("malloc not declared");
+        let raw_ptr = self.builder.build_call(malloc_fn, &[size.into()], "tuple_box")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("malloc returned void")?
+            .into_pointer_value();
+        let ptr = self.builder.build_pointer_cast(
+            raw_ptr,
+            tuple_type.ptr_type(inkwell::AddressSpace::default()),
+            "tuple_box_ptr",
+        ).map_err(|e| e.to_string())?;
+        self.builder.build_store(ptr, value.value).map_err(|e| e.to_string())?;
+        self.builder.build_ptr_to_int(ptr, self.i64_type, "tuple_handle")
+            .map_err(|e| e.to_string())
+    }
+
+    /// Load a tuple back out of a handle produced by `box_tuple`.
+    fn unbox_tuple(&mut self, handle: BasicValueEnum<'ctx>, tuple_type: &AhaType) -> Result<TypedValue<'ctx>, String> {
+        let llvm_type = match tuple_type {
+            AhaType::Tuple(elems) => self.tuple_llvm_type(elems)?,
+            other => return Err(format!("Cannot unbox non-tuple type {}", other)),
+        };
+        let ptr = self.builder.build_int_to_ptr(
+            handle.into_int_value(),
+            llvm_type.ptr_type(inkwell::AddressSpace::default()),
+            "tuple_unbox_ptr",
+        ).map_err(|e| e.to_string())?;
+        let val = self.builder.build_load(ptr, "tuple_unbox").map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(val, tuple_type.clone()))
+    }
+
+    /// Does this List or Map keep its elements as `box_tuple` handles?
+    fn holds_boxed_tuples(t: &AhaType) -> bool {
+        match t {
+            AhaType::List(elem) | AhaType::Map(_, elem) => matches!(**elem, AhaType::Tuple(_)),
+            _ => false,
+        }
+    }
+
+    /// Free the tuple boxes held by a List or Map before the container
+    /// itself is freed. Does nothing for other containers.
+    fn free_boxed_tuples(&mut self, handle: BasicValueEnum<'ctx>, container: &AhaType) -> Result<(), String> {
+        if !Self::holds_boxed_tuples(container) {
+            return Ok(());
+        }
+        let function = self.free_tuples_function(container)?;
+        self.builder.build_call(function, &[handle.into()], "").map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Free the tuple box a Map holds for a key, if any, before `map_set`
+    /// overwrites it or `map_remove` drops it. `args` are the map handle
+    /// and the key; `{prefix}_get` returns 0 for a missing key, and
+    /// freeing null is a no-op.
+    fn free_map_tuple(&mut self, prefix: &str, args: &[BasicValueEnum<'ctx>]) -> Result<(), String> {
+        let get_fn = *self.functions.get(&format!("{}_get", prefix)).expect("map_get not declared");
+        let args_meta: Vec<_> = args.iter().map(|a| (*a).into()).collect();
+        let old = self.builder.build_call(get_fn, &args_meta, "old_tuple")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("map_get returned void")?
+            .into_int_value();
+        let raw_ptr = self.builder.build_int_to_ptr(old, self.i8_ptr_type(), "old_tuple_ptr")
+            .map_err(|e| e.to_string())?;
+        let free_fn = *self.functions.get("free").expect("free not declared");
+        self.builder.build_call(free_fn, &[raw_ptr.into()], "").map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// `free_tuples.<T>(handle)`, emitted once per container type: walks
+    /// the elements of a List, or the occupied slots of a Map (laid out
+    /// as in `emit_map_combo`), and frees each tuple box.
+    fn free_tuples_function(&mut self, container: &AhaType) -> Result<FunctionValue<'ctx>, String> {
+        let name = format!("free_tuples.{}", container);
+        if let Some(f) = self.module.get_function(&name) {
+            return Ok(f);
+        }
+        let i64_t = self.i64_type;
+        let fn_type = self.context.void_type().fn_type(&[i64_t.into()], false);
+        let function = self.module.add_function(&name, fn_type, None);
+        let saved_block = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(function, "entry");
+        let cond = self.context.append_basic_block(function, "cond");
+        let body = self.context.append_basic_block(function, "body");
+        let next = self.context.append_basic_block(function, "next");
+        let done = self.context.append_basic_block(function, "done");
+
+        let result = (|| -> Result<(), String> {
+            self.builder.position_at_end(entry);
+            let handle = function.get_nth_param(0).ok_or("Failed to get parameter")?.into_int_value();
+            let index_ptr = self.builder.build_alloca(i64_t, "i").map_err(|e| e.to_string())?;
+            self.builder.build_store(index_ptr, i64_t.const_zero()).map_err(|e| e.to_string())?;
+            let count = match container {
+                AhaType::Map(..) => {
+                    let header = self.map_header_ptr(handle)?;
+                    let cap_ptr = self.builder.build_struct_gep(header, 2, "cap_ptr").map_err(|_| "bad map header")?;
+                    self.builder.build_load(cap_ptr, "cap").map_err(|e| e.to_string())?.into_int_value()
+                }
+                _ => {
+                    let len_fn = *self.functions.get("list_len").expect("list_len not declared");
+                    self.builder.build_call(len_fn, &[handle.into()], "len")
+                        .map_err(|e| e.to_string())?
+                        .try_as_basic_value().left().ok_or("list_len returned void")?
+                        .into_int_value()
+                }
+            };
+            self.builder.build_unconditional_branch(cond).map_err(|e| e.to_string())?;
+
+            self.builder.position_at_end(cond);
+            let index = self.builder.build_load(index_ptr, "i").map_err(|e| e.to_string())?.into_int_value();
+            let more = self.builder.build_int_compare(inkwell::IntPredicate::SLT, index, count, "more")
+                .map_err(|e| e.to_string())?;
+            self.builder.build_conditional_branch(more, body, done).map_err(|e| e.to_string())?;
+
+            self.builder.position_at_end(body);
+            let tuple = match container {
+                AhaType::Map(key, _) => {
+                    // Slot: key word(s), the value handle, the occupied flag.
+                    let key_sz: u64 = if key.is_string() { 16 } else { 8 };
+                    let i64_ptr = i64_t.ptr_type(inkwell::AddressSpace::default());
+                    let header = self.map_header_ptr(handle)?;
+                    let data_ptr = self.builder.build_struct_gep(header, 0, "data_ptr").map_err(|_| "bad map header")?;
+                    let data = self.builder.build_load(data_ptr, "data").map_err(|e| e.to_string())?.into_pointer_value();
+                    let offset = self.builder.build_int_mul(index, i64_t.const_int(key_sz + 16, false), "slot_off")
+                        .map_err(|e| e.to_string())?;
+                    let word = |cg: &Self, at: u64, name: &str| -> Result<PointerValue<'ctx>, String> {
+                        let byte_off = cg.builder.build_int_add(offset, i64_t.const_int(at, false), name).map_err(|e| e.to_string())?;
+                        let raw = unsafe { cg.builder.build_gep(data, &[byte_off], name) }.map_err(|e| e.to_string())?;
+                        cg.builder.build_pointer_cast(raw, i64_ptr, name).map_err(|e| e.to_string())
+                    };
+                    let occupied = self.builder.build_load(word(self, key_sz + 8, "occ_ptr")?, "occ")
+                        .map_err(|e| e.to_string())?.into_int_value();
+                    let is_occupied = self.builder.build_int_compare(inkwell::IntPredicate::NE, occupied, i64_t.const_zero(), "is_occ")
+                        .map_err(|e| e.to_string())?;
+                    let full = self.context.append_basic_block(function, "full");
+                    self.builder.build_conditional_branch(is_occupied, full, next).map_err(|e| e.to_string())?;
+                    self.builder.position_at_end(full);
+                    self.builder.build_load(word(self, key_sz, "val_ptr")?, "tuple").map_err(|e| e.to_string())?.into_int_value()
+                }
+                _ => {
+                    let get_fn = *self.functions.get("list_get").expect("list_get not declared");
+                    self.builder.build_call(get_fn, &[handle.into(), index.into()], "tuple")
+                        .map_err(|e| e.to_string())?
+                        .try_as_basic_value().left().ok_or("list_get returned void")?
+                        .into_int_value()
+                }
+            };
+            let raw_ptr = self.builder.build_int_to_ptr(tuple, self.i8_ptr_type(), "tuple_ptr")
+                .map_err(|e| e.to_string())?;
+            let free_fn = *self.functions.get("free").expect("free not declared");
+            self.builder.build_call(free_fn, &[raw_ptr.into()], "").map_err(|e| e.to_string())?;
+            self.builder.build_unconditional_branch(next).map_err(|e| e.to_string())?;
+
+            self.builder.position_at_end(next);
+            let following = self.builder.build_int_add(index, i64_t.const_int(1, false), "i_next")
+                .map_err(|e| e.to_string())?;
+            self.builder.build_store(index_ptr, following).map_err(|e| e.to_string())?;
+            self.builder.build_unconditional_branch(cond).map_err(|e| e.to_string())?;
+
+            self.builder.position_at_end(done);
+            self.builder.build_return(None).map_err(|e| e.to_string())?;
+            Ok(())
+        })();
+        if let Some(block) = saved_block {
+            self.builder.position_at_end(block);
+        }
+        result.map(|_| function)
+    }
+
+    /// Move a value to the heap: malloc room for it, store it there and
+    /// return the pointer as an i64 handle of type Box<T>.
+    fn box_value(&mut self, value: TypedValue<'ctx>) -> Result<TypedValue<'ctx>, String> {
+        let llvm_type = self.aha_type_to_llvm_type(&value.aha_type)?;
+        let size = llvm_type.size_of().ok_or("boxed type has no size")?;
+        let malloc_fn = *self.functions.get("malloc").expect("malloc not declared");
+        let raw_ptr = self.builder.build_call(malloc_fn, &[size.into()], "box")
+            .map_err(|e| e.to_string())?
+            .try_as_basic_value().left().ok_or("malloc returned void")?
+            .into_pointer_value();
+        let ptr = self.builder.build_pointer_cast(
+            raw_ptr,
+            llvm_type.ptr_type(inkwell::AddressSpace::default()),
+            "box_ptr",
+        ).map_err(|e| e.to_string())?;
+        self.builder.build_store(ptr, value.value).map_err(|e| e.to_string())?;
+        let handle = self.builder.build_ptr_to_int(ptr, self.i64_type, "box_handle")
+            .map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(handle.into(), AhaType::Box(Box::new(value.aha_type))))
+    }
+
+    /// Load the value a Box<T> handle points to.
+    fn unbox_value(&mut self, boxed: &TypedValue<'ctx>) -> Result<TypedValue<'ctx>, String> {
+        let inner = match &boxed.aha_type {
+            AhaType::Box(inner) => (**inner).clone(),
+            other => return Err(format!("Cannot unbox non-Box type {}", other)),
+        };
+        let llvm_type = self.aha_type_to_llvm_type(&inner)?;
+        let ptr = self.builder.build_int_to_ptr(
+            boxed.value.into_int_value(),
+            llvm_type.ptr_type(inkwell::AddressSpace::default()),
+            "unbox_ptr",
+        ).map_err(|e| e.to_string())?;
+        let val = self.builder.build_load(ptr, "unbox").map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(val, inner))
+    }
+
+    /// Index of a field within a struct's layout.
+    fn field_index(&self, struct_name: &str, field: &str) -> Result<u32, String> {
+        let fields = self.struct_defs.get(struct_name)
+            .ok_or_else(|| format!("Unknown struct type '{}'", struct_name))?;
+        fields.iter().position(|(f, _)| f == field)
+            .map(|i| i as u32)
+            .ok_or_else(|| format!("Struct '{}' has no field '{}'", struct_name, field))
+    }
+
+    // --- Enum support ---
+
+    /// Walk top-level statements and record every enum definition's
+    /// variant names + payload types for constructors and match.
+    /// All names are registered before any payload resolves, so
+    /// `Add(Box<Expr>, Box<Expr>)` can refer to the enum being defined.
+    fn register_enums(&mut self, statements: &[ast::Statement]) -> Result<(), String> {
+        let defs: Vec<&ast::EnumDefinition> = statements.iter()
+            .filter_map(|stmt| match stmt {
+                ast::Statement::Enum(def) => Some(def),
+                _ => None,
+            })
+            .collect();
+        for stmt in statements {
+            if let ast::Statement::UseVariants(open) = stmt {
+                self.open_enums.insert(open.enum_name.value.clone());
+            }
+        }
+        for def in &defs {
+            // Generic enums are instantiated per type-argument list.
+            if def.type_params.is_empty() {
+                self.enum_defs.insert(def.name.value.clone(), Vec::new());
+            } else {
+                self.generic_enum_defs.insert(def.name.value.clone(), (*def).clone());
+            }
+        }
+        for def in &defs {
+            self.register_discriminants(def)?;
+        }
+        for def in defs.iter().filter(|d| d.type_params.is_empty()) {
+            let mut variants = Vec::with_capacity(def.variants.len());
+            for v in &def.variants {
+                let mut types = Vec::with_capacity(v.payload_types.len());
+                for hint in &v.payload_types {
+                    let bare = hint.chars().all(|c| c.is_alphanumeric() || c == '_');
+                    if bare && AhaType::from_hint(hint).is_none()
+                        && !self.struct_defs.contains_key(hint)
+                        && !self.enum_defs.contains_key(hint)
+                    {
+                        return Err(format!(
+                            "Unknown type '{}' in payload of '{}::{}'",
+                            hint, def.name.value, v.name.value
+                        ));
+                    }
+                    self.instantiate_hint_generics(hint)?;
+                    types.push(self.resolve_hint_type(hint));
+                }
+                variants.push((v.name.value.clone(), types));
+            }
+            self.enum_defs.insert(def.name.value.clone(), variants);
+        }
+        for def in defs.iter().filter(|d| d.type_params.is_empty()) {
+            let name = &def.name.value;
+            if self.enum_holds_unboxed(name, name, &mut Vec::new()) {
+                return Err(format!(
+                    "Recursive enum '{}' has infinite size: wrap the recursive payload in Box<{}>",
+                    name, name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Number the variants of a non-generic enum: an explicit `= value`,
+    /// else one past the previous variant (0 for the first), checked for
+    /// clashes and against the `#[repr]` tag width.
+    fn register_discriminants(&mut self, def: &ast::EnumDefinition) -> Result<(), String> {
+        let name = &def.name.value;
+        let explicit = def.variants.iter().find(|v| v.discriminant.is_some());
+        if !def.type_params.is_empty() {
+            if def.repr.is_some() || explicit.is_some() {
+                return Err(format!("Generic enum '{}' cannot declare discriminants or a repr", name));
+            }
+            return Ok(());
+        }
+        if let (Some(_), Some(v)) = (explicit, def.variants.iter().find(|v| !v.payload_types.is_empty())) {
+            return Err(format!(
+                "Enum '{}' cannot give its variants discriminants: '{}::{}' carries a payload",
+                name, name, v.name.value
+            ));
+        }
+        let (bits, signed) = match def.repr.as_deref() {
+            None => (64, true),
+            Some(repr) => Self::repr_width(repr).ok_or_else(|| format!(
+                "Unknown repr '{}' for enum '{}': expected u8, u16, u32, u64, i8, i16, i32 or i64",
+                repr, name
+            ))?,
+        };
+        let mut values: Vec<i64> = Vec::with_capacity(def.variants.len());
+        let mut next = Some(0i64);
+        for (i, v) in def.variants.iter().enumerate() {
+            let value = match v.discriminant {
+                Some(d) => d,
+                None => next.ok_or_else(|| format!(
+                    "Discriminant of '{}::{}' overflows int", name, v.name.value
+                ))?,
+            };
+            let fits = match (bits, signed) {
+                (64, true) => true,
+                (64, false) => value >= 0,
+                (_, true) => value >= -(1i64 << (bits - 1)) && value < (1i64 << (bits - 1)),
+                (_, false) => value >= 0 && value < (1i64 << bits),
+            };
+            if !fits {
+                return Err(format!(
+                    "Discriminant {} of '{}::{}' does not fit #[repr({})]",
+                    value, name, v.name.value, def.repr.as_deref().unwrap_or("i64")
+                ));
+            }
+            if let Some(j) = values.iter().position(|&seen| seen == value) {
+                return Err(format!(
+                    "Enum '{}' gives discriminant {} to both '{}' and '{}'",
+                    name, value, def.variants[j].name.value, def.variants[i].name.value
+                ));
+            }
+            values.push(value);
+            next = value.checked_add(1);
+        }
+        self.enum_discriminants.insert(name.clone(), values);
+        if def.repr.is_some() {
+            self.enum_reprs.insert(name.clone(), (bits, signed));
+        }
+        Ok(())
+    }
+
+    /// Bit width and signedness of a `#[repr(...)]` tag type.
+    fn repr_width(repr: &str) -> Option<(u32, bool)> {
+        let signed = match repr.chars().next()? {
+            'i' => true,
+            'u' => false,
+            _ => return None,
+        };
+        match &repr[1..] {
+            "8" => Some((8, signed)),
+            "16" => Some((16, signed)),
+            "32" => Some((32, signed)),
+            "64" => Some((64, signed)),
+            _ => None,
+        }
+    }
+
+    /// Does enum `name` hold `target` inline, directly or through other
+    /// unboxed enum payloads and tuples? Boxed payloads don't count.
+    /// Cycles through structs are caught by `resolve_struct_fields`.
+    fn enum_holds_unboxed(&self, name: &str, target: &str, seen: &mut Vec<String>) -> bool {
+        if seen.iter().any(|s| s == name) {
+            return false;
+        }
+        seen.push(name.to_string());
+        let Some(variants) = self.enum_defs.get(name) else { return false };
+        variants.iter().flat_map(|(_, ts)| ts).any(|t| self.type_holds_enum(t, target, seen))
+    }
+
+    fn type_holds_enum(&self, t: &AhaType, target: &str, seen: &mut Vec<String>) -> bool {
+        match t {
+            AhaType::Enum(inner) => inner == target || self.enum_holds_unboxed(inner, target, seen),
+            AhaType::Tuple(elems) => elems.iter().any(|e| self.type_holds_enum(e, target, seen)),
+            _ => false,
+        }
+    }
+
+    /// Find which enum owns a variant. `Color::Red` names the enum; a
+    /// bare name must belong to one enum, or to one enum opened with
+    /// `use Color::*` (typeck rejects the ambiguous rest). For a generic
+    /// enum this is the generic name (`Option`), not one of its
+    /// instances; the constructor picks the instance.
+    fn find_enum_for_variant(&self, qualifier: Option<&str>, variant_name: &str) -> Option<String> {
+        let mut owners: Vec<String> = self.enum_defs.iter()
+            .filter(|(enum_name, _)| !self.enum_instances.contains_key(*enum_name))
+            .filter(|(_, variants)| variants.iter().any(|(name, _)| name == variant_name))
+            .map(|(enum_name, _)| enum_name.clone())
+            .chain(self.generic_enum_defs.iter()
+                .filter(|(_, def)| def.variants.iter().any(|v| v.name.value == variant_name))
+                .map(|(enum_name, _)| enum_name.clone()))
+            .collect();
+        if let Some(qualifier) = qualifier {
+            return owners.into_iter().find(|o| o == qualifier);
+        }
+        if owners.len() > 1 {
+            owners.retain(|o| self.open_enums.contains(o));
+        }
+        (owners.len() == 1).then(|| owners.remove(0))
+    }
+
+    /// The (enum, variant) a callee constructs, if it is an enum
+    /// constructor: `Red`, or `Color::Red` where `Color` is an enum
+    /// (`math::add` is a module path, not a variant).
+    fn variant_callee(&self, function: &ast::Expression) -> Option<(String, String)> {
+        let (qualifier, name) = match function {
+            ast::Expression::Identifier(id) => (None, &id.value),
+            ast::Expression::ModuleAccess(ma) => {
+                let is_enum = (self.enum_defs.contains_key(&ma.module) && !self.enum_instances.contains_key(&ma.module))
+                    || self.generic_enum_defs.contains_key(&ma.module);
+                (is_enum.then_some(ma.module.as_str()), &ma.name)
+            }
+            _ => return None,
+        };
+        self.find_enum_for_variant(qualifier, name).map(|e| (e, name.clone()))
+    }
+
+    /// Generic name of an enum instance (`Option<Int>` → `Option`); other
+    /// enum names are returned as-is.
+    fn enum_base_name(enum_name: &str) -> &str {
+        enum_name.split_once('<').map_or(enum_name, |(base, _)| base)
+    }
+
+    /// Register the concrete instance of a generic enum for `args`,
+    /// resolving each payload hint with the type params bound. Returns the
+    /// instance's full name; repeated calls reuse the first registration.
+    fn instantiate_enum(&mut self, base: &str, args: Vec<AhaType>) -> Result<String, String> {
+        let def = self.generic_enum_defs.get(base).cloned()
+            .ok_or_else(|| format!("Unknown generic enum '{}'", base))?;
+        if args.len() != def.type_params.len() {
+            return Err(format!(
+                "Enum '{}' expects {}, got {}",
+                base, count_of(def.type_params.len(), "type argument"), args.len()
+            ));
+        }
+        let name = generic_type_name(base, &args);
+        if self.enum_defs.contains_key(&name) {
+            return Ok(name);
+        }
+        // Placeholder first, so `Box<Tree<T>>` naming this same instance terminates.
+        self.enum_defs.insert(name.clone(), Vec::new());
+        self.enum_instances.insert(name.clone(), (base.to_string(), args.clone()));
+
+        let bindings: HashMap<String, AhaType> = def.type_params.iter().cloned().zip(args.clone()).collect();
+        let saved_tpm = std::mem::replace(&mut self.type_param_map, bindings);
+        let result = (|| -> Result<Vec<(String, Vec<AhaType>)>, String> {
+            let mut variants = Vec::with_capacity(def.variants.len());
+            for v in &def.variants {
+                let mut types = Vec::with_capacity(v.payload_types.len());
+                for hint in &v.payload_types {
+                    self.instantiate_hint_generics(hint)?;
+                    let t = self.resolve_hint_type(hint);
+                    if t == AhaType::Enum(name.clone()) {
+                        return Err(format!(
+                            "Recursive enum '{}' has infinite size: wrap the recursive payload in Box<{}>",
+                            name, hint
+                        ));
+                    }
+                    types.push(t);
+                }
+                variants.push((v.name.value.clone(), types));
+            }
+            Ok(variants)
+        })();
+        self.type_param_map = saved_tpm;
+        match result {
+            Ok(variants) => {
+                self.enum_defs.insert(name.clone(), variants);
+                Ok(name)
+            }
+            Err(e) => {
+                self.enum_defs.remove(&name);
+                self.enum_instances.remove(&name);
+                Err(e)
+            }
+        }
+    }
+
+    /// Type arguments for a generic enum constructor, inferred from the
+    /// argument types at the variant's payload positions. Params the
+    /// variant doesn't mention (`None()`) default to Int.
+    fn infer_enum_type_args(&self, def: &ast::EnumDefinition, variant_name: &str, arg_types: &[AhaType]) -> Vec<AhaType> {
+        let mut bound = HashMap::new();
+        if let Some(v) = def.variants.iter().find(|v| v.name.value == variant_name) {
+            for (hint, actual) in v.payload_types.iter().zip(arg_types) {
+                self.bind_type_params(&def.type_params, hint, actual, &mut bound);
+            }
+        }
+        def.type_params.iter()
+            .map(|p| bound.get(p).cloned().unwrap_or(AhaType::Int))
+            .collect()
+    }
+
+    /// LLVM layout of an enum: a tag (i64 unless `#[repr]` narrows it)
+    /// followed by a union of payload words, sized to the largest
+    /// variant. Each variant packs its payload from the first word (see
+    /// `payload_word_count`); every leaf is at most 8 bytes, so i64 words
+    /// keep each one aligned.
+    fn enum_llvm_type(&self, name: &str) -> Result<inkwell::types::StructType<'ctx>, String> {
+        let variants = self.enum_defs.get(name)
+            .ok_or_else(|| format!("Unknown enum type '{}'", name))?;
+        let mut max_words = 0;
+        for (_, types) in variants {
+            let mut words = 0;
+            for t in types {
+                words += Self::payload_word_count(self.aha_type_to_llvm_type(t)?);
+            }
+            max_words = max_words.max(words);
+        }
+        let mut field_types: Vec<inkwell::types::BasicTypeEnum<'ctx>> =
+            Vec::with_capacity(1 + max_words);
+        field_types.push(self.enum_tag_type(name).into());
+        for _ in 0..max_words {
+            field_types.push(self.i64_type.into()); // payload words
+        }
+        Ok(self.context.struct_type(&field_types, false))
+    }
+
+    /// Number of i64 words a value of this LLVM type takes in an enum
+    /// payload: one per integer or pointer leaf of its layout.
+    fn payload_word_count(ty: inkwell::types::BasicTypeEnum<'ctx>) -> usize {
+        match ty {
+            inkwell::types::BasicTypeEnum::StructType(st) => {
+                st.get_field_types().into_iter().map(Self::payload_word_count).sum()
+            }
+            inkwell::types::BasicTypeEnum::ArrayType(at) => {
+                at.len() as usize * Self::payload_word_count(at.get_element_type())
+            }
+            _ => 1,
+        }
+    }
+
+    /// Split a payload value into i64 words, leaf by leaf.
+    fn push_payload_words(&self, value: BasicValueEnum<'ctx>, words: &mut Vec<inkwell::values::IntValue<'ctx>>) -> Result<(), String> {
+        match value {
+            BasicValueEnum::StructValue(sv) => {
+                for i in 0..sv.get_type().count_fields() {
+                    let field = self.builder.build_extract_value(sv, i, "leaf")
+                        .map_err(|e| e.to_string())?;
+                    self.push_payload_words(field, words)?;
+                }
+            }
+            BasicValueEnum::ArrayValue(av) => {
+                for i in 0..av.get_type().len() {
+                    let elem = self.builder.build_extract_value(av, i, "leaf")
+                        .map_err(|e| e.to_string())?;
+                    self.push_payload_words(elem, words)?;
+                }
+            }
+            BasicValueEnum::PointerValue(ptr) => {
+                words.push(self.builder.build_ptr_to_int(ptr, self.i64_type, "word")
+                    .map_err(|e| e.to_string())?);
+            }
+            BasicValueEnum::IntValue(int) => {
+                words.push(self.builder.build_int_z_extend_or_bit_cast(int, self.i64_type, "word")
+                    .map_err(|e| e.to_string())?);
+            }
+            other => return Err(format!("Unsupported enum payload value of type {}", other.get_type())),
+        }
+        Ok(())
+    }
+
+    /// Rebuild a value of LLVM type `ty` from payload words, starting at
+    /// `*pos` and advancing past the words it uses.
+    fn pop_payload_words(&self, ty: inkwell::types::BasicTypeEnum<'ctx>, words: &[inkwell::values::IntValue<'ctx>], pos: &mut usize) -> Result<BasicValueEnum<'ctx>, String> {
+        match ty {
+            inkwell::types::BasicTypeEnum::StructType(st) => {
+                let mut val = st.const_zero();
+                for (i, field_type) in st.get_field_types().into_iter().enumerate() {
+                    let field = self.pop_payload_words(field_type, words, pos)?;
+                    val = self.builder.build_insert_value(val, field, i as u32, "leaf")
+                        .map_err(|e| e.to_string())?
+                        .into_struct_value();
+                }
+                Ok(val.into())
+            }
+            inkwell::types::BasicTypeEnum::ArrayType(at) => {
+                let mut val = at.const_zero();
+                for i in 0..at.len() {
+                    let elem = self.pop_payload_words(at.get_element_type(), words, pos)?;
+                    val = self.builder.build_insert_value(val, elem, i, "leaf")
+                        .map_err(|e| e.to_string())?
+                        .into_array_value();
+                }
+                Ok(val.into())
+            }
+            inkwell::types::BasicTypeEnum::PointerType(pt) => {
+                let word = words[*pos];
+                *pos += 1;
+                Ok(self.builder.build_int_to_ptr(word, pt, "leaf").map_err(|e| e.to_string())?.into())
+            }
+            inkwell::types::BasicTypeEnum::IntType(it) => {
+                let word = words[*pos];
+                *pos += 1;
+                Ok(self.builder.build_int_truncate_or_bit_cast(word, it, "leaf").map_err(|e| e.to_string())?.into())
+            }
+            other => Err(format!("Unsupported enum payload type {}", other)),
+        }
+    }
+
+    /// Variant tag: its discriminant, which defaults to the 0-based
+    /// declaration index.
+    fn variant_tag(&self, enum_name: &str, variant_name: &str) -> Result<i64, String> {
+        let variants = self.enum_defs.get(enum_name)
+            .ok_or_else(|| format!("Unknown enum type '{}'", enum_name))?;
+        let i = variants.iter()
+            .position(|(name, _)| name == variant_name)
+            .ok_or_else(|| format!("Enum '{}' has no variant '{}'", enum_name, variant_name))?;
+        Ok(self.enum_discriminants.get(enum_name).map_or(i as i64, |values| values[i]))
+    }
+
+    /// LLVM type of an enum's tag field.
+    fn enum_tag_type(&self, enum_name: &str) -> inkwell::types::IntType<'ctx> {
+        match self.enum_reprs.get(enum_name) {
+            Some(&(bits, _)) => self.context.custom_width_int_type(bits),
+            None => self.i64_type,
+        }
+    }
+
+    /// Read an enum value's tag, widened to i64 by its repr's signedness.
+    fn load_enum_tag(&self, enum_name: &str, value: inkwell::values::StructValue<'ctx>) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let tag = self.builder.build_extract_value(value, 0, "tag")
+            .map_err(|e| e.to_string())?
+            .into_int_value();
+        match self.enum_reprs.get(enum_name) {
+            Some(&(bits, true)) if bits < 64 => self.builder.build_int_s_extend(tag, self.i64_type, "tag_wide"),
+            Some(&(bits, false)) if bits < 64 => self.builder.build_int_z_extend(tag, self.i64_type, "tag_wide"),
+            _ => return Ok(tag),
+        }.map_err(|e| e.to_string())
+    }
+
+    /// Build a value of `enum_name` holding `variant` and its payload.
+    fn build_enum_value(&self, enum_name: &str, variant: &str, payload: &[BasicValueEnum<'ctx>]) -> Result<inkwell::values::StructValue<'ctx>, String> {
+        let tag = self.variant_tag(enum_name, variant)?;
+        let mut val = self.enum_llvm_type(enum_name)?.const_zero();
+        val = self.builder.build_insert_value(val, self.enum_tag_type(enum_name).const_int(tag as u64, true), 0, "tag")
+            .map_err(|e| e.to_string())?
+            .into_struct_value();
+        // Pack the payload into words 1, 2, ...; unused words stay zero.
+        let mut words = Vec::new();
+        for value in payload {
+            self.push_payload_words(*value, &mut words)?;
+        }
+        for (i, word) in words.into_iter().enumerate() {
+            val = self.builder.build_insert_value(val, word, (i + 1) as u32, "payload")
+                .map_err(|e| e.to_string())?
+                .into_struct_value();
+        }
+        Ok(val)
+    }
+
+    /// Payload types for a variant.
+    fn variant_payload(&self, enum_name: &str, variant_name: &str) -> Result<Vec<AhaType>, String> {
+        let variants = self.enum_defs.get(enum_name)
             .ok_or_else(|| format!("Unknown enum type '{}'", enum_name))?;
         variants.iter()
             .find(|(name, _)| name == variant_name)
@@ -4951,72 +7557,169 @@ impl<'ctx> CodeGenerator<'ctx> {
             .ok_or_else(|| format!("Enum '{}' has no variant '{}'", enum_name, variant_name))
     }
 
-    /// Compile an enum constructor call: `Variant(args...)` or `Variant`.
-    /// Called from compile_call when the name matches a registered enum variant.
-    fn compile_enum_constructor(&mut self, enum_name: &str, variant_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
-        let tag = self.variant_tag(enum_name, variant_name)?;
-        let payload_types = self.variant_payload(enum_name, variant_name)?;
-        let enum_type = self.enum_llvm_type(enum_name)?;
+    /// Compile an enum constructor call: `Variant(args...)` or `Variant`.
+    /// Called from compile_call when the name matches a registered enum variant.
+    /// A generic enum's instance comes from the expected type when that
+    /// names one (`let o: Option<bool> = None()`), else from the argument
+    /// types (`Some(1)` is `Option<Int>`).
+    fn compile_enum_constructor(&mut self, enum_name: &str, variant_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
+        let generic = self.generic_enum_defs.get(enum_name).cloned();
+        let instance = match (&generic, &self.expected_type) {
+            (None, _) => Some(enum_name.to_string()),
+            (Some(_), Some(AhaType::Enum(expected))) if Self::enum_base_name(expected) == enum_name => Some(expected.clone()),
+            _ => None,
+        };
+        // Known payload types become the expected types of the arguments.
+        let payload_hints: Vec<Option<AhaType>> = match (&instance, &generic) {
+            (Some(name), _) => self.variant_payload(name, variant_name)?.into_iter().map(Some).collect(),
+            (None, def) => {
+                let arity = def.iter()
+                    .flat_map(|d| &d.variants)
+                    .find(|v| v.name.value == variant_name)
+                    .map(|v| v.payload_types.len())
+                    .ok_or_else(|| format!("Enum '{}' has no variant '{}'", enum_name, variant_name))?;
+                vec![None; arity]
+            }
+        };
+
+        // Verify argument count matches payload arity.
+        if call.arguments.len() != payload_hints.len() {
+            return Err(format!(
+                "Enum variant '{}::{}' expects {}, got {}",
+                enum_name, variant_name, count_of(payload_hints.len(), "argument"), call.arguments.len()
+            ));
+        }
+
+        let mut values = Vec::with_capacity(call.arguments.len());
+        for (arg, expected) in call.arguments.iter().zip(payload_hints) {
+            let expected = match expected {
+                Some(AhaType::Box(inner)) => Some(*inner),
+                other => other,
+            };
+            let value = self.compile_owned(arg, expected)?;
+            self.mark_moved(arg, &value.aha_type);
+            values.push(value);
+        }
+        let enum_name = match (instance, generic) {
+            (Some(name), _) => name,
+            (None, def) => {
+                let observed: Vec<AhaType> = values.iter().map(|v| v.aha_type.clone()).collect();
+                let args = def.map(|d| self.infer_enum_type_args(&d, variant_name, &observed)).unwrap_or_default();
+                self.instantiate_enum(enum_name, args)?
+            }
+        };
+        let enum_name = enum_name.as_str();
+        let payload_types = self.variant_payload(enum_name, variant_name)?;
+
+        let mut payload = Vec::with_capacity(values.len());
+        for (i, tv) in values.into_iter().enumerate() {
+            let expected = &payload_types[i];
+            // Box<T> slots take a plain T too: `Add(Num(1), Num(2))`.
+            let tv = match expected {
+                AhaType::Box(inner) if Self::types_compatible(&tv.aha_type, inner) => self.box_value(tv)?,
+                _ => tv,
+            };
+            if !Self::types_compatible(&tv.aha_type, expected) {
+                return Err(format!(
+                    "Enum variant '{}::{}' arg {} expects {}, got {}",
+                    enum_name, variant_name, i, expected, tv.aha_type
+                ));
+            }
+            payload.push(tv.value);
+        }
+        let val = self.build_enum_value(enum_name, variant_name, &payload)?;
+        Ok(TypedValue::new(val.into(), AhaType::Enum(enum_name.to_string())))
+    }
+
+    /// `Opcode::from_int(x)` when `from_int` isn't one of the enum's own
+    /// variants: the enum named, if it is a non-generic enum.
+    fn enum_from_int_callee(&self, function: &ast::Expression) -> Option<String> {
+        let ast::Expression::ModuleAccess(ma) = function else { return None };
+        let is_enum = self.enum_defs.contains_key(&ma.module) && !self.enum_instances.contains_key(&ma.module);
+        (ma.name == "from_int" && is_enum && self.variant_tag(&ma.module, "from_int").is_err())
+            .then(|| ma.module.clone())
+    }
+
+    /// The `Option<T>` instance a builtin such as `E::from_int` or
+    /// `checked_add` (named by `producer`) returns. There is no builtin
+    /// Option, so the program must declare the usual one.
+    fn option_instance(&mut self, inner: AhaType, producer: &str) -> Result<String, String> {
+        let declared = self.generic_enum_defs.get("Option").is_some_and(|def| {
+            let param = def.type_params.first();
+            def.type_params.len() == 1
+                && def.variants.iter().any(|v| v.name.value == "Some" && v.payload_types.iter().eq(param))
+                && def.variants.iter().any(|v| v.name.value == "None" && v.payload_types.is_empty())
+        });
+        if !declared {
+            return Err(format!(
+                "{} returns Option<{}>: declare `enum Option<T> {{ Some(T), None }}`",
+                producer, inner
+            ));
+        }
+        self.instantiate_enum("Option", vec![inner])
+    }
 
-        // Verify argument count matches payload arity.
-        if call.arguments.len() != payload_types.len() {
+    /// Compile `Opcode::from_int(x)`: `Some(variant)` when `x` is one of
+    /// the enum's discriminants, else `None`.
+    fn compile_enum_from_int(&mut self, enum_name: &str, call: &ast::CallExpression) -> Result<TypedValue<'ctx>, String> {
+        let variants = self.enum_defs.get(enum_name).cloned().unwrap_or_default();
+        if let Some((variant, _)) = variants.iter().find(|(_, types)| !types.is_empty()) {
             return Err(format!(
-                "Enum variant '{}::{}' expects {} arguments, got {}",
-                enum_name, variant_name, payload_types.len(), call.arguments.len()
+                "`{}::from_int` needs an enum without payloads; '{}::{}' carries one",
+                enum_name, enum_name, variant
             ));
         }
+        if call.arguments.len() != 1 {
+            return Err(format!("`{}::from_int` expects 1 argument, got {}", enum_name, call.arguments.len()));
+        }
+        let option = self.option_instance(AhaType::Enum(enum_name.to_string()), &format!("`{}::from_int`", enum_name))?;
+        let arg = self.compile_expression(&call.arguments[0])?;
+        if !Self::types_compatible(&arg.aha_type, &AhaType::Int) {
+            return Err(format!("`{}::from_int` expects Int, got {}", enum_name, arg.aha_type));
+        }
+        let x = arg.value.into_int_value();
 
-        let mut val = enum_type.const_zero();
-        // Set tag (field 0).
-        val = self.builder.build_insert_value(val, self.i64_type.const_int(tag, false), 0, "tag")
+        let mut valid = self.context.bool_type().const_zero();
+        for (variant, _) in &variants {
+            let key = self.i64_type.const_int(self.variant_tag(enum_name, variant)? as u64, false);
+            let hit = self.builder.build_int_compare(inkwell::IntPredicate::EQ, x, key, "is_variant")
+                .map_err(|e| e.to_string())?;
+            valid = self.builder.build_or(valid, hit, "valid").map_err(|e| e.to_string())?;
+        }
+        let tag = self.builder.build_int_truncate_or_bit_cast(x, self.enum_tag_type(enum_name), "tag")
+            .map_err(|e| e.to_string())?;
+        let value = self.builder.build_insert_value(self.enum_llvm_type(enum_name)?.const_zero(), tag, 0, "value")
             .map_err(|e| e.to_string())?
             .into_struct_value();
+        let some = self.build_enum_value(&option, "Some", &[value.into()])?;
+        let none = self.build_enum_value(&option, "None", &[])?;
+        let result = self.builder.build_select(valid, some, none, "from_int").map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(result, AhaType::Enum(option)))
+    }
 
-        // Set payload fields (field 1, 2, ...).
-        let mut field_idx: u32 = 1;
-        for (i, arg) in call.arguments.iter().enumerate() {
-            let tv = self.compile_expression(arg)?;
-            let expected = &payload_types[i];
-            if !Self::types_compatible(&tv.aha_type, expected) {
-                return Err(format!(
-                    "Enum variant '{}::{}' arg {} expects {}, got {}",
-                    enum_name, variant_name, i, expected, tv.aha_type
-                ));
-            }
-            if let AhaType::Enum(_) = &tv.aha_type {
-                // Flatten nested enum: extract tag + payload fields as separate i64s
-                let inner_struct = tv.value.into_struct_value();
-                let inner_tag = self.builder.build_extract_value(inner_struct, 0, "inner_tag")
-                    .map_err(|e| e.to_string())?;
-                val = self.builder.build_insert_value(val, inner_tag, field_idx, "payload")
-                    .map_err(|e| e.to_string())?
-                    .into_struct_value();
-                field_idx += 1;
-                // Extract inner payload slots
-                let inner_variants = self.enum_defs.get(match &tv.aha_type {
-                    AhaType::Enum(n) => n.as_str(),
-                    _ => unreachable!(),
-                });
-                let inner_max_payload = inner_variants.map_or(0, |v| {
-                    v.iter().map(|(_, ts)| ts.len()).max().unwrap_or(0)
-                });
-                for j in 0..inner_max_payload {
-                    let slot = self.builder.build_extract_value(inner_struct, (j + 1) as u32, "inner_payload")
-                        .map_err(|e| e.to_string())?;
-                    val = self.builder.build_insert_value(val, slot, field_idx, "payload")
-                        .map_err(|e| e.to_string())?
-                        .into_struct_value();
-                    field_idx += 1;
+    /// Compile `value as int`. Enums without payloads convert to their
+    /// discriminant; ints and bools pass through.
+    fn compile_cast(&mut self, cast: &ast::CastExpression) -> Result<TypedValue<'ctx>, String> {
+        if cast.target != "int" {
+            return Err(format!("line {}: Cannot cast to '{}': only `as int` is supported", cast.line, cast.target));
+        }
+        let value = self.compile_expression(&cast.value)?;
+        match &value.aha_type {
+            AhaType::Int | AhaType::Bool => Ok(TypedValue::int(value.value)),
+            AhaType::Enum(name) => {
+                let payload = self.enum_defs.get(name)
+                    .and_then(|vs| vs.iter().find(|(_, types)| !types.is_empty()));
+                if let Some((variant, _)) = payload {
+                    return Err(format!(
+                        "line {}: Cannot cast {} to int: '{}::{}' carries a payload",
+                        cast.line, name, name, variant
+                    ));
                 }
-            } else {
-                val = self.builder.build_insert_value(val, tv.value, field_idx, "payload")
-                    .map_err(|e| e.to_string())?
-                    .into_struct_value();
-                field_idx += 1;
+                let tag = self.load_enum_tag(name, value.value.into_struct_value())?;
+                Ok(TypedValue::int(tag.into()))
             }
+            other => Err(format!("line {}: Cannot cast {} to int", cast.line, other)),
         }
-
-        Ok(TypedValue::new(val.into(), AhaType::Enum(enum_name.to_string())))
     }
 
     /// Loose type compatibility check for enum payloads (Int~Bool, same name).
@@ -5024,29 +7727,60 @@ impl<'ctx> CodeGenerator<'ctx> {
         if a == b { return true; }
         match (a, b) {
             (AhaType::Int, AhaType::Bool) | (AhaType::Bool, AhaType::Int) => true,
+            (AhaType::Array(a, n), AhaType::Array(b, m)) => n == m && Self::types_compatible(a, b),
             _ => false,
         }
     }
 
-    /// Compile: match expr { Pattern => body, ... }
+    /// Compile: match expr { Pattern [if guard] => body, ... }
+    ///
+    /// Arms whose patterns are plain constants (enum tags, integer literals,
+    /// `|` of those) with no guards lower to a single `switch`; anything
+    /// else (ranges, strings, guards) becomes a compare chain where each
+    /// failed test or guard falls through to the next arm.
     fn compile_match_expression(&mut self, m: &ast::MatchExpression) -> Result<TypedValue<'ctx>, String> {
-        let scrutinee = self.compile_expression(&m.value)?;
-        let enum_name = match &scrutinee.aha_type {
-            AhaType::Enum(name) => name.clone(),
-            _ => return Err(format!(
-                "match requires an enum value, got {}", scrutinee.aha_type
+        let mut scrutinee = self.compile_expression(&m.value)?;
+        while matches!(scrutinee.aha_type, AhaType::Box(_)) {
+            scrutinee = self.unbox_value(&scrutinee)?;
+        }
+        match &scrutinee.aha_type {
+            AhaType::Enum(_) | AhaType::Struct(_) | AhaType::Tuple(_) | AhaType::Int | AhaType::String => {}
+            other => return Err(format!(
+                "match requires an enum, struct, tuple, Int or String value, got {}", other
             )),
-        };
+        }
+
+        // Int, String, struct and tuple values can't be matched exhaustively by
+        // literals, so require an unguarded catch-all arm.
+        let has_catch_all = m.arms.iter()
+            .any(|a| a.guard.is_none() && self.pattern_is_catch_all(&a.pattern, &scrutinee.aha_type));
+        if !has_catch_all && !matches!(scrutinee.aha_type, AhaType::Enum(_)) {
+            return Err(format!(
+                "Non-exhaustive match on {}: add a `_` arm", scrutinee.aha_type
+            ));
+        }
+        // An enum match needs an unguarded arm naming every variant: a
+        // guard can fail, so a guarded arm covers nothing.
+        if let (false, AhaType::Enum(enum_name)) = (has_catch_all, &scrutinee.aha_type) {
+            let mut covered = Vec::new();
+            for arm in m.arms.iter().filter(|a| a.guard.is_none()) {
+                self.pattern_variants(&arm.pattern, enum_name, &mut covered);
+            }
+            let missing: Vec<String> = self.enum_defs.get(enum_name).into_iter().flatten()
+                .filter(|(variant, _)| !covered.contains(variant))
+                .map(|(variant, _)| format!("'{}'", variant))
+                .collect();
+            if !missing.is_empty() {
+                return Err(format!(
+                    "line {}: Non-exhaustive match on {}: {} not covered by an unguarded arm; add it or a `_` arm",
+                    m.line, enum_name, missing.join(", ")
+                ));
+            }
+        }
 
-        let enum_type = self.enum_llvm_type(&enum_name)?;
         let current_fn = self.current_function.ok_or("match outside function")?;
         let merge_block = self.context.append_basic_block(current_fn, "match.merge");
 
-        // Load the tag (field 0) for branching.
-        let tag_val = self.builder.build_extract_value(scrutinee.value.into_struct_value(), 0, "tag")
-            .map_err(|e| e.to_string())?
-            .into_int_value();
-
         // Create blocks for each arm.
         let arm_count = m.arms.len();
         let mut arm_blocks = Vec::with_capacity(arm_count);
@@ -5055,153 +7789,643 @@ impl<'ctx> CodeGenerator<'ctx> {
             arm_blocks.push(bb);
         }
 
-        // Build switch cases: collect all (IntValue, BasicBlock) pairs.
-        // For exhaustive matches (no wildcard), default goes to an unreachable
-        // dead block — never executed but satisfies LLVM IR predecessor requirements.
-        let default_bb = if let Some(wi) = m.arms.iter().position(|a| matches!(a.pattern, ast::Pattern::Wildcard)) {
-            arm_blocks[wi]
+        // For enum matches without a catch-all, unmatched values go to a
+        // dead block. Every variant has an arm, but an arm with refutable
+        // sub-patterns (`Some(0)`) can still miss, so the block panics.
+        let dead = self.context.append_basic_block(current_fn, "match.dead");
+        let saved_block = self.builder.get_insert_block();
+        self.builder.position_at_end(dead);
+        self.build_panic("no match arm matched the value", m.line)?;
+        if let Some(prev) = saved_block {
+            self.builder.position_at_end(prev);
+        }
+
+        // `fallthrough[i]` is where control goes when arm i's pattern or
+        // guard fails: the next arm's test, or the dead block after the last.
+        let mut fallthrough = Vec::with_capacity(arm_count);
+        if let Some(cases) = self.match_switch_cases(m, &scrutinee)? {
+            let (default_bb, cases): (_, Vec<_>) = match cases {
+                (Some(d), c) => (arm_blocks[d], c),
+                (None, c) => (dead, c),
+            };
+            if cases.is_empty() {
+                // Only a catch-all arm (e.g. a struct pattern) — nothing to switch on.
+                self.builder.build_unconditional_branch(default_bb).map_err(|e| e.to_string())?;
+            } else {
+                let key = self.match_switch_key(&scrutinee)?;
+                let cases: Vec<(inkwell::values::IntValue<'ctx>, inkwell::basic_block::BasicBlock<'ctx>)> = cases.iter()
+                    .map(|(k, i)| (self.i64_type.const_int(*k as u64, true), arm_blocks[*i]))
+                    .collect();
+                self.builder.build_switch(key, default_bb, &cases)
+                    .map_err(|e| e.to_string())?;
+            }
+            fallthrough.resize(arm_count, dead);
         } else {
-            let saved_block = self.builder.get_insert_block();
-            let dead = self.context.append_basic_block(current_fn, "match.dead");
-            self.builder.position_at_end(dead);
-            self.builder.build_unreachable().map_err(|e| e.to_string())?;
-            if let Some(prev) = saved_block {
-                self.builder.position_at_end(prev);
+            let mut test_blocks = Vec::with_capacity(arm_count);
+            for i in 0..arm_count {
+                test_blocks.push(self.context.append_basic_block(current_fn, &format!("match.test{}", i)));
             }
-            dead
-        };
-        let mut cases: Vec<(inkwell::values::IntValue<'ctx>, inkwell::basic_block::BasicBlock<'ctx>)> = Vec::new();
-        for (i, arm) in m.arms.iter().enumerate() {
-            if let ast::Pattern::EnumUnit(name) | ast::Pattern::EnumTuple(name, _) = &arm.pattern {
-                let tag = self.variant_tag(&enum_name, name)?;
-                let case_val = self.i64_type.const_int(tag, false);
-                cases.push((case_val, arm_blocks[i]));
+            self.builder.build_unconditional_branch(test_blocks.first().copied().unwrap_or(dead))
+                .map_err(|e| e.to_string())?;
+            for (i, arm) in m.arms.iter().enumerate() {
+                let next = test_blocks.get(i + 1).copied().unwrap_or(dead);
+                self.builder.position_at_end(test_blocks[i]);
+                let hit = self.compile_pattern_test(&arm.pattern, &scrutinee)?;
+                self.builder.build_conditional_branch(hit, arm_blocks[i], next)
+                    .map_err(|e| e.to_string())?;
+                fallthrough.push(next);
             }
         }
-        self.builder.build_switch(tag_val, default_bb, &cases)
-            .map_err(|e| e.to_string())?;
 
-        // Compile each arm body.
+        // Compile each arm: bindings, optional guard, then the body.
         let mut results: Vec<(BasicValueEnum<'ctx>, AhaType, inkwell::basic_block::BasicBlock<'ctx>)> = Vec::new();
         for (i, arm) in m.arms.iter().enumerate() {
             self.builder.position_at_end(arm_blocks[i]);
             self.enter_scope();
+            self.bind_pattern(&arm.pattern, &scrutinee)?;
 
-            // Destructure bindings for tuple patterns.
-            if let ast::Pattern::EnumTuple(name, bindings) = &arm.pattern {
-                let payload = self.variant_payload(&enum_name, name)?;
-                let mut field_idx: u32 = 1;
-                for (j, binding) in bindings.iter().enumerate() {
-                    if j >= payload.len() { break; }
-                    if let AhaType::Enum(inner_name) = &payload[j] {
-                        // Reconstruct nested enum from flattened fields
-                        let inner_type = self.enum_llvm_type(inner_name)?;
-                        let mut inner_val = inner_type.const_zero();
-                        // Extract inner tag
-                        let inner_tag = self.builder.build_extract_value(
-                            scrutinee.value.into_struct_value(),
-                            field_idx,
-                            "inner_tag",
-                        ).map_err(|e| e.to_string())?;
-                        inner_val = self.builder.build_insert_value(inner_val, inner_tag, 0, "tag")
-                            .map_err(|e| e.to_string())?
-                            .into_struct_value();
-                        field_idx += 1;
-                        // Extract inner payload slots
-                        let inner_variants = self.enum_defs.get(inner_name.as_str());
-                        let inner_max_payload = inner_variants.map_or(0, |v| {
-                            v.iter().map(|(_, ts)| ts.len()).max().unwrap_or(0)
-                        });
-                        for k in 0..inner_max_payload {
-                            let slot = self.builder.build_extract_value(
-                                scrutinee.value.into_struct_value(),
-                                field_idx,
-                                "inner_payload",
-                            ).map_err(|e| e.to_string())?;
-                            inner_val = self.builder.build_insert_value(inner_val, slot, (k + 1) as u32, "payload")
-                                .map_err(|e| e.to_string())?
-                                .into_struct_value();
-                            field_idx += 1;
-                        }
-                        let ptr = self.builder.build_alloca(inner_type, binding)
-                            .map_err(|e| e.to_string())?;
-                        self.builder.build_store(ptr, inner_val).map_err(|e| e.to_string())?;
-                        self.insert_variable(binding.clone(), ptr, payload[j].clone());
-                    } else {
-                        let field_val = self.builder.build_extract_value(
-                            scrutinee.value.into_struct_value(),
-                            field_idx,
-                            "destructure",
-                        ).map_err(|e| e.to_string())?;
-                        let ptr = self.builder.build_alloca(
-                            self.aha_type_to_llvm_type(&payload[j])?,
-                            binding,
-                        ).map_err(|e| e.to_string())?;
-                        self.builder.build_store(ptr, field_val).map_err(|e| e.to_string())?;
-                        self.insert_variable(binding.clone(), ptr, payload[j].clone());
-                        field_idx += 1;
-                    }
+            let body_block = if let Some(guard) = &arm.guard {
+                let cond = self.compile_expression(guard)?;
+                if !matches!(cond.aha_type, AhaType::Bool | AhaType::Int) {
+                    return Err(format!("match guard must be Bool, got {}", cond.aha_type));
                 }
-            }
+                let cond_bool = self.builder.build_int_compare(
+                    inkwell::IntPredicate::NE,
+                    cond.value.into_int_value(),
+                    self.i64_type.const_int(0, false),
+                    "guard_bool",
+                ).map_err(|e| e.to_string())?;
+                let body_bb = self.context.append_basic_block(current_fn, &format!("match.body{}", i));
+                self.builder.build_conditional_branch(cond_bool, body_bb, fallthrough[i])
+                    .map_err(|e| e.to_string())?;
+                self.builder.position_at_end(body_bb);
+                body_bb
+            } else {
+                arm_blocks[i]
+            };
 
             let tv = self.compile_expression(&arm.body)?;
             self.exit_scope();
             // After compiling the body, the builder may have moved to a
             // different block (e.g. nested match merge block). Ensure BOTH
-            // the original arm block and the current block terminate.
+            // the original body block and the current block terminate.
             let end_block = self.builder.get_insert_block().unwrap();
-            if !end_block.get_terminator().is_some() {
-                self.builder.build_unconditional_branch(merge_block).map_err(|e| e.to_string())?;
+            if end_block.get_terminator().is_some() {
+                // Body diverged (return/break/continue) — no value flows to merge.
+                continue;
             }
-            // The original arm block may lack a terminator if the body
+            self.builder.build_unconditional_branch(merge_block).map_err(|e| e.to_string())?;
+            // The original body block may lack a terminator if the body
             // created nested blocks. Wire it to the end block so LLVM's
             // verifier is satisfied.
-            if !arm_blocks[i].get_terminator().is_some() {
-                self.builder.position_at_end(arm_blocks[i]);
+            if body_block.get_terminator().is_none() {
+                self.builder.position_at_end(body_block);
                 self.builder.build_unconditional_branch(end_block).map_err(|e| e.to_string())?;
             }
             results.push((tv.value, tv.aha_type, end_block));
         }
 
-        // Merge: phi node across all arms.
+        // Merge: phi node across all arms that reach it.
         self.builder.position_at_end(merge_block);
+        if results.is_empty() {
+            self.builder.build_unreachable().map_err(|e| e.to_string())?;
+            let after = self.context.append_basic_block(current_fn, "match.after");
+            self.builder.position_at_end(after);
+            return Ok(TypedValue::int(self.i64_type.const_int(0, false).into()));
+        }
         let result_type = &results[0].1;
+        for (_, typ, _) in &results[1..] {
+            if typ != result_type && !Self::types_compatible(typ, result_type) {
+                return Err(format!(
+                    "match arms have different types: {} and {}", result_type, typ
+                ));
+            }
+        }
         let phi_llvm_type = self.aha_type_to_llvm_type(result_type)?;
         let phi = self.builder.build_phi(phi_llvm_type, "match.result").map_err(|e| e.to_string())?;
         for (val, _typ, block) in &results {
-            // ponytail: every arm block branches to merge — add all unconditionally
             phi.add_incoming(&[(val as &dyn inkwell::values::BasicValue, *block)]);
         }
 
         Ok(TypedValue::new(phi.as_basic_value(), result_type.clone()))
     }
 
-    fn compile_struct_literal(&mut self, lit: &ast::StructLiteral) -> Result<TypedValue<'ctx>, String> {
-        let struct_name = lit.name.value.clone();
-        let struct_type = self.struct_llvm_type(&struct_name)?;
+    /// True when `name` is a variant of the scrutinee's enum, so a bare
+    /// identifier pattern means that unit variant rather than a binding.
+    fn names_enum_variant(&self, name: &str, scrutinee_type: &AhaType) -> bool {
+        match scrutinee_type {
+            AhaType::Enum(enum_name) => self.variant_tag(enum_name, name).is_ok(),
+            AhaType::Box(inner) => self.names_enum_variant(name, inner),
+            _ => false,
+        }
+    }
 
-        // Compile each provided field's value and place it at the correct
-        // index defined by the struct declaration order. Missing fields
-        // default to 0. field_index() rejects unknown fields.
-        let mut struct_val = struct_type.const_zero();
+    /// Resolve a variant path from a pattern (`Variant` or `Enum::Variant`)
+    /// against the scrutinee's enum, returning the bare variant name.
+    fn pattern_variant<'p>(&self, path: &'p str, enum_name: &str) -> Result<&'p str, String> {
+        match path.rsplit_once("::") {
+            // `Option::Some` qualifies every instance of a generic enum.
+            Some((qualifier, _)) if qualifier != Self::enum_base_name(enum_name) => Err(format!(
+                "Pattern '{}' belongs to enum '{}', but the value is {}",
+                path, qualifier, enum_name
+            )),
+            Some((_, variant)) => Ok(variant),
+            None => Ok(path),
+        }
+    }
+
+    /// Does a struct pattern's name refer to this struct type? A generic
+    /// struct's name covers all of its instances: `Pair { .. }` matches
+    /// `Pair<Int, String>`.
+    fn struct_pattern_names(&self, pattern_name: &str, struct_name: &str) -> bool {
+        pattern_name == struct_name
+            || self.struct_instances.get(struct_name).is_some_and(|(base, _)| base == pattern_name)
+    }
+
+    /// Collect the variants of `enum_name` that a pattern names at its top
+    /// level, whatever its sub-patterns.
+    fn pattern_variants(&self, pattern: &ast::Pattern, enum_name: &str, out: &mut Vec<String>) {
+        match pattern {
+            ast::Pattern::EnumUnit(path) | ast::Pattern::EnumTuple(path, _) | ast::Pattern::Binding(path) => {
+                if let Ok(variant) = self.pattern_variant(path, enum_name) {
+                    out.push(variant.to_string());
+                }
+            }
+            ast::Pattern::Or(alts) => {
+                for alt in alts {
+                    self.pattern_variants(alt, enum_name, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Does this pattern match every value of the scrutinee type?
+    fn pattern_is_catch_all(&self, pattern: &ast::Pattern, scrutinee_type: &AhaType) -> bool {
+        if let AhaType::Box(inner) = scrutinee_type {
+            return self.pattern_is_catch_all(pattern, inner);
+        }
+        match pattern {
+            ast::Pattern::Wildcard | ast::Pattern::MutBinding(_) => true,
+            ast::Pattern::Binding(name) => !self.names_enum_variant(name, scrutinee_type),
+            ast::Pattern::Or(alts) => alts.iter().any(|p| self.pattern_is_catch_all(p, scrutinee_type)),
+            ast::Pattern::Struct(name, fields) => match scrutinee_type {
+                AhaType::Struct(s) if self.struct_pattern_names(name, s) => fields.iter().all(|(f, p)| {
+                    self.field_type(s, f)
+                        .map(|t| self.pattern_is_catch_all(p, &t))
+                        .unwrap_or(false)
+                }),
+                _ => false,
+            },
+            ast::Pattern::Tuple(subs) => match scrutinee_type {
+                // Arity mismatches are reported when the pattern binds.
+                AhaType::Tuple(elems) => subs.iter().zip(elems).all(|(p, t)| self.pattern_is_catch_all(p, t)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Collect the constant keys a pattern switches on (enum tags or integer
+    /// literals), or None if the pattern needs a runtime comparison.
+    fn pattern_switch_keys(&self, pattern: &ast::Pattern, scrutinee_type: &AhaType) -> Result<Option<Vec<i64>>, String> {
+        match (pattern, scrutinee_type) {
+            (ast::Pattern::EnumUnit(path), AhaType::Enum(enum_name)) => {
+                let variant = self.pattern_variant(path, enum_name)?;
+                Ok(Some(vec![self.variant_tag(enum_name, variant)?]))
+            }
+            (ast::Pattern::EnumTuple(path, subs), AhaType::Enum(enum_name)) => {
+                // Nested refutable sub-patterns need the compare chain.
+                let variant = self.pattern_variant(path, enum_name)?;
+                let payload = self.variant_payload(enum_name, variant)?;
+                if subs.iter().zip(payload.iter()).any(|(p, t)| !self.pattern_is_catch_all(p, t)) {
+                    return Ok(None);
+                }
+                Ok(Some(vec![self.variant_tag(enum_name, variant)?]))
+            }
+            (ast::Pattern::Binding(name), AhaType::Enum(enum_name)) if self.names_enum_variant(name, scrutinee_type) => {
+                Ok(Some(vec![self.variant_tag(enum_name, name)?]))
+            }
+            (ast::Pattern::Integer(n), AhaType::Int) => Ok(Some(vec![*n])),
+            (ast::Pattern::Or(alts), _) => {
+                let mut keys = Vec::new();
+                for alt in alts {
+                    match self.pattern_switch_keys(alt, scrutinee_type)? {
+                        Some(k) => keys.extend(k),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some(keys))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Switch cases `(key, arm index)` plus the default arm, when every arm
+    /// up to the first catch-all is an unguarded constant pattern.
+    /// Returns None when the match needs a compare chain instead.
+    #[allow(clippy::type_complexity)]
+    fn match_switch_cases(&self, m: &ast::MatchExpression, scrutinee: &TypedValue<'ctx>) -> Result<Option<(Option<usize>, Vec<(i64, usize)>)>, String> {
+        let mut cases: Vec<(i64, usize)> = Vec::new();
+        for (i, arm) in m.arms.iter().enumerate() {
+            if arm.guard.is_some() {
+                return Ok(None);
+            }
+            if self.pattern_is_catch_all(&arm.pattern, &scrutinee.aha_type) {
+                return Ok(Some((Some(i), cases)));
+            }
+            match self.pattern_switch_keys(&arm.pattern, &scrutinee.aha_type)? {
+                Some(keys) => {
+                    for k in keys {
+                        // Earlier arms win; a duplicate key is simply unreachable.
+                        if !cases.iter().any(|(seen, _)| *seen == k) {
+                            cases.push((k, i));
+                        }
+                    }
+                }
+                None => return Ok(None),
+            }
+        }
+        Ok(Some((None, cases)))
+    }
+
+    /// The integer a switch dispatches on: the tag for enums, the value for Int.
+    fn match_switch_key(&self, scrutinee: &TypedValue<'ctx>) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        match &scrutinee.aha_type {
+            AhaType::Enum(name) => self.load_enum_tag(name, scrutinee.value.into_struct_value()),
+            _ => Ok(scrutinee.value.into_int_value()),
+        }
+    }
+
+    /// Emit an i1 that is true when `pattern` matches the scrutinee.
+    fn compile_pattern_test(&mut self, pattern: &ast::Pattern, scrutinee: &TypedValue<'ctx>) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let bool_type = self.context.bool_type();
+        // Boxes are matched through: the pattern tests the boxed value.
+        if matches!(scrutinee.aha_type, AhaType::Box(_)) && !matches!(pattern, ast::Pattern::Wildcard) {
+            let inner = self.unbox_value(scrutinee)?;
+            return self.compile_pattern_test(pattern, &inner);
+        }
+        match (pattern, &scrutinee.aha_type) {
+            (ast::Pattern::Wildcard, _) => Ok(bool_type.const_int(1, false)),
+            (ast::Pattern::Binding(name), AhaType::Enum(enum_name)) if self.names_enum_variant(name, &scrutinee.aha_type) => {
+                let enum_name = enum_name.clone();
+                self.compile_tag_test(&enum_name, name, scrutinee)
+            }
+            (ast::Pattern::Binding(_) | ast::Pattern::MutBinding(_), _) => Ok(bool_type.const_int(1, false)),
+            (ast::Pattern::EnumUnit(path), AhaType::Enum(enum_name)) => {
+                let enum_name = enum_name.clone();
+                let variant = self.pattern_variant(path, &enum_name)?.to_string();
+                self.compile_tag_test(&enum_name, &variant, scrutinee)
+            }
+            (ast::Pattern::EnumTuple(path, subs), AhaType::Enum(enum_name)) => {
+                let enum_name = enum_name.clone();
+                let variant = self.pattern_variant(path, &enum_name)?.to_string();
+                let tag_hit = self.compile_tag_test(&enum_name, &variant, scrutinee)?;
+                let payload = self.enum_payload_values(&enum_name, &variant, subs.len(), scrutinee)?;
+                let sub_tests = |cg: &mut Self| -> Result<inkwell::values::IntValue<'ctx>, String> {
+                    let mut acc = bool_type.const_int(1, false);
+                    for (sub, value) in subs.iter().zip(payload.iter()) {
+                        if cg.pattern_is_catch_all(sub, &value.aha_type) {
+                            continue;
+                        }
+                        let hit = cg.compile_pattern_test(sub, value)?;
+                        acc = cg.builder.build_and(acc, hit, "pat_and").map_err(|e| e.to_string())?;
+                    }
+                    Ok(acc)
+                };
+                // A Box slot only holds a valid pointer when the tag matches,
+                // so sub-patterns that look inside one run behind the tag test.
+                let derefs_box = subs.iter().zip(payload.iter()).any(|(sub, value)| {
+                    matches!(value.aha_type, AhaType::Box(_)) && !self.pattern_is_catch_all(sub, &value.aha_type)
+                });
+                if derefs_box {
+                    return self.compile_guarded_test(tag_hit, sub_tests);
+                }
+                let rest = sub_tests(self)?;
+                self.builder.build_and(tag_hit, rest, "pat_and").map_err(|e| e.to_string())
+            }
+            (ast::Pattern::Struct(name, fields), AhaType::Struct(struct_name)) if self.struct_pattern_names(name, struct_name) => {
+                let mut acc = bool_type.const_int(1, false);
+                for (field, sub) in fields {
+                    let value = self.struct_field_value(struct_name, field, scrutinee)?;
+                    if self.pattern_is_catch_all(sub, &value.aha_type) {
+                        continue;
+                    }
+                    let hit = self.compile_pattern_test(sub, &value)?;
+                    acc = self.builder.build_and(acc, hit, "pat_and").map_err(|e| e.to_string())?;
+                }
+                Ok(acc)
+            }
+            (ast::Pattern::Tuple(subs), AhaType::Tuple(_)) => {
+                let mut acc = bool_type.const_int(1, false);
+                let values = self.tuple_pattern_values(subs.len(), scrutinee)?;
+                for (sub, value) in subs.iter().zip(values.iter()) {
+                    if self.pattern_is_catch_all(sub, &value.aha_type) {
+                        continue;
+                    }
+                    let hit = self.compile_pattern_test(sub, value)?;
+                    acc = self.builder.build_and(acc, hit, "pat_and").map_err(|e| e.to_string())?;
+                }
+                Ok(acc)
+            }
+            (ast::Pattern::Integer(n), AhaType::Int) => {
+                self.builder.build_int_compare(
+                    inkwell::IntPredicate::EQ,
+                    scrutinee.value.into_int_value(),
+                    self.i64_type.const_int(*n as u64, true),
+                    "pat_eq",
+                ).map_err(|e| e.to_string())
+            }
+            (ast::Pattern::Range(lo, hi), AhaType::Int) => {
+                let v = scrutinee.value.into_int_value();
+                let ge = self.builder.build_int_compare(
+                    inkwell::IntPredicate::SGE, v, self.i64_type.const_int(*lo as u64, true), "pat_ge",
+                ).map_err(|e| e.to_string())?;
+                let le = self.builder.build_int_compare(
+                    inkwell::IntPredicate::SLE, v, self.i64_type.const_int(*hi as u64, true), "pat_le",
+                ).map_err(|e| e.to_string())?;
+                self.builder.build_and(ge, le, "pat_range").map_err(|e| e.to_string())
+            }
+            (ast::Pattern::Str(text), AhaType::String) => {
+                let lit = self.compile_expression(&ast::Expression::String(ast::StringLiteral { value: text.clone() }))?;
+                let eq = self.compile_string_compare(scrutinee, &lit, "==")?;
+                self.builder.build_int_compare(
+                    inkwell::IntPredicate::NE,
+                    eq.value.into_int_value(),
+                    self.i64_type.const_int(0, false),
+                    "pat_streq",
+                ).map_err(|e| e.to_string())
+            }
+            (ast::Pattern::Or(alts), _) => {
+                let mut acc = bool_type.const_int(0, false);
+                for alt in alts {
+                    let hit = self.compile_pattern_test(alt, scrutinee)?;
+                    acc = self.builder.build_or(acc, hit, "pat_or").map_err(|e| e.to_string())?;
+                }
+                Ok(acc)
+            }
+            (p, t) => Err(format!(
+                "Pattern {} cannot match a value of type {}", Self::describe_pattern(p), t
+            )),
+        }
+    }
+
+    /// `guard && rest(..)`, where `rest` only runs when `guard` holds —
+    /// for tests that are unsafe otherwise, like loading through a Box.
+    fn compile_guarded_test(
+        &mut self,
+        guard: inkwell::values::IntValue<'ctx>,
+        rest: impl FnOnce(&mut Self) -> Result<inkwell::values::IntValue<'ctx>, String>,
+    ) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let guard_block = self.builder.get_insert_block().ok_or("pattern test outside a block")?;
+        let function = guard_block.get_parent().ok_or("pattern test outside function")?;
+        let rest_block = self.context.append_basic_block(function, "pat.guarded");
+        let merge_block = self.context.append_basic_block(function, "pat.guard_merge");
+        self.builder.build_conditional_branch(guard, rest_block, merge_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(rest_block);
+        let hit = rest(self)?;
+        let rest_end = self.builder.get_insert_block().ok_or("pattern test outside a block")?;
+        self.builder.build_unconditional_branch(merge_block).map_err(|e| e.to_string())?;
+
+        self.builder.position_at_end(merge_block);
+        let phi = self.builder.build_phi(self.context.bool_type(), "pat_guarded").map_err(|e| e.to_string())?;
+        phi.add_incoming(&[(&guard, guard_block), (&hit, rest_end)]);
+        Ok(phi.as_basic_value().into_int_value())
+    }
+
+    /// Compare an enum scrutinee's tag against a variant.
+    fn compile_tag_test(&mut self, enum_name: &str, variant: &str, scrutinee: &TypedValue<'ctx>) -> Result<inkwell::values::IntValue<'ctx>, String> {
+        let tag = self.variant_tag(enum_name, variant)?;
+        let tag_val = self.load_enum_tag(enum_name, scrutinee.value.into_struct_value())?;
+        self.builder.build_int_compare(
+            inkwell::IntPredicate::EQ,
+            tag_val,
+            self.i64_type.const_int(tag as u64, false),
+            "pat_tag",
+        ).map_err(|e| e.to_string())
+    }
+
+    /// Does matching this pattern introduce any variable bindings?
+    fn pattern_binds(&self, pattern: &ast::Pattern, scrutinee_type: &AhaType) -> bool {
+        match pattern {
+            ast::Pattern::Binding(name) => !self.names_enum_variant(name, scrutinee_type),
+            ast::Pattern::MutBinding(_) => true,
+            // Sub-pattern types only matter for resolving bare variant
+            // names, and those are capitalised, so Int is a safe stand-in.
+            ast::Pattern::EnumTuple(_, subs) => subs.iter().any(|p| self.pattern_binds(p, &AhaType::Int)),
+            ast::Pattern::Struct(_, fields) => fields.iter().any(|(_, p)| self.pattern_binds(p, &AhaType::Int)),
+            ast::Pattern::Tuple(subs) => subs.iter().any(|p| self.pattern_binds(p, &AhaType::Int)),
+            ast::Pattern::Or(alts) => alts.iter().any(|p| self.pattern_binds(p, scrutinee_type)),
+            _ => false,
+        }
+    }
+
+    /// Short source-like rendering of a pattern for diagnostics.
+    fn describe_pattern(pattern: &ast::Pattern) -> String {
+        match pattern {
+            ast::Pattern::Wildcard => "_".to_string(),
+            ast::Pattern::EnumUnit(name) | ast::Pattern::Binding(name) => format!("'{}'", name),
+            ast::Pattern::MutBinding(name) => format!("'mut {}'", name),
+            ast::Pattern::EnumTuple(name, _) => format!("'{}(..)'", name),
+            ast::Pattern::Struct(name, _) => format!("'{} {{ .. }}'", name),
+            ast::Pattern::Tuple(subs) => format!(
+                "({})", subs.iter().map(Self::describe_pattern).collect::<Vec<_>>().join(", ")
+            ),
+            ast::Pattern::Integer(n) => n.to_string(),
+            ast::Pattern::Str(s) => format!("{:?}", s),
+            ast::Pattern::Range(lo, hi) => format!("{}..={}", lo, hi),
+            ast::Pattern::Or(alts) => alts.iter().map(Self::describe_pattern).collect::<Vec<_>>().join(" | "),
+        }
+    }
+
+    /// Bring a matched pattern's bindings into the current scope.
+    fn bind_pattern(&mut self, pattern: &ast::Pattern, scrutinee: &TypedValue<'ctx>) -> Result<(), String> {
+        // Boxes are matched through: bindings see the boxed value.
+        if matches!(scrutinee.aha_type, AhaType::Box(_)) && !matches!(pattern, ast::Pattern::Wildcard) {
+            let inner = self.unbox_value(scrutinee)?;
+            return self.bind_pattern(pattern, &inner);
+        }
+        match pattern {
+            ast::Pattern::Binding(name) | ast::Pattern::MutBinding(name)
+                if matches!(pattern, ast::Pattern::MutBinding(_)) || !self.names_enum_variant(name, &scrutinee.aha_type) =>
+            {
+                let ptr = self.builder.build_alloca(
+                    self.aha_type_to_llvm_type(&scrutinee.aha_type)?,
+                    name,
+                ).map_err(|e| e.to_string())?;
+                self.builder.build_store(ptr, scrutinee.value).map_err(|e| e.to_string())?;
+                self.insert_variable(name.clone(), ptr, scrutinee.aha_type.clone());
+                // The boxes a binding reaches stay owned by the scrutinee,
+                // so auto-free leaves the binding alone, like a param.
+                if self.needs_drop(&scrutinee.aha_type) {
+                    self.mark_param(name);
+                }
+                Ok(())
+            }
+            ast::Pattern::EnumTuple(path, subs) => {
+                let enum_name = match &scrutinee.aha_type {
+                    AhaType::Enum(e) => e.clone(),
+                    other => return Err(format!(
+                        "Pattern '{}(..)' cannot match a value of type {}", path, other
+                    )),
+                };
+                let variant = self.pattern_variant(path, &enum_name)?.to_string();
+                let payload = self.enum_payload_values(&enum_name, &variant, subs.len(), scrutinee)?;
+                for (sub, value) in subs.iter().zip(payload.iter()) {
+                    self.bind_pattern(sub, value)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::Struct(name, fields) => {
+                let struct_name = match &scrutinee.aha_type {
+                    AhaType::Struct(s) if self.struct_pattern_names(name, s) => s.clone(),
+                    other => return Err(format!(
+                        "Pattern '{} {{ .. }}' cannot match a value of type {}", name, other
+                    )),
+                };
+                for (field, sub) in fields {
+                    let value = self.struct_field_value(&struct_name, field, scrutinee)?;
+                    self.bind_pattern(sub, &value)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::Tuple(subs) => {
+                let values = self.tuple_pattern_values(subs.len(), scrutinee)?;
+                for (sub, value) in subs.iter().zip(values.iter()) {
+                    self.bind_pattern(sub, value)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::Or(_) if self.pattern_binds(pattern, &scrutinee.aha_type) => {
+                Err("Bindings are not allowed in `|` patterns".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Read one field out of a struct value as a typed value.
+    fn struct_field_value(&mut self, struct_name: &str, field: &str, value: &TypedValue<'ctx>) -> Result<TypedValue<'ctx>, String> {
+        let idx = self.field_index(struct_name, field)?;
+        let field_type = self.field_type(struct_name, field)?;
+        let field_val = self.builder.build_extract_value(value.value.into_struct_value(), idx, field)
+            .map_err(|e| e.to_string())?;
+        Ok(TypedValue::new(field_val, field_type))
+    }
+
+    /// Read every element of a tuple value for a tuple pattern with
+    /// `arity` sub-patterns, which must match the tuple's length.
+    fn tuple_pattern_values(&mut self, arity: usize, scrutinee: &TypedValue<'ctx>) -> Result<Vec<TypedValue<'ctx>>, String> {
+        let len = match &scrutinee.aha_type {
+            AhaType::Tuple(elems) => elems.len(),
+            other => return Err(format!(
+                "Tuple pattern cannot match a value of type {}", other
+            )),
+        };
+        if arity != len {
+            return Err(format!(
+                "Tuple pattern expects {} elements, got {}", len, arity
+            ));
+        }
+        (0..len).map(|i| self.tuple_element_value(scrutinee, i)).collect()
+    }
+
+    /// Read a variant's payload out of an enum value, one typed value per
+    /// payload field, each rebuilt from its words of the union.
+    /// `arity` is the number of sub-patterns and must match the payload.
+    fn enum_payload_values(&mut self, enum_name: &str, variant: &str, arity: usize, scrutinee: &TypedValue<'ctx>) -> Result<Vec<TypedValue<'ctx>>, String> {
+        let payload = self.variant_payload(enum_name, variant)?;
+        if arity != payload.len() {
+            return Err(format!(
+                "Pattern '{}' expects {} fields, got {}",
+                variant, payload.len(), arity
+            ));
+        }
+        let llvm_types = payload.iter()
+            .map(|t| self.aha_type_to_llvm_type(t))
+            .collect::<Result<Vec<_>, String>>()?;
+        let word_count: usize = llvm_types.iter().map(|t| Self::payload_word_count(*t)).sum();
+        let mut words = Vec::with_capacity(word_count);
+        for i in 0..word_count {
+            let word = self.builder.build_extract_value(scrutinee.value.into_struct_value(), (i + 1) as u32, "word")
+                .map_err(|e| e.to_string())?;
+            words.push(word.into_int_value());
+        }
+        let mut pos = 0;
+        let mut values = Vec::with_capacity(payload.len());
+        for (ty, llvm_type) in payload.iter().zip(llvm_types) {
+            let value = self.pop_payload_words(llvm_type, &words, &mut pos)?;
+            values.push(TypedValue::new(value, ty.clone()));
+        }
+        Ok(values)
+    }
+
+    fn compile_struct_literal(&mut self, lit: &ast::StructLiteral) -> Result<TypedValue<'ctx>, String> {
+        // Compile the field values first: a generic struct's instance is
+        // picked from their types (`Pair { first: 1, second: "a" }` is
+        // `Pair<Int, String>`).
+        let omitted = self.omitted_fields(&lit.name.value, &lit.fields)?;
+        let mut values = Vec::with_capacity(lit.fields.len() + omitted.len());
         for (field_ident, value_expr) in &lit.fields {
-            let idx = self.field_index(&struct_name, &field_ident.value)?;
-            let declared = self.field_type(&struct_name, &field_ident.value)?;
             let value = self.compile_expression(value_expr)?;
-            // Type-check: a field declared `string` must be given a string
-            // literal/variable; everything else is stored as i64.
-            if declared == AhaType::String && !value.aha_type.is_string() {
-                return Err(format!(
-                    "Field '{}' of '{}' expects a string, got {}",
-                    field_ident.value, struct_name, value.aha_type
-                ));
+            let value = self.own_static_read(value_expr, value)?;
+            self.mark_stored(value_expr, &value.aha_type);
+            values.push((field_ident.value.clone(), value));
+        }
+        // `..base` supplies every omitted field (and, for a generic
+        // struct, the instance); otherwise declared defaults do.
+        let base = match &lit.base {
+            Some(expr) => {
+                let base = self.compile_expression(expr)?;
+                let base_name = match &base.aha_type {
+                    AhaType::Struct(n) if n == &lit.name.value || n.starts_with(&format!("{}<", lit.name.value)) => n.clone(),
+                    other => return Err(format!(
+                        "Struct update base for '{}' has type {}, expected {}", lit.name.value, other, lit.name.value
+                    )),
+                };
+                Some((base.value.into_struct_value(), base_name))
             }
-            if declared != AhaType::String && value.aha_type.is_string() {
-                return Err(format!(
-                    "Field '{}' of '{}' expects {}, got string",
-                    field_ident.value, struct_name, declared
-                ));
+            None => None,
+        };
+        let mut missing = Vec::new();
+        for field in omitted {
+            if let Some((base_val, base_name)) = &base {
+                let idx = self.field_index(base_name, &field)?;
+                let declared = self.field_type(base_name, &field)?;
+                let v = self.builder
+                    .build_extract_value(*base_val, idx, "basefield")
+                    .map_err(|e| e.to_string())?;
+                values.push((field, TypedValue::new(v, declared)));
+            } else if let Some(default) = self.field_default(&lit.name.value, &field) {
+                values.push((field, self.compile_expression(&default)?));
+            } else {
+                missing.push(field);
+            }
+        }
+        if !missing.is_empty() {
+            return Err(Self::missing_fields_error(&lit.name.value, &missing));
+        }
+        let struct_name = match self.generic_struct_defs.get(&lit.name.value).cloned() {
+            // The base already is an instance of the struct.
+            _ if base.is_some() => base.map(|(_, n)| n).unwrap_or_default(),
+            Some(def) => {
+                let observed: Vec<(String, AhaType)> = values.iter()
+                    .map(|(f, v)| (f.clone(), v.aha_type.clone()))
+                    .collect();
+                let args = self.infer_struct_type_args(&def, &observed);
+                self.instantiate_struct(&lit.name.value, args)?
             }
+            None => lit.name.value.clone(),
+        };
+        let struct_type = self.struct_llvm_type(&struct_name)?;
+
+        // Place each field's value at the correct index defined by the
+        // struct declaration order.
+        let mut struct_val = struct_type.const_zero();
+        for (field, value) in &values {
+            let idx = self.field_index(&struct_name, field)?;
+            let declared = self.field_type(&struct_name, field)?;
+            self.check_field_value(&struct_name, field, &declared, &value.aha_type)?;
             struct_val = self.builder
                 .build_insert_value(struct_val, value.value, idx, "structfield")
                 .map_err(|e| e.to_string())?
@@ -5211,8 +8435,53 @@ impl<'ctx> CodeGenerator<'ctx> {
         Ok(TypedValue::struct_val(struct_val.into(), struct_name))
     }
 
+    /// Check a struct literal's or `spawn`'s fields against the
+    /// declaration: unknown and repeated fields are errors. Returns the
+    /// omitted fields in declaration order.
+    fn omitted_fields(&self, type_name: &str, given: &[(ast::Identifier, ast::Expression)]) -> Result<Vec<String>, String> {
+        let declared: Vec<String> = match self.generic_struct_defs.get(type_name) {
+            Some(def) => def.fields.iter().map(|f| f.name.value.clone()).collect(),
+            None => self.struct_defs.get(type_name)
+                .ok_or_else(|| format!("Unknown struct type '{}'", type_name))?
+                .iter().map(|(f, _)| f.clone()).collect(),
+        };
+        for (i, (field, _)) in given.iter().enumerate() {
+            if !declared.contains(&field.value) {
+                return Err(format!("Struct '{}' has no field '{}'", type_name, field.value));
+            }
+            if given[..i].iter().any(|(f, _)| f.value == field.value) {
+                return Err(format!("Field '{}' is set twice in '{}' literal", field.value, type_name));
+            }
+        }
+        Ok(declared.into_iter()
+            .filter(|f| !given.iter().any(|(g, _)| &g.value == f))
+            .collect())
+    }
+
+    /// The declared default of a struct or actor field, if any, as the
+    /// literal to compile in its place.
+    fn field_default(&self, type_name: &str, field: &str) -> Option<ast::Expression> {
+        self.struct_defaults.get(type_name)?.get(field).map(ConstValue::to_literal)
+    }
+
+    fn missing_fields_error(type_name: &str, missing: &[String]) -> String {
+        let names: Vec<String> = missing.iter().map(|f| format!("'{}'", f)).collect();
+        format!(
+            "Missing field{} {} in '{}' literal",
+            if missing.len() == 1 { "" } else { "s" },
+            names.join(", "),
+            type_name
+        )
+    }
+
     fn compile_field_access(&mut self, access: &ast::FieldAccess) -> Result<TypedValue<'ctx>, String> {
         let object = self.compile_expression(&access.object)?;
+        if let AhaType::Tuple(_) = &object.aha_type {
+            let index = access.field.value.parse::<usize>().map_err(|_| format!(
+                "Field access '.{}' on tuple type {}: use .0, .1, ...", access.field.value, object.aha_type
+            ))?;
+            return self.tuple_element_value(&object, index);
+        }
         let struct_name = match &object.aha_type {
             AhaType::Struct(name) => name.clone(),
             other => return Err(format!(
@@ -5226,8 +8495,8 @@ impl<'ctx> CodeGenerator<'ctx> {
             .build_extract_value(struct_val, idx, "fieldval")
             .map_err(|e| e.to_string())?;
         match declared {
-            AhaType::String => Ok(TypedValue::string(field_val)),
-            _ => Ok(TypedValue::int(field_val)),
+            AhaType::Int | AhaType::Bool => Ok(TypedValue::int(field_val)),
+            _ => Ok(TypedValue::new(field_val, declared)),
         }
     }
 
@@ -5241,8 +8510,74 @@ impl<'ctx> CodeGenerator<'ctx> {
         self.module.print_to_string().to_string()
     }
     
-    pub fn run_jit(&self) -> Result<i64, String> {
-        let execution_engine = self.module.create_jit_execution_engine(inkwell::OptimizationLevel::None)
+    /// JIT-execute `main` and return its value.
+    pub fn run_jit(&self) -> Result<i64, RunError> {
+        self.run_jit_entry("main", false)
+    }
+
+    /// Names of the `test fn` functions in the compiled program, in
+    /// declaration order.
+    pub fn test_functions(&self) -> &[String] {
+        &self.test_functions
+    }
+
+    /// JIT-execute one `test fn` instead of `main`. A failed assertion or
+    /// any other panic comes back as `Err` with its message, which is not
+    /// printed; the caller reports it.
+    pub fn run_test(&self, name: &str) -> Result<(), String> {
+        if !self.test_functions.iter().any(|t| t == name) {
+            return Err(format!("No test function named '{}'", name));
+        }
+        self.run_jit_entry(&format!("aha_test.{}", name), true)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Names of the `bench fn` functions in the compiled program, in
+    /// declaration order.
+    pub fn bench_functions(&self) -> &[String] {
+        &self.bench_functions
+    }
+
+    /// JIT-compile the program once, with optimized machine code, for
+    /// running its benchmarks many times.
+    pub fn bench_runner(&self) -> Result<BenchRunner<'ctx>, String> {
+        let engine = self.create_jit_engine(inkwell::OptimizationLevel::Default)?;
+        let run = Self::aha_run_address(&engine)?;
+        let iterations = match self.module.get_function("aha_bench.iterations") {
+            Some(_) => unsafe {
+                let address = engine.get_function_address("aha_bench.iterations")
+                    .map_err(|e| format!("Failed to get function address: {}", e))?;
+                std::mem::transmute::<usize, unsafe extern "C" fn() -> *mut i64>(address)()
+            },
+            None => std::ptr::null_mut(),
+        };
+        let mut entries = HashMap::new();
+        for name in &self.bench_functions {
+            let address = engine.get_function_address(&format!("aha_bench.{}", name))
+                .map_err(|e| format!("Failed to get function address: {}", e))?;
+            entries.insert(name.clone(), address);
+        }
+        Ok(BenchRunner { _engine: engine, run, iterations, entries })
+    }
+
+    /// Run `entry` (an `i64 ()` function) through `aha_run`, which
+    /// reports a panic instead of exiting. A `quiet` panic is returned
+    /// without being printed.
+    fn run_jit_entry(&self, entry: &str, quiet: bool) -> Result<i64, RunError> {
+        let execution_engine = self.create_jit_engine(inkwell::OptimizationLevel::None).map_err(RunError::Jit)?;
+        self.module.get_function(entry)
+            .ok_or_else(|| RunError::Jit(format!("Function '{}' not found", entry)))?;
+        let entry_fn = execution_engine.get_function_address(entry)
+            .map_err(|e| RunError::Jit(format!("Failed to get function address: {}", e)))?;
+        let run = Self::aha_run_address(&execution_engine).map_err(RunError::Jit)?;
+        let mut outcome = PanicRecord::none();
+        let result = unsafe { run(&mut outcome, entry_fn, quiet as i64) };
+        outcome.into_result().map(|()| result)
+    }
+
+    fn create_jit_engine(&self, level: inkwell::OptimizationLevel) -> Result<ExecutionEngine<'ctx>, String> {
+        let execution_engine = self.module.create_jit_execution_engine(level)
             .map_err(|e| format!("Failed to create JIT engine: {}", e))?;
 
         // Register native runtime functions so the JIT can call them.
@@ -5257,18 +8592,13 @@ impl<'ctx> CodeGenerator<'ctx> {
         if let Some(f) = self.module.get_function("actor_call") {
             execution_engine.add_global_mapping(&f, crate::runtime::actor_call as usize);
         }
+        Ok(execution_engine)
+    }
 
-        let function_name = "main";
-        let _function = self.module.get_function(function_name)
-            .ok_or_else(|| format!("Function '{}' not found", function_name))?;
-
-        unsafe {
-            let compiled_fn: unsafe extern "C" fn() -> i64 = execution_engine.get_function_address(function_name)
-                .map_err(|e| format!("Failed to get function address: {}", e))
-                .map(|addr| std::mem::transmute(addr))?;
-
-            Ok(compiled_fn())
-        }
+    fn aha_run_address(engine: &ExecutionEngine<'ctx>) -> Result<AhaRunFn, String> {
+        let address = engine.get_function_address("aha_run")
+            .map_err(|e| format!("Failed to get function address: {}", e))?;
+        Ok(unsafe { std::mem::transmute::<usize, AhaRunFn>(address) })
     }
 
     /// Rename the LLVM `main` function to `new_name`.
@@ -5328,7 +8658,8 @@ impl<'ctx> CodeGenerator<'ctx> {
             &cpu,
             &features,
             inkwell::OptimizationLevel::Default,
-            RelocMode::Default,
+            // `cc` links position-independent executables by default.
+            RelocMode::PIC,
             CodeModel::Default,
         ).ok_or("Failed to create target machine")?;
 
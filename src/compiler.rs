@@ -10,7 +10,8 @@ use crate::parser::Parser;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-/// Check if a statement is a public item (pub function or pub struct).
+/// Check if a statement is a public item (pub function, struct, actor,
+/// const, static, type alias or newtype).
 fn is_pub_item(stmt: &Statement) -> bool {
     match stmt {
         Statement::Expression(es) => {
@@ -22,6 +23,10 @@ fn is_pub_item(stmt: &Statement) -> bool {
         }
         Statement::Struct(s) => s.is_pub,
         Statement::Actor(a) => a.is_pub,
+        Statement::Const(c) => c.is_pub,
+        Statement::Static(s) => s.is_pub,
+        Statement::TypeAlias(t) => t.is_pub,
+        Statement::Newtype(n) => n.is_pub,
         _ => false,
     }
 }
@@ -118,6 +123,31 @@ impl Compiler {
         Ok(Program { statements: all_statements })
     }
 
+    /// The files of a module graph: `main_path` first, then every file it
+    /// imports, directly or not, each once.
+    pub fn module_files(&self, main_path: &str) -> Result<Vec<PathBuf>, Vec<CompileError>> {
+        let mut files = Vec::new();
+        let mut pending = vec![self.resolve_path(main_path)];
+        while let Some(path) = pending.pop() {
+            if files.contains(&path) {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path).map_err(|e| vec![CompileError {
+                message: format!("Failed to read file '{}': {}", path.display(), e),
+                file: path.to_string_lossy().to_string(),
+            }])?;
+            let program = Parser::new(Lexer::new(contents)).parse_program();
+            // Visit imports in source order.
+            for stmt in program.statements.iter().rev() {
+                if let Statement::Import(import) = stmt {
+                    pending.push(self.resolve_path(&import.path));
+                }
+            }
+            files.push(path);
+        }
+        Ok(files)
+    }
+
     /// Recursively compile a file and its imports.
     /// Appends non-import statements to `all_statements`.
     /// Collects struct names into `all_struct_names`.
@@ -0,0 +1,525 @@
+// src/consteval.rs
+//
+// AHA! Constant Evaluator — computes the value of every module-level
+// `const`, the initial value of every `static` and every struct or actor
+// field default, before codegen. Initializers may use literals, other constants,
+// arithmetic, comparisons, string concatenation, `if`, and calls to pure
+// functions: user functions whose bodies stay within the same subset
+// (locals, `let mut`, `while`, `for` over ranges, `return`) plus the
+// builtins `abs`, `min`, `max` and `len`. Codegen inlines constants as
+// LLVM constants at each use and emits statics as initialized globals.
+// Static initializers may use constants but not other statics.
+//
+// Anything with side effects or heap state (printing, lists, maps,
+// structs, actors) is rejected with an error on the constant's line.
+
+use crate::ast;
+use crate::typeck::TypeError;
+use crate::types::{count_of, AhaType};
+use std::collections::HashMap;
+
+/// Recursion depth limit for calls made while evaluating a constant.
+const MAX_DEPTH: usize = 256;
+/// Evaluation step limit, so a non-terminating loop is an error rather
+/// than a hang.
+const MAX_STEPS: usize = 1_000_000;
+
+/// The compile-time value of a constant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+}
+
+impl ConstValue {
+    pub fn aha_type(&self) -> AhaType {
+        match self {
+            ConstValue::Int(_) => AhaType::Int,
+            ConstValue::Bool(_) => AhaType::Bool,
+            ConstValue::Str(_) => AhaType::String,
+        }
+    }
+
+    /// The literal expression codegen compiles in place of the constant.
+    pub fn to_literal(&self) -> ast::Expression {
+        match self {
+            ConstValue::Int(v) => ast::Expression::Integer(ast::IntegerLiteral { value: *v }),
+            ConstValue::Bool(v) => ast::Expression::Boolean(ast::BooleanLiteral { value: *v }),
+            ConstValue::Str(v) => ast::Expression::String(ast::StringLiteral { value: v.clone() }),
+        }
+    }
+
+    fn truthy(&self) -> Result<bool, String> {
+        match self {
+            ConstValue::Int(v) => Ok(*v != 0),
+            ConstValue::Bool(v) => Ok(*v),
+            ConstValue::Str(_) => Err("a String is not a condition".to_string()),
+        }
+    }
+}
+
+/// Compile-time values of a program's globals.
+#[derive(Debug, Default)]
+pub struct GlobalValues {
+    pub consts: HashMap<String, ConstValue>,
+    /// Initial value of each `static`.
+    pub statics: HashMap<String, ConstValue>,
+    /// Struct or actor name → field → declared default.
+    pub field_defaults: HashMap<String, HashMap<String, ConstValue>>,
+}
+
+/// Evaluate every top-level `const` and `static` initializer. Returns
+/// the values, or every global that could not be evaluated.
+pub fn evaluate(program: &ast::Program) -> Result<GlobalValues, Vec<TypeError>> {
+    let mut eval = Evaluator::new(program);
+    let mut statics = HashMap::new();
+    let mut field_defaults: HashMap<String, HashMap<String, ConstValue>> = HashMap::new();
+    let mut errors = Vec::new();
+    for stmt in &program.statements {
+        eval.steps = 0;
+        match stmt {
+            ast::Statement::Const(def) => {
+                if let Err(message) = eval.constant(&def.name.value) {
+                    errors.push(TypeError {
+                        line: def.line,
+                        message: format!("Constant '{}': {}", def.name.value, message),
+                    });
+                }
+            }
+            ast::Statement::Static(def) => {
+                let value = match check_global_type("static", def.type_annotation.as_deref()) {
+                    Err(message) => Err(message),
+                    Ok(()) => match eval.value(&def.value) {
+                        Ok(v) => coerce(def.type_annotation.as_deref(), v),
+                        Err(Exit::Error(message)) => Err(message),
+                        Err(_) => Err("'return', 'break' and 'continue' are not allowed here".to_string()),
+                    },
+                };
+                match value {
+                    Ok(v) => {
+                        statics.insert(def.name.value.clone(), v);
+                    }
+                    Err(message) => errors.push(TypeError {
+                        line: def.line,
+                        message: format!("Static '{}': {}", def.name.value, message),
+                    }),
+                }
+            }
+            ast::Statement::Struct(ast::StructDefinition { name, fields, .. })
+            | ast::Statement::Actor(ast::ActorDefinition { name, fields, .. }) => {
+                for field in fields {
+                    let Some(default) = &field.default else { continue };
+                    eval.steps = 0;
+                    let value = match eval.value(default) {
+                        Ok(v) => coerce_field(field.type_hint.as_deref(), v),
+                        Err(Exit::Error(message)) => Err(message),
+                        Err(_) => Err("'return', 'break' and 'continue' are not allowed here".to_string()),
+                    };
+                    match value {
+                        Ok(v) => {
+                            field_defaults.entry(name.value.clone()).or_default().insert(field.name.value.clone(), v);
+                        }
+                        Err(message) => errors.push(TypeError {
+                            line: field.line,
+                            message: format!("Default of field '{}' of '{}': {}", field.name.value, name.value, message),
+                        }),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if errors.is_empty() {
+        Ok(GlobalValues { consts: eval.values, statics, field_defaults })
+    } else {
+        Err(errors)
+    }
+}
+
+/// Globals hold an Int, Bool or String; reject an annotation naming any
+/// other type before its initializer is evaluated.
+fn check_global_type(kind: &str, hint: Option<&str>) -> Result<(), String> {
+    match hint.and_then(AhaType::from_hint) {
+        None | Some(AhaType::Int | AhaType::Bool | AhaType::String) => Ok(()),
+        Some(t) => Err(format!("{} of type {} is not supported", kind, t)),
+    }
+}
+
+/// Check a value against a global's type annotation. Int and Bool
+/// convert into each other, as everywhere else.
+fn coerce(hint: Option<&str>, value: ConstValue) -> Result<ConstValue, String> {
+    match (hint, value) {
+        (None, v) => Ok(v),
+        (Some("int" | "i64"), ConstValue::Bool(b)) => Ok(ConstValue::Int(b as i64)),
+        (Some("bool"), ConstValue::Int(n)) => Ok(ConstValue::Bool(n != 0)),
+        (Some(hint), v) => match AhaType::from_hint(hint) {
+            Some(t) if t == v.aha_type() => Ok(v),
+            Some(t) => Err(format!("declared as {} but the value is {}", t, v.aha_type())),
+            None => Err(format!("type '{}' is not allowed for globals (use int, bool or string)", hint)),
+        },
+    }
+}
+
+/// Check a field default against the field's type hint; a field without
+/// one is an Int.
+fn coerce_field(hint: Option<&str>, value: ConstValue) -> Result<ConstValue, String> {
+    let hint = hint.unwrap_or("int");
+    match AhaType::from_hint(hint) {
+        Some(AhaType::Int | AhaType::Bool | AhaType::String) => coerce(Some(hint), value),
+        _ => Err(format!("a field of type '{}' cannot have a default (use int, bool or string)", hint)),
+    }
+}
+
+/// Why evaluation left an expression early: control flow, or an error.
+enum Exit {
+    Return(ConstValue),
+    Break,
+    Continue,
+    Error(String),
+}
+
+impl From<String> for Exit {
+    fn from(message: String) -> Self {
+        Exit::Error(message)
+    }
+}
+
+/// `None` is the value of statements like `while` and assignment.
+type Eval = Result<Option<ConstValue>, Exit>;
+
+struct Evaluator<'a> {
+    defs: HashMap<String, &'a ast::ConstStatement>,
+    functions: HashMap<String, &'a ast::FunctionLiteral>,
+    values: HashMap<String, ConstValue>,
+    /// Constants being evaluated, to report cycles.
+    in_progress: Vec<String>,
+    /// Local scopes of the function calls being evaluated.
+    scopes: Vec<HashMap<String, ConstValue>>,
+    depth: usize,
+    steps: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(program: &'a ast::Program) -> Self {
+        let mut defs = HashMap::new();
+        let mut functions = HashMap::new();
+        for stmt in &program.statements {
+            match stmt {
+                ast::Statement::Const(def) => {
+                    defs.insert(def.name.value.clone(), def);
+                }
+                ast::Statement::Expression(ast::ExpressionStatement {
+                    expression: ast::Expression::Function(func),
+                }) => {
+                    if let Some(name) = &func.name {
+                        functions.insert(name.value.clone(), func);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Evaluator {
+            defs,
+            functions,
+            values: HashMap::new(),
+            in_progress: Vec::new(),
+            scopes: Vec::new(),
+            depth: 0,
+            steps: 0,
+        }
+    }
+
+    /// The value of constant `name`, evaluating it on first use.
+    fn constant(&mut self, name: &str) -> Result<ConstValue, String> {
+        if let Some(v) = self.values.get(name) {
+            return Ok(v.clone());
+        }
+        if self.in_progress.iter().any(|n| n == name) {
+            return Err(format!("cycle through constant '{}'", name));
+        }
+        let def = self.defs[name];
+        check_global_type("const", def.type_annotation.as_deref())?;
+        self.in_progress.push(name.to_string());
+        // A constant's initializer sees only constants, never the locals
+        // of a function that happens to mention it.
+        let saved_scopes = std::mem::take(&mut self.scopes);
+        let result = self.value(&def.value);
+        self.scopes = saved_scopes;
+        self.in_progress.pop();
+
+        let value = match result {
+            Ok(v) => v,
+            Err(Exit::Error(message)) => return Err(message),
+            Err(_) => return Err("'return', 'break' and 'continue' are not allowed here".to_string()),
+        };
+        let value = coerce(def.type_annotation.as_deref(), value)?;
+        self.values.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    /// Evaluate an expression that must produce a value.
+    fn value(&mut self, expr: &ast::Expression) -> Result<ConstValue, Exit> {
+        self.expr(expr)?.ok_or_else(|| Exit::Error("expression has no value".to_string()))
+    }
+
+    fn expr(&mut self, expr: &ast::Expression) -> Eval {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(Exit::Error(format!("evaluation did not finish within {} steps", MAX_STEPS)));
+        }
+        match expr {
+            ast::Expression::Integer(i) => Ok(Some(ConstValue::Int(i.value))),
+            ast::Expression::Boolean(b) => Ok(Some(ConstValue::Bool(b.value))),
+            ast::Expression::String(s) => Ok(Some(ConstValue::Str(s.value.clone()))),
+            ast::Expression::Identifier(id) => self.lookup(&id.value).map(Some),
+            ast::Expression::ModuleAccess(ma) => self.lookup(&ma.name).map(Some),
+            ast::Expression::Prefix(prefix) => {
+                let right = self.value(&prefix.right)?;
+                let v = match (prefix.operator.as_str(), right) {
+                    ("-", ConstValue::Int(n)) => ConstValue::Int(n.checked_neg().ok_or_else(|| "arithmetic overflow".to_string())?),
+                    ("!", v) => ConstValue::Bool(!v.truthy()?),
+                    (op, v) => return Err(Exit::Error(format!(
+                        "cannot apply prefix operator '{}' to {}", op, v.aha_type()
+                    ))),
+                };
+                Ok(Some(v))
+            }
+            ast::Expression::Cast(cast) if cast.target == "int" => match self.value(&cast.value)? {
+                ConstValue::Int(n) => Ok(Some(ConstValue::Int(n))),
+                ConstValue::Bool(b) => Ok(Some(ConstValue::Int(b as i64))),
+                v => Err(Exit::Error(format!("cannot cast {} to int", v.aha_type()))),
+            },
+            ast::Expression::Infix(infix) => {
+                let left = self.value(&infix.left)?;
+                // `&&` and `||` short-circuit: the right side is only
+                // evaluated when the left one doesn't settle the result.
+                let op = infix.operator.as_str();
+                if matches!(op, "&&" | "||") && matches!(left, ConstValue::Int(_) | ConstValue::Bool(_)) {
+                    let is_or = op == "||";
+                    if left.truthy()? == is_or {
+                        return Ok(Some(ConstValue::Int(is_or as i64)));
+                    }
+                }
+                let right = self.value(&infix.right)?;
+                Self::binary(infix.operator.as_str(), left, right).map(Some).map_err(Exit::Error)
+            }
+            ast::Expression::If(if_expr) => {
+                if self.value(&if_expr.condition)?.truthy()? {
+                    self.block(&if_expr.consequence)
+                } else if let Some(alt) = &if_expr.alternative {
+                    self.block(alt)
+                } else {
+                    Ok(None)
+                }
+            }
+            ast::Expression::While(while_expr) => {
+                while self.value(&while_expr.condition)?.truthy()? {
+                    match self.block(&while_expr.body) {
+                        Err(Exit::Break) => break,
+                        Err(Exit::Continue) | Ok(_) => {}
+                        Err(other) => return Err(other),
+                    }
+                }
+                Ok(None)
+            }
+            ast::Expression::For(for_expr) => {
+                let ast::Expression::Range(range) = &*for_expr.iterable else {
+                    return Err(Exit::Error("only 'for' over a range is allowed".to_string()));
+                };
+                let start = self.int(&range.start)?;
+                let end = self.int(&range.end)?;
+                for i in start..end {
+                    self.scopes.push(HashMap::new());
+                    self.bind(&for_expr.variable.value, ConstValue::Int(i));
+                    let result = self.block(&for_expr.body);
+                    self.scopes.pop();
+                    match result {
+                        Err(Exit::Break) => break,
+                        Err(Exit::Continue) | Ok(_) => {}
+                        Err(other) => return Err(other),
+                    }
+                }
+                Ok(None)
+            }
+            ast::Expression::Assignment(assign) => {
+                let ast::Expression::Identifier(id) = &*assign.target else {
+                    return Err(Exit::Error("only local variables can be assigned".to_string()));
+                };
+                let value = self.value(&assign.value)?;
+                match self.scopes.iter_mut().rev().find_map(|s| s.get_mut(&id.value)) {
+                    Some(slot) => *slot = value,
+                    None => return Err(Exit::Error(format!("cannot assign to '{}'", id.value))),
+                }
+                Ok(None)
+            }
+            ast::Expression::Call(call) => self.call(call).map(Some),
+            ast::Expression::Break => Err(Exit::Break),
+            ast::Expression::Continue => Err(Exit::Continue),
+            other => Err(Exit::Error(format!("{} is not allowed in a constant expression", Self::describe(other)))),
+        }
+    }
+
+    fn int(&mut self, expr: &ast::Expression) -> Result<i64, Exit> {
+        match self.value(expr)? {
+            ConstValue::Int(n) => Ok(n),
+            v => Err(Exit::Error(format!("expected Int, got {}", v.aha_type()))),
+        }
+    }
+
+    /// Same operators and result types as `AhaType::check_binary_op`;
+    /// comparisons and logic yield Int 0/1.
+    fn binary(op: &str, left: ConstValue, right: ConstValue) -> Result<ConstValue, String> {
+        use ConstValue::{Bool, Int, Str};
+        let overflow = || "arithmetic overflow".to_string();
+        let v = match (left, op, right) {
+            (Int(a), "+", Int(b)) => Int(a.checked_add(b).ok_or_else(overflow)?),
+            (Int(a), "-", Int(b)) => Int(a.checked_sub(b).ok_or_else(overflow)?),
+            (Int(a), "*", Int(b)) => Int(a.checked_mul(b).ok_or_else(overflow)?),
+            (Int(_), "/" | "%", Int(0)) => return Err("division by zero".to_string()),
+            (Int(a), "/", Int(b)) => Int(a.checked_div(b).ok_or_else(overflow)?),
+            (Int(a), "%", Int(b)) => Int(a.checked_rem(b).ok_or_else(overflow)?),
+            (Int(a), "==", Int(b)) => Int((a == b) as i64),
+            (Int(a), "!=", Int(b)) => Int((a != b) as i64),
+            (Int(a), "<", Int(b)) => Int((a < b) as i64),
+            (Int(a), ">", Int(b)) => Int((a > b) as i64),
+            (Int(a), "<=", Int(b)) => Int((a <= b) as i64),
+            (Int(a), ">=", Int(b)) => Int((a >= b) as i64),
+            (Str(a), "+", Str(b)) => Str(a + &b),
+            (Str(a), "==", Str(b)) => Int((a == b) as i64),
+            (Str(a), "!=", Str(b)) => Int((a != b) as i64),
+            (Bool(a), "==", Bool(b)) => Int((a == b) as i64),
+            (Bool(a), "!=", Bool(b)) => Int((a != b) as i64),
+            (Int(a), "&&", Int(b)) => Int((a != 0 && b != 0) as i64),
+            (Int(a), "||", Int(b)) => Int((a != 0 || b != 0) as i64),
+            (Bool(a), "&&", Bool(b)) => Int((a && b) as i64),
+            (Bool(a), "||", Bool(b)) => Int((a || b) as i64),
+            (a, op, b) => return Err(format!(
+                "cannot apply operator '{}' to {} and {}", op, a.aha_type(), b.aha_type()
+            )),
+        };
+        Ok(v)
+    }
+
+    fn lookup(&mut self, name: &str) -> Result<ConstValue, Exit> {
+        if let Some(v) = self.scopes.iter().rev().find_map(|s| s.get(name)) {
+            return Ok(v.clone());
+        }
+        if self.defs.contains_key(name) {
+            return self.constant(name).map_err(|message| {
+                if message.starts_with("cycle") {
+                    Exit::Error(message)
+                } else {
+                    Exit::Error(format!("uses constant '{}', which is invalid", name))
+                }
+            });
+        }
+        Err(Exit::Error(format!("'{}' is not a constant", name)))
+    }
+
+    fn bind(&mut self, name: &str, value: ConstValue) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    /// Evaluate a block in its own scope; its value is the last
+    /// expression statement's.
+    fn block(&mut self, block: &ast::BlockStatement) -> Eval {
+        self.scopes.push(HashMap::new());
+        let result = self.statements(&block.statements);
+        self.scopes.pop();
+        result
+    }
+
+    fn statements(&mut self, statements: &[ast::Statement]) -> Eval {
+        let mut last = None;
+        for stmt in statements {
+            last = None;
+            match stmt {
+                ast::Statement::Let(let_stmt) => {
+                    let value = self.value(&let_stmt.value)?;
+                    self.bind(&let_stmt.name.value, value);
+                }
+                ast::Statement::Return(ret) => {
+                    let value = self.value(&ret.return_value)?;
+                    return Err(Exit::Return(value));
+                }
+                ast::Statement::Expression(es) => {
+                    last = self.expr(&es.expression)?;
+                }
+                _ => return Err(Exit::Error("only 'let', 'return' and expressions are allowed in a constant function".to_string())),
+            }
+        }
+        Ok(last)
+    }
+
+    fn call(&mut self, call: &ast::CallExpression) -> Result<ConstValue, Exit> {
+        let name = match call.function.as_ref() {
+            ast::Expression::Identifier(id) => id.value.clone(),
+            ast::Expression::ModuleAccess(ma) => ma.name.clone(),
+            _ => return Err(Exit::Error("only named functions can be called".to_string())),
+        };
+        let mut args = Vec::with_capacity(call.arguments.len());
+        for arg in &call.arguments {
+            args.push(self.value(arg)?);
+        }
+
+        let Some(func) = self.functions.get(&name).copied() else {
+            return Self::builtin(&name, args).map_err(Exit::Error);
+        };
+        if args.len() != func.parameters.len() {
+            return Err(Exit::Error(format!(
+                "'{}' expects {}, got {}", name, count_of(func.parameters.len(), "argument"), args.len()
+            )));
+        }
+        if self.depth >= MAX_DEPTH {
+            return Err(Exit::Error(format!("calls nested deeper than {}", MAX_DEPTH)));
+        }
+
+        let frame = func.parameters.iter().map(|p| p.value.clone()).zip(args).collect();
+        let saved_scopes = std::mem::replace(&mut self.scopes, vec![frame]);
+        self.depth += 1;
+        let result = self.statements(&func.body.statements);
+        self.depth -= 1;
+        self.scopes = saved_scopes;
+
+        match result {
+            Ok(Some(v)) | Err(Exit::Return(v)) => Ok(v),
+            Ok(None) => Err(Exit::Error(format!("'{}' returns no value", name))),
+            Err(Exit::Break | Exit::Continue) => Err(Exit::Error(format!("'break' outside a loop in '{}'", name))),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The pure builtins.
+    fn builtin(name: &str, args: Vec<ConstValue>) -> Result<ConstValue, String> {
+        use ConstValue::{Int, Str};
+        match (name, args.as_slice()) {
+            ("abs", [Int(n)]) => n.checked_abs().map(Int).ok_or_else(|| "arithmetic overflow".to_string()),
+            ("min", [Int(a), Int(b)]) => Ok(Int(*a.min(b))),
+            ("max", [Int(a), Int(b)]) => Ok(Int(*a.max(b))),
+            ("len", [Str(s)]) => Ok(Int(s.len() as i64)),
+            ("abs" | "min" | "max" | "len", _) => Err(format!("invalid arguments to '{}'", name)),
+            _ => Err(format!("call to '{}' is not allowed in a constant expression", name)),
+        }
+    }
+
+    fn describe(expr: &ast::Expression) -> &'static str {
+        match expr {
+            ast::Expression::Function(_) => "a function literal",
+            ast::Expression::Array(_) => "an array",
+            ast::Expression::Tuple(_) => "a tuple",
+            ast::Expression::Index(_) => "indexing",
+            ast::Expression::Range(_) => "a range",
+            ast::Expression::Cast(_) => "a cast",
+            ast::Expression::StructLiteral(_) => "a struct literal",
+            ast::Expression::FieldAccess(_) => "field access",
+            ast::Expression::Spawn(_) => "spawn",
+            ast::Expression::Match(_) => "match",
+            ast::Expression::IfLet(_) => "if let",
+            ast::Expression::WhileLet(_) => "while let",
+            _ => "this expression",
+        }
+    }
+}
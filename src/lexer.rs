@@ -68,9 +68,24 @@ impl Lexer {
         self.input[position..self.position].iter().collect()
     }
 
-    // Read an integer literal (digits only, for now)
+    // Read an integer literal: decimal digits, or hex after `0x`.
+    // Hex literals are handed to the parser in decimal.
     fn read_number(&mut self) -> String {
         let position = self.position;
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'X') {
+            self.read_char(); // Skip '0'
+            self.read_char(); // Skip 'x'
+            let digits_start = self.position;
+            while self.ch.is_ascii_hexdigit() {
+                self.read_char();
+            }
+            let digits: String = self.input[digits_start..self.position].iter().collect();
+            return match i64::from_str_radix(&digits, 16) {
+                Ok(v) => v.to_string(),
+                // Leave it as written so the parser reports it.
+                Err(_) => self.input[position..self.position].iter().collect(),
+            };
+        }
         while self.ch.is_digit(10) {
             self.read_char();
         }
@@ -131,6 +146,11 @@ impl Lexer {
         match ident {
             "fn" => TokenType::Fn,
             "let" => TokenType::Let,
+            "mut" => TokenType::Mut,
+            "const" => TokenType::Const,
+            "static" => TokenType::Static,
+            "type" => TokenType::Type,
+            "newtype" => TokenType::Newtype,
             "true" => TokenType::True,
             "false" => TokenType::False,
             "if" => TokenType::If,
@@ -148,6 +168,7 @@ impl Lexer {
             "spawn" => TokenType::Spawn,
             "enum" => TokenType::Enum,
             "match" => TokenType::Match,
+            "as" => TokenType::As,
             _ => TokenType::Identifier,
         }
     }
@@ -192,6 +213,7 @@ impl Lexer {
             }
             '*' => tok = Token::new(TokenType::Asterisk, self.ch.to_string(), line, column),
             '%' => tok = Token::new(TokenType::Percent, self.ch.to_string(), line, column),
+            '#' => tok = Token::new(TokenType::Hash, self.ch.to_string(), line, column),
             '&' => {
                 if self.peek_char() == '&' {
                     self.read_char();
@@ -205,7 +227,7 @@ impl Lexer {
                     self.read_char();
                     tok = Token::new(TokenType::Or, "||".to_string(), line, column);
                 } else {
-                    tok = Token::new(TokenType::Illegal, self.ch.to_string(), line, column);
+                    tok = Token::new(TokenType::Pipe, self.ch.to_string(), line, column);
                 }
             }
             '/' => {
@@ -243,7 +265,12 @@ impl Lexer {
             '.' => {
                 if self.peek_char() == '.' {
                     self.read_char();
-                    tok = Token::new(TokenType::DotDot, "..".to_string(), line, column);
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        tok = Token::new(TokenType::DotDotEq, "..=".to_string(), line, column);
+                    } else {
+                        tok = Token::new(TokenType::DotDot, "..".to_string(), line, column);
+                    }
                 } else {
                     tok = Token::new(TokenType::Dot, ".".to_string(), line, column);
                 }
@@ -3,11 +3,17 @@
 // Public modules for the AHA! compiler
 pub mod ast;
 pub mod types;
+pub mod typeck;
+pub mod units;
+pub mod consteval;
+pub mod typedefs;
 pub mod lexer;
 pub mod parser;
 pub mod codegen;
 pub mod compiler;
 pub mod runtime;
+pub mod testing;
+pub mod bench;
 
 // Re-exports for convenient access
 pub use lexer::Lexer;
@@ -4,17 +4,20 @@ use clap::Parser;
 use std::fs;
 use aha_lang::lexer::Lexer;
 use aha_lang::parser::Parser as AhaParser;
-use aha_lang::codegen::CodeGenerator;
-use aha_lang::compiler::Compiler;
+use aha_lang::codegen::{CodeGenerator, RunError, PANIC_EXIT_CODE};
+use aha_lang::compiler::{CompileError, Compiler};
 use inkwell::context::Context;
 
 /// AHA! Lang Compiler v1.5
 #[derive(Parser, Debug)]
 #[command(author = "AHA! Lang Team", version = "1.5.0", about = "AHA! Lang Compiler", long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Source file to compile
     #[arg(short, long)]
-    file: String,
+    file: Option<String>,
 
     /// Save LLVM IR to file
     #[arg(long)]
@@ -23,28 +26,90 @@ struct Args {
     /// Compile to native executable (AOT)
     #[arg(long)]
     emit_exe: Option<String>,
+
+    /// Release build: Int overflow wraps unless --overflow-checks is given
+    #[arg(long)]
+    release: bool,
+
+    /// Panic on Int overflow (default: on, off with --release)
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    overflow_checks: Option<bool>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Run the `test fn` functions of a file and the modules it imports
+    Test {
+        /// Root source file
+        file: String,
+
+        /// Only run tests whose name contains one of these strings
+        filters: Vec<String>,
+    },
+    /// Time the `bench fn` functions of a file and the modules it imports
+    Bench {
+        /// Root source file
+        file: String,
+
+        /// Only run benchmarks whose name contains one of these strings
+        filters: Vec<String>,
+
+        /// Print the results as JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Warm-up time per benchmark, in milliseconds
+        #[arg(long, default_value_t = 200)]
+        warm_up_ms: u64,
+
+        /// Measurement time per benchmark, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        measurement_ms: u64,
+
+        /// Timed samples per benchmark
+        #[arg(long, default_value_t = 50)]
+        samples: usize,
+
+        /// Panic on Int overflow (benchmarks are built like --release)
+        #[arg(long)]
+        overflow_checks: bool,
+    },
 }
 
 fn main() {
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Test { file, filters }) => std::process::exit(run_tests(file, filters)),
+        Some(Command::Bench { file, filters, json, warm_up_ms, measurement_ms, samples, overflow_checks }) => {
+            let config = aha_lang::bench::BenchConfig {
+                warm_up: std::time::Duration::from_millis(*warm_up_ms),
+                measurement: std::time::Duration::from_millis(*measurement_ms),
+                samples: *samples,
+                overflow_checks: *overflow_checks,
+            };
+            std::process::exit(run_benches(file, filters, &config, *json));
+        }
+        None => {}
+    }
+    let Some(file) = args.file.clone() else {
+        eprintln!("[ERROR] No input: pass --file <path> or a subcommand (see --help)");
+        std::process::exit(2);
+    };
     println!("--- AHA! COMPILER v1.5 ---");
-    println!("Reading file: {}", args.file);
+    println!("Reading file: {}", file);
 
     // 0. RESOLVE IMPORTS (multi-file compilation)
     println!("\n[0] RESOLVING IMPORTS...");
-    let search_dir = Compiler::parent_dir(&args.file);
+    let search_dir = Compiler::parent_dir(&file);
     let compiler = Compiler::new(vec![search_dir]);
 
-    let program = match compiler.compile(&args.file) {
+    let program = match compiler.compile(&file) {
         Ok(program) => {
             println!("Imports resolved!");
             program
         }
         Err(errors) => {
-            eprintln!("\n[ERROR] Compilation failed with {} error(s):", errors.len());
-            for error in &errors {
-                eprintln!("- {}", error);
-            }
+            print_compile_errors(&errors);
             return;
         }
     };
@@ -67,6 +132,7 @@ fn main() {
     println!("[2] CODE GENERATION...");
     let context = Context::create();
     let mut codegen = CodeGenerator::new(&context);
+    codegen.set_overflow_checks(args.overflow_checks.unwrap_or(!args.release));
 
     if let Err(e) = codegen.compile(&program) {
         eprintln!("\n[ERROR] Code generation failed: {}", e);
@@ -131,6 +197,91 @@ fn main() {
     println!("[3] EXECUTION (JIT)...");
     match codegen.run_jit() {
         Ok(result) => println!("Program executed successfully. Result: {}", result),
+        // The panic runtime has already printed the message and backtrace.
+        Err(RunError::Panic { .. }) => std::process::exit(PANIC_EXIT_CODE),
         Err(e) => eprintln!("[ERROR] Failed to execute program: {}", e),
     }
 }
+
+/// `aha test`: run the tests, print one line per test, the failures and
+/// a summary. Returns the process exit code.
+fn run_tests(file: &str, filters: &[String]) -> i32 {
+    let report = aha_lang::testing::run_tests(file, filters, |outcome| {
+        let status = if outcome.result.is_ok() { "ok" } else { "FAILED" };
+        println!("test {} ... {}", outcome.name, status);
+    });
+    let report = match report {
+        Ok(report) => report,
+        Err(errors) => {
+            print_compile_errors(&errors);
+            return 2;
+        }
+    };
+    let failures = report.outcomes.iter()
+        .filter_map(|o| o.result.as_ref().err().map(|message| (o.name.as_str(), message.as_str())));
+    let counts = format!("{} passed; {} failed; {} filtered out", report.passed(), report.failed(), report.filtered_out);
+    finish_report("test", failures, &counts, report.success())
+}
+
+/// `aha bench`: time the benchmarks and print one line per benchmark (or
+/// a JSON report), the failures and a summary. Returns the process exit
+/// code.
+fn run_benches(file: &str, filters: &[String], config: &aha_lang::bench::BenchConfig, json: bool) -> i32 {
+    use aha_lang::bench::format_ns;
+    let report = aha_lang::bench::run_benches(file, filters, config, |outcome| {
+        if json {
+            return;
+        }
+        match &outcome.result {
+            Ok(stats) => println!(
+                "bench {} ... mean {} (median {}, stddev {}; {} samples of {} iterations)",
+                outcome.name,
+                format_ns(stats.mean_ns),
+                format_ns(stats.median_ns),
+                format_ns(stats.stddev_ns),
+                stats.samples,
+                stats.iterations
+            ),
+            Err(_) => println!("bench {} ... FAILED", outcome.name),
+        }
+    });
+    let report = match report {
+        Ok(report) => report,
+        Err(errors) => {
+            print_compile_errors(&errors);
+            return 2;
+        }
+    };
+    if json {
+        println!("{}", report.to_json());
+        return if report.success() { 0 } else { 1 };
+    }
+    let failures = report.outcomes.iter()
+        .filter_map(|o| o.result.as_ref().err().map(|message| (o.name.as_str(), message.as_str())));
+    let counts = format!(
+        "{} measured; {} failed; {} filtered out",
+        report.outcomes.len() - report.failed(), report.failed(), report.filtered_out
+    );
+    finish_report("bench", failures, &counts, report.success())
+}
+
+/// The end of an `aha test` or `aha bench` run: each failure's message,
+/// then the `<kind> result:` line. Returns the process exit code.
+fn finish_report<'a>(kind: &str, failures: impl Iterator<Item = (&'a str, &'a str)>, counts: &str, success: bool) -> i32 {
+    let failures: Vec<_> = failures.collect();
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, message) in &failures {
+            println!("\n---- {} ----\n{}", name, message);
+        }
+    }
+    println!("\n{} result: {}. {}", kind, if success { "ok" } else { "FAILED" }, counts);
+    if success { 0 } else { 1 }
+}
+
+fn print_compile_errors(errors: &[CompileError]) {
+    eprintln!("\n[ERROR] Compilation failed with {} error(s):", errors.len());
+    for error in errors {
+        eprintln!("- {}", error);
+    }
+}
@@ -5,11 +5,13 @@ use crate::ast;
 use crate::ast::{
     Program, Statement, Expression, Identifier, IntegerLiteral, BooleanLiteral,
     StringLiteral, PrefixExpression, InfixExpression, LetStatement, ReturnStatement,
-    ExpressionStatement, BlockStatement, WhileExpression, ForExpression, ArrayLiteral,
+    ExpressionStatement, BlockStatement, WhileExpression, ForExpression, ArrayLiteral, TupleLiteral,
     IndexExpression, StructDefinition, StructField, StructLiteral, FieldAccess,
-    AssignmentExpression, FunctionLiteral, ImportStatement, ModuleAccess,
+    AssignmentExpression, FunctionKind, FunctionLiteral, ImportStatement, ModuleAccess,
     ActorDefinition, SpawnExpression,
-    EnumDefinition, EnumVariant, MatchExpression, MatchArm, Pattern,
+    EnumDefinition, EnumVariant, MatchExpression, MatchArm, Pattern, LetPatternStatement,
+    IfLetExpression, WhileLetExpression, ConstStatement, StaticStatement, TypeAlias, NewtypeDefinition,
+    UseVariants,
 };
 use crate::ast::Token;
 use crate::ast::TokenType;
@@ -35,6 +37,7 @@ pub enum Precedence {
     Range,       // ..
     Sum,         // +
     Product,     // *
+    Cast,        // x as int
     Prefix,      // -X or !X
     Call,        // myFunction(X)
     Index,       // arr[i]
@@ -100,31 +103,89 @@ impl Parser {
     /// the first identifier of the hint). Consumes the full hint and returns
     /// the canonical hint string ("List<int>", "Map<string,int>", ...).
     fn parse_type_hint(&mut self) -> Option<String> {
+        // Tuple hint: '(' hint, hint, ... ')', canonicalised as "(a, b)".
+        if self.current_token_is(TokenType::LeftParen) {
+            let mut parts = Vec::new();
+            loop {
+                self.next_token(); // current = element hint start
+                parts.push(self.parse_type_hint()?);
+                if !self.peek_token_is(TokenType::Comma) {
+                    break;
+                }
+                self.next_token(); // current = ','
+                if self.peek_token_is(TokenType::RightParen) {
+                    break; // trailing comma: (int,)
+                }
+            }
+            if !self.expect_peek(TokenType::RightParen) {
+                self.errors.push("Expected ')' to close tuple type hint".to_string());
+                return None;
+            }
+            if parts.len() < 2 {
+                return Some(format!("({},)", parts[0]));
+            }
+            return Some(format!("({})", parts.join(", ")));
+        }
+        // Array hint: '[' hint ';' length ']', canonicalised as "[a; n]".
+        if self.current_token_is(TokenType::LeftBracket) {
+            self.next_token(); // current = element hint start
+            let elem = self.parse_type_hint()?;
+            if !self.expect_peek(TokenType::Semicolon) || !self.expect_peek(TokenType::Integer) {
+                self.errors.push("Expected `[T; N]` array type".to_string());
+                return None;
+            }
+            let len = self.current_token.literal.clone();
+            if !self.expect_peek(TokenType::RightBracket) {
+                return None;
+            }
+            return Some(format!("[{}; {}]", elem, len));
+        }
         if !self.current_token_is(TokenType::Identifier) {
             return None;
         }
         let hint = self.current_token.literal.clone();
+        // Unit of measure: int<m>, int<m/s>, int<1/s>.
+        if hint == "int" && self.peek_token_is(TokenType::LT) {
+            self.next_token(); // current = '<'
+            let mut unit = String::new();
+            while !self.peek_token_is(TokenType::GT) {
+                self.next_token();
+                match self.current_token.kind {
+                    TokenType::Identifier | TokenType::Integer | TokenType::Asterisk | TokenType::Slash => {
+                        unit.push_str(&self.current_token.literal);
+                    }
+                    _ => {
+                        self.errors.push(format!(
+                            "Unexpected '{}' in unit of measure int<{}...>",
+                            self.current_token.literal, unit
+                        ));
+                        return None;
+                    }
+                }
+            }
+            self.next_token(); // current = '>'
+            return Some(format!("int<{}>", unit));
+        }
         // Compound hint: identifier followed by '<'.
         if self.peek_token_is(TokenType::LT) {
             self.next_token(); // current = '<', peek = first token of inner
             self.next_token(); // current = inner hint start, peek = '>' or '<'
-            let first_hint = self.parse_type_hint()?;
-            if self.peek_token_is(TokenType::Comma) {
-                // Map<K, V>: after the key hint comes a comma, then the value hint.
+            let mut args = vec![self.parse_type_hint()?];
+            while self.peek_token_is(TokenType::Comma) {
                 self.next_token(); // current = ','
-                self.next_token(); // current = value hint start
-                let second_hint = self.parse_type_hint()?;
-                if !self.expect_peek(TokenType::GT) {
-                    self.errors.push("Expected '>' to close Map<K,V> type hint".to_string());
-                    return None;
-                }
-                return Some(format!("Map<{}, {}>", first_hint, second_hint));
+                self.next_token(); // current = next hint start
+                args.push(self.parse_type_hint()?);
             }
             if !self.expect_peek(TokenType::GT) {
-                self.errors.push("Expected '>' to close List<T> type hint".to_string());
+                self.errors.push(format!("Expected '>' to close {}<...> type hint", hint));
                 return None;
             }
-            return Some(format!("List<{}>", first_hint));
+            return Some(match hint.as_str() {
+                "Map" | "map" => format!("Map<{}>", args.join(", ")),
+                "List" | "list" => format!("List<{}>", args.join(", ")),
+                // Generic struct instance: Pair<int, string>
+                _ => format!("{}<{}>", hint, args.join(", ")),
+            });
         }
         Some(hint)
     }
@@ -152,12 +213,24 @@ impl Parser {
     fn parse_statement(&mut self) -> Option<Statement> {
         match self.current_token.kind {
             TokenType::Let => self.parse_let_statement(),
+            TokenType::Const => self.parse_const_statement(false),
+            TokenType::Static => self.parse_static_statement(false),
+            TokenType::Type => self.parse_type_alias(false),
+            TokenType::Newtype => self.parse_newtype_definition(false),
             TokenType::Return => self.parse_return_statement(),
             TokenType::Struct => self.parse_struct_definition(false),
             TokenType::Actor => self.parse_actor_definition(false),
             TokenType::Enum => self.parse_enum_definition(false),
             TokenType::Use => self.parse_use_statement(),
             TokenType::Pub => self.parse_pub_statement(),
+            TokenType::Hash => self.parse_attributed_statement(),
+            // `test` and `bench` are only keywords in front of `fn`.
+            TokenType::Identifier if self.current_token.literal == "test" && self.peek_token_is(TokenType::Fn) => {
+                self.parse_harness_function(FunctionKind::Test)
+            }
+            TokenType::Identifier if self.current_token.literal == "bench" && self.peek_token_is(TokenType::Fn) => {
+                self.parse_harness_function(FunctionKind::Bench)
+            }
             _ => self.parse_expression_statement(),
         }
     }
@@ -169,13 +242,73 @@ impl Parser {
             TokenType::Struct => self.parse_struct_definition(true),
             TokenType::Actor => self.parse_actor_definition(true),
             TokenType::Enum => self.parse_enum_definition(true),
+            TokenType::Const => self.parse_const_statement(true),
+            TokenType::Static => self.parse_static_statement(true),
+            TokenType::Type => self.parse_type_alias(true),
+            TokenType::Newtype => self.parse_newtype_definition(true),
             _ => {
-                self.errors.push("Expected 'fn', 'struct', 'enum', or 'actor' after 'pub'".to_string());
+                self.errors.push("Expected 'fn', 'struct', 'enum', 'actor', 'const', 'static', 'type', or 'newtype' after 'pub'".to_string());
                 None
             }
         }
     }
 
+    /// Parse `#[repr(u8)] enum ...`, the one attribute there is.
+    fn parse_attributed_statement(&mut self) -> Option<Statement> {
+        if !self.expect_peek(TokenType::LeftBracket) || !self.expect_peek(TokenType::Identifier) {
+            return None;
+        }
+        if self.current_token.literal != "repr" {
+            self.errors.push(format!(
+                "Unknown attribute '#[{}]': only #[repr(...)] on enums is supported",
+                self.current_token.literal
+            ));
+            return None;
+        }
+        if !self.expect_peek(TokenType::LeftParen) || !self.expect_peek(TokenType::Identifier) {
+            return None;
+        }
+        let repr = self.current_token.literal.clone();
+        if !self.expect_peek(TokenType::RightParen) || !self.expect_peek(TokenType::RightBracket) {
+            return None;
+        }
+        self.next_token(); // Skip ']'
+        let is_pub = self.current_token_is(TokenType::Pub);
+        if is_pub {
+            self.next_token(); // Skip 'pub'
+        }
+        if !self.current_token_is(TokenType::Enum) {
+            self.errors.push(format!("#[repr({})] must be followed by an enum", repr));
+            return None;
+        }
+        match self.parse_enum_definition(is_pub) {
+            Some(Statement::Enum(mut def)) => {
+                def.repr = Some(repr);
+                Some(Statement::Enum(def))
+            }
+            other => other,
+        }
+    }
+
+    /// Parse `test fn name() { ... }` or `bench fn name() { ... }`: a
+    /// function `aha test` or `aha bench` runs on its own. It takes no
+    /// parameters.
+    fn parse_harness_function(&mut self, kind: FunctionKind) -> Option<Statement> {
+        let keyword = self.current_token.literal.clone();
+        self.next_token(); // Skip 'test' / 'bench'
+        let Expression::Function(mut func) = self.parse_function_literal_with_pub(false) else { return None };
+        let name = func.name.as_ref().map_or("<anonymous>", |n| n.value.as_str()).to_string();
+        if func.name.is_none() || !func.parameters.is_empty() || !func.type_params.is_empty() {
+            self.errors.push(format!(
+                "line {}: {} fn '{}' must be named and take no parameters or type parameters",
+                func.line, keyword, name
+            ));
+            return None;
+        }
+        func.kind = kind;
+        Some(Statement::Expression(ExpressionStatement { expression: Expression::Function(func) }))
+    }
+
     fn parse_function_statement(&mut self, is_pub: bool) -> Option<Statement> {
         let expr = self.parse_function_literal_with_pub(is_pub);
         Some(Statement::Expression(ExpressionStatement { expression: expr }))
@@ -191,6 +324,18 @@ impl Parser {
         let name = Identifier { value: self.current_token.literal.clone() };
         self.struct_names.insert(name.value.clone());
 
+        // Generic type parameters: struct Pair<A, B> { ... }
+        let mut type_params = Vec::new();
+        if self.peek_token_is(TokenType::LT) {
+            self.next_token(); // skip name → '<'
+            while !self.current_token_is(TokenType::GT) && !self.current_token_is(TokenType::Eof) {
+                if self.current_token_is(TokenType::Identifier) {
+                    type_params.push(self.current_token.literal.clone());
+                }
+                self.next_token(); // skip ',' or type name
+            }
+        }
+
         if !self.expect_peek(TokenType::LeftBrace) {
             return None;
         }
@@ -203,6 +348,7 @@ impl Parser {
                 break;
             }
             let field_name = Identifier { value: self.current_token.literal.clone() };
+            let line = self.current_token.line;
             
             // Optional type hint after colon
             let type_hint = if self.peek_token_is(TokenType::Colon) {
@@ -212,8 +358,9 @@ impl Parser {
             } else {
                 None
             };
+            let default = self.parse_field_default();
             
-            fields.push(StructField { name: field_name, type_hint });
+            fields.push(StructField { name: field_name, type_hint, default, line });
             
             if self.peek_token_is(TokenType::Comma) {
                 self.next_token(); // Skip current
@@ -223,7 +370,18 @@ impl Parser {
             }
         }
         
-        Some(Statement::Struct(StructDefinition { name, is_pub, fields }))
+        Some(Statement::Struct(StructDefinition { name, is_pub, type_params, fields, is_newtype: false }))
+    }
+
+    /// Optional `= expr` after a field's type hint; current_token is the
+    /// last token of the field declaration.
+    fn parse_field_default(&mut self) -> Option<Expression> {
+        if !self.peek_token_is(TokenType::Assign) {
+            return None;
+        }
+        self.next_token(); // current = '='
+        self.next_token(); // current = default expression start
+        Some(self.parse_expression(Precedence::Lowest))
     }
 
     fn parse_actor_definition(&mut self, is_pub: bool) -> Option<Statement> {
@@ -248,6 +406,7 @@ impl Parser {
                 break;
             }
             let field_name = Identifier { value: self.current_token.literal.clone() };
+            let line = self.current_token.line;
 
             let type_hint = if self.peek_token_is(TokenType::Colon) {
                 self.next_token(); // Skip field name
@@ -256,8 +415,9 @@ impl Parser {
             } else {
                 None
             };
+            let default = self.parse_field_default();
 
-            fields.push(StructField { name: field_name, type_hint });
+            fields.push(StructField { name: field_name, type_hint, default, line });
 
             if self.peek_token_is(TokenType::Comma) {
                 self.next_token();
@@ -283,6 +443,18 @@ impl Parser {
         }
         let name = Identifier { value: self.current_token.literal.clone() };
 
+        // Generic type parameters: enum Tree<T> { ... }
+        let mut type_params = Vec::new();
+        if self.peek_token_is(TokenType::LT) {
+            self.next_token(); // skip name → '<'
+            while !self.current_token_is(TokenType::GT) && !self.current_token_is(TokenType::Eof) {
+                if self.current_token_is(TokenType::Identifier) {
+                    type_params.push(self.current_token.literal.clone());
+                }
+                self.next_token(); // skip ',' or type name
+            }
+        }
+
         if !self.expect_peek(TokenType::LeftBrace) {
             return None;
         }
@@ -306,8 +478,15 @@ impl Parser {
                 let mut types = Vec::new();
                 self.next_token(); // first token inside parens
                 while !self.current_token_is(TokenType::RightParen) && !self.current_token_is(TokenType::Eof) {
-                    if self.current_token_is(TokenType::Identifier) {
-                        types.push(self.current_token.literal.clone());
+                    // Full type hints, so payloads can be `T`, `List<T>`, `Pair<A, B>`.
+                    if self.current_token_is(TokenType::Identifier)
+                        || self.current_token_is(TokenType::LeftParen)
+                        || self.current_token_is(TokenType::LeftBracket)
+                    {
+                        match self.parse_type_hint() {
+                            Some(hint) => types.push(hint),
+                            None => break,
+                        }
                     }
                     self.next_token();
                 }
@@ -319,8 +498,22 @@ impl Parser {
 
             // For tuple variants, current is already on comma after ')'.
             // For unit variants, current is still on variant name — advance first.
-            let is_tuple = !payload_types.is_empty();
-            variants.push(EnumVariant { name: variant_name, payload_types });
+            let mut is_tuple = !payload_types.is_empty();
+
+            // Optional discriminant: Variant = 0x10
+            let discriminant = if (is_tuple && self.current_token_is(TokenType::Assign))
+                || (!is_tuple && self.peek_token_is(TokenType::Assign))
+            {
+                if !is_tuple {
+                    self.next_token(); // variant name → '='
+                }
+                self.next_token(); // Skip '='
+                is_tuple = true; // current is now past the value, like after ')'
+                self.parse_signed_integer("discriminant")
+            } else {
+                None
+            };
+            variants.push(EnumVariant { name: variant_name, payload_types, discriminant });
 
             if is_tuple {
                 // Tuple: current = comma, check directly
@@ -350,7 +543,7 @@ impl Parser {
         }
         // ponytail: current stays on '}' — parse_program advances past it
 
-        Some(Statement::Enum(EnumDefinition { name, is_pub, variants }))
+        Some(Statement::Enum(EnumDefinition { name, is_pub, type_params, variants, repr: None }))
     }
 
     /// Parse: spawn ActorName { field: value, ... }
@@ -408,11 +601,19 @@ impl Parser {
         self.next_token(); // move to '{'
 
         let mut fields: Vec<(Identifier, Expression)> = Vec::new();
+        let mut base = None;
         self.next_token(); // move past '{'
 
         while !self.current_token_is(TokenType::RightBrace)
             && !self.current_token_is(TokenType::Eof)
         {
+            // `..base` copies the remaining fields and must come last.
+            if self.current_token_is(TokenType::DotDot) {
+                self.next_token(); // move to base expression
+                base = Some(Box::new(self.parse_expression(Precedence::Lowest)));
+                self.next_token(); // move to '}'
+                break;
+            }
             if !self.current_token_is(TokenType::Identifier) {
                 self.errors.push("Expected field name in struct literal".to_string());
                 break;
@@ -442,14 +643,28 @@ impl Parser {
             self.errors.push("Expected '}' to close struct literal".to_string());
         }
 
-        Expression::StructLiteral(StructLiteral { name, fields })
+        Expression::StructLiteral(StructLiteral { name, fields, base })
     }
 
     /// Parse a `use "file"` statement.
     /// Syntax: `use "path/to/file"` — imports all functions and structs from the file.
+    /// `use Color::*` opens an enum's variants instead.
     fn parse_use_statement(&mut self) -> Option<Statement> {
+        let line = self.current_token.line;
         self.next_token(); // Skip 'use'
 
+        if self.current_token_is(TokenType::Identifier) && self.peek_token_is(TokenType::ColonColon) {
+            let enum_name = Identifier { value: self.current_token.literal.clone() };
+            self.next_token(); // Skip name → '::'
+            if !self.expect_peek(TokenType::Asterisk) {
+                return None;
+            }
+            if self.peek_token_is(TokenType::Semicolon) {
+                self.next_token();
+            }
+            return Some(Statement::UseVariants(UseVariants { enum_name, line }));
+        }
+
         if !self.current_token_is(TokenType::String) {
             self.errors.push(format!(
                 "Expected file path string after 'use', got '{}'",
@@ -471,8 +686,38 @@ impl Parser {
     }
 
     fn parse_let_statement(&mut self) -> Option<Statement> {
+        let line = self.current_token.line;
         self.next_token(); // Skip 'let'
 
+        let mutable = self.current_token_is(TokenType::Mut);
+        if mutable {
+            self.next_token(); // Skip 'mut'
+        }
+
+        // Destructuring: `let Point { x, y } = p` or `let (a, b) = t`.
+        // `let mut (a, b) = t` makes every binding of the pattern mutable.
+        if self.current_token_is(TokenType::LeftParen)
+            || (self.current_token_is(TokenType::Identifier) && self.peek_token_is(TokenType::LeftBrace))
+        {
+            let mut pattern = self.parse_single_pattern(); // leaves current on '='
+            if mutable {
+                Self::make_bindings_mutable(&mut pattern);
+            }
+            if !self.current_token_is(TokenType::Assign) {
+                self.errors.push(format!(
+                    "Expected = after let pattern, got {:?}",
+                    self.current_token.kind
+                ));
+                return None;
+            }
+            self.next_token(); // Skip '='
+            let value = self.parse_expression(Precedence::Lowest);
+            if self.peek_token_is(TokenType::Semicolon) {
+                self.next_token(); // Skip ';'
+            }
+            return Some(Statement::LetPattern(LetPatternStatement { pattern, value }));
+        }
+
         if !self.current_token_is(TokenType::Identifier) {
             self.errors.push(format!(
                 "Expected identifier after 'let', got {:?} instead",
@@ -486,9 +731,13 @@ impl Parser {
         let mut type_annotation: Option<String> = None;
         if self.peek_token_is(TokenType::Colon) {
             self.next_token(); // Skip ':'
-            if !self.expect_peek(TokenType::Identifier) {
+            let bracketed = self.peek_token_is(TokenType::LeftParen) || self.peek_token_is(TokenType::LeftBracket);
+            if !bracketed && !self.expect_peek(TokenType::Identifier) {
                 return None;
             }
+            if bracketed {
+                self.next_token();
+            }
             type_annotation = self.parse_type_hint();
         }
 
@@ -503,10 +752,114 @@ impl Parser {
             self.next_token(); // Skip ';'
         }
 
-        Some(Statement::Let(LetStatement { name, value, type_annotation }))
+        Some(Statement::Let(LetStatement { name, value, type_annotation, mutable, line }))
+    }
+
+    /// Turn every plain binding of a `let mut` pattern into a `mut` one.
+    fn make_bindings_mutable(pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Binding(name) => *pattern = Pattern::MutBinding(std::mem::take(name)),
+            Pattern::EnumTuple(_, subs) | Pattern::Tuple(subs) | Pattern::Or(subs) => {
+                subs.iter_mut().for_each(Self::make_bindings_mutable);
+            }
+            Pattern::Struct(_, fields) => {
+                fields.iter_mut().for_each(|(_, sub)| Self::make_bindings_mutable(sub));
+            }
+            _ => {}
+        }
+    }
+
+    /// `const NAME: type = value` — like `let`, but module-level and
+    /// evaluated at compile time.
+    fn parse_const_statement(&mut self, is_pub: bool) -> Option<Statement> {
+        let line = self.current_token.line;
+        let (name, type_annotation, value) = self.parse_global_item()?;
+        Some(Statement::Const(ConstStatement { name, value, type_annotation, is_pub, line }))
+    }
+
+    /// `static [mut] NAME: type = value` — a module-level global.
+    fn parse_static_statement(&mut self, is_pub: bool) -> Option<Statement> {
+        let line = self.current_token.line;
+        let mutable = self.peek_token_is(TokenType::Mut);
+        if mutable {
+            self.next_token(); // Skip 'static'
+        }
+        let (name, type_annotation, value) = self.parse_global_item()?;
+        Some(Statement::Static(StaticStatement { name, value, type_annotation, mutable, is_pub, line }))
+    }
+
+    /// `type Name = <type>`
+    fn parse_type_alias(&mut self, is_pub: bool) -> Option<Statement> {
+        let line = self.current_token.line;
+        let (name, target) = self.parse_type_definition()?;
+        Some(Statement::TypeAlias(TypeAlias { name, target, is_pub, line }))
+    }
+
+    /// `newtype Name = <type>`
+    fn parse_newtype_definition(&mut self, is_pub: bool) -> Option<Statement> {
+        let line = self.current_token.line;
+        let (name, base) = self.parse_type_definition()?;
+        Some(Statement::Newtype(NewtypeDefinition { name, base, is_pub, line }))
+    }
+
+    /// The `Name = <type>` shared by `type` and `newtype`; current is the
+    /// keyword.
+    fn parse_type_definition(&mut self) -> Option<(Identifier, String)> {
+        if !self.expect_peek(TokenType::Identifier) {
+            return None;
+        }
+        let name = Identifier { value: self.current_token.literal.clone() };
+        if !self.expect_peek(TokenType::Assign) {
+            return None;
+        }
+        if self.peek_token_is(TokenType::LeftParen) || self.peek_token_is(TokenType::LeftBracket) {
+            self.next_token();
+        } else if !self.expect_peek(TokenType::Identifier) {
+            return None;
+        }
+        let hint = self.parse_type_hint()?;
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token(); // Skip ';'
+        }
+        Some((name, hint))
+    }
+
+    /// The `NAME[: type] = value` shared by `const` and `static`; current
+    /// is the token before the name.
+    fn parse_global_item(&mut self) -> Option<(Identifier, Option<String>, Expression)> {
+        if !self.expect_peek(TokenType::Identifier) {
+            return None;
+        }
+        let name = Identifier { value: self.current_token.literal.clone() };
+
+        let mut type_annotation: Option<String> = None;
+        if self.peek_token_is(TokenType::Colon) {
+            self.next_token(); // Skip ':'
+            let bracketed = self.peek_token_is(TokenType::LeftParen) || self.peek_token_is(TokenType::LeftBracket);
+            if !bracketed && !self.expect_peek(TokenType::Identifier) {
+                return None;
+            }
+            if bracketed {
+                self.next_token();
+            }
+            type_annotation = self.parse_type_hint();
+        }
+
+        if !self.expect_peek(TokenType::Assign) {
+            return None;
+        }
+        self.next_token(); // Skip '='
+        let value = self.parse_expression(Precedence::Lowest);
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token(); // Skip ';'
+        }
+
+        Some((name, type_annotation, value))
     }
 
     fn parse_return_statement(&mut self) -> Option<Statement> {
+        let line = self.current_token.line;
         self.next_token(); // Skip 'return'
         let return_value = self.parse_expression(Precedence::Lowest);
 
@@ -514,7 +867,7 @@ impl Parser {
             self.next_token(); // Skip ';'
         }
 
-        Some(Statement::Return(ReturnStatement { return_value }))
+        Some(Statement::Return(ReturnStatement { return_value, line }))
     }
 
     fn parse_expression_statement(&mut self) -> Option<Statement> {
@@ -535,6 +888,7 @@ impl Parser {
         while !self.peek_token_is(TokenType::Semicolon) && precedence < self.peek_precedence() {
             // Handle index expression: arr[i]
             if self.peek_token_is(TokenType::LeftBracket) {
+                let line = self.peek_token.line;
                 self.next_token(); // consume '['
                 self.next_token(); // move to index expression
                 let index = self.parse_expression(Precedence::Lowest);
@@ -544,6 +898,7 @@ impl Parser {
                 left = Expression::Index(IndexExpression {
                     left: Box::new(left),
                     index: Box::new(index),
+                    line,
                 });
                 continue;
             }
@@ -552,7 +907,8 @@ impl Parser {
             if self.peek_token_is(TokenType::Dot) {
                 self.next_token(); // consume '.'
                 self.next_token(); // move to field name
-                if !self.current_token_is(TokenType::Identifier) {
+                // Tuple element access uses the position as the field name: t.0
+                if !self.current_token_is(TokenType::Identifier) && !self.current_token_is(TokenType::Integer) {
                     return Expression::Integer(IntegerLiteral { value: 0 });
                 }
                 let field = Identifier { value: self.current_token.literal.clone() };
@@ -563,26 +919,52 @@ impl Parser {
                 continue;
             }
 
+            // Handle cast: opcode as int
+            if self.peek_token_is(TokenType::As) {
+                self.next_token(); // consume 'as'
+                let line = self.current_token.line;
+                if !self.expect_peek(TokenType::Identifier) {
+                    return Expression::Integer(IntegerLiteral { value: 0 });
+                }
+                left = Expression::Cast(ast::CastExpression {
+                    value: Box::new(left),
+                    target: self.current_token.literal.clone(),
+                    line,
+                });
+                continue;
+            }
+
             // Handle assignment: left = expr
             // Left can be an identifier (x = 5) or a field access (p.x = 5)
             if self.peek_token_is(TokenType::Assign) {
+                let line = self.current_token.line;
                 self.next_token(); // consume '='
                 self.next_token(); // consume '='
                 let value = self.parse_expression(Precedence::Lowest);
                 left = Expression::Assignment(AssignmentExpression {
                     target: Box::new(left),
                     value: Box::new(value),
+                    line,
                 });
                 continue;
             }
             
-            // Handle function call: expr(args)
+            // Handle function call: expr(args). The '(' must be on the same
+            // line as the callee: a '(' on a new line starts a new statement,
+            // so a block can end in a tuple `(q, r)` and a line can start
+            // with a grouped expression. A call's arguments may still span
+            // lines once the '(' is open.
             if self.peek_token_is(TokenType::LeftParen) {
+                if self.peek_token.line > self.current_token.line {
+                    break;
+                }
+                let line = self.current_token.line;
                 self.next_token(); // consume '('
                 let arguments = self.parse_call_arguments();
                 left = Expression::Call(ast::CallExpression {
                     function: Box::new(left),
                     arguments,
+                    line,
                 });
                 continue;
             }
@@ -602,6 +984,7 @@ impl Parser {
             // Generic infix operator
             self.next_token(); // consume operator
             let operator = self.current_token.literal.clone();
+            let line = self.current_token.line;
             let right_precedence = self.current_precedence();
             self.next_token(); // move to right-hand expression
             let right = Box::new(self.parse_expression(right_precedence));
@@ -610,6 +993,7 @@ impl Parser {
                 left: Box::new(left),
                 operator,
                 right,
+                line,
             });
         }
 
@@ -629,6 +1013,7 @@ impl Parser {
                 }
                 // Module access: module::name
                 if self.peek_token_is(TokenType::ColonColon) {
+                    let line = self.current_token.line;
                     self.next_token(); // skip '::'
                     self.next_token(); // skip to name
                     let name = self.current_token.literal.clone();
@@ -642,6 +1027,7 @@ impl Parser {
                     return Expression::ModuleAccess(ModuleAccess {
                         module: ident.value,
                         name,
+                        line,
                     });
                 }
                 Expression::Identifier(ident)
@@ -674,13 +1060,17 @@ impl Parser {
             TokenType::LeftBracket => self.parse_array_literal(),
             TokenType::Bang | TokenType::Minus => {
                 let operator = self.current_token.literal.clone();
+                let line = self.current_token.line;
                 self.next_token();
                 let right = Box::new(self.parse_expression(Precedence::Prefix));
-                Expression::Prefix(PrefixExpression { operator, right })
+                Expression::Prefix(PrefixExpression { operator, right, line })
             },
             TokenType::LeftParen => {
                 self.next_token();
                 let exp = self.parse_expression(Precedence::Lowest);
+                if self.peek_token_is(TokenType::Comma) {
+                    return self.parse_tuple_literal(exp);
+                }
                 if !self.expect_peek(TokenType::RightParen) {
                     self.errors.push("Expected closing parenthesis ')'".to_string());
                     return exp;
@@ -694,8 +1084,27 @@ impl Parser {
         }
     }
 
+    // Parse the rest of a tuple literal after its first element:
+    // (a, b, c) or the one-element form (a,).
+    fn parse_tuple_literal(&mut self, first: Expression) -> Expression {
+        let mut elements = vec![first];
+        while self.peek_token_is(TokenType::Comma) {
+            self.next_token(); // current = ','
+            if self.peek_token_is(TokenType::RightParen) {
+                break; // trailing comma
+            }
+            self.next_token(); // move to next element
+            elements.push(self.parse_expression(Precedence::Lowest));
+        }
+        if !self.expect_peek(TokenType::RightParen) {
+            self.errors.push("Expected ')' to close tuple".to_string());
+        }
+        Expression::Tuple(TupleLiteral { elements })
+    }
+
     // Parse function literal: fn name(params) { body }
     fn parse_function_literal_with_pub(&mut self, is_pub: bool) -> Expression {
+        let line = self.current_token.line;
         let name = if self.peek_token_is(TokenType::Identifier) {
             self.next_token();
             Some(Identifier { value: self.current_token.literal.clone() })
@@ -722,12 +1131,14 @@ impl Parser {
             return Expression::Integer(IntegerLiteral { value: 0 });
         }
 
-        let (parameters, param_type_hints) = self.parse_function_parameters();
+        let (parameters, param_type_hints, param_patterns, mutable_params) = self.parse_function_parameters();
 
         // Optional return type annotation: fn f(...) -> T
         let return_type_hint = if self.peek_token_is(TokenType::Arrow) {
             self.next_token(); // skip '->'
-            if !self.expect_peek(TokenType::Identifier) {
+            if self.peek_token_is(TokenType::LeftParen) || self.peek_token_is(TokenType::LeftBracket) {
+                self.next_token();
+            } else if !self.expect_peek(TokenType::Identifier) {
                 self.errors.push("Expected type after '->' in function return".to_string());
             }
             self.parse_type_hint()
@@ -740,57 +1151,90 @@ impl Parser {
             return Expression::Integer(IntegerLiteral { value: 0 });
         }
 
-        let body = self.parse_block_statement();
+        let mut body = self.parse_block_statement();
 
-        Expression::Function(FunctionLiteral { name, parameters, is_pub, type_params, param_type_hints, return_type_hint, body })
+        // Destructured parameters: `fn f(Point { x, y }: Point)` binds a
+        // hidden `__paramN` and opens the body with `let <pattern> = __paramN`.
+        for (i, pattern) in param_patterns.into_iter().rev() {
+            body.statements.insert(0, Statement::LetPattern(LetPatternStatement {
+                pattern,
+                value: Expression::Identifier(parameters[i].clone()),
+            }));
+        }
+
+        Expression::Function(FunctionLiteral {
+            name, parameters, mutable_params, is_pub, type_params, param_type_hints, return_type_hint, body, line,
+            kind: FunctionKind::Plain,
+        })
     }
 
-    // Parse function parameters: (a, b, c) or (a: T, b: int)
-    fn parse_function_parameters(&mut self) -> (Vec<Identifier>, Vec<Option<String>>) {
+    // Parse function parameters: (a, b, c), (a: T, b: int),
+    // (Point { x, y }: Point) or ((a, b): (int, int)). Destructuring patterns are returned with
+    // their parameter index; the parameter itself gets a hidden name.
+    #[allow(clippy::type_complexity)]
+    fn parse_function_parameters(&mut self) -> (Vec<Identifier>, Vec<Option<String>>, Vec<(usize, Pattern)>, Vec<bool>) {
         let mut params = Vec::new();
         let mut hints = Vec::new();
+        let mut patterns = Vec::new();
+        let mut mutable = Vec::new();
 
         if self.peek_token_is(TokenType::RightParen) {
             self.next_token();
-            return (params, hints);
+            return (params, hints, patterns, mutable);
         }
 
         self.next_token(); // Skip '('
-        params.push(Identifier { value: self.current_token.literal.clone() });
-
-        // Optional per-param type hint: name: Type
-        let hint = if self.peek_token_is(TokenType::Colon) {
-            self.next_token(); // skip ':'
-            if !self.expect_peek(TokenType::Identifier) {
-                self.errors.push("Expected type after ':' in parameter".to_string());
+        loop {
+            let index = params.len();
+            // `mut n`: the parameter may be reassigned in the body.
+            let is_mut = self.current_token_is(TokenType::Mut);
+            if is_mut {
+                self.next_token(); // Skip 'mut'
+            }
+            mutable.push(is_mut);
+            let mut pattern_hint = None;
+            let is_pattern = self.current_token_is(TokenType::LeftParen)
+                || (self.current_token_is(TokenType::Identifier) && self.peek_token_is(TokenType::LeftBrace));
+            if is_pattern {
+                let pattern = self.parse_single_pattern(); // current → ':' / ',' / ')'
+                if let Pattern::Struct(ref name, _) = pattern {
+                    pattern_hint = Some(name.clone());
+                }
+                params.push(Identifier { value: format!("__param{}", index) });
+                patterns.push((index, pattern));
+            } else {
+                params.push(Identifier { value: self.current_token.literal.clone() });
+                self.next_token(); // Skip param name
             }
-            self.parse_type_hint()
-        } else {
-            None
-        };
-        hints.push(hint);
 
-        while self.peek_token_is(TokenType::Comma) {
-            self.next_token(); // Skip current param
-            self.next_token(); // Skip ','
-            params.push(Identifier { value: self.current_token.literal.clone() });
-            let hint = if self.peek_token_is(TokenType::Colon) {
+            // Optional per-param type hint: name: Type
+            let hint = if self.current_token_is(TokenType::Colon) {
                 self.next_token(); // skip ':'
-                if !self.expect_peek(TokenType::Identifier) {
+                if !self.current_token_is(TokenType::Identifier)
+                    && !self.current_token_is(TokenType::LeftParen)
+                    && !self.current_token_is(TokenType::LeftBracket)
+                {
                     self.errors.push("Expected type after ':' in parameter".to_string());
                 }
-                self.parse_type_hint()
+                let hint = self.parse_type_hint();
+                self.next_token(); // Skip last token of the hint
+                hint
             } else {
-                None
+                pattern_hint
             };
             hints.push(hint);
-        }
 
-        if !self.expect_peek(TokenType::RightParen) {
-            self.errors.push("Expected ')' after function parameters".to_string());
+            if self.current_token_is(TokenType::Comma) {
+                self.next_token(); // Skip ','
+                continue;
+            }
+            if !self.current_token_is(TokenType::RightParen) {
+                self.errors.push("Expected ')' after function parameters".to_string());
+            }
+            break;
         }
 
-        (params, hints)
+        (params, hints, patterns, mutable)
     }
 
     // Parse function call arguments: (expr, expr, ...)
@@ -821,6 +1265,22 @@ impl Parser {
     // Parse while expression: while condition { body }
     fn parse_while_expression(&mut self) -> Expression {
         self.next_token(); // Skip 'while'
+
+        if self.current_token_is(TokenType::Let) {
+            let (pattern, value) = match self.parse_let_condition() {
+                Some(pv) => pv,
+                None => return Expression::Integer(IntegerLiteral { value: 0 }),
+            };
+            if !self.expect_peek(TokenType::LeftBrace) {
+                return Expression::Integer(IntegerLiteral { value: 0 });
+            }
+            let body = self.parse_block_statement();
+            return Expression::WhileLet(WhileLetExpression {
+                pattern,
+                value: Box::new(value),
+                body,
+            });
+        }
         
         let condition = self.parse_expression(Precedence::Lowest);
         
@@ -838,17 +1298,32 @@ impl Parser {
 
     // Parse for expression: for variable in iterable { body }
     fn parse_for_expression(&mut self) -> Expression {
+        let line = self.current_token.line;
         self.next_token(); // Skip 'for'
         
-        if !self.current_token_is(TokenType::Identifier) {
-            self.errors.push("Expected identifier in for loop".to_string());
-            return Expression::Integer(IntegerLiteral { value: 0 });
-        }
-        let variable = Identifier { value: self.current_token.literal.clone() };
-        
-        if !self.expect_peek(TokenType::In) {
-            return Expression::Integer(IntegerLiteral { value: 0 });
-        }
+        // `for (k, v) in ...` destructures each element.
+        let mut pattern = None;
+        let variable = if self.current_token_is(TokenType::LeftParen) {
+            pattern = Some(self.parse_single_pattern()); // leaves current on 'in'
+            if !self.current_token_is(TokenType::In) {
+                self.errors.push(format!(
+                    "Expected 'in' after for pattern, got {:?}",
+                    self.current_token.kind
+                ));
+                return Expression::Integer(IntegerLiteral { value: 0 });
+            }
+            Identifier { value: "__for_item".to_string() }
+        } else {
+            if !self.current_token_is(TokenType::Identifier) {
+                self.errors.push("Expected identifier in for loop".to_string());
+                return Expression::Integer(IntegerLiteral { value: 0 });
+            }
+            let variable = Identifier { value: self.current_token.literal.clone() };
+            if !self.expect_peek(TokenType::In) {
+                return Expression::Integer(IntegerLiteral { value: 0 });
+            }
+            variable
+        };
         
         self.next_token(); // Skip 'in'
         
@@ -858,17 +1333,25 @@ impl Parser {
             return Expression::Integer(IntegerLiteral { value: 0 });
         }
         
-        let body = self.parse_block_statement();
+        let mut body = self.parse_block_statement();
+        if let Some(pattern) = pattern {
+            body.statements.insert(0, Statement::LetPattern(LetPatternStatement {
+                pattern,
+                value: Expression::Identifier(variable.clone()),
+            }));
+        }
         
         Expression::For(ForExpression {
             variable,
             iterable: Box::new(iterable),
             body,
+            line,
         })
     }
 
     /// Parse: match expr { pattern => body, ... }
     fn parse_match_expression(&mut self) -> Expression {
+        let line = self.current_token.line;
         self.next_token(); // Skip 'match'
 
         let value = self.parse_expression(Precedence::Lowest);
@@ -882,11 +1365,22 @@ impl Parser {
         while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::Eof) {
             let pattern = self.parse_pattern();
 
-            // parse_pattern leaves current_token on ',' (unit) or '=>' (tuple).
+            // parse_pattern leaves current_token on ',' (unit), 'if' or '=>'.
             // For unit patterns, advance past comma to reach '=>'.
             if self.current_token_is(TokenType::Comma) {
                 self.next_token(); // ',' → '=>'
             }
+
+            // Optional guard: `pattern if cond => body`
+            let guard = if self.current_token_is(TokenType::If) {
+                self.next_token(); // Skip 'if'
+                let cond = self.parse_expression(Precedence::Lowest);
+                self.next_token(); // cond's last token → '=>'
+                Some(cond)
+            } else {
+                None
+            };
+
             if !self.current_token_is(TokenType::FatArrow) {
                 self.errors.push(format!(
                     "Expected => after pattern, got {:?}",
@@ -898,7 +1392,7 @@ impl Parser {
             self.next_token(); // Skip '=>'
 
             let body = self.parse_expression(Precedence::Lowest);
-            arms.push(MatchArm { pattern, body });
+            arms.push(MatchArm { pattern, guard, body });
 
             // After parse_expression, current_token is on body's last token,
             // peek_token is on ',' or '}'. Skip comma if present.
@@ -914,17 +1408,94 @@ impl Parser {
         Expression::Match(MatchExpression {
             value: Box::new(value),
             arms,
+            line,
         })
     }
 
-    /// Parse a match pattern: `_`, `Variant`, or `Variant(a, b, ...)`
-    /// Advances current_token to `=>` (FatArrow) in all cases.
+    /// Parse a match pattern, including `a | b | ...` alternatives.
+    /// Advances current_token past the pattern (to `=>`, `if` or `,`).
     fn parse_pattern(&mut self) -> Pattern {
+        let first = self.parse_single_pattern();
+        if !self.current_token_is(TokenType::Pipe) {
+            return first;
+        }
+        let mut alternatives = vec![first];
+        while self.current_token_is(TokenType::Pipe) {
+            self.next_token(); // Skip '|'
+            alternatives.push(self.parse_single_pattern());
+        }
+        Pattern::Or(alternatives)
+    }
+
+    /// Parse one pattern alternative: `_`, a literal, `lo..=hi`, a binding
+    /// (`mut` optional), `(a, b)`, `Variant`, or `Variant(a, b, ...)`.
+    fn parse_single_pattern(&mut self) -> Pattern {
         if self.current_token_is(TokenType::Identifier) && self.current_token.literal == "_" {
             self.next_token(); // Skip '_'
             return Pattern::Wildcard;
         }
 
+        if self.current_token_is(TokenType::String) {
+            let value = self.current_token.literal.clone();
+            self.next_token(); // Skip string literal
+            return Pattern::Str(value);
+        }
+
+        // `mut name` binds a variable that can be reassigned.
+        if self.current_token_is(TokenType::Mut) {
+            if !self.expect_peek(TokenType::Identifier) {
+                return Pattern::Wildcard;
+            }
+            let name = self.current_token.literal.clone();
+            self.next_token(); // Skip name
+            return Pattern::MutBinding(name);
+        }
+
+        if self.current_token_is(TokenType::Integer) || self.current_token_is(TokenType::Minus) {
+            let lo = match self.parse_pattern_integer() {
+                Some(v) => v,
+                None => return Pattern::Wildcard,
+            };
+            if self.current_token_is(TokenType::DotDotEq) {
+                self.next_token(); // Skip '..='
+                let hi = match self.parse_pattern_integer() {
+                    Some(v) => v,
+                    None => return Pattern::Wildcard,
+                };
+                if lo > hi {
+                    self.errors.push(format!("Empty range pattern {}..={}", lo, hi));
+                }
+                return Pattern::Range(lo, hi);
+            }
+            if self.current_token_is(TokenType::DotDot) {
+                self.errors.push("Exclusive range patterns are not supported, use `..=`".to_string());
+                self.next_token(); // Skip '..'
+                self.parse_pattern_integer();
+                return Pattern::Wildcard;
+            }
+            return Pattern::Integer(lo);
+        }
+
+        // Tuple pattern: (p1, p2, ...)
+        if self.current_token_is(TokenType::LeftParen) {
+            self.next_token(); // Skip '('
+            let mut elements = Vec::new();
+            while !self.current_token_is(TokenType::RightParen) && !self.current_token_is(TokenType::Eof) {
+                elements.push(self.parse_pattern());
+                if self.current_token_is(TokenType::Comma) {
+                    self.next_token(); // Skip ','
+                } else if !self.current_token_is(TokenType::RightParen) {
+                    self.errors.push(format!(
+                        "Expected , or ) in tuple pattern, got {:?}",
+                        self.current_token.kind
+                    ));
+                    break;
+                }
+            }
+            self.next_token(); // Skip ')'
+            return Pattern::Tuple(elements);
+        }
+
         if !self.current_token_is(TokenType::Identifier) {
             self.errors.push(format!(
                 "Expected pattern, got {:?}",
@@ -934,27 +1505,127 @@ impl Parser {
             return Pattern::Wildcard;
         }
 
-        let name = self.current_token.literal.clone();
+        let mut name = self.current_token.literal.clone();
 
-        // Check for tuple pattern: Variant(a, b, ...)
+        // Qualified variant path: `Enum::Variant`
+        while self.peek_token_is(TokenType::ColonColon) {
+            self.next_token(); // Skip name → '::'
+            if !self.expect_peek(TokenType::Identifier) {
+                return Pattern::Wildcard;
+            }
+            name = format!("{}::{}", name, self.current_token.literal);
+        }
+
+        // Tuple pattern: Variant(p1, p2, ...)
         if self.peek_token_is(TokenType::LeftParen) {
             self.next_token(); // Skip variant name
             self.next_token(); // Skip '('
-            let mut bindings = Vec::new();
+            let mut fields = Vec::new();
             while !self.current_token_is(TokenType::RightParen) && !self.current_token_is(TokenType::Eof) {
-                if self.current_token_is(TokenType::Identifier) {
-                    bindings.push(self.current_token.literal.clone());
+                fields.push(self.parse_pattern());
+                if self.current_token_is(TokenType::Comma) {
+                    self.next_token(); // Skip ','
+                } else if !self.current_token_is(TokenType::RightParen) {
+                    self.errors.push(format!(
+                        "Expected , or ) in pattern, got {:?}",
+                        self.current_token.kind
+                    ));
+                    break;
                 }
-                self.next_token();
             }
             self.next_token(); // Skip ')'
-            Pattern::EnumTuple(name, bindings)
+            return Pattern::EnumTuple(name, fields);
+        }
+
+        // Struct pattern: Name { field, field: pattern, .. }
+        if self.peek_token_is(TokenType::LeftBrace) {
+            self.next_token(); // Skip struct name
+            self.next_token(); // Skip '{'
+            let mut fields = Vec::new();
+            while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::Eof) {
+                if self.current_token_is(TokenType::DotDot) {
+                    self.next_token(); // `..` — remaining fields are ignored anyway
+                } else if self.current_token_is(TokenType::Mut) && self.peek_token_is(TokenType::Identifier) {
+                    self.next_token(); // Skip 'mut'
+                    let field = self.current_token.literal.clone();
+                    self.next_token(); // Skip field name
+                    fields.push((field.clone(), Pattern::MutBinding(field)));
+                } else if self.current_token_is(TokenType::Identifier) {
+                    let field = self.current_token.literal.clone();
+                    self.next_token(); // Skip field name
+                    let sub = if self.current_token_is(TokenType::Colon) {
+                        self.next_token(); // Skip ':'
+                        self.parse_pattern()
+                    } else {
+                        Pattern::Binding(field.clone())
+                    };
+                    fields.push((field, sub));
+                } else {
+                    self.errors.push(format!(
+                        "Expected field name in struct pattern, got {:?}",
+                        self.current_token.kind
+                    ));
+                    break;
+                }
+                if self.current_token_is(TokenType::Comma) {
+                    self.next_token(); // Skip ','
+                } else if !self.current_token_is(TokenType::RightBrace) {
+                    self.errors.push(format!(
+                        "Expected , or }} in struct pattern, got {:?}",
+                        self.current_token.kind
+                    ));
+                    break;
+                }
+            }
+            self.next_token(); // Skip '}'
+            return Pattern::Struct(name, fields);
+        }
+
+        self.next_token(); // Skip name → ','
+        // Variants are capitalised by convention; lowercase names bind.
+        // Codegen still resolves a binding that names a variant of the
+        // scrutinee's enum as that unit variant.
+        if name.starts_with(|c: char| c.is_lowercase()) && !name.contains("::") {
+            Pattern::Binding(name)
         } else {
-            self.next_token(); // Skip variant name → ','
             Pattern::EnumUnit(name)
         }
     }
 
+    /// Parse an optionally negated integer literal inside a pattern and
+    /// advance past it.
+    fn parse_pattern_integer(&mut self) -> Option<i64> {
+        self.parse_signed_integer("pattern")
+    }
+
+    /// Parse an optionally negated integer literal and advance past it;
+    /// `context` names where it appears, for errors.
+    fn parse_signed_integer(&mut self, context: &str) -> Option<i64> {
+        let negative = self.current_token_is(TokenType::Minus);
+        if negative {
+            self.next_token(); // Skip '-'
+        }
+        if !self.current_token_is(TokenType::Integer) {
+            self.errors.push(format!(
+                "Expected integer in {}, got {:?}",
+                context, self.current_token.kind
+            ));
+            self.next_token();
+            return None;
+        }
+        // Parse the sign with the digits so `-9223372036854775808` fits.
+        let literal = &self.current_token.literal;
+        let parsed = if negative { format!("-{}", literal).parse::<i64>() } else { literal.parse::<i64>() };
+        self.next_token(); // Skip integer
+        match parsed {
+            Ok(v) => Some(v),
+            Err(_) => {
+                self.errors.push(format!("Integer literal in {} out of range", context));
+                None
+            }
+        }
+    }
+
     // Parse array literal: [elem1, elem2, ...]
     fn parse_array_literal(&mut self) -> Expression {
         let mut elements = Vec::new();
@@ -984,6 +1655,10 @@ impl Parser {
     fn parse_if_expression(&mut self) -> Expression {
         self.next_token(); // Skip 'if'
 
+        if self.current_token_is(TokenType::Let) {
+            return self.parse_if_let_expression();
+        }
+
         let condition = self.parse_expression(Precedence::Lowest);
 
         if !self.expect_peek(TokenType::LeftBrace) {
@@ -991,28 +1666,7 @@ impl Parser {
         }
 
         let consequence = self.parse_block_statement();
-
-        // Check for 'else' branch
-        let alternative = if self.peek_token_is(TokenType::Else) {
-            self.next_token(); // Skip 'else'
-            
-            // Check for 'else if' chain
-            if self.peek_token_is(TokenType::If) {
-                self.next_token(); // Skip to 'if'
-                // Recursive: wrap else-if as a block containing an if expression
-                Some(BlockStatement {
-                    statements: vec![Statement::Expression(ExpressionStatement {
-                        expression: self.parse_if_expression(),
-                    })],
-                })
-            } else if self.expect_peek(TokenType::LeftBrace) {
-                Some(self.parse_block_statement())
-            } else {
-                return Expression::Integer(IntegerLiteral { value: 0 });
-            }
-        } else {
-            None
-        };
+        let alternative = self.parse_else_branch();
 
         Expression::If(ast::IfExpression {
             condition: Box::new(condition),
@@ -1021,6 +1675,69 @@ impl Parser {
         })
     }
 
+    /// Parse: if let Pattern = expr { ... } [else { ... }]
+    /// Called with current_token on 'let'.
+    fn parse_if_let_expression(&mut self) -> Expression {
+        let (pattern, value) = match self.parse_let_condition() {
+            Some(pv) => pv,
+            None => return Expression::Integer(IntegerLiteral { value: 0 }),
+        };
+
+        if !self.expect_peek(TokenType::LeftBrace) {
+            return Expression::Integer(IntegerLiteral { value: 0 });
+        }
+
+        let consequence = self.parse_block_statement();
+        let alternative = self.parse_else_branch();
+
+        Expression::IfLet(IfLetExpression {
+            pattern,
+            value: Box::new(value),
+            consequence,
+            alternative,
+        })
+    }
+
+    /// Parse `let Pattern = expr` in an `if let` / `while let` head.
+    /// Leaves current_token on the last token of expr.
+    fn parse_let_condition(&mut self) -> Option<(Pattern, Expression)> {
+        self.next_token(); // Skip 'let'
+        let pattern = self.parse_pattern(); // leaves current on '='
+        if !self.current_token_is(TokenType::Assign) {
+            self.errors.push(format!(
+                "Expected = after pattern in let condition, got {:?}",
+                self.current_token.kind
+            ));
+            return None;
+        }
+        self.next_token(); // Skip '='
+        let value = self.parse_expression(Precedence::Lowest);
+        Some((pattern, value))
+    }
+
+    /// Parse an optional `else { ... }` or `else if ...` after an if body.
+    fn parse_else_branch(&mut self) -> Option<BlockStatement> {
+        if !self.peek_token_is(TokenType::Else) {
+            return None;
+        }
+        self.next_token(); // Skip 'else'
+
+        // Check for 'else if' chain
+        if self.peek_token_is(TokenType::If) {
+            self.next_token(); // Skip to 'if'
+            // Recursive: wrap else-if as a block containing an if expression
+            Some(BlockStatement {
+                statements: vec![Statement::Expression(ExpressionStatement {
+                    expression: self.parse_if_expression(),
+                })],
+            })
+        } else if self.expect_peek(TokenType::LeftBrace) {
+            Some(self.parse_block_statement())
+        } else {
+            None
+        }
+    }
+
     // Parse a block statement: { stmt1; stmt2; ... }
     fn parse_block_statement(&mut self) -> BlockStatement {
         self.next_token(); // Skip '{'
@@ -1064,6 +1781,7 @@ impl Parser {
             TokenType::Slash => Precedence::Product,
             TokenType::Asterisk => Precedence::Product,
             TokenType::Percent => Precedence::Product,
+            TokenType::As => Precedence::Cast,
             TokenType::LeftParen => Precedence::Call,
             TokenType::LeftBracket => Precedence::Index,
             TokenType::Dot => Precedence::Index,
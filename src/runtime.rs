@@ -6,20 +6,61 @@
 //
 // Each actor has:
 //   - A thread running a message loop
-//   - A mailbox (mpsc channel) for incoming messages
-//   - A shared result slot (Mutex + Condvar) for request-response
+//   - A mailbox (mpsc channel) for incoming messages; a `call` message
+//     carries its own reply channel
 //
-// actor_spawn(fn_ptr, init_state) -> handle
-// actor_send(handle, msg)           — fire-and-forget
-// actor_call(handle, msg) -> i64    — blocking request-response
+// actor_spawn(fn_ptr, init_state, run) -> handle
+// actor_send(handle, msg)                — fire-and-forget
+// actor_call(handle, msg, out) -> i64    — blocking request-response
+//
+// The actor thread runs each message through `aha_actor_run`, so a panic
+// in the handler ends the actor instead of the process. The panic goes
+// back to the caller waiting in `actor_call`, which re-raises it on its
+// own thread.
 
 use std::collections::HashMap;
-use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
 
+/// How a panic travels between threads and back to `run_jit`: filled by
+/// `aha_run` and `aha_actor_run` when their callee panics, and by
+/// `actor_call` for its compiled caller. Laid out like codegen's
+/// `{i64, i8*, i64, i64}` record.
+#[repr(C)]
+pub struct PanicRecord {
+    /// 0: no panic. `PANIC_RAISED`: the panic was raised and printed on
+    /// another thread. `PANIC_DEAD_ACTOR`: `actor_call` found no actor.
+    pub(crate) panicked: i64,
+    pub(crate) message: *const u8,
+    pub(crate) len: i64,
+    pub(crate) line: i64,
+}
+
+/// `PanicRecord::panicked` for a panic raised in an actor's handler.
+pub(crate) const PANIC_RAISED: i64 = 1;
+/// `PanicRecord::panicked` for an `actor_call` on a dead handle.
+pub(crate) const PANIC_DEAD_ACTOR: i64 = 2;
+
+impl PanicRecord {
+    pub(crate) fn none() -> Self {
+        PanicRecord { panicked: 0, message: std::ptr::null(), len: 0, line: 0 }
+    }
+}
+
+// The message points at a string constant or a buffer that is never
+// freed, so the record can move to the thread that waits for it.
+unsafe impl Send for PanicRecord {}
+
+/// `aha_actor_run(out, fn_ptr, state, msg)`: call the handler, recording
+/// a panic in `out` instead of exiting.
+type ActorRunFn = unsafe extern "C" fn(*mut PanicRecord, i64, i64, i64) -> i64;
+
+/// A message in an actor's mailbox, with the channel a `call` waits on.
+type Envelope = (i64, Option<Sender<Result<i64, PanicRecord>>>);
+
 struct ActorEntry {
-    mailbox_tx: std::sync::mpsc::Sender<i64>,
-    result: Arc<(Mutex<Option<i64>>, Condvar)>,
+    mailbox_tx: Sender<Envelope>,
 }
 
 static ACTORS: OnceLock<Mutex<HashMap<i64, ActorEntry>>> = OnceLock::new();
@@ -41,43 +82,42 @@ fn next_handle() -> i64 {
 // Native functions (linked to LLVM JIT via add_global_mapping)
 // ---------------------------------------------------------------------------
 
-/// actor_spawn(fn_ptr, init_state) -> handle
+/// actor_spawn(fn_ptr, init_state, run) -> handle
 ///
 /// Spawns an actor thread. fn_ptr is a JIT function with signature
-/// `fn(state: i64, msg: i64) -> i64` that handles one message.
-/// The actor loops: receive msg → call fn(state, msg) → store result.
+/// `fn(state: i64, msg: i64) -> i64` that handles one message, and run
+/// is the JIT's `aha_actor_run`, which calls it under the panic runtime.
+/// The actor loops: receive msg → call fn(state, msg) → reply to a call.
+/// A panic in the handler is sent to the waiting caller, if any, and
+/// ends the actor.
 ///
 /// # Safety
-/// fn_ptr must be a valid JIT function pointer with the above signature.
+/// fn_ptr and run must be valid JIT function pointers with the above
+/// signatures.
 #[no_mangle]
-pub extern "C" fn actor_spawn(fn_ptr: i64, init_state: i64) -> i64 {
+pub extern "C" fn actor_spawn(fn_ptr: i64, init_state: i64, run: i64) -> i64 {
     let handle = next_handle();
-    let (tx, rx) = std::sync::mpsc::channel::<i64>();
-    let result_slot = Arc::new((Mutex::<Option<i64>>::new(None), Condvar::new()));
-
-    let func: extern "C" fn(i64, i64) -> i64 = unsafe { std::mem::transmute(fn_ptr) };
-    let slot_clone = result_slot.clone();
-
-    thread::spawn(move || loop {
-        match rx.recv() {
-            Ok(msg) => {
-                let ret = func(init_state, msg);
-                let (lock, cvar) = &*slot_clone;
-                let mut guard = lock.lock().unwrap();
-                *guard = Some(ret);
-                cvar.notify_all();
+    let (tx, rx) = channel::<Envelope>();
+    let run: ActorRunFn = unsafe { std::mem::transmute(run) };
+
+    thread::spawn(move || {
+        while let Ok((msg, reply)) = rx.recv() {
+            let mut outcome = PanicRecord::none();
+            let ret = unsafe { run(&mut outcome, fn_ptr, init_state, msg) };
+            let panicked = outcome.panicked != 0;
+            if panicked {
+                actors().lock().unwrap().remove(&handle);
+            }
+            if let Some(reply) = reply {
+                let _ = reply.send(if panicked { Err(outcome) } else { Ok(ret) });
+            }
+            if panicked {
+                break;
             }
-            Err(_) => break,
         }
     });
 
-    actors().lock().unwrap().insert(
-        handle,
-        ActorEntry {
-            mailbox_tx: tx,
-            result: result_slot,
-        },
-    );
+    actors().lock().unwrap().insert(handle, ActorEntry { mailbox_tx: tx });
     handle
 }
 
@@ -86,33 +126,31 @@ pub extern "C" fn actor_spawn(fn_ptr: i64, init_state: i64) -> i64 {
 pub extern "C" fn actor_send(handle: i64, msg: i64) {
     let actors = actors().lock().unwrap();
     if let Some(entry) = actors.get(&handle) {
-        let _ = entry.mailbox_tx.send(msg);
+        let _ = entry.mailbox_tx.send((msg, None));
     }
 }
 
-/// actor_call(handle, msg) -> result — blocking request-response.
-/// Sends msg, waits for handler to process it, returns the result.
+/// actor_call(handle, msg, out) -> result — blocking request-response.
+/// Sends msg, waits for the handler to process it, returns the result.
+/// If the handler panicked, or the actor is gone, `out` says so and the
+/// compiled caller panics.
+///
+/// # Safety
+/// out must point to a writable `PanicRecord`.
 #[no_mangle]
-pub extern "C" fn actor_call(handle: i64, msg: i64) -> i64 {
-    let actors_guard = actors().lock().unwrap();
-    let entry = match actors_guard.get(&handle) {
-        Some(e) => e,
-        None => return 0,
+pub unsafe extern "C" fn actor_call(handle: i64, msg: i64, out: *mut PanicRecord) -> i64 {
+    let (reply_tx, reply_rx) = channel();
+    let sent = actors().lock().unwrap()
+        .get(&handle)
+        .is_some_and(|entry| entry.mailbox_tx.send((msg, Some(reply_tx))).is_ok());
+    let (result, outcome) = match sent.then(|| reply_rx.recv().ok()).flatten() {
+        Some(Ok(result)) => (result, PanicRecord::none()),
+        Some(Err(panic)) => (0, panic),
+        None => {
+            let message = "called `call` on an actor that is not running";
+            (0, PanicRecord { panicked: PANIC_DEAD_ACTOR, message: message.as_ptr(), len: message.len() as i64, line: 0 })
+        }
     };
-
-    // Clear previous result, send message.
-    {
-        let (lock, _) = &*entry.result;
-        let mut guard = lock.lock().unwrap();
-        *guard = None;
-    }
-    let _ = entry.mailbox_tx.send(msg);
-
-    // Wait for result.
-    let (lock, cvar) = &*entry.result;
-    let mut guard = lock.lock().unwrap();
-    while guard.is_none() {
-        guard = cvar.wait(guard).unwrap();
-    }
-    guard.unwrap_or(0)
+    unsafe { *out = outcome };
+    result
 }
@@ -0,0 +1,107 @@
+// src/testing.rs
+//
+// `aha test` — runs the `test fn` functions of a module graph.
+// Each file in the graph is compiled as its own root (so its tests see
+// its private items), and each test gets a fresh LLVM context and JIT:
+// no state leaks between tests, and a failed assertion or any other
+// panic fails only that test.
+
+use crate::ast::Program;
+use crate::codegen::CodeGenerator;
+use crate::compiler::{CompileError, Compiler};
+use inkwell::context::Context;
+use std::path::Path;
+
+/// The result of one test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestOutcome {
+    /// `<file stem>::<test name>`, e.g. `math::adds_small_numbers`.
+    pub name: String,
+    /// `Err` holds the panic message.
+    pub result: Result<(), String>,
+}
+
+/// All tests of a run, in discovery order.
+#[derive(Debug, Default)]
+pub struct TestReport {
+    pub outcomes: Vec<TestOutcome>,
+    /// Tests skipped because no filter matched their name.
+    pub filtered_out: usize,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.result.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes.len() - self.passed()
+    }
+
+    pub fn success(&self) -> bool {
+        self.failed() == 0
+    }
+}
+
+/// One file of a module graph, compiled as its own root.
+pub(crate) struct ModuleUnit {
+    pub file: String,
+    /// The file stem, which prefixes the names of its tests and
+    /// benchmarks.
+    pub module: String,
+    pub program: Program,
+}
+
+/// Each file of the module graph rooted at `main_path`, in the order
+/// `Compiler::module_files` finds them. Shared with `aha bench`.
+pub(crate) fn module_units(main_path: &str) -> Result<Vec<ModuleUnit>, Vec<CompileError>> {
+    let compiler = Compiler::new(vec![Compiler::parent_dir(main_path)]);
+    compiler.module_files(main_path)?.into_iter().map(|path| {
+        let file = path.to_string_lossy().to_string();
+        let program = compiler.compile(&file)?;
+        let module = Path::new(&file)
+            .file_stem()
+            .map_or_else(|| file.clone(), |stem| stem.to_string_lossy().to_string());
+        Ok(ModuleUnit { file, module, program })
+    }).collect()
+}
+
+/// Does a test or benchmark named `name` run? Yes without filters,
+/// otherwise if its name contains one of them.
+pub(crate) fn selected(name: &str, filters: &[String]) -> bool {
+    filters.is_empty() || filters.iter().any(|f| name.contains(f.as_str()))
+}
+
+/// Discover and run the tests of `main_path` and every module it
+/// imports. With `filters`, only tests whose full name contains one of
+/// them run. `on_result` sees each outcome as soon as it is known.
+pub fn run_tests(
+    main_path: &str,
+    filters: &[String],
+    mut on_result: impl FnMut(&TestOutcome),
+) -> Result<TestReport, Vec<CompileError>> {
+    let mut report = TestReport::default();
+    for unit in module_units(main_path)? {
+        // Compile once to find the tests and report compile errors.
+        let context = Context::create();
+        let mut codegen = CodeGenerator::new(&context);
+        codegen.compile(&unit.program).map_err(|message| vec![CompileError { message, file: unit.file.clone() }])?;
+        let tests = codegen.test_functions().to_vec();
+        drop(codegen);
+
+        for test in tests {
+            let name = format!("{}::{}", unit.module, test);
+            if !selected(&name, filters) {
+                report.filtered_out += 1;
+                continue;
+            }
+            let context = Context::create();
+            let mut codegen = CodeGenerator::new(&context);
+            let result = codegen.compile(&unit.program).and_then(|()| codegen.run_test(&test));
+            let outcome = TestOutcome { name, result };
+            on_result(&outcome);
+            report.outcomes.push(outcome);
+        }
+    }
+    Ok(report)
+}
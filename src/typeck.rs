@@ -0,0 +1,1677 @@
+// src/typeck.rs
+//
+// AHA! Type Checker — a standalone pass over the parsed `Program` that
+// runs before codegen. It settles the parameter and return types of every
+// non-generic function (hints first, then call sites, then bodies) and of
+// each generic function instance it sees called, types each expression it
+// can, and reports all type errors up front with the line they occur on.
+// Codegen takes every signature from the resulting `TypeTable`.
+//
+// Types the checker cannot pin down (the body of a generic function
+// outside any instance, actors, `None()` with no annotation) stay `None`;
+// they never produce errors here, and codegen treats them as Int.
+
+use crate::ast;
+use crate::types::{count_of, generic_fn_name, generic_type_name, split_array_hint, split_type_list, AhaType};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A type error found before codegen, with its source line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Result of a successful check: settled function signatures and the
+/// type of every expression the checker could type. It borrows the
+/// checked `Program`, so the nodes its expression types are keyed by
+/// stay alive (and keep their addresses) for as long as the table.
+#[derive(Debug, Default)]
+pub struct TypeTable<'p> {
+    /// Parameter types of each non-generic function. `None` where neither
+    /// a hint nor any call site pins the parameter down.
+    pub params: HashMap<String, Vec<Option<AhaType>>>,
+    /// Return types of the non-generic functions that have one.
+    pub returns: HashMap<String, AhaType>,
+    /// Return types of generic function instances, by instance name
+    /// (`max_Int`, see `generic_fn_name`).
+    pub instance_returns: HashMap<String, AhaType>,
+    /// Generic struct and enum instances the checked types name, as
+    /// (generic name, type arguments), sorted by instance name.
+    pub instances: Vec<(String, Vec<AhaType>)>,
+    /// Expression types keyed by node address.
+    exprs: HashMap<*const ast::Expression, AhaType>,
+    program: PhantomData<&'p ast::Program>,
+}
+
+impl TypeTable<'_> {
+    /// The checked type of `expr`, if it is a node of the checked program
+    /// and the checker could type it.
+    pub fn expr_type(&self, expr: &ast::Expression) -> Option<&AhaType> {
+        self.exprs.get(&(expr as *const ast::Expression))
+    }
+}
+
+/// An enum variant's name and payload types, as far as they are known.
+type VariantSig = (String, Vec<Option<AhaType>>);
+
+/// A generic struct field's or enum variant's name and type hints.
+type MemberHints = (String, Vec<Option<String>>);
+
+/// A user function's signature as the checker currently knows it.
+struct FnSig {
+    type_params: Vec<String>,
+    param_names: Vec<String>,
+    param_hints: Vec<Option<String>>,
+    params: Vec<Option<AhaType>>,
+    /// Parameters with a type hint are fixed; the rest come from call sites.
+    hinted: Vec<bool>,
+    ret: Option<AhaType>,
+    /// The `-> T` annotation as written, if any.
+    ret_hint: Option<String>,
+    generic: bool,
+    line: usize,
+}
+
+/// A module-level `const` or `static`, visible in every function.
+struct Global {
+    ty: Option<AhaType>,
+    kind: GlobalKind,
+    line: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GlobalKind {
+    Const,
+    Static,
+    StaticMut,
+}
+
+/// Check a whole program. Returns the type table, or every error found.
+pub fn check_program(program: &ast::Program) -> Result<TypeTable<'_>, Vec<TypeError>> {
+    let mut checker = TypeChecker::new(program);
+    checker.check(program);
+    if checker.errors.is_empty() {
+        Ok(checker.into_table())
+    } else {
+        Err(checker.errors)
+    }
+}
+
+/// A variable in scope.
+struct Binding {
+    ty: Option<AhaType>,
+    mutable: bool,
+    origin: Origin,
+    /// Loops enclosing the binding, so a move inside a deeper loop can
+    /// be caught.
+    loops: usize,
+    /// Line a `let` that owns boxes was moved away on; reading it
+    /// afterwards would see (or drop again) boxes it no longer owns.
+    moved: Option<usize>,
+}
+
+/// What introduced a binding, for the "not mutable" hint.
+enum Origin {
+    /// `let` on this line.
+    Let(usize),
+    Param,
+    Pattern,
+}
+
+struct TypeChecker {
+    /// Non-generic struct name → field types.
+    structs: HashMap<String, Vec<(String, Option<AhaType>)>>,
+    /// Non-generic enum name → (variant, payload types).
+    enums: HashMap<String, Vec<VariantSig>>,
+    /// Variant name → every enum declaring it, in declaration order.
+    variants: HashMap<String, Vec<String>>,
+    /// (enum, variant) → (enum as written, e.g. `Option<T>`, payload
+    /// hints), for constructor arity and signatures.
+    variant_hints: HashMap<(String, String), (String, Vec<String>)>,
+    /// Enums opened with `use Color::*`: they win a bare variant name
+    /// that several enums declare.
+    open_enums: HashSet<String>,
+    /// Names of generic structs and enums.
+    generic_types: HashSet<String>,
+    /// Generic definitions, instantiated per use the way codegen does.
+    generic_structs: HashMap<String, ast::StructDefinition>,
+    generic_enums: HashMap<String, ast::EnumDefinition>,
+    generic_fns: HashMap<String, ast::FunctionLiteral>,
+    /// Generic struct and enum instances by full name (`Pair<Int, String>`)
+    /// → (generic name, type arguments). Each is also in `structs` or
+    /// `enums` under its full name.
+    instances: HashMap<String, (String, Vec<AhaType>)>,
+    /// Type params bound while resolving an instance's hints or walking a
+    /// generic function's body for one instance.
+    type_args: HashMap<String, AhaType>,
+    /// Return types of generic function instances, and the instances
+    /// whose bodies are being walked (so recursion stops).
+    instance_returns: HashMap<String, AhaType>,
+    walking: HashSet<String>,
+    /// Structs lowered from `newtype` declarations.
+    newtypes: HashSet<String>,
+    sigs: HashMap<String, FnSig>,
+    globals: HashMap<String, Global>,
+    /// Final pass: user functions each function calls, and the
+    /// `static mut` globals it touches (for the actor-thread rule).
+    calls: HashMap<String, HashSet<String>>,
+    static_mut_uses: HashMap<String, Vec<String>>,
+    scopes: Vec<HashMap<String, Binding>>,
+    /// Loops enclosing the code being walked, within the current function.
+    loops: usize,
+    /// Line of the statement or call being walked, for errors on
+    /// identifiers, which carry none.
+    line: usize,
+    /// Function whose body is being walked.
+    current_fn: Option<String>,
+    /// Final pass: record expression types and report errors.
+    recording: bool,
+    /// Inside a generic function body, where types vary per instance.
+    in_generic: bool,
+    /// Contextual type of the expression about to be typed (a `let`
+    /// annotation or the function's return type), like codegen's
+    /// `expected_type`: `None()` takes its instance from it.
+    expected: Option<AhaType>,
+    /// Signatures changed during this inference round.
+    changed: bool,
+    errors: Vec<TypeError>,
+    exprs: HashMap<*const ast::Expression, AhaType>,
+}
+
+impl TypeChecker {
+    fn new(program: &ast::Program) -> Self {
+        let mut checker = TypeChecker {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            variants: HashMap::new(),
+            variant_hints: HashMap::new(),
+            open_enums: HashSet::new(),
+            generic_types: HashSet::new(),
+            generic_structs: HashMap::new(),
+            generic_enums: HashMap::new(),
+            generic_fns: HashMap::new(),
+            instances: HashMap::new(),
+            type_args: HashMap::new(),
+            instance_returns: HashMap::new(),
+            walking: HashSet::new(),
+            newtypes: HashSet::new(),
+            sigs: HashMap::new(),
+            globals: HashMap::new(),
+            calls: HashMap::new(),
+            static_mut_uses: HashMap::new(),
+            scopes: Vec::new(),
+            loops: 0,
+            line: 0,
+            current_fn: None,
+            recording: false,
+            in_generic: false,
+            expected: None,
+            changed: false,
+            errors: Vec::new(),
+            exprs: HashMap::new(),
+        };
+        checker.collect_definitions(&program.statements);
+        checker
+    }
+
+    /// Settle signatures, then walk the program once more to record
+    /// expression types and report errors.
+    fn check(&mut self, program: &ast::Program) {
+        // Each round only fills in unknown types, so this reaches a fixed
+        // point within one round per function; the bound is a safety net.
+        for _ in 0..=self.sigs.len() {
+            self.changed = false;
+            self.walk_program(program);
+            if !self.changed {
+                break;
+            }
+        }
+        self.recording = true;
+        self.walk_program(program);
+        if program.statements.iter().any(|s| matches!(s, ast::Statement::Actor(_))) {
+            self.check_actor_statics();
+        }
+    }
+
+    /// Actor handlers run on their own threads, so `handle` and every
+    /// function it reaches must leave `static mut` globals alone; those
+    /// belong to the main thread. Immutable statics are safe to share.
+    fn check_actor_statics(&mut self) {
+        let mut reached = vec!["handle".to_string()];
+        let mut i = 0;
+        while i < reached.len() {
+            let mut callees: Vec<String> = self.calls.get(&reached[i])
+                .map(|c| c.iter().cloned().collect())
+                .unwrap_or_default();
+            callees.sort();
+            for callee in callees {
+                if !reached.contains(&callee) {
+                    reached.push(callee);
+                }
+            }
+            i += 1;
+        }
+        for name in reached {
+            let Some(line) = self.sigs.get(&name).map(|sig| sig.line) else { continue };
+            for global in self.static_mut_uses.get(&name).cloned().unwrap_or_default() {
+                let via = if name == "handle" { String::new() } else { " (called from 'handle')".to_string() };
+                self.error(line, format!(
+                    "Function '{}' runs on actor threads{} and cannot use static mut '{}'; pass the value in a message instead",
+                    name, via, global
+                ));
+            }
+        }
+    }
+
+    fn into_table<'p>(self) -> TypeTable<'p> {
+        let mut instances: Vec<(String, (String, Vec<AhaType>))> = self.instances.into_iter().collect();
+        instances.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut table = TypeTable {
+            exprs: self.exprs,
+            instance_returns: self.instance_returns,
+            instances: instances.into_iter().map(|(_, instance)| instance).collect(),
+            ..TypeTable::default()
+        };
+        for (name, sig) in self.sigs {
+            if sig.generic {
+                continue;
+            }
+            if let Some(ret) = sig.ret {
+                table.returns.insert(name.clone(), ret);
+            }
+            table.params.insert(name, sig.params);
+        }
+        table
+    }
+
+    // --- Definitions ---
+
+    fn collect_definitions(&mut self, statements: &[ast::Statement]) {
+        for stmt in statements {
+            match stmt {
+                ast::Statement::Struct(def) if !def.type_params.is_empty() => {
+                    self.generic_types.insert(def.name.value.clone());
+                    self.generic_structs.insert(def.name.value.clone(), def.clone());
+                }
+                ast::Statement::Struct(def) => {
+                    self.structs.insert(def.name.value.clone(), Vec::new());
+                    if def.is_newtype {
+                        self.newtypes.insert(def.name.value.clone());
+                    }
+                }
+                ast::Statement::Enum(def) => {
+                    let generic = !def.type_params.is_empty();
+                    let written = if generic {
+                        self.generic_types.insert(def.name.value.clone());
+                        self.generic_enums.insert(def.name.value.clone(), def.clone());
+                        format!("{}<{}>", def.name.value, def.type_params.join(", "))
+                    } else {
+                        def.name.value.clone()
+                    };
+                    for v in &def.variants {
+                        self.variants.entry(v.name.value.clone()).or_default().push(def.name.value.clone());
+                        self.variant_hints.insert(
+                            (def.name.value.clone(), v.name.value.clone()),
+                            (written.clone(), v.payload_types.clone()),
+                        );
+                    }
+                }
+                ast::Statement::UseVariants(open) => {
+                    self.open_enums.insert(open.enum_name.value.clone());
+                }
+                _ => {}
+            }
+        }
+        // Field and payload hints may name any struct or enum, so resolve
+        // them once every name is known.
+        for stmt in statements {
+            match stmt {
+                ast::Statement::Struct(def) if def.type_params.is_empty() => {
+                    let fields = def.fields.iter()
+                        .map(|f| (f.name.value.clone(), f.type_hint.as_deref().and_then(|h| self.resolve_hint(h))))
+                        .collect();
+                    self.structs.insert(def.name.value.clone(), fields);
+                }
+                ast::Statement::Enum(def) if def.type_params.is_empty() => {
+                    let variants = def.variants.iter()
+                        .map(|v| (
+                            v.name.value.clone(),
+                            v.payload_types.iter().map(|h| self.resolve_hint(h)).collect(),
+                        ))
+                        .collect();
+                    self.enums.insert(def.name.value.clone(), variants);
+                }
+                ast::Statement::Expression(es) => {
+                    if let ast::Expression::Function(func) = &es.expression {
+                        self.collect_function(func);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_function(&mut self, func: &ast::FunctionLiteral) {
+        let Some(name) = &func.name else { return };
+        let generic = !func.type_params.is_empty();
+        if generic {
+            self.generic_fns.insert(name.value.clone(), func.clone());
+        }
+        let params: Vec<Option<AhaType>> = func.param_type_hints.iter()
+            .map(|h| if generic { None } else { h.as_deref().and_then(|h| self.resolve_hint(h)) })
+            .collect();
+        let ret = if generic { None } else { func.return_type_hint.as_deref().and_then(|h| self.resolve_hint(h)) };
+        self.sigs.insert(name.value.clone(), FnSig {
+            type_params: func.type_params.clone(),
+            param_names: func.parameters.iter().map(|p| p.value.clone()).collect(),
+            param_hints: func.param_type_hints.clone(),
+            hinted: func.param_type_hints.iter().map(|h| h.is_some()).collect(),
+            ret_hint: func.return_type_hint.clone(),
+            params,
+            ret,
+            generic,
+            line: func.line,
+        });
+    }
+
+    /// Resolve a type hint, instantiating the generic structs and enums it
+    /// names. Type params outside an instance stay unknown (`None`).
+    fn resolve_hint(&mut self, hint: &str) -> Option<AhaType> {
+        if let Some(t) = self.type_args.get(hint) {
+            return Some(t.clone());
+        }
+        if let Some(t) = AhaType::from_hint(hint) {
+            return Some(t);
+        }
+        if let Some(inner) = hint.strip_prefix("List<").and_then(|s| s.strip_suffix('>')) {
+            return Some(AhaType::List(Box::new(self.resolve_hint(inner)?)));
+        }
+        if let Some(inner) = hint.strip_prefix("Box<").and_then(|s| s.strip_suffix('>')) {
+            return Some(AhaType::Box(Box::new(self.resolve_hint(inner)?)));
+        }
+        if let Some(inner) = hint.strip_prefix("Map<").and_then(|s| s.strip_suffix('>')) {
+            if let [k, v] = split_type_list(inner).as_slice() {
+                return Some(AhaType::Map(Box::new(self.resolve_hint(k)?), Box::new(self.resolve_hint(v)?)));
+            }
+        }
+        if let Some(inner) = hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            let elems = split_type_list(inner)
+                .into_iter()
+                .map(|e| self.resolve_hint(e))
+                .collect::<Option<Vec<_>>>()?;
+            return Some(AhaType::Tuple(elems));
+        }
+        if let Some((elem, len)) = split_array_hint(hint) {
+            return Some(AhaType::Array(Box::new(self.resolve_hint(elem)?), len));
+        }
+        if let Some((base, inner)) = hint.strip_suffix('>').and_then(|h| h.split_once('<')) {
+            if !self.generic_types.contains(base) {
+                return None;
+            }
+            let args = split_type_list(inner)
+                .into_iter()
+                .map(|a| self.resolve_hint(a))
+                .collect::<Option<Vec<_>>>()?;
+            return self.instantiate(base, args);
+        }
+        if self.generic_types.contains(hint) {
+            return None;
+        }
+        if self.structs.contains_key(hint) {
+            return Some(AhaType::Struct(hint.to_string()));
+        }
+        if self.enums.contains_key(hint) || self.is_enum(hint) {
+            return Some(AhaType::Enum(hint.to_string()));
+        }
+        None
+    }
+
+    /// Register the instance of a generic struct or enum for `args`,
+    /// typing its fields or payloads with the type params bound. `None`
+    /// for a wrong number of type arguments (codegen reports it).
+    fn instantiate(&mut self, base: &str, args: Vec<AhaType>) -> Option<AhaType> {
+        // Each field or variant with its hints; a field has one.
+        let (type_params, hints): (Vec<String>, Vec<MemberHints>) =
+            if let Some(def) = self.generic_structs.get(base) {
+                let fields = def.fields.iter().map(|f| (f.name.value.clone(), vec![f.type_hint.clone()])).collect();
+                (def.type_params.clone(), fields)
+            } else {
+                let def = self.generic_enums.get(base)?;
+                let variants = def.variants.iter()
+                    .map(|v| (v.name.value.clone(), v.payload_types.iter().cloned().map(Some).collect()))
+                    .collect();
+                (def.type_params.clone(), variants)
+            };
+        if type_params.len() != args.len() {
+            return None;
+        }
+        let is_struct = self.generic_structs.contains_key(base);
+        let name = generic_type_name(base, &args);
+        let t = if is_struct { AhaType::Struct(name.clone()) } else { AhaType::Enum(name.clone()) };
+        // Registered before its hints resolve, so a payload naming this
+        // same instance (`Box<List<T>>`) terminates.
+        if self.instances.insert(name.clone(), (base.to_string(), args.clone())).is_some() {
+            return Some(t);
+        }
+        let saved = std::mem::replace(&mut self.type_args, type_params.into_iter().zip(args).collect());
+        let resolved: Vec<(String, Vec<Option<AhaType>>)> = hints.into_iter()
+            .map(|(member, hints)| {
+                let types = hints.iter().map(|h| h.as_deref().and_then(|h| self.resolve_hint(h))).collect();
+                (member, types)
+            })
+            .collect();
+        self.type_args = saved;
+        if is_struct {
+            let fields = resolved.into_iter().map(|(f, mut ts)| (f, ts.pop().flatten())).collect();
+            self.structs.insert(name, fields);
+        } else {
+            self.enums.insert(name, resolved);
+        }
+        Some(t)
+    }
+
+    /// Bind the type params a hint mentions to the matching parts of
+    /// `actual`, like codegen's `bind_type_params`: `List<T>` against
+    /// `List<String>` binds `T` to String. The first binding wins.
+    fn bind_type_params(&self, params: &[String], hint: &str, actual: &AhaType, bound: &mut HashMap<String, AhaType>) {
+        if params.iter().any(|p| p == hint) {
+            bound.entry(hint.to_string()).or_insert_with(|| actual.clone());
+            return;
+        }
+        if let (Some(inner), AhaType::Tuple(elems)) = (hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')), actual) {
+            for (h, t) in split_type_list(inner).into_iter().zip(elems) {
+                self.bind_type_params(params, h, t, bound);
+            }
+            return;
+        }
+        let Some((base, inner)) = hint.strip_suffix('>').and_then(|h| h.split_once('<')) else { return };
+        let parts = split_type_list(inner);
+        match actual {
+            AhaType::List(elem) if parts.len() == 1 => self.bind_type_params(params, parts[0], elem, bound),
+            AhaType::Box(elem) if base == "Box" => self.bind_type_params(params, inner, elem, bound),
+            // A plain T fills a Box<T> slot (auto-boxed by the constructor).
+            _ if base == "Box" => self.bind_type_params(params, inner, actual, bound),
+            AhaType::Map(k, v) if parts.len() == 2 => {
+                self.bind_type_params(params, parts[0], k, bound);
+                self.bind_type_params(params, parts[1], v, bound);
+            }
+            AhaType::Struct(name) | AhaType::Enum(name) => {
+                if let Some((generic, args)) = self.instances.get(name) {
+                    if generic == base {
+                        for (h, t) in parts.into_iter().zip(args) {
+                            self.bind_type_params(params, h, t, bound);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Type arguments for `params` once every one is bound.
+    fn bound_args(params: &[String], bound: &HashMap<String, AhaType>) -> Option<Vec<AhaType>> {
+        params.iter().map(|p| bound.get(p).cloned()).collect()
+    }
+
+    /// The program's `Option<T>` instance for `inner`, which builtins such
+    /// as `Opcode::from_int` return; codegen checks its declaration.
+    fn option_of(&mut self, inner: AhaType) -> Option<AhaType> {
+        self.instantiate("Option", vec![inner])
+    }
+
+    // --- Walking ---
+
+    fn walk_program(&mut self, program: &ast::Program) {
+        self.scopes = vec![HashMap::new()];
+        for stmt in &program.statements {
+            self.walk_statement(stmt);
+        }
+    }
+
+    fn walk_statement(&mut self, stmt: &ast::Statement) {
+        match stmt {
+            ast::Statement::Let(let_stmt) => {
+                self.line = let_stmt.line;
+                let declared = let_stmt.type_annotation.as_deref().and_then(|h| self.resolve_hint(h));
+                let value = self.expr_expecting(&let_stmt.value, declared.clone());
+                self.note_move(&let_stmt.value, let_stmt.line);
+                let loops = self.loops;
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(let_stmt.name.value.clone(), Binding {
+                        ty: declared.or(value),
+                        mutable: let_stmt.mutable,
+                        origin: Origin::Let(let_stmt.line),
+                        loops,
+                        moved: None,
+                    });
+                }
+            }
+            ast::Statement::LetPattern(let_pat) => {
+                let value = self.expr(&let_pat.value);
+                self.bind_pattern(&let_pat.pattern, value);
+            }
+            ast::Statement::Return(ret) => {
+                self.line = ret.line;
+                let value = self.expr_expecting(&ret.return_value, self.return_type());
+                self.check_return(value.as_ref(), ret.line);
+            }
+            ast::Statement::Const(def) => {
+                if self.current_fn.is_some() || self.scopes.len() > 1 {
+                    self.error(def.line, format!("Constant '{}' must be declared at module level", def.name.value));
+                }
+                let value = self.expr(&def.value);
+                let declared = def.type_annotation.as_deref().and_then(|h| self.resolve_hint(h));
+                self.globals.insert(def.name.value.clone(), Global {
+                    ty: declared.or(value),
+                    kind: GlobalKind::Const,
+                    line: def.line,
+                });
+            }
+            ast::Statement::Static(def) => {
+                if self.current_fn.is_some() || self.scopes.len() > 1 {
+                    self.error(def.line, format!("Static '{}' must be declared at module level", def.name.value));
+                }
+                let value = self.expr(&def.value);
+                let declared = def.type_annotation.as_deref().and_then(|h| self.resolve_hint(h));
+                self.globals.insert(def.name.value.clone(), Global {
+                    ty: declared.or(value),
+                    kind: if def.mutable { GlobalKind::StaticMut } else { GlobalKind::Static },
+                    line: def.line,
+                });
+            }
+            ast::Statement::Expression(es) => {
+                self.expr(&es.expression);
+            }
+            // Module-level type definitions are desugared before type
+            // checking; any left over were declared inside a block.
+            ast::Statement::TypeAlias(def) => {
+                self.error(def.line, format!("Type alias '{}' must be declared at module level", def.name.value));
+            }
+            ast::Statement::Newtype(def) => {
+                self.error(def.line, format!("Newtype '{}' must be declared at module level", def.name.value));
+            }
+            ast::Statement::UseVariants(open) => {
+                let name = &open.enum_name.value;
+                if self.current_fn.is_some() || self.scopes.len() > 1 {
+                    self.error(open.line, format!("`use {}::*` must be at module level", name));
+                } else if !self.is_enum(name) {
+                    self.error(open.line, format!("Unknown enum '{}' in `use {}::*`", name, name));
+                }
+            }
+            ast::Statement::Struct(_)
+            | ast::Statement::Actor(_)
+            | ast::Statement::Enum(_)
+            | ast::Statement::Import(_) => {}
+        }
+    }
+
+    /// Walk a block in its own scope; returns the type of its value (the
+    /// last statement, when it is an expression).
+    fn block(&mut self, block: &ast::BlockStatement) -> Option<AhaType> {
+        self.scopes.push(HashMap::new());
+        let mut value = None;
+        for (i, stmt) in block.statements.iter().enumerate() {
+            match stmt {
+                ast::Statement::Expression(es) if i + 1 == block.statements.len() => {
+                    value = self.expr(&es.expression);
+                }
+                _ => self.walk_statement(stmt),
+            }
+        }
+        self.scopes.pop();
+        value
+    }
+
+    /// Bind an immutable name introduced by a pattern or loop.
+    fn bind(&mut self, name: &str, t: Option<AhaType>) {
+        self.bind_with(name, t, false);
+    }
+
+    /// Bind a name introduced by a pattern; `mut name` makes it mutable.
+    fn bind_with(&mut self, name: &str, t: Option<AhaType>, mutable: bool) {
+        let loops = self.loops;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), Binding { ty: t, mutable, origin: Origin::Pattern, loops, moved: None });
+        }
+    }
+
+    /// Does a value of this type own boxes that codegen drops at scope
+    /// exit? Mirrors codegen's `needs_drop`.
+    fn needs_drop(&self, t: &AhaType) -> bool {
+        match t {
+            AhaType::Box(_) => true,
+            AhaType::Enum(name) => self.enums.get(name).is_some_and(|variants| {
+                variants.iter().flat_map(|(_, ts)| ts).flatten().any(|t| self.needs_drop(t))
+            }),
+            _ => false,
+        }
+    }
+
+    /// `source` is bound or wrapped by value (`let b = a`, `Cons(1, a)`,
+    /// `box(a)`, `[a]`), the places codegen hands a local's boxes over
+    /// to a new owner. Final pass: remember the move so later reads of
+    /// the local are rejected, and reject a move out of an enclosing
+    /// loop, which would hand the same boxes over again next iteration.
+    fn note_move(&mut self, source: &ast::Expression, line: usize) {
+        let ast::Expression::Identifier(id) = source else { return };
+        let owns_boxes = matches!(self.binding(&id.value),
+            Some(Binding { origin: Origin::Let(_), ty: Some(t), moved: None, .. }) if self.needs_drop(t));
+        if !owns_boxes {
+            return;
+        }
+        let loops = self.loops;
+        let Some(binding) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&id.value)) else { return };
+        binding.moved = Some(line);
+        if binding.loops < loops {
+            let message = format!("Cannot move '{}' inside a loop: it would be moved again on the next iteration", id.value);
+            self.error(line, message);
+        }
+    }
+
+    /// Final pass: remember that the current function reads or writes a
+    /// `static mut`.
+    fn note_static_mut_use(&mut self, name: &str) {
+        if !self.recording || self.binding(name).is_some() {
+            return;
+        }
+        if !self.globals.get(name).is_some_and(|g| g.kind == GlobalKind::StaticMut) {
+            return;
+        }
+        if let Some(f) = &self.current_fn {
+            let uses = self.static_mut_uses.entry(f.clone()).or_default();
+            if !uses.iter().any(|u| u == name) {
+                uses.push(name.to_string());
+            }
+        }
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup(&self, name: &str) -> Option<AhaType> {
+        match self.binding(name) {
+            Some(b) => b.ty.clone(),
+            None => self.globals.get(name).and_then(|g| g.ty.clone()),
+        }
+    }
+
+    /// Assignment targets `x`, `x.f` and `x[i]` all need `x` to be a
+    /// `let mut` binding.
+    fn check_assignable(&mut self, target: &ast::Expression, line: usize) {
+        let mut root = target;
+        loop {
+            match root {
+                ast::Expression::FieldAccess(fa) => root = &fa.object,
+                ast::Expression::Index(idx) => root = &idx.left,
+                _ => break,
+            }
+        }
+        let ast::Expression::Identifier(id) = root else { return };
+        let Some(binding) = self.binding(&id.value) else {
+            let message = match self.globals.get(&id.value) {
+                Some(Global { kind: GlobalKind::Const, .. }) => format!("Cannot assign to constant '{}'", id.value),
+                Some(Global { kind: GlobalKind::Static, line: decl, .. }) => format!(
+                    "Cannot assign to immutable static '{}' (declared at line {}; use 'static mut {}')",
+                    id.value, decl, id.value
+                ),
+                _ => return,
+            };
+            self.error(line, message);
+            return;
+        };
+        if binding.mutable {
+            return;
+        }
+        let name = &id.value;
+        let what = match target {
+            ast::Expression::FieldAccess(fa) => format!("to field '{}' of immutable variable '{}'", fa.field.value, name),
+            ast::Expression::Index(_) => format!("to an element of immutable variable '{}'", name),
+            _ => format!("twice to immutable variable '{}'", name),
+        };
+        let message = match binding.origin {
+            Origin::Let(decl) => format!("Cannot assign {} (declared at line {}; use 'let mut {}')", what, decl, name),
+            Origin::Param => format!("Cannot assign {} (a parameter; declare it as 'mut {}')", what, name),
+            Origin::Pattern => format!("Cannot assign {} (bound by a pattern or loop)", what),
+        };
+        self.error(line, message);
+    }
+
+    /// Type an expression whose value should have type `expected`.
+    fn expr_expecting(&mut self, expr: &ast::Expression, expected: Option<AhaType>) -> Option<AhaType> {
+        self.expected = expected;
+        self.expr(expr)
+    }
+
+    /// Type an expression, recording the result in the final pass.
+    fn expr(&mut self, expr: &ast::Expression) -> Option<AhaType> {
+        let t = self.infer(expr);
+        if self.recording && !self.in_generic {
+            if let Some(t) = &t {
+                self.exprs.insert(expr as *const ast::Expression, t.clone());
+            }
+        }
+        t
+    }
+
+    fn infer(&mut self, expr: &ast::Expression) -> Option<AhaType> {
+        // The context applies to this node only, not its operands.
+        let expected = self.expected.take();
+        match expr {
+            ast::Expression::Integer(_) => Some(AhaType::Int),
+            ast::Expression::Boolean(_) => Some(AhaType::Bool),
+            ast::Expression::String(_) => Some(AhaType::String),
+            ast::Expression::Identifier(id) => {
+                self.note_static_mut_use(&id.value);
+                if let Some(moved) = self.binding(&id.value).and_then(|b| b.moved) {
+                    let message = format!("Use of moved value '{}' (value moved at line {})", id.value, moved);
+                    self.error(self.line, message);
+                }
+                self.lookup(&id.value)
+            }
+            ast::Expression::Prefix(prefix) => {
+                self.expr(&prefix.right);
+                Some(if prefix.operator == "!" { AhaType::Bool } else { AhaType::Int })
+            }
+            ast::Expression::Infix(infix) => {
+                let lt = self.expr(&infix.left);
+                let rt = self.expr(&infix.right);
+                match infix.operator.as_str() {
+                    // Arithmetic on two values of one newtype keeps the newtype.
+                    "+" | "-" | "*" | "/" | "%" if matches!((&lt, &rt),
+                        (Some(AhaType::Struct(l)), Some(AhaType::Struct(r))) if l == r && self.newtypes.contains(l)) => lt,
+                    // `+` concatenates when either side is a String.
+                    "+" => match (lt, rt) {
+                        (Some(AhaType::String), _) | (_, Some(AhaType::String)) => Some(AhaType::String),
+                        (Some(l), Some(r)) if l.is_numeric() && r.is_numeric() => Some(AhaType::Int),
+                        _ => None,
+                    },
+                    // Comparisons and logical operators yield Int (0/1).
+                    _ => Some(AhaType::Int),
+                }
+            }
+            ast::Expression::If(if_expr) => {
+                self.expr(&if_expr.condition);
+                let cons = self.block(&if_expr.consequence);
+                let alt = if_expr.alternative.as_ref().and_then(|alt| self.block(alt));
+                if if_expr.alternative.is_some() { Self::join(cons, alt) } else { None }
+            }
+            ast::Expression::IfLet(if_let) => {
+                let value = self.expr(&if_let.value);
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&if_let.pattern, value);
+                let cons = self.block(&if_let.consequence);
+                self.scopes.pop();
+                let alt = if_let.alternative.as_ref().and_then(|alt| self.block(alt));
+                if if_let.alternative.is_some() { Self::join(cons, alt) } else { None }
+            }
+            ast::Expression::While(while_expr) => {
+                self.loops += 1;
+                self.expr(&while_expr.condition);
+                self.block(&while_expr.body);
+                self.loops -= 1;
+                None
+            }
+            ast::Expression::WhileLet(while_let) => {
+                self.loops += 1;
+                let value = self.expr(&while_let.value);
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&while_let.pattern, value);
+                self.block(&while_let.body);
+                self.scopes.pop();
+                self.loops -= 1;
+                None
+            }
+            ast::Expression::For(for_expr) => {
+                let iterable = self.expr(&for_expr.iterable);
+                self.scopes.push(HashMap::new());
+                let var_type = match &*for_expr.iterable {
+                    ast::Expression::Range(_) => Some(AhaType::Int),
+                    _ => match iterable {
+                        Some(AhaType::List(elem)) | Some(AhaType::Array(elem, _)) => Some(*elem),
+                        Some(AhaType::String) => Some(AhaType::Int),
+                        Some(AhaType::Map(key, value)) => Some(AhaType::Tuple(vec![*key, *value])),
+                        _ => None,
+                    },
+                };
+                self.bind(&for_expr.variable.value, var_type);
+                self.loops += 1;
+                self.block(&for_expr.body);
+                self.loops -= 1;
+                self.scopes.pop();
+                None
+            }
+            ast::Expression::Range(range) => {
+                self.expr(&range.start);
+                self.expr(&range.end);
+                None
+            }
+            ast::Expression::Function(func) => {
+                self.function(func);
+                None
+            }
+            ast::Expression::Call(call) => self.call(call, expected.as_ref()),
+            // An array is typed by its first element; codegen reports
+            // elements that disagree.
+            ast::Expression::Array(arr) => {
+                let elems: Vec<Option<AhaType>> = arr.elements.iter().map(|e| self.expr(e)).collect();
+                for elem in &arr.elements {
+                    self.note_move(elem, self.line);
+                }
+                let elems = elems.into_iter().collect::<Option<Vec<_>>>()?;
+                let first = elems.first()?.clone();
+                elems.iter().all(|t| Self::compatible(&first, t))
+                    .then(|| AhaType::Array(Box::new(first), elems.len()))
+            }
+            ast::Expression::Tuple(t) => {
+                let elems: Vec<Option<AhaType>> = t.elements.iter().map(|e| self.expr(e)).collect();
+                elems.into_iter().collect::<Option<Vec<_>>>().map(AhaType::Tuple)
+            }
+            ast::Expression::Index(idx) => {
+                let container = self.expr(&idx.left);
+                let index = self.expr(&idx.index);
+                if let Some(t) = index.filter(|t| !t.is_numeric()) {
+                    self.error(idx.line, format!("Index must be Int, got {}", t));
+                }
+                match container? {
+                    AhaType::Array(elem, len) => {
+                        if let ast::Expression::Integer(i) = &*idx.index {
+                            if i.value as u64 >= len as u64 {
+                                let message = format!("Index {} is out of bounds for [{}; {}]", i.value, elem, len);
+                                self.error(idx.line, message);
+                            }
+                        }
+                        Some(*elem)
+                    }
+                    AhaType::List(elem) => Some(*elem),
+                    _ => None,
+                }
+            }
+            ast::Expression::StructLiteral(sl) => {
+                let values: Vec<Option<AhaType>> = sl.fields.iter().map(|(_, value)| self.expr(value)).collect();
+                if let Some(base) = &sl.base {
+                    self.expr(base);
+                }
+                // A generic struct's instance follows from its field values.
+                if let Some(def) = self.generic_structs.get(&sl.name.value).cloned() {
+                    let mut bound = HashMap::new();
+                    for ((field, _), value) in sl.fields.iter().zip(&values) {
+                        let hint = def.fields.iter().find(|f| f.name.value == field.value).and_then(|f| f.type_hint.as_deref());
+                        if let (Some(hint), Some(value)) = (hint, value) {
+                            self.bind_type_params(&def.type_params, hint, value, &mut bound);
+                        }
+                    }
+                    let args = Self::bound_args(&def.type_params, &bound)?;
+                    return self.instantiate(&sl.name.value, args);
+                }
+                self.structs.contains_key(&sl.name.value).then(|| AhaType::Struct(sl.name.value.clone()))
+            }
+            ast::Expression::FieldAccess(fa) => {
+                match self.expr(&fa.object)? {
+                    AhaType::Tuple(elems) => fa.field.value.parse::<usize>().ok()
+                        .and_then(|i| elems.get(i).cloned()),
+                    AhaType::Struct(name) => self.structs.get(&name)?
+                        .iter()
+                        .find(|(field, _)| field == &fa.field.value)
+                        .and_then(|(_, t)| t.clone()),
+                    _ => None,
+                }
+            }
+            // `Color::Red` constructs a unit variant.
+            ast::Expression::ModuleAccess(ma) if self.is_enum(&ma.module) => {
+                let owner = self.variant_enum(Some(&ma.module), &ma.name, ma.line)?;
+                self.check_constructor(&owner, &ma.name, &[], ma.line);
+                self.variant_type(&owner, &ma.name, &[], expected.as_ref())
+            }
+            ast::Expression::ModuleAccess(ma) => self.lookup(&ma.name),
+            ast::Expression::Cast(cast) => {
+                let value = self.expr(&cast.value);
+                if cast.target != "int" {
+                    self.error(cast.line, format!("Cannot cast to '{}': only `as int` is supported", cast.target));
+                    return None;
+                }
+                match value {
+                    None | Some(AhaType::Int | AhaType::Bool) => {}
+                    Some(AhaType::Enum(name)) => {
+                        if let Some(variant) = self.payload_variant(&name) {
+                            self.error(cast.line, format!(
+                                "Cannot cast {} to int: '{}::{}' carries a payload", name, name, variant
+                            ));
+                        }
+                    }
+                    Some(other) => self.error(cast.line, format!("Cannot cast {} to int", other)),
+                }
+                Some(AhaType::Int)
+            }
+            ast::Expression::Spawn(spawn) => {
+                for (_, value) in &spawn.fields {
+                    self.expr(value);
+                }
+                None
+            }
+            ast::Expression::Assignment(assign) => {
+                self.line = assign.line;
+                let value = self.expr(&assign.value);
+                // A moved local holds a value again once reassigned.
+                if let ast::Expression::Identifier(id) = &*assign.target {
+                    if let Some(binding) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&id.value)) {
+                        binding.moved = None;
+                    }
+                }
+                self.expr(&assign.target);
+                self.check_assignable(&assign.target, assign.line);
+                // Codegen yields the assigned value for `x = v`, so a
+                // function ending in one returns it.
+                match &*assign.target {
+                    ast::Expression::Identifier(_) => value,
+                    _ => None,
+                }
+            }
+            ast::Expression::Match(m) => {
+                let scrutinee = self.expr(&m.value);
+                let mut result: Option<Option<AhaType>> = None;
+                for arm in &m.arms {
+                    self.scopes.push(HashMap::new());
+                    self.bind_pattern(&arm.pattern, scrutinee.clone());
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                    }
+                    let body = self.expr(&arm.body);
+                    self.scopes.pop();
+                    // Diverging arms (`return`, `break`) don't constrain the type.
+                    if matches!(arm.body, ast::Expression::Break | ast::Expression::Continue) {
+                        continue;
+                    }
+                    result = Some(match result {
+                        None => body,
+                        Some(prev) => Self::join(prev, body),
+                    });
+                }
+                result.flatten()
+            }
+            ast::Expression::Break | ast::Expression::Continue => None,
+        }
+    }
+
+    /// The type of a two-way branch: known only when both sides agree.
+    fn join(a: Option<AhaType>, b: Option<AhaType>) -> Option<AhaType> {
+        match (a, b) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(a), Some(b)) if a.is_numeric() && b.is_numeric() => Some(AhaType::Int),
+            _ => None,
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &ast::Pattern, t: Option<AhaType>) {
+        // Patterns see through boxes.
+        let t = match t {
+            Some(AhaType::Box(inner)) => Some(*inner),
+            other => other,
+        };
+        match pattern {
+            ast::Pattern::Binding(name) => {
+                // A bare name that is a variant of the scrutinee's enum is
+                // that variant, not a binding.
+                if let Some(AhaType::Enum(enum_name)) = &t {
+                    if self.variants.get(name).is_some_and(|owners| owners.contains(enum_name)) {
+                        return;
+                    }
+                }
+                self.bind(name, t);
+            }
+            ast::Pattern::MutBinding(name) => self.bind_with(name, t, true),
+            ast::Pattern::EnumTuple(path, subs) => {
+                let variant = path.rsplit("::").next().unwrap_or(path);
+                let payload = match &t {
+                    Some(AhaType::Enum(name)) => self.enums.get(name)
+                        .and_then(|vs| vs.iter().find(|(v, _)| v == variant))
+                        .map(|(_, ts)| ts.clone()),
+                    _ => None,
+                };
+                for (i, sub) in subs.iter().enumerate() {
+                    let sub_type = payload.as_ref().and_then(|ts| ts.get(i).cloned()).flatten();
+                    self.bind_pattern(sub, sub_type);
+                }
+            }
+            ast::Pattern::Struct(name, fields) => {
+                let defs = self.structs.get(name).cloned();
+                for (field, sub) in fields {
+                    let field_type = defs.as_ref()
+                        .and_then(|fs| fs.iter().find(|(f, _)| f == field))
+                        .and_then(|(_, t)| t.clone());
+                    self.bind_pattern(sub, field_type);
+                }
+            }
+            ast::Pattern::Tuple(subs) => {
+                for (i, sub) in subs.iter().enumerate() {
+                    let elem = match &t {
+                        Some(AhaType::Tuple(elems)) => elems.get(i).cloned(),
+                        _ => None,
+                    };
+                    self.bind_pattern(sub, elem);
+                }
+            }
+            ast::Pattern::Or(alts) => {
+                for alt in alts {
+                    self.bind_pattern(alt, t.clone());
+                }
+            }
+            ast::Pattern::Wildcard
+            | ast::Pattern::EnumUnit(_)
+            | ast::Pattern::Integer(_)
+            | ast::Pattern::Str(_)
+            | ast::Pattern::Range(_, _) => {}
+        }
+    }
+
+    // --- Functions ---
+
+    fn function(&mut self, func: &ast::FunctionLiteral) {
+        let name = func.name.as_ref().map(|n| n.value.clone());
+        let sig_params = name.as_ref()
+            .and_then(|n| self.sigs.get(n))
+            .map(|sig| sig.params.clone())
+            .unwrap_or_default();
+        let generic = !func.type_params.is_empty();
+
+        let saved_generic = self.in_generic;
+        self.in_generic = saved_generic || generic;
+        let value = self.function_body(func, name.clone(), &sig_params);
+        if let Some(sig) = name.as_ref().and_then(|n| self.sigs.get_mut(n)) {
+            if sig.ret_hint.is_none() && !generic && sig.ret.is_none() && value.is_some() {
+                sig.ret = value;
+                self.changed = true;
+            }
+        }
+        self.in_generic = saved_generic;
+    }
+
+    /// Walk a function body with its parameters typed `params`; returns
+    /// the type of its value.
+    fn function_body(&mut self, func: &ast::FunctionLiteral, name: Option<String>, params: &[Option<AhaType>]) -> Option<AhaType> {
+        let saved_scopes = std::mem::replace(&mut self.scopes, vec![HashMap::new()]);
+        let saved_fn = std::mem::replace(&mut self.current_fn, name);
+        let saved_loops = std::mem::replace(&mut self.loops, 0);
+        for (i, param) in func.parameters.iter().enumerate() {
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert(param.value.clone(), Binding {
+                    ty: params.get(i).cloned().flatten(),
+                    mutable: func.mutable_params.get(i).copied().unwrap_or(false),
+                    origin: Origin::Param,
+                    loops: 0,
+                    moved: None,
+                });
+            }
+        }
+
+        // The value is the first top-level `return`, else the last
+        // expression statement — the same rule codegen lowers with.
+        let mut returned = None;
+        let mut tail = None;
+        for (i, stmt) in func.body.statements.iter().enumerate() {
+            match stmt {
+                ast::Statement::Return(ret) if returned.is_none() => {
+                    let value = self.expr_expecting(&ret.return_value, self.return_type());
+                    self.check_return(value.as_ref(), ret.line);
+                    returned = Some(value);
+                }
+                ast::Statement::Expression(es) if i + 1 == func.body.statements.len() => {
+                    tail = self.expr_expecting(&es.expression, self.return_type());
+                }
+                ast::Statement::Expression(es) => {
+                    tail = self.expr(&es.expression);
+                }
+                _ => self.walk_statement(stmt),
+            }
+        }
+        if let (None, Some(ast::Statement::Expression(es))) = (&returned, func.body.statements.last()) {
+            match Self::loop_keyword(&es.expression) {
+                Some(keyword) => self.check_loop_return(keyword, func.line),
+                None => self.check_return(tail.as_ref(), func.line),
+            }
+        }
+        self.current_fn = saved_fn;
+        self.loops = saved_loops;
+        self.scopes = saved_scopes;
+        returned.unwrap_or(tail)
+    }
+
+    /// Return type of the instance of generic function `name` that these
+    /// argument types select: its `-> T` hint with the type params bound,
+    /// else the type of its body walked with those bindings. Recorded by
+    /// instance name for codegen.
+    fn instance_return(&mut self, name: &str, arg_types: &[Option<AhaType>]) -> Option<AhaType> {
+        let func = self.generic_fns.get(name)?.clone();
+        let mut bound = HashMap::new();
+        for (hint, actual) in func.param_type_hints.iter().zip(arg_types) {
+            if let (Some(hint), Some(actual)) = (hint, actual) {
+                self.bind_type_params(&func.type_params, hint, actual, &mut bound);
+            }
+        }
+        Self::bound_args(&func.type_params, &bound)?;
+        let instance = generic_fn_name(name, &bound);
+        if self.walking.contains(&instance) {
+            return self.instance_returns.get(&instance).cloned();
+        }
+        let saved_args = std::mem::replace(&mut self.type_args, bound);
+        let ret = match &func.return_type_hint {
+            Some(hint) => self.resolve_hint(hint),
+            None => {
+                let params: Vec<Option<AhaType>> = func.param_type_hints.iter().zip(arg_types)
+                    .map(|(hint, actual)| match hint {
+                        Some(hint) => self.resolve_hint(hint),
+                        None => actual.clone(),
+                    })
+                    .collect();
+                // The generic body itself is checked once, outside any
+                // instance; this walk only types it.
+                let saved_recording = std::mem::replace(&mut self.recording, false);
+                self.walking.insert(instance.clone());
+                let value = self.function_body(&func, Some(name.to_string()), &params);
+                self.walking.remove(&instance);
+                self.recording = saved_recording;
+                value
+            }
+        };
+        self.type_args = saved_args;
+        if let Some(ret) = &ret {
+            self.instance_returns.insert(instance, ret.clone());
+        }
+        ret
+    }
+
+    /// `while`, `while let` or `for`, if `expr` is that loop.
+    fn loop_keyword(expr: &ast::Expression) -> Option<&'static str> {
+        match expr {
+            ast::Expression::While(_) => Some("while"),
+            ast::Expression::WhileLet(_) => Some("while let"),
+            ast::Expression::For(_) => Some("for"),
+            _ => None,
+        }
+    }
+
+    /// Final pass: a loop has no value, so a function ending in one can
+    /// only have an Int (0) or no declared return type.
+    fn check_loop_return(&mut self, keyword: &str, line: usize) {
+        if !self.recording {
+            return;
+        }
+        let Some(name) = self.current_fn.clone() else { return };
+        let Some(declared) = self.sigs.get(&name).and_then(|sig| sig.ret_hint.as_ref().and(sig.ret.as_ref())) else { return };
+        if !declared.is_numeric() && !declared.is_void() {
+            let message = format!(
+                "Function '{}' ends in a `{}` loop, which has no value, but its return type is {}",
+                name, keyword, declared
+            );
+            self.error(line, message);
+        }
+    }
+
+    /// Return type of the function being walked, as far as it is known.
+    fn return_type(&self) -> Option<AhaType> {
+        self.sigs.get(self.current_fn.as_ref()?)?.ret.clone()
+    }
+
+    /// Final pass: a value returned from the current function must fit
+    /// its declared return type.
+    fn check_return(&mut self, value: Option<&AhaType>, line: usize) {
+        if !self.recording {
+            return;
+        }
+        let Some(name) = self.current_fn.clone() else { return };
+        let Some(sig) = self.sigs.get(&name) else { return };
+        if let (Some(hint), Some(declared), Some(actual)) = (&sig.ret_hint, &sig.ret, value) {
+            if !declared.is_void() && !Self::compatible(declared, actual) {
+                let message = format!(
+                    "Return type annotation '{}' does not match actual return type '{}' in function '{}'",
+                    hint, actual, name
+                );
+                self.error(line, message);
+            }
+        }
+    }
+
+    // --- Calls ---
+
+    fn call(&mut self, call: &ast::CallExpression, expected: Option<&AhaType>) -> Option<AhaType> {
+        self.line = call.line;
+        let arg_types: Vec<Option<AhaType>> = call.arguments.iter().map(|a| self.expr(a)).collect();
+        let (qualifier, name) = match call.function.as_ref() {
+            ast::Expression::Identifier(id) => (None, id.value.clone()),
+            ast::Expression::ModuleAccess(ma) if self.is_enum(&ma.module) => (Some(ma.module.as_str()), ma.name.clone()),
+            ast::Expression::ModuleAccess(ma) => (None, ma.name.clone()),
+            other => {
+                self.expr(other);
+                return None;
+            }
+        };
+
+        if let Some(enum_name) = qualifier.filter(|_| name == "from_int") {
+            if !self.variant_hints.contains_key(&(enum_name.to_string(), name.clone())) {
+                let enum_name = enum_name.to_string();
+                self.check_from_int(&enum_name, &arg_types, call.line);
+                return self.option_of(AhaType::Enum(enum_name));
+            }
+        }
+        if qualifier.is_some() || (!self.sigs.contains_key(&name) && self.variants.contains_key(&name)) {
+            let owner = self.variant_enum(qualifier, &name, call.line)?;
+            self.check_constructor(&owner, &name, &arg_types, call.line);
+            for arg in &call.arguments {
+                self.note_move(arg, call.line);
+            }
+            return self.variant_type(&owner, &name, &arg_types, expected);
+        }
+        if self.sigs.contains_key(&name) {
+            if let (true, Some(caller)) = (self.recording, &self.current_fn) {
+                self.calls.entry(caller.clone()).or_default().insert(name.clone());
+            }
+            return self.user_call(&name, &arg_types, call.line);
+        }
+        // `len(arr)` is the array's static length.
+        if let ("len", [Some(AhaType::Array(_, _))]) = (name.as_str(), arg_types.as_slice()) {
+            return Some(AhaType::Int);
+        }
+        self.check_builtin(&name, &arg_types, call.line);
+        let first = arg_types.first().cloned().flatten();
+        if name == "box" {
+            if let Some(arg) = call.arguments.first() {
+                self.note_move(arg, call.line);
+            }
+        }
+        match name.as_str() {
+            "box" => first.map(|t| AhaType::Box(Box::new(t))),
+            "print" | "print_str" | "abs" | "min" | "max" | "len" | "string_to_int" | "char_at"
+            | "file_write" | "call" | "list_len" | "list_free" | "wrapping_add" | "saturating_add" => Some(AhaType::Int),
+            "int_to_string" | "string_sub" | "file_read" => Some(AhaType::String),
+            "checked_add" => self.option_of(AhaType::Int),
+            // The payload of the `Some(x)` / `Ok(x)` variant.
+            "unwrap" => match first {
+                Some(AhaType::Enum(name)) => self.enums.get(&name)?.iter()
+                    .find(|(variant, payload)| (variant == "Some" || variant == "Ok") && payload.len() == 1)
+                    .and_then(|(_, payload)| payload[0].clone()),
+                _ => None,
+            },
+            "list_new" => Some(AhaType::List(Box::new(AhaType::Int))),
+            "list_new_string" => Some(AhaType::List(Box::new(AhaType::String))),
+            "list_push" | "list_push_string" => first,
+            "list_get" | "list_get_string" => match first {
+                Some(AhaType::List(elem)) => Some(*elem),
+                _ => None,
+            },
+            "map_new" => Some(AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::Int))),
+            "map_string_key_new" => Some(AhaType::Map(Box::new(AhaType::String), Box::new(AhaType::Int))),
+            "map_string_val_new" => Some(AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::String))),
+            "map_strings_new" => Some(AhaType::Map(Box::new(AhaType::String), Box::new(AhaType::String))),
+            _ if name.starts_with("map_") => match (name.rsplit('_').next(), first) {
+                (Some("set" | "remove"), map @ Some(AhaType::Map(_, _))) => map,
+                (Some("get"), Some(AhaType::Map(_, v))) => Some(*v),
+                (Some("len" | "contains" | "free"), _) => Some(AhaType::Int),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// A call to a user function: check the argument count, fill unknown
+    /// parameter types from this call site, and (final pass) report
+    /// arguments that conflict with types settled by other call sites.
+    fn user_call(&mut self, name: &str, arg_types: &[Option<AhaType>], line: usize) -> Option<AhaType> {
+        let sig = &self.sigs[name];
+        if sig.params.len() != arg_types.len() {
+            let (expected, ret) = (sig.params.len(), sig.ret.clone());
+            let message = format!(
+                "Function '{}' expects {}, got {} (signature: {})",
+                name, count_of(expected, "argument"), arg_types.len(), self.signature(name)
+            );
+            self.error(line, message);
+            return ret;
+        }
+        if sig.generic {
+            return self.instance_return(name, arg_types);
+        }
+
+        let mut errors = Vec::new();
+        let sig = self.sigs.get_mut(name).expect("signature checked above");
+        for (i, arg) in arg_types.iter().enumerate() {
+            let Some(arg) = arg else { continue };
+            match &sig.params[i] {
+                None => {
+                    sig.params[i] = Some(arg.clone());
+                    self.changed = true;
+                }
+                Some(param) if !Self::compatible(param, arg) => {
+                    let message = if sig.hinted[i] {
+                        format!("Function '{}' argument '{}' expects {}, got {}", name, sig.param_names[i], param, arg)
+                    } else {
+                        format!(
+                            "Conflicting types for parameter '{}' of '{}': {} here, {} at another call site",
+                            sig.param_names[i], name, arg, param
+                        )
+                    };
+                    errors.push(message);
+                }
+                Some(_) => {}
+            }
+        }
+        let ret = sig.ret.clone();
+        for message in errors {
+            let message = format!("{} (signature: {})", message, self.signature(name));
+            self.error(line, message);
+        }
+        ret
+    }
+
+    /// A user function's signature as far as it is known:
+    /// `add(a: Int, b) -> Int`, `id<T>(x: T) -> T`.
+    fn signature(&self, name: &str) -> String {
+        let sig = &self.sigs[name];
+        let params: Vec<String> = sig.param_names.iter().enumerate()
+            .map(|(i, p)| {
+                let known = sig.params[i].as_ref().map(|t| t.to_string());
+                match known.or_else(|| sig.param_hints[i].clone()) {
+                    Some(t) => format!("{}: {}", p, t),
+                    None => p.clone(),
+                }
+            })
+            .collect();
+        let type_params = if sig.type_params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", sig.type_params.join(", "))
+        };
+        let ret = sig.ret.as_ref().map(|t| t.to_string()).or_else(|| sig.ret_hint.clone());
+        match ret {
+            Some(ret) => format!("{}{}({}) -> {}", name, type_params, params.join(", "), ret),
+            None => format!("{}{}({})", name, type_params, params.join(", ")),
+        }
+    }
+
+    /// Does `name` name an enum (generic or not)?
+    fn is_enum(&self, name: &str) -> bool {
+        self.variants.values().any(|owners| owners.iter().any(|o| o == name))
+    }
+
+    /// The enum a constructor belongs to. `Color::Red` names it; a bare
+    /// `Red` must be declared by one enum, or by one enum opened with
+    /// `use Color::*`.
+    fn variant_enum(&mut self, qualifier: Option<&str>, variant: &str, line: usize) -> Option<String> {
+        let owners = self.variants.get(variant).cloned().unwrap_or_default();
+        if let Some(qualifier) = qualifier {
+            if owners.iter().any(|o| o == qualifier) {
+                return Some(qualifier.to_string());
+            }
+            self.error(line, format!("Enum '{}' has no variant '{}'", qualifier, variant));
+            return None;
+        }
+        let open: Vec<String> = owners.iter().filter(|o| self.open_enums.contains(*o)).cloned().collect();
+        match (owners.as_slice(), open.as_slice()) {
+            ([only], _) | (_, [only]) => Some(only.clone()),
+            ([], _) => None,
+            _ => {
+                // Opening every candidate doesn't help, so only suggest it
+                // when none is open yet.
+                let hint = if open.is_empty() { format!(" or add `use {}::*`", owners[0]) } else { String::new() };
+                self.error(line, format!(
+                    "Variant '{}' is ambiguous: it belongs to enums {}; write {}::{}{}",
+                    variant,
+                    owners.iter().map(|o| format!("'{}'", o)).collect::<Vec<_>>().join(", "),
+                    owners[0], variant, hint
+                ));
+                None
+            }
+        }
+    }
+
+    /// Type of a value built by one of `enum_name`'s constructors. As in
+    /// codegen, a generic enum's instance comes from the expected type
+    /// when that names one, else from the payload types, with params
+    /// they leave open as Int (`None()` is `Option<Int>`).
+    fn variant_type(
+        &mut self,
+        enum_name: &str,
+        variant: &str,
+        arg_types: &[Option<AhaType>],
+        expected: Option<&AhaType>,
+    ) -> Option<AhaType> {
+        let Some(def) = self.generic_enums.get(enum_name).cloned() else {
+            return Some(AhaType::Enum(enum_name.to_string()));
+        };
+        if let Some(AhaType::Enum(name)) = expected {
+            if self.instances.get(name).is_some_and(|(base, _)| base == enum_name) {
+                return expected.cloned();
+            }
+        }
+        let hints = &def.variants.iter().find(|v| v.name.value == variant)?.payload_types;
+        let mut bound = HashMap::new();
+        for (hint, actual) in hints.iter().zip(arg_types) {
+            // A payload typecheck could not type leaves the instance open.
+            let actual = actual.as_ref()?;
+            self.bind_type_params(&def.type_params, hint, actual, &mut bound);
+        }
+        let args = def.type_params.iter().map(|p| bound.get(p).cloned().unwrap_or(AhaType::Int)).collect();
+        self.instantiate(enum_name, args)
+    }
+
+    /// An enum constructor must get one argument per payload; payloads of
+    /// non-generic enums are type-checked too (a `Box<T>` slot takes a T).
+    fn check_constructor(&mut self, enum_name: &str, variant: &str, arg_types: &[Option<AhaType>], line: usize) {
+        let key = (enum_name.to_string(), variant.to_string());
+        let Some((written, hints)) = self.variant_hints.get(&key).cloned() else { return };
+        let base = written.split('<').next().unwrap_or(&written).to_string();
+        let signature = if hints.is_empty() {
+            format!("{}::{}", written, variant)
+        } else {
+            format!("{}::{}({})", written, variant, hints.join(", "))
+        };
+        if hints.len() != arg_types.len() {
+            self.error(line, format!(
+                "Enum variant '{}::{}' expects {}, got {} (signature: {})",
+                base, variant, count_of(hints.len(), "argument"), arg_types.len(), signature
+            ));
+            return;
+        }
+        let payload = self.enums.get(&base)
+            .and_then(|vs| vs.iter().find(|(v, _)| v == variant))
+            .map(|(_, ts)| ts.clone())
+            .unwrap_or_default();
+        for (i, (expected, actual)) in payload.iter().zip(arg_types).enumerate() {
+            let (Some(expected), Some(actual)) = (expected, actual) else { continue };
+            let fits = Self::compatible(expected, actual)
+                || matches!(expected, AhaType::Box(inner) if Self::compatible(inner, actual));
+            if !fits {
+                self.error(line, format!(
+                    "Enum variant '{}::{}' arg {} expects {}, got {} (signature: {})",
+                    base, variant, i, expected, actual, signature
+                ));
+            }
+        }
+    }
+
+    /// First variant of `enum_name` that carries a payload, if any; enums
+    /// without one convert to and from int.
+    fn payload_variant(&self, enum_name: &str) -> Option<String> {
+        let enum_name = enum_name.split('<').next().unwrap_or(enum_name);
+        self.variant_hints.iter()
+            .filter(|((e, _), (_, hints))| e == enum_name && !hints.is_empty())
+            .map(|((_, v), _)| v.clone())
+            .min()
+    }
+
+    /// `Opcode::from_int(x)` takes one int and needs an enum without
+    /// payloads.
+    fn check_from_int(&mut self, enum_name: &str, arg_types: &[Option<AhaType>], line: usize) {
+        if self.generic_types.contains(enum_name) {
+            self.error(line, format!("`{}::from_int` needs a non-generic enum", enum_name));
+        } else if let Some(variant) = self.payload_variant(enum_name) {
+            self.error(line, format!(
+                "`{}::from_int` needs an enum without payloads; '{}::{}' carries one",
+                enum_name, enum_name, variant
+            ));
+        } else if arg_types.len() != 1 {
+            self.error(line, format!("`{}::from_int` expects 1 argument, got {}", enum_name, arg_types.len()));
+        } else if let Some(actual) = arg_types[0].as_ref().filter(|t| !Self::compatible(&AhaType::Int, t)) {
+            let message = format!("`{}::from_int` expects Int, got {}", enum_name, actual);
+            self.error(line, message);
+        }
+    }
+
+    /// Builtins have fixed signatures; list and map element, key and
+    /// value arguments are checked against the container passed first.
+    fn check_builtin(&mut self, name: &str, arg_types: &[Option<AhaType>], line: usize) {
+        let Some((params, ret)) = builtin_signature(name) else { return };
+        let signature = format!(
+            "{}({}) -> {}",
+            name,
+            params.iter().map(|(p, kind)| format!("{}: {}", p, kind)).collect::<Vec<_>>().join(", "),
+            ret
+        );
+        if params.len() != arg_types.len() {
+            self.error(line, format!(
+                "Builtin '{}' expects {}, got {} (signature: {})",
+                name, count_of(params.len(), "argument"), arg_types.len(), signature
+            ));
+            return;
+        }
+        let container = arg_types.first().cloned().flatten();
+        for ((param, kind), actual) in params.iter().zip(arg_types) {
+            let Some(actual) = actual else { continue };
+            let expected = match (kind, &container) {
+                (BuiltinParam::Int, _) => Some(AhaType::Int),
+                (BuiltinParam::Str, _) => Some(AhaType::String),
+                (BuiltinParam::List, _) if !matches!(actual, AhaType::List(_)) => {
+                    Some(AhaType::List(Box::new(AhaType::Int)))
+                }
+                (BuiltinParam::Map, _) if !matches!(actual, AhaType::Map(_, _)) => {
+                    Some(AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::Int)))
+                }
+                (BuiltinParam::Elem, Some(AhaType::List(elem))) => Some((**elem).clone()),
+                (BuiltinParam::Key, Some(AhaType::Map(key, _))) => Some((**key).clone()),
+                (BuiltinParam::Val, Some(AhaType::Map(_, val))) => Some((**val).clone()),
+                _ => None,
+            };
+            let Some(expected) = expected else { continue };
+            if !Self::compatible(&expected, actual) {
+                let wanted = match kind {
+                    BuiltinParam::List | BuiltinParam::Map => kind.to_string(),
+                    _ => expected.to_string(),
+                };
+                self.error(line, format!(
+                    "Builtin '{}' argument '{}' expects {}, got {} (signature: {})",
+                    name, param, wanted, actual, signature
+                ));
+            }
+        }
+    }
+
+    /// Can a value of type `actual` be used where `expected` is wanted?
+    /// Int and Bool mix freely (comparisons yield Int); list and map
+    /// element types are settled by codegen at their first use.
+    fn compatible(expected: &AhaType, actual: &AhaType) -> bool {
+        match (expected, actual) {
+            (a, b) if a == b => true,
+            (a, b) if a.is_numeric() && b.is_numeric() => true,
+            (AhaType::List(_), AhaType::List(_)) | (AhaType::Map(_, _), AhaType::Map(_, _)) => true,
+            (AhaType::Array(a, n), AhaType::Array(b, m)) => n == m && Self::compatible(a, b),
+            (AhaType::Tuple(a), AhaType::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| Self::compatible(x, y))
+            }
+            _ => false,
+        }
+    }
+
+    /// Record an error in the final pass (earlier rounds see the same
+    /// code with less information).
+    fn error(&mut self, line: usize, message: String) {
+        if self.recording {
+            self.errors.push(TypeError { line, message });
+        }
+    }
+}
+
+/// Parameter kinds in builtin signatures. `Elem`, `Key` and `Val` take
+/// their type from the list or map passed as the first argument.
+#[derive(Clone, Copy)]
+enum BuiltinParam {
+    Int,
+    Str,
+    Any,
+    List,
+    Elem,
+    Map,
+    Key,
+    Val,
+}
+
+impl fmt::Display for BuiltinParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuiltinParam::Int => write!(f, "Int"),
+            BuiltinParam::Str => write!(f, "String"),
+            BuiltinParam::Any | BuiltinParam::Elem => write!(f, "T"),
+            BuiltinParam::List => write!(f, "List<T>"),
+            BuiltinParam::Map => write!(f, "Map<K, V>"),
+            BuiltinParam::Key => write!(f, "K"),
+            BuiltinParam::Val => write!(f, "V"),
+        }
+    }
+}
+
+type BuiltinSignature = (&'static [(&'static str, BuiltinParam)], &'static str);
+
+/// Parameters and return type (as displayed) of a builtin function.
+fn builtin_signature(name: &str) -> Option<BuiltinSignature> {
+    use BuiltinParam::*;
+    let sig: BuiltinSignature = match name {
+        "print" => (&[("value", Int)], "Int"),
+        "print_str" => (&[("s", Str)], "Int"),
+        "abs" => (&[("n", Int)], "Int"),
+        "min" | "max" => (&[("a", Int), ("b", Int)], "Int"),
+        "len" => (&[("s", Str)], "Int"),
+        "int_to_string" => (&[("n", Int)], "String"),
+        "string_to_int" => (&[("s", Str)], "Int"),
+        "string_sub" => (&[("s", Str), ("start", Int), ("len", Int)], "String"),
+        "char_at" => (&[("s", Str), ("index", Int)], "Int"),
+        "file_read" => (&[("path", Str)], "String"),
+        "file_write" => (&[("path", Str), ("content", Str)], "Int"),
+        "box" => (&[("value", Any)], "Box<T>"),
+        "panic" => (&[("message", Str)], "Never"),
+        "assert" => (&[("condition", Int)], "Void"),
+        "assert_eq" | "assert_ne" => (&[("left", Any), ("right", Any)], "Void"),
+        "wrapping_add" | "saturating_add" => (&[("a", Int), ("b", Int)], "Int"),
+        "checked_add" => (&[("a", Int), ("b", Int)], "Option<Int>"),
+        "unwrap" => (&[("value", Any)], "T"),
+        "send" => (&[("actor", Any), ("msg", Any)], "Void"),
+        "call" => (&[("actor", Any), ("msg", Any)], "Int"),
+        "list_new" => (&[], "List<Int>"),
+        "list_new_string" => (&[], "List<String>"),
+        "list_push" | "list_push_string" => (&[("list", List), ("value", Elem)], "List<T>"),
+        "list_get" | "list_get_string" => (&[("list", List), ("index", Int)], "T"),
+        "list_len" | "list_free" => (&[("list", List)], "Int"),
+        _ => {
+            // map_<op>, map_string_key_<op>, map_string_val_<op>, map_strings_<op>
+            let rest = name.strip_prefix("map_")?;
+            let op = ["string_key_", "string_val_", "strings_"].iter()
+                .find_map(|variant| rest.strip_prefix(variant))
+                .unwrap_or(rest);
+            match op {
+                "new" => (&[], "Map<K, V>"),
+                "set" => (&[("map", Map), ("key", Key), ("value", Val)], "Map<K, V>"),
+                "get" => (&[("map", Map), ("key", Key)], "V"),
+                "contains" => (&[("map", Map), ("key", Key)], "Int"),
+                "remove" => (&[("map", Map), ("key", Key)], "Map<K, V>"),
+                "len" | "free" => (&[("map", Map)], "Int"),
+                _ => return None,
+            }
+        }
+    };
+    Some(sig)
+}
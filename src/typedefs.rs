@@ -0,0 +1,307 @@
+// src/typedefs.rs
+//
+// AHA! Type Definitions — desugars `type` aliases and `newtype`s on the
+// merged `Program` before type checking, so the rest of the pipeline never
+// sees them:
+//
+//   - `type Index = Map<string, List<int>>` is expanded wherever `Index`
+//     appears in a type hint (lets, parameters, returns, fields, enum
+//     payloads, const/static annotations). Aliases may refer to other
+//     aliases, in any order.
+//   - `newtype Meters = int` becomes a one-field struct `Meters { 0: int }`
+//     (`is_newtype`), which has the base type's layout but is a distinct
+//     type. Calls `Meters(5)` become struct literals; `m.0` reads the value.
+
+use crate::ast;
+use std::collections::{HashMap, HashSet};
+
+/// Expand aliases and lower newtypes. Errors carry the definition's line.
+pub fn desugar(program: &ast::Program) -> Result<ast::Program, String> {
+    let mut aliases = HashMap::new();
+    let mut newtypes = HashSet::new();
+    for stmt in &program.statements {
+        match stmt {
+            ast::Statement::TypeAlias(alias) => {
+                aliases.insert(alias.name.value.clone(), alias);
+            }
+            ast::Statement::Newtype(def) => {
+                newtypes.insert(def.name.value.clone());
+            }
+            _ => {}
+        }
+    }
+    if aliases.is_empty() && newtypes.is_empty() {
+        return Ok(program.clone());
+    }
+
+    // Expand each alias's own target first, so one substitution pass
+    // per hint suffices.
+    let mut expanded: HashMap<String, String> = HashMap::new();
+    let mut names: Vec<&String> = aliases.keys().collect();
+    names.sort();
+    for name in names {
+        expand_alias(name, &aliases, &mut expanded, &mut Vec::new())?;
+    }
+
+    let desugarer = Desugarer { aliases: expanded, newtypes };
+    let mut statements = Vec::with_capacity(program.statements.len());
+    for stmt in &program.statements {
+        match stmt {
+            ast::Statement::TypeAlias(_) => {}
+            ast::Statement::Newtype(def) => {
+                statements.push(ast::Statement::Struct(ast::StructDefinition {
+                    name: def.name.clone(),
+                    is_pub: def.is_pub,
+                    type_params: Vec::new(),
+                    fields: vec![ast::StructField {
+                        name: ast::Identifier { value: "0".to_string() },
+                        type_hint: Some(desugarer.hint(&def.base)),
+                        default: None,
+                        line: def.line,
+                    }],
+                    is_newtype: true,
+                }));
+            }
+            other => {
+                let mut stmt = other.clone();
+                desugarer.statement(&mut stmt);
+                statements.push(stmt);
+            }
+        }
+    }
+    Ok(ast::Program { statements })
+}
+
+fn expand_alias(
+    name: &str,
+    aliases: &HashMap<String, &ast::TypeAlias>,
+    expanded: &mut HashMap<String, String>,
+    in_progress: &mut Vec<String>,
+) -> Result<String, String> {
+    if let Some(done) = expanded.get(name) {
+        return Ok(done.clone());
+    }
+    let alias = aliases[name];
+    if in_progress.iter().any(|n| n == name) {
+        return Err(format!("line {}: Type alias '{}' refers to itself", alias.line, name));
+    }
+    in_progress.push(name.to_string());
+    let mut result = String::new();
+    for (word, is_ident) in split_words(&alias.target) {
+        if is_ident && aliases.contains_key(word) {
+            result.push_str(&expand_alias(word, aliases, expanded, in_progress)?);
+        } else {
+            result.push_str(word);
+        }
+    }
+    in_progress.pop();
+    expanded.insert(name.to_string(), result.clone());
+    Ok(result)
+}
+
+/// Split a hint into identifier and punctuation runs:
+/// `List<Index>` → ["List", "<", "Index", ">"].
+fn split_words(hint: &str) -> Vec<(&str, bool)> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_ident = false;
+    for (i, c) in hint.char_indices() {
+        let ident = c.is_alphanumeric() || c == '_';
+        if i > start && ident != in_ident {
+            words.push((&hint[start..i], in_ident));
+            start = i;
+        }
+        in_ident = ident;
+    }
+    if start < hint.len() {
+        words.push((&hint[start..], in_ident));
+    }
+    words
+}
+
+struct Desugarer {
+    /// Alias name → fully expanded target.
+    aliases: HashMap<String, String>,
+    newtypes: HashSet<String>,
+}
+
+impl Desugarer {
+    fn hint(&self, hint: &str) -> String {
+        split_words(hint)
+            .into_iter()
+            .map(|(word, is_ident)| match self.aliases.get(word) {
+                Some(target) if is_ident => target.as_str(),
+                _ => word,
+            })
+            .collect()
+    }
+
+    fn opt_hint(&self, hint: &mut Option<String>) {
+        if let Some(h) = hint {
+            *h = self.hint(h);
+        }
+    }
+
+    fn statement(&self, stmt: &mut ast::Statement) {
+        match stmt {
+            ast::Statement::Let(let_stmt) => {
+                self.opt_hint(&mut let_stmt.type_annotation);
+                self.expr(&mut let_stmt.value);
+            }
+            ast::Statement::LetPattern(let_pat) => self.expr(&mut let_pat.value),
+            ast::Statement::Return(ret) => self.expr(&mut ret.return_value),
+            ast::Statement::Expression(es) => self.expr(&mut es.expression),
+            ast::Statement::Const(def) => {
+                self.opt_hint(&mut def.type_annotation);
+                self.expr(&mut def.value);
+            }
+            ast::Statement::Static(def) => {
+                self.opt_hint(&mut def.type_annotation);
+                self.expr(&mut def.value);
+            }
+            ast::Statement::Struct(def) => self.fields(&mut def.fields),
+            ast::Statement::Actor(def) => self.fields(&mut def.fields),
+            ast::Statement::Enum(def) => {
+                for variant in &mut def.variants {
+                    for payload in &mut variant.payload_types {
+                        *payload = self.hint(payload);
+                    }
+                }
+            }
+            ast::Statement::Import(_)
+            | ast::Statement::TypeAlias(_)
+            | ast::Statement::Newtype(_)
+            | ast::Statement::UseVariants(_) => {}
+        }
+    }
+
+    fn fields(&self, fields: &mut [ast::StructField]) {
+        for field in fields {
+            self.opt_hint(&mut field.type_hint);
+            if let Some(default) = &mut field.default {
+                self.expr(default);
+            }
+        }
+    }
+
+    fn block(&self, block: &mut ast::BlockStatement) {
+        for stmt in &mut block.statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn expr(&self, expr: &mut ast::Expression) {
+        match expr {
+            ast::Expression::Call(call) => {
+                for arg in &mut call.arguments {
+                    self.expr(arg);
+                }
+                // `Meters(5)` constructs the newtype.
+                if let ast::Expression::Identifier(id) = call.function.as_ref() {
+                    if self.newtypes.contains(&id.value) && call.arguments.len() == 1 {
+                        let name = id.clone();
+                        let value = call.arguments.remove(0);
+                        *expr = ast::Expression::StructLiteral(ast::StructLiteral {
+                            name,
+                            fields: vec![(ast::Identifier { value: "0".to_string() }, value)],
+                            base: None,
+                        });
+                        return;
+                    }
+                }
+                self.expr(&mut call.function);
+            }
+            ast::Expression::Function(func) => {
+                for hint in &mut func.param_type_hints {
+                    self.opt_hint(hint);
+                }
+                self.opt_hint(&mut func.return_type_hint);
+                self.block(&mut func.body);
+            }
+            ast::Expression::Prefix(prefix) => self.expr(&mut prefix.right),
+            ast::Expression::Cast(cast) => self.expr(&mut cast.value),
+            ast::Expression::Infix(infix) => {
+                self.expr(&mut infix.left);
+                self.expr(&mut infix.right);
+            }
+            ast::Expression::If(if_expr) => {
+                self.expr(&mut if_expr.condition);
+                self.block(&mut if_expr.consequence);
+                if let Some(alt) = &mut if_expr.alternative {
+                    self.block(alt);
+                }
+            }
+            ast::Expression::IfLet(if_let) => {
+                self.expr(&mut if_let.value);
+                self.block(&mut if_let.consequence);
+                if let Some(alt) = &mut if_let.alternative {
+                    self.block(alt);
+                }
+            }
+            ast::Expression::While(while_expr) => {
+                self.expr(&mut while_expr.condition);
+                self.block(&mut while_expr.body);
+            }
+            ast::Expression::WhileLet(while_let) => {
+                self.expr(&mut while_let.value);
+                self.block(&mut while_let.body);
+            }
+            ast::Expression::For(for_expr) => {
+                self.expr(&mut for_expr.iterable);
+                self.block(&mut for_expr.body);
+            }
+            ast::Expression::Range(range) => {
+                self.expr(&mut range.start);
+                self.expr(&mut range.end);
+            }
+            ast::Expression::Array(arr) => {
+                for elem in &mut arr.elements {
+                    self.expr(elem);
+                }
+            }
+            ast::Expression::Tuple(tuple) => {
+                for elem in &mut tuple.elements {
+                    self.expr(elem);
+                }
+            }
+            ast::Expression::Index(idx) => {
+                self.expr(&mut idx.left);
+                self.expr(&mut idx.index);
+            }
+            ast::Expression::StructLiteral(sl) => {
+                for (_, value) in &mut sl.fields {
+                    self.expr(value);
+                }
+                if let Some(base) = &mut sl.base {
+                    self.expr(base);
+                }
+            }
+            ast::Expression::FieldAccess(fa) => self.expr(&mut fa.object),
+            ast::Expression::Spawn(spawn) => {
+                for (_, value) in &mut spawn.fields {
+                    self.expr(value);
+                }
+            }
+            ast::Expression::Assignment(assign) => {
+                self.expr(&mut assign.target);
+                self.expr(&mut assign.value);
+            }
+            ast::Expression::Match(m) => {
+                self.expr(&mut m.value);
+                for arm in &mut m.arms {
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.expr(&mut arm.body);
+                }
+            }
+            ast::Expression::Integer(_)
+            | ast::Expression::Boolean(_)
+            | ast::Expression::String(_)
+            | ast::Expression::Identifier(_)
+            | ast::Expression::ModuleAccess(_)
+            | ast::Expression::Break
+            | ast::Expression::Continue => {}
+        }
+    }
+}
@@ -3,6 +3,7 @@
 // AHA! Type System — Defines the type representation used throughout
 // the compiler for type tracking and type checking.
 
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 /// Represents all types that AHA! language understands.
@@ -17,8 +18,9 @@ pub enum AhaType {
     String,
     /// Void/Unit type — result of statements that don't produce a value
     Void,
-    /// Homogeneous array of elements
-    Array(Box<AhaType>),
+    /// Fixed-size array `[T; N]`: element type and length, laid out as an
+    /// LLVM `[N x T]` and passed by value.
+    Array(Box<AhaType>, usize),
     /// Heap-allocated dynamic list: List<T> — handle is an i64 pointer
     /// to a header struct {data: i8*, len: i64, cap: i64, elem_size: i64}.
     List(Box<AhaType>),
@@ -33,6 +35,12 @@ pub enum AhaType {
     /// Named enum — carries the enum's declared name so codegen can
     /// look up its variant layout and LLVM tagged union type.
     Enum(String),
+    /// Anonymous tuple: (T1, T2, ...) — laid out like a struct whose
+    /// fields are the element types in order, passed by value.
+    Tuple(Vec<AhaType>),
+    /// Heap-allocated value: Box<T> — handle is an i64 pointer to a T.
+    /// Lets an enum payload refer to its own enum (`Add(Box<Expr>, ...)`).
+    Box(Box<AhaType>),
     /// Function type with parameter types and return type
     Function {
         params: Vec<AhaType>,
@@ -118,6 +126,11 @@ impl AhaType {
             "map" => Some(AhaType::Map(Box::new(AhaType::Int), Box::new(AhaType::Int))),
             "list" => Some(AhaType::List(Box::new(AhaType::Int))),
             _ => {
+                // int<m/s> — units of measure are checked by `units` and
+                // erased here, so a quantity is a plain i64 at runtime.
+                if let Some(unit) = hint.strip_prefix("int<").and_then(|s| s.strip_suffix('>')) {
+                    return Unit::parse(unit).map(|_| AhaType::Int);
+                }
                 // List<T> — parse the inner type.
                 if let Some(inner) = hint.strip_prefix("List<").and_then(|s| s.strip_suffix('>')) {
                     let inner_type = match inner {
@@ -131,7 +144,8 @@ impl AhaType {
                 }
                 // Map<K,V> — parse two comma-separated inner types.
                 if let Some(inner) = hint.strip_prefix("Map<").and_then(|s| s.strip_suffix('>')) {
-                    let (key, value) = inner.split_once(',')?;
+                    let parts = split_type_list(inner);
+                    let [key, value] = parts.as_slice() else { return None };
                     let key_type = match key.trim() {
                         "int" | "i64" => AhaType::Int,
                         "bool" => AhaType::Bool,
@@ -146,28 +160,26 @@ impl AhaType {
                     };
                     return Some(AhaType::Map(Box::new(key_type), Box::new(value_type)));
                 }
+                // Box<T> — the inner type must be known here too.
+                if let Some(inner) = hint.strip_prefix("Box<").and_then(|s| s.strip_suffix('>')) {
+                    return Some(AhaType::Box(Box::new(Self::from_hint(inner)?)));
+                }
+                // (T1, T2, ...) — tuple of element types.
+                if let Some(inner) = hint.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+                    let elems = split_type_list(inner)
+                        .into_iter()
+                        .map(Self::from_hint)
+                        .collect::<Option<Vec<_>>>()?;
+                    return Some(AhaType::Tuple(elems));
+                }
+                // [T; N] — fixed-size array.
+                if let Some((elem, len)) = split_array_hint(hint) {
+                    return Some(AhaType::Array(Box::new(Self::from_hint(elem)?), len));
+                }
                 None
             }
         }
     }
-
-    /// Merge a newly inferred type into an existing one when multiple
-    /// call sites disagree. Used by scan_expr_for_calls to narrow a
-    /// param's type: String and named structs are kept when observed,
-    /// Int stays as the default. Two different struct names never meet
-    /// (each call site's arg type wins for its position).
-    pub fn unify_with(&self, other: &AhaType) -> AhaType {
-        match (self, other) {
-            (AhaType::Int, t) => t.clone(),
-            // Map<K,V> unifies key/value independently: Int defaults upgrade
-            // to observed types (String, ...), matching List<T> semantics.
-            (AhaType::Map(k1, v1), AhaType::Map(k2, v2)) => AhaType::Map(
-                Box::new(k1.unify_with(k2)),
-                Box::new(v1.unify_with(v2)),
-            ),
-            (_, _) => self.clone(),
-        }
-    }
 }
 
 impl fmt::Display for AhaType {
@@ -177,11 +189,21 @@ impl fmt::Display for AhaType {
             AhaType::Bool => write!(f, "Bool"),
             AhaType::String => write!(f, "String"),
             AhaType::Void => write!(f, "Void"),
-            AhaType::Array(inner) => write!(f, "[{}]", inner),
+            AhaType::Array(inner, len) => write!(f, "[{}; {}]", inner, len),
             AhaType::List(inner) => write!(f, "List<{}>", inner),
             AhaType::Map(key, value) => write!(f, "Map<{}, {}>", key, value),
+            AhaType::Box(inner) => write!(f, "Box<{}>", inner),
             AhaType::Struct(name) => write!(f, "{}", name),
             AhaType::Enum(name) => write!(f, "{}", name),
+            AhaType::Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, e) in elems.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    write!(f, "{}", e)?;
+                }
+                if elems.len() == 1 { write!(f, ",")?; }
+                write!(f, ")")
+            }
             AhaType::Function { params, ret } => {
                 write!(f, "fn(")?;
                 for (i, p) in params.iter().enumerate() {
@@ -194,6 +216,149 @@ impl fmt::Display for AhaType {
     }
 }
 
+/// Split a comma-separated list of type hints at the top level only, so
+/// `int, (int, string), Map<string, int>` yields three entries. Empty
+/// entries (from a trailing comma) are dropped.
+pub fn split_type_list(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(list[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Full name of a generic struct or enum instance: `Pair<Int, String>`.
+pub fn generic_type_name(base: &str, args: &[AhaType]) -> String {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    format!("{}<{}>", base, args.join(", "))
+}
+
+/// Name of a generic function's instance for bound type params: the
+/// types in type-param order, e.g. `pair_Int_String` for `T`, `U`.
+pub fn generic_fn_name(func: &str, bound: &HashMap<String, AhaType>) -> String {
+    let mut keyed: Vec<(&String, &AhaType)> = bound.iter().collect();
+    keyed.sort_by(|a, b| a.0.cmp(b.0));
+    let suffix: String = keyed.iter().map(|(_, t)| format!("_{}", t)).collect();
+    format!("{}{}", func, suffix)
+}
+
+/// `n` followed by `noun`, pluralized by count: `1 argument`,
+/// `2 arguments`.
+pub fn count_of(n: usize, noun: &str) -> String {
+    if n == 1 { format!("1 {}", noun) } else { format!("{} {}s", n, noun) }
+}
+
+/// Split an array hint `[T; N]` into its element hint and length.
+pub fn split_array_hint(hint: &str) -> Option<(&str, usize)> {
+    let inner = hint.strip_prefix('[')?.strip_suffix(']')?;
+    let (elem, len) = inner.rsplit_once(';')?;
+    Some((elem.trim(), len.trim().parse().ok()?))
+}
+
+/// A unit of measure: each base unit's exponent, so `m/s` is
+/// `{m: 1, s: -1}` and `m*m` is `{m: 2}`. The empty unit is dimensionless
+/// (`1`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Unit(BTreeMap<String, i32>);
+
+impl Unit {
+    /// Parse the inside of `int<...>`: base units joined by `*` and `/`,
+    /// with `1` for a dimensionless numerator (`1/s`).
+    pub fn parse(text: &str) -> Option<Unit> {
+        let mut unit = Unit::default();
+        let mut sign = 1;
+        let mut rest = text.trim();
+        loop {
+            let end = rest.find(['*', '/']).unwrap_or(rest.len());
+            let name = rest[..end].trim();
+            if name != "1" {
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    || name.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    return None;
+                }
+                unit = unit.with(name, sign);
+            }
+            if end == rest.len() {
+                return Some(unit);
+            }
+            sign = if rest[end..].starts_with('*') { 1 } else { -1 };
+            rest = &rest[end + 1..];
+        }
+    }
+
+    fn with(mut self, name: &str, power: i32) -> Unit {
+        let exp = self.0.entry(name.to_string()).or_insert(0);
+        *exp += power;
+        if *exp == 0 {
+            self.0.remove(name);
+        }
+        self
+    }
+
+    pub fn mul(&self, other: &Unit) -> Unit {
+        other.0.iter().fold(self.clone(), |u, (name, exp)| u.with(name, *exp))
+    }
+
+    pub fn div(&self, other: &Unit) -> Unit {
+        other.0.iter().fold(self.clone(), |u, (name, exp)| u.with(name, -exp))
+    }
+
+    /// Unit algebra for a binary operator. `None` is a value whose unit is
+    /// not tracked (literals, plain `int`), which combines with anything.
+    /// `+`, `-`, `%` and comparisons need equal units; `*` and `/`
+    /// multiply and divide them.
+    pub fn check_binary_op(op: &str, left: Option<&Unit>, right: Option<&Unit>) -> Result<Option<Unit>, String> {
+        match (op, left, right) {
+            ("+" | "-" | "%" | "==" | "!=" | "<" | ">" | "<=" | ">=", Some(l), Some(r)) if l != r => Err(format!(
+                "Cannot apply operator '{}' to int<{}> and int<{}>: units differ",
+                op, l, r
+            )),
+            ("+" | "-" | "%", l, r) => Ok(l.or(r).cloned()),
+            ("*", Some(l), Some(r)) => Ok(Some(l.mul(r))),
+            ("*", l, r) => Ok(l.or(r).cloned()),
+            ("/", Some(l), Some(r)) => Ok(Some(l.div(r))),
+            ("/", None, Some(r)) => Ok(Some(Unit::default().div(r))),
+            ("/", l, None) => Ok(l.cloned()),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    /// `m*m/s/s`, `1/s`, or `1` when dimensionless.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repeat = |positive: bool| -> Vec<&str> {
+            self.0.iter()
+                .filter(|(_, exp)| (**exp > 0) == positive)
+                .flat_map(|(name, exp)| std::iter::repeat_n(name.as_str(), exp.unsigned_abs() as usize))
+                .collect()
+        };
+        let (num, den) = (repeat(true), repeat(false));
+        if num.is_empty() {
+            write!(f, "1")?;
+        } else {
+            write!(f, "{}", num.join("*"))?;
+        }
+        for name in den {
+            write!(f, "/{}", name)?;
+        }
+        Ok(())
+    }
+}
+
 /// A typed value — combines an LLVM value with its AHA! type information.
 /// This is the core unit passed around during code generation.
 ///
@@ -0,0 +1,478 @@
+// src/units.rs
+//
+// AHA! Units of Measure — checks `int<m>`, `int<m/s>`, ... annotations
+// after type checking. Units flow from annotated lets, parameters, return
+// types, struct fields and globals through `Unit::check_binary_op`:
+// `m / s` is `m/s`, `m * m` is `m*m`, and adding or comparing two
+// different units is an error on the operator's line.
+//
+// Checking is gradual: literals and plain `int` values carry no unit and
+// combine with anything, so `altitude * 2 + 10` stays `int<m>`. A slot
+// annotated plain `int` only takes unitless values; `as int` drops a
+// unit explicitly. Units are
+// erased by `AhaType::from_hint`, so a quantity is an i64 at runtime and
+// codegen never sees them.
+
+use crate::ast;
+use crate::typeck::{TypeError, TypeTable};
+use crate::types::{AhaType, Unit};
+use std::collections::HashMap;
+
+/// Check a whole program. `table` is the type checker's result, used to
+/// find the struct behind a field access.
+pub fn check_program(program: &ast::Program, table: &TypeTable) -> Result<(), Vec<TypeError>> {
+    let mut checker = UnitChecker {
+        table,
+        fns: HashMap::new(),
+        fields: HashMap::new(),
+        scopes: vec![HashMap::new()],
+        returns: Vec::new(),
+        line: 0,
+        errors: Vec::new(),
+    };
+    checker.collect(&program.statements);
+    for stmt in &program.statements {
+        checker.statement(stmt);
+    }
+    if checker.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.errors)
+    }
+}
+
+/// The unit in an `int<...>` hint, if any.
+fn hint_unit(hint: Option<&str>) -> Option<Unit> {
+    hint?.strip_prefix("int<")?.strip_suffix('>').and_then(Unit::parse)
+}
+
+fn show(unit: &Unit) -> String {
+    format!("int<{}>", unit)
+}
+
+/// What an `int` annotation declares about units.
+#[derive(Clone)]
+enum Declared {
+    /// `int<m>`: values of exactly this unit.
+    Unit(Unit),
+    /// Plain `int`: unitless values only.
+    Plain,
+}
+
+impl Declared {
+    fn from_hint(hint: Option<&str>) -> Option<Declared> {
+        match hint? {
+            "int" => Some(Declared::Plain),
+            hint => hint_unit(Some(hint)).map(Declared::Unit),
+        }
+    }
+
+    /// The unit a value of this slot carries.
+    fn unit(&self) -> Option<Unit> {
+        match self {
+            Declared::Unit(unit) => Some(unit.clone()),
+            Declared::Plain => None,
+        }
+    }
+}
+
+/// Units of a function's parameters and result.
+struct FnUnits {
+    params: Vec<Option<Declared>>,
+    ret: Option<Declared>,
+}
+
+struct UnitChecker<'a> {
+    table: &'a TypeTable<'a>,
+    fns: HashMap<String, FnUnits>,
+    /// Struct name → field → declared unit, for `int` fields.
+    fields: HashMap<String, HashMap<String, Declared>>,
+    /// Each binding's declared unit, or the unit of its initial value
+    /// when unannotated.
+    scopes: Vec<HashMap<String, Option<Declared>>>,
+    /// Declared result unit of each function being walked.
+    returns: Vec<(String, Option<Declared>)>,
+    /// Line of the innermost statement or call being walked, for nodes
+    /// that record none (struct literals).
+    line: usize,
+    errors: Vec<TypeError>,
+}
+
+impl UnitChecker<'_> {
+    fn error(&mut self, line: usize, message: String) {
+        self.errors.push(TypeError { line, message });
+    }
+
+    /// Record function signatures, field units and global units, so uses
+    /// may come before declarations.
+    fn collect(&mut self, statements: &[ast::Statement]) {
+        for stmt in statements {
+            match stmt {
+                ast::Statement::Expression(es) => {
+                    if let ast::Expression::Function(func) = &es.expression {
+                        self.declare_fn(func);
+                    }
+                }
+                ast::Statement::Struct(def) => {
+                    let units: HashMap<String, Declared> = def.fields.iter()
+                        .filter_map(|f| Some((f.name.value.clone(), Declared::from_hint(f.type_hint.as_deref())?)))
+                        .collect();
+                    if !units.is_empty() {
+                        self.fields.insert(def.name.value.clone(), units);
+                    }
+                }
+                ast::Statement::Const(def) => {
+                    let declared = Declared::from_hint(def.type_annotation.as_deref());
+                    self.scopes[0].insert(def.name.value.clone(), declared);
+                }
+                ast::Statement::Static(def) => {
+                    let declared = Declared::from_hint(def.type_annotation.as_deref());
+                    self.scopes[0].insert(def.name.value.clone(), declared);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn declare_fn(&mut self, func: &ast::FunctionLiteral) {
+        if let Some(name) = &func.name {
+            self.fns.insert(name.value.clone(), FnUnits {
+                params: func.param_type_hints.iter().map(|h| Declared::from_hint(h.as_deref())).collect(),
+                ret: Declared::from_hint(func.return_type_hint.as_deref()),
+            });
+        }
+    }
+
+    fn declared(&self, name: &str) -> Option<Declared> {
+        self.scopes.iter().rev().find_map(|s| s.get(name)).cloned().flatten()
+    }
+
+    fn lookup(&self, name: &str) -> Option<Unit> {
+        self.declared(name)?.unit()
+    }
+
+    fn bind(&mut self, name: &str, declared: Option<Declared>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), declared);
+        }
+    }
+
+    /// Pattern variables carry no unit, but must shadow outer bindings.
+    fn bind_pattern(&mut self, pattern: &ast::Pattern) {
+        match pattern {
+            ast::Pattern::Binding(name) | ast::Pattern::MutBinding(name) => self.bind(name, None),
+            ast::Pattern::EnumTuple(_, pats) | ast::Pattern::Tuple(pats) | ast::Pattern::Or(pats) => {
+                for p in pats {
+                    self.bind_pattern(p);
+                }
+            }
+            ast::Pattern::Struct(_, fields) => {
+                for (_, p) in fields {
+                    self.bind_pattern(p);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A value of unit `actual` flows into a slot declared as `expected`.
+    fn check_flow(&mut self, line: usize, expected: &Option<Declared>, actual: &Option<Unit>, what: impl FnOnce() -> String) {
+        let message = match (expected, actual) {
+            (Some(Declared::Unit(e)), Some(a)) if e != a => {
+                format!("{} has unit {}, expected {}", what(), show(a), show(e))
+            }
+            (Some(Declared::Plain), Some(a)) if *a != Unit::default() => {
+                format!("{} has unit {}, expected int; use `as int` to drop the unit", what(), show(a))
+            }
+            _ => return,
+        };
+        self.error(line, message);
+    }
+
+    fn statement(&mut self, stmt: &ast::Statement) -> Option<Unit> {
+        match stmt {
+            ast::Statement::Let(let_stmt) => {
+                self.line = let_stmt.line;
+                let value = self.expr(&let_stmt.value);
+                let declared = match let_stmt.type_annotation.as_deref() {
+                    Some(hint) => {
+                        let declared = Declared::from_hint(Some(hint));
+                        self.check_flow(let_stmt.line, &declared, &value, || format!("Value of '{}'", let_stmt.name.value));
+                        declared
+                    }
+                    None => value.map(Declared::Unit),
+                };
+                self.bind(&let_stmt.name.value, declared);
+                None
+            }
+            ast::Statement::LetPattern(let_pat) => {
+                self.expr(&let_pat.value);
+                self.bind_pattern(&let_pat.pattern);
+                None
+            }
+            ast::Statement::Return(ret) => {
+                self.line = ret.line;
+                let value = self.expr(&ret.return_value);
+                if let Some((name, expected)) = self.returns.last().cloned() {
+                    self.check_flow(ret.line, &expected, &value, || format!("Return value of '{}'", name));
+                }
+                None
+            }
+            ast::Statement::Expression(es) => self.expr(&es.expression),
+            ast::Statement::Const(def) => {
+                let value = self.expr(&def.value);
+                let declared = Declared::from_hint(def.type_annotation.as_deref());
+                self.check_flow(def.line, &declared, &value, || format!("Constant '{}'", def.name.value));
+                None
+            }
+            ast::Statement::Static(def) => {
+                let value = self.expr(&def.value);
+                let declared = Declared::from_hint(def.type_annotation.as_deref());
+                self.check_flow(def.line, &declared, &value, || format!("Static '{}'", def.name.value));
+                None
+            }
+            ast::Statement::Struct(_)
+            | ast::Statement::Actor(_)
+            | ast::Statement::Enum(_)
+            | ast::Statement::Import(_)
+            | ast::Statement::TypeAlias(_)
+            | ast::Statement::Newtype(_)
+            | ast::Statement::UseVariants(_) => None,
+        }
+    }
+
+    /// Walk a block in its own scope; returns the unit of its value.
+    fn block(&mut self, block: &ast::BlockStatement) -> Option<Unit> {
+        self.scopes.push(HashMap::new());
+        let mut last = None;
+        for stmt in &block.statements {
+            last = self.statement(stmt);
+        }
+        self.scopes.pop();
+        last
+    }
+
+    /// Branches agree on a unit, or the result is not tracked.
+    fn join(a: Option<Unit>, b: Option<Unit>) -> Option<Unit> {
+        match (a, b) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(u), None) | (None, Some(u)) => Some(u),
+            _ => None,
+        }
+    }
+
+    fn function(&mut self, func: &ast::FunctionLiteral) {
+        self.declare_fn(func);
+        let name = func.name.as_ref().map_or("<anonymous>", |n| n.value.as_str()).to_string();
+        let ret = Declared::from_hint(func.return_type_hint.as_deref());
+        self.scopes.push(HashMap::new());
+        for (param, hint) in func.parameters.iter().zip(&func.param_type_hints) {
+            self.bind(&param.value, Declared::from_hint(hint.as_deref()));
+        }
+        self.returns.push((name.clone(), ret.clone()));
+        let value = self.block(&func.body);
+        self.returns.pop();
+        self.scopes.pop();
+        self.check_flow(func.line, &ret, &value, || format!("Result of '{}'", name));
+    }
+
+    fn expr(&mut self, expr: &ast::Expression) -> Option<Unit> {
+        match expr {
+            ast::Expression::Identifier(id) => self.lookup(&id.value),
+            ast::Expression::Prefix(prefix) => {
+                let unit = self.expr(&prefix.right);
+                if prefix.operator == "-" { unit } else { None }
+            }
+            ast::Expression::Infix(infix) => {
+                let l = self.expr(&infix.left);
+                let r = self.expr(&infix.right);
+                match Unit::check_binary_op(&infix.operator, l.as_ref(), r.as_ref()) {
+                    Ok(unit) => unit,
+                    Err(message) => {
+                        self.error(infix.line, message);
+                        None
+                    }
+                }
+            }
+            ast::Expression::If(if_expr) => {
+                self.expr(&if_expr.condition);
+                let cons = self.block(&if_expr.consequence);
+                let alt = if_expr.alternative.as_ref().and_then(|alt| self.block(alt));
+                Self::join(cons, alt)
+            }
+            ast::Expression::IfLet(if_let) => {
+                self.expr(&if_let.value);
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&if_let.pattern);
+                let cons = self.block(&if_let.consequence);
+                self.scopes.pop();
+                let alt = if_let.alternative.as_ref().and_then(|alt| self.block(alt));
+                Self::join(cons, alt)
+            }
+            ast::Expression::While(while_expr) => {
+                self.expr(&while_expr.condition);
+                self.block(&while_expr.body);
+                None
+            }
+            ast::Expression::WhileLet(while_let) => {
+                self.expr(&while_let.value);
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&while_let.pattern);
+                self.block(&while_let.body);
+                self.scopes.pop();
+                None
+            }
+            ast::Expression::For(for_expr) => {
+                self.expr(&for_expr.iterable);
+                self.scopes.push(HashMap::new());
+                self.bind(&for_expr.variable.value, None);
+                self.block(&for_expr.body);
+                self.scopes.pop();
+                None
+            }
+            ast::Expression::Function(func) => {
+                self.function(func);
+                None
+            }
+            ast::Expression::Call(call) => self.call(call),
+            ast::Expression::StructLiteral(sl) => {
+                for (field, value) in &sl.fields {
+                    let unit = self.expr(value);
+                    let declared = self.fields.get(&sl.name.value).and_then(|f| f.get(&field.value)).cloned();
+                    self.check_flow(self.line, &declared, &unit, || format!("Field '{}' of '{}'", field.value, sl.name.value));
+                }
+                if let Some(base) = &sl.base {
+                    self.expr(base);
+                }
+                None
+            }
+            ast::Expression::FieldAccess(fa) => {
+                self.expr(&fa.object);
+                self.field_unit(&fa.object, &fa.field.value)
+            }
+            ast::Expression::Assignment(assign) => {
+                self.line = assign.line;
+                let value = self.expr(&assign.value);
+                let (what, declared) = match assign.target.as_ref() {
+                    ast::Expression::Identifier(id) => {
+                        (format!("Value assigned to '{}'", id.value), self.declared(&id.value))
+                    }
+                    ast::Expression::FieldAccess(fa) => {
+                        self.expr(&fa.object);
+                        (format!("Value assigned to field '{}'", fa.field.value), self.field_declared(&fa.object, &fa.field.value))
+                    }
+                    other => {
+                        self.expr(other);
+                        return None;
+                    }
+                };
+                self.check_flow(assign.line, &declared, &value, || what);
+                None
+            }
+            ast::Expression::Match(m) => {
+                self.expr(&m.value);
+                let mut result: Option<Option<Unit>> = None;
+                for arm in &m.arms {
+                    self.scopes.push(HashMap::new());
+                    self.bind_pattern(&arm.pattern);
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                    }
+                    let unit = self.expr(&arm.body);
+                    self.scopes.pop();
+                    result = Some(match result {
+                        None => unit,
+                        Some(prev) => Self::join(prev, unit),
+                    });
+                }
+                result.flatten()
+            }
+            ast::Expression::Range(range) => {
+                self.expr(&range.start);
+                self.expr(&range.end);
+                None
+            }
+            ast::Expression::Array(arr) => {
+                for elem in &arr.elements {
+                    self.expr(elem);
+                }
+                None
+            }
+            ast::Expression::Tuple(tuple) => {
+                for elem in &tuple.elements {
+                    self.expr(elem);
+                }
+                None
+            }
+            ast::Expression::Index(idx) => {
+                self.expr(&idx.left);
+                self.expr(&idx.index);
+                None
+            }
+            ast::Expression::Spawn(spawn) => {
+                for (_, value) in &spawn.fields {
+                    self.expr(value);
+                }
+                None
+            }
+            // `as int` yields a plain int, whatever unit the value had.
+            ast::Expression::Cast(cast) => {
+                self.expr(&cast.value);
+                None
+            }
+            ast::Expression::Integer(_)
+            | ast::Expression::Boolean(_)
+            | ast::Expression::String(_)
+            | ast::Expression::ModuleAccess(_)
+            | ast::Expression::Break
+            | ast::Expression::Continue => None,
+        }
+    }
+
+    fn call(&mut self, call: &ast::CallExpression) -> Option<Unit> {
+        self.line = call.line;
+        let args: Vec<Option<Unit>> = call.arguments.iter().map(|a| self.expr(a)).collect();
+        let ast::Expression::Identifier(callee) = call.function.as_ref() else {
+            self.expr(&call.function);
+            return None;
+        };
+        match callee.value.as_str() {
+            "abs" => return args.into_iter().next().flatten(),
+            // min/max pick one of their arguments, so both need one unit.
+            "min" | "max" if args.len() == 2 => {
+                return match Unit::check_binary_op("+", args[0].as_ref(), args[1].as_ref()) {
+                    Ok(unit) => unit,
+                    Err(message) => {
+                        self.error(call.line, format!("{}: {}", callee.value, message));
+                        None
+                    }
+                };
+            }
+            _ => {}
+        }
+        // A local binding shadows the function of the same name.
+        if self.scopes.iter().any(|s| s.contains_key(&callee.value)) {
+            return None;
+        }
+        let sig = self.fns.get(&callee.value)?;
+        let params = sig.params.clone();
+        let ret = sig.ret.as_ref().and_then(Declared::unit);
+        for (i, (expected, actual)) in params.iter().zip(&args).enumerate() {
+            self.check_flow(call.line, expected, actual, || format!("Argument {} of '{}'", i + 1, callee.value));
+        }
+        ret
+    }
+
+    /// Declared unit of `object.field`, when the checker typed `object`
+    /// as a struct with an `int` field of that name.
+    fn field_declared(&self, object: &ast::Expression, field: &str) -> Option<Declared> {
+        match self.table.expr_type(object)? {
+            AhaType::Struct(name) => self.fields.get(name)?.get(field).cloned(),
+            _ => None,
+        }
+    }
+
+    fn field_unit(&self, object: &ast::Expression, field: &str) -> Option<Unit> {
+        self.field_declared(object, field)?.unit()
+    }
+}
@@ -0,0 +1,169 @@
+// tests/arrays.rs
+//
+// BACKEND TESTS — fixed-size arrays. `[T; N]` is a by-value `[N x T]` of
+// any element type; `len(arr)` is its static length, `arr[i] = v` writes
+// through fields and nested arrays, a constant index past the end is a
+// compile error, and a runtime one exits with status 101 and the index,
+// length and source line on stderr.
+
+mod common;
+
+use common::{emit_ir, expect_error, run, run_binary};
+
+// --- Types and length ---
+
+#[test]
+fn annotated_array_and_len() {
+    let result = run(r#"
+        fn main() -> int {
+            let a: [int; 4] = [5, 6, 7, 8]
+            a[0] + a[3] + len(a) * 100
+        }
+    "#);
+    assert_eq!(result, 413);
+}
+
+#[test]
+fn string_and_struct_elements() {
+    let result = run(r#"
+        fn main() -> int {
+            let names = ["ab", "cde", "f"]
+            len(names[0]) * 100 + len(names[1]) * 10 + len(names[2])
+        }
+    "#);
+    assert_eq!(result, 231);
+
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn main() -> int {
+            let ps = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+            ps[0].y * 10 + ps[1].x
+        }
+    "#);
+    assert_eq!(result, 23);
+}
+
+#[test]
+fn array_param_and_return() {
+    let result = run(r#"
+        fn rev(a: [int; 3]) -> [int; 3] { [a[2], a[1], a[0]] }
+        fn main() -> int {
+            let r = rev([1, 2, 3])
+            r[0] * 100 + r[1] * 10 + r[2]
+        }
+    "#);
+    assert_eq!(result, 321);
+}
+
+// --- Assignment ---
+
+#[test]
+fn index_assignment_in_loop() {
+    let result = run(r#"
+        fn main() -> int {
+            let mut squares = [0, 0, 0, 0, 0]
+            let mut i = 0
+            while i < len(squares) {
+                squares[i] = i * i
+                i = i + 1
+            }
+            squares[2] + squares[4]
+        }
+    "#);
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn nested_array_assignment() {
+    let result = run(r#"
+        fn main() -> int {
+            let mut grid: [[int; 3]; 2] = [[1, 2, 3], [4, 5, 6]]
+            grid[1][2] = 60
+            grid[1][2] + grid[0][1] + len(grid) * 1000 + len(grid[0]) * 100
+        }
+    "#);
+    assert_eq!(result, 2362);
+}
+
+#[test]
+fn element_field_and_struct_field_assignment() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Board { cells: [int; 4] }
+        fn main() -> int {
+            let mut ps = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+            ps[1].y = 40
+            let mut b = Board { cells: [0, 0, 0, 0] }
+            b.cells[2] = 7
+            ps[1].y + b.cells[2] + len(b.cells)
+        }
+    "#);
+    assert_eq!(result, 51);
+}
+
+#[test]
+fn arrays_are_copied_by_value() {
+    let result = run(r#"
+        fn main() -> int {
+            let a = [1, 2, 3]
+            let mut b = a
+            b[0] = 100
+            a[0] + b[0]
+        }
+    "#);
+    assert_eq!(result, 101);
+}
+
+#[test]
+fn local_arrays_are_indexed_in_place() {
+    let source = r#"
+        fn first(xs: [int; 2]) -> int { xs[0] }
+        fn make() -> [int; 3] { [7, 8, 9] }
+        fn main() -> int {
+            let mut a = [1, 2, 3]
+            a[1] = 20
+            let mut total = 0
+            for x in a { total = total + x }
+            total + a[1] + first([4, 5]) + make()[2]
+        }
+    "#;
+    assert_eq!(run(source), 57);
+    // Only the call result `make()` is spilled to a slot; locals and
+    // parameters are addressed through their own allocas.
+    let ir = emit_ir(source);
+    assert_eq!(ir.matches("%arr_slot = alloca").count(), 1, "IR:\n{}", ir);
+}
+
+// --- Bounds checks ---
+
+#[test]
+fn runtime_out_of_bounds_exits_with_location() {
+    let run = run_binary("arrays_oob", None, "fn get(a: [int; 3], i: int) -> int {\n    a[i]\n}\nget([1, 2, 3], 7)\n", &[]);
+    assert_eq!(run.code, Some(101));
+    assert!(
+        run.stderr.contains("panicked at line 2: index out of bounds: the index is 7 but the length is 3"),
+        "got:\n{}", run.stderr
+    );
+
+    // A negative index is out of bounds too.
+    let run = run_binary("arrays_neg", None, "let a = [1, 2, 3]\nlet i = 0 - 1\na[i]\n", &[]);
+    assert_eq!(run.code, Some(101));
+    assert!(run.stderr.contains("panicked at line 3: index out of bounds: the index is -1 but the length is 3"), "got:\n{}", run.stderr);
+}
+
+// --- Errors ---
+
+#[test]
+fn constant_index_out_of_bounds_is_error() {
+    let err = expect_error("let a = [1, 2, 3]\na[5]");
+    assert_eq!(err, "line 2: Index 5 is out of bounds for [Int; 3]");
+}
+
+#[test]
+fn wrong_element_type_is_error() {
+    let err = expect_error("let a = [1, \"x\"]\n0");
+    assert_eq!(err, "Array element 1 is String, but the array holds Int");
+    let err = expect_error("let mut a = [1, 2]\na[0] = \"s\"\n0");
+    assert_eq!(err, "Array element expects Int, got String");
+}
+
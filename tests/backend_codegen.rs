@@ -224,8 +224,8 @@ fn test_jit_boolean_result_flows_into_arithmetic() {
 #[test]
 fn test_jit_while_accumulator_codegen() {
     let src = r#"
-        let sum = 0;
-        let i = 0;
+        let mut sum = 0;
+        let mut i = 0;
         while i < 100 {
             sum = sum + i;
             i = i + 1;
@@ -238,7 +238,7 @@ fn test_jit_while_accumulator_codegen() {
 #[test]
 fn test_jit_for_range_codegen() {
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..11 {
             total = total + i;
         }
@@ -0,0 +1,189 @@
+// tests/bench.rs
+//
+// BACKEND TESTS — `aha bench`. `bench fn name() { ... }` declares a
+// benchmark. The program is JIT-compiled once; `BenchRunner::run` calls a
+// benchmark in a loop of a given length. The harness warms up, sizes the
+// samples adaptively and reports per-iteration mean, median and standard
+// deviation, as text or JSON.
+
+mod common;
+
+use aha_lang::bench::{format_ns, run_benches, BenchConfig, BenchReport, BenchStats};
+use std::time::Duration;
+use common::{parse_only, run_binary, with_codegen, write_files};
+
+/// A configuration that keeps the tests fast.
+fn quick() -> BenchConfig {
+    BenchConfig { warm_up: Duration::from_millis(5), measurement: Duration::from_millis(20), samples: 5, overflow_checks: false }
+}
+
+/// Helper: run the benchmarks of a module graph with `quick()`.
+fn run_graph(dir_name: &str, files: &[(&str, &str)], filters: &[&str]) -> BenchReport {
+    let root = write_files(&format!("bench_{}", dir_name), files);
+    let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+    run_benches(root.to_str().unwrap(), &filters, &quick(), |_| {}).expect("compile failed")
+}
+
+const SUM: &str = "fn sum_to(n: int) -> int {\n    let mut total = 0\n    let mut i = 0\n    while i < n {\n        total = total + i\n        i = i + 1\n    }\n    total\n}\n";
+
+// --- Declarations ---
+
+#[test]
+fn bench_functions_are_listed_in_order() {
+    let source = format!("{}bench fn small() {{ sum_to(10) }}\ntest fn t() {{ assert(1) }}\nbench fn large() {{ sum_to(1000) }}\nsum_to(3)", SUM);
+    with_codegen(&source, true, |codegen| {
+        assert_eq!(codegen.bench_functions(), ["small", "large"]);
+        assert_eq!(codegen.test_functions(), ["t"]);
+        assert_eq!(codegen.run_jit(), Ok(3));
+    });
+}
+
+#[test]
+fn bench_fn_with_parameters_is_parse_error() {
+    assert_eq!(
+        parse_only("bench fn b(n: int) { n }"),
+        vec!["line 1: bench fn 'b' must be named and take no parameters or type parameters".to_string()]
+    );
+}
+
+#[test]
+fn bench_is_an_ordinary_name_elsewhere() {
+    with_codegen("fn bench(n: int) -> int { n * 2 }\nlet bench_x = bench(21)\nbench_x", true, |codegen| {
+        assert!(codegen.bench_functions().is_empty());
+        assert_eq!(codegen.run_jit(), Ok(42));
+    });
+}
+
+// --- BenchRunner ---
+
+#[test]
+fn runner_calls_the_benchmark_the_given_number_of_times() {
+    let source = "static mut COUNT: int = 0\nbench fn count() {\n    COUNT = COUNT + 1\n    if COUNT > 7 {\n        panic(\"ran \" + int_to_string(COUNT) + \" times\")\n    }\n}\n0";
+    with_codegen(source, true, |codegen| {
+        let runner = codegen.bench_runner().expect("JIT failed");
+        assert!(runner.run("count", 5).is_ok());
+        assert!(runner.run("count", 2).is_ok());
+        assert!(runner.run("count", 0).is_ok());
+        assert_eq!(runner.run("count", 1), Err("panicked at line 5: ran 8 times".to_string()));
+        assert_eq!(runner.run("missing", 1), Err("No bench function named 'missing'".to_string()));
+    });
+}
+
+#[test]
+fn longer_benchmarks_take_longer() {
+    let source = format!("{}bench fn small() {{ sum_to(10) }}\nbench fn large() {{ sum_to(100000) }}\n0", SUM);
+    with_codegen(&source, true, |codegen| {
+        let runner = codegen.bench_runner().expect("JIT failed");
+        let small = runner.run("small", 20).unwrap();
+        let large = runner.run("large", 20).unwrap();
+        assert!(large > small * 10, "small {:?}, large {:?}", small, large);
+    });
+}
+
+// --- Statistics ---
+
+#[test]
+fn stats_mean_median_stddev() {
+    let stats = BenchStats::from_samples(100, &[4.0, 2.0, 8.0, 6.0]);
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.iterations, 100);
+    assert_eq!(stats.mean_ns, 5.0);
+    assert_eq!(stats.median_ns, 5.0);
+    assert_eq!((stats.min_ns, stats.max_ns), (2.0, 8.0));
+    assert!((stats.stddev_ns - (20.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+    let single = BenchStats::from_samples(1, &[3.0]);
+    assert_eq!((single.median_ns, single.stddev_ns), (3.0, 0.0));
+}
+
+#[test]
+fn durations_are_formatted_with_units() {
+    assert_eq!(format_ns(812.0), "812.0 ns");
+    assert_eq!(format_ns(1234.0), "1.234 µs");
+    assert_eq!(format_ns(56_700_000.0), "56.700 ms");
+    assert_eq!(format_ns(2.5e9), "2.500 s");
+}
+
+// --- Harness ---
+
+#[test]
+fn harness_measures_every_module() {
+    let report = run_graph("graph", &[
+        ("main.aha", &format!("use \"util\"\n{}bench fn sums() {{ sum_to(one() * 100) }}\n0\n", SUM)),
+        ("util.aha", "pub fn one() -> int { 1 }\nfn hidden() -> int { 2 }\nbench fn private_call() { hidden() }\n"),
+    ], &[]);
+    let names: Vec<&str> = report.outcomes.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["main::sums", "util::private_call"]);
+    for outcome in &report.outcomes {
+        let stats = outcome.result.as_ref().unwrap();
+        assert_eq!(stats.samples, 5);
+        assert!(stats.iterations >= 1);
+        assert!(stats.min_ns <= stats.median_ns && stats.median_ns <= stats.max_ns);
+        assert!(stats.mean_ns > 0.0);
+    }
+}
+
+#[test]
+fn adaptive_iterations_scale_with_cost() {
+    let report = run_graph("adaptive", &[(
+        "main.aha",
+        &format!("{}bench fn cheap() {{ sum_to(1) }}\nbench fn costly() {{ sum_to(20000) }}\n0\n", SUM),
+    )], &[]);
+    let iterations: Vec<u64> = report.outcomes.iter().map(|o| o.result.as_ref().unwrap().iterations).collect();
+    assert!(iterations[0] > iterations[1] * 10, "iterations {:?}", iterations);
+}
+
+#[test]
+fn panics_fail_one_benchmark_and_filters_apply() {
+    let report = run_graph("failures", &[(
+        "main.aha",
+        "bench fn divides() {\n    let z = 0\n    10 / z\n}\nbench fn fine() { 1 }\nbench fn skipped() { 2 }\n",
+    )], &["divides", "fine"]);
+    assert_eq!(report.outcomes.len(), 2);
+    assert_eq!(report.filtered_out, 1);
+    assert_eq!(report.outcomes[0].result, Err("panicked at line 3: attempt to divide by zero".to_string()));
+    assert!(report.outcomes[1].result.is_ok());
+    assert!(!report.success());
+}
+
+#[test]
+fn json_report() {
+    let report = BenchReport {
+        outcomes: vec![
+            aha_lang::bench::BenchOutcome { name: "m::fast".to_string(), result: Ok(BenchStats::from_samples(8, &[1.5, 2.5])) },
+            aha_lang::bench::BenchOutcome { name: "m::bad".to_string(), result: Err("panicked at line 1: \"x\"\nhere".to_string()) },
+        ],
+        filtered_out: 3,
+    };
+    assert_eq!(report.to_json(), concat!(
+        "{\n",
+        "  \"benchmarks\": [\n",
+        "    {\"name\": \"m::fast\", \"samples\": 2, \"iterations\": 8, \"mean_ns\": 2, \"median_ns\": 2, \"stddev_ns\": 0.7071067811865476, \"min_ns\": 1.5, \"max_ns\": 2.5}\n",
+        "  ],\n",
+        "  \"failures\": [\n",
+        "    {\"name\": \"m::bad\", \"message\": \"panicked at line 1: \\\"x\\\"\\nhere\"}\n",
+        "  ],\n",
+        "  \"filtered_out\": 3\n",
+        "}"
+    ));
+}
+
+#[test]
+fn cli_text_and_json_output() {
+    let source = format!("{}bench fn sums() {{ sum_to(50) }}\n", SUM);
+    let run = |extra: &[&str]| {
+        let args = [&["--warm-up-ms", "5", "--measurement-ms", "20", "--samples", "4"], extra].concat();
+        let run = run_binary("bench_cli", Some("bench"), &source, &args);
+        (run.code, run.stdout)
+    };
+    let (code, stdout) = run(&[]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("bench main::sums ... mean "), "got:\n{}", stdout);
+    assert!(stdout.contains("4 samples of "), "got:\n{}", stdout);
+    assert!(stdout.contains("bench result: ok. 1 measured; 0 failed; 0 filtered out"), "got:\n{}", stdout);
+
+    let (code, stdout) = run(&["--json"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.starts_with("{\n  \"benchmarks\": [\n    {\"name\": \"main::sums\", \"samples\": 4, "), "got:\n{}", stdout);
+    assert!(stdout.ends_with("  \"failures\": [],\n  \"filtered_out\": 0\n}\n"), "got:\n{}", stdout);
+}
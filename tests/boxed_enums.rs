@@ -0,0 +1,308 @@
+// tests/boxed_enums.rs
+//
+// BACKEND TESTS — Box<T> and recursive enums. A `Box<T>` payload is an
+// i64 handle to a heap-allocated T, so an enum may refer to itself
+// (`Add(Box<Expr>, Box<Expr>)`). Constructors box their arguments
+// automatically, patterns match through boxes, and an owning local is
+// dropped recursively at scope exit.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Recursive enums ---
+
+#[test]
+fn boxed_expr_tree_eval() {
+    let result = run(r#"
+        enum Expr { Num(int), Add(Box<Expr>, Box<Expr>), Mul(Box<Expr>, Box<Expr>) }
+        fn eval(e: Expr) -> int {
+            match e {
+                Num(n) => n,
+                Add(a, b) => eval(a) + eval(b),
+                Mul(a, b) => eval(a) * eval(b),
+            }
+        }
+        fn main() -> int {
+            let e = Add(Num(2), Mul(Num(4), Num(10)))
+            eval(e)
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn boxed_explicit_box_call() {
+    let result = run(r#"
+        enum Expr { Num(int), Neg(Box<Expr>) }
+        fn eval(e: Expr) -> int {
+            match e {
+                Num(n) => n,
+                Neg(inner) => 0 - eval(inner),
+            }
+        }
+        fn main() -> int {
+            let inner = box(Num(7))
+            eval(Neg(inner))
+        }
+    "#);
+    assert_eq!(result, -7);
+}
+
+#[test]
+fn boxed_nested_pattern_through_box() {
+    let result = run(r#"
+        enum Expr { Num(int), Add(Box<Expr>, Box<Expr>) }
+        fn simplify(e: Expr) -> int {
+            match e {
+                Add(Num(0), b) => 100,
+                Add(Num(a), Num(b)) => a + b,
+                Add(_, _) => -1,
+                Num(n) => n,
+            }
+        }
+        fn main() -> int {
+            simplify(Add(Num(0), Num(9))) + simplify(Add(Num(3), Num(4))) + simplify(Num(5))
+        }
+    "#);
+    assert_eq!(result, 112);
+}
+
+#[test]
+fn boxed_guarded_test_skips_other_variant() {
+    // `Add(Num(a), _)` must not look inside the box of a `Num` value.
+    let result = run(r#"
+        enum Expr { Num(int), Add(Box<Expr>, Box<Expr>) }
+        fn left(e: Expr) -> int {
+            match e {
+                Add(Num(a), _) => a,
+                _ => 0,
+            }
+        }
+        fn main() -> int {
+            left(Num(3)) + left(Add(Num(5), Num(1)))
+        }
+    "#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn boxed_linked_list_built_by_recursion() {
+    let result = run(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn range(n: int) -> List {
+            if n == 0 { Nil() } else { Cons(n, range(n - 1)) }
+        }
+        fn sum(l: List) -> int {
+            match l {
+                Nil => 0,
+                Cons(head, tail) => head + sum(tail),
+            }
+        }
+        fn main() -> int {
+            let l = range(50)
+            sum(l) + sum(Cons(1, Cons(2, Nil())))
+        }
+    "#);
+    assert_eq!(result, 1278);
+}
+
+#[test]
+fn boxed_generic_tree() {
+    let result = run(r#"
+        enum Tree<T> { Leaf, Node(Box<Tree<T>>, T, Box<Tree<T>>) }
+        fn total(t: Tree<int>) -> int {
+            match t {
+                Leaf => 0,
+                Node(l, v, r) => total(l) + v + total(r),
+            }
+        }
+        fn main() -> int {
+            let t: Tree<int> = Node(Node(Leaf(), 1, Leaf()), 2, Node(Leaf(), 3, Leaf()))
+            total(t)
+        }
+    "#);
+    assert_eq!(result, 6);
+}
+
+#[test]
+fn boxed_scalar_matched_through() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn main() -> int {
+            let b = box(5)
+            let o = Some(b)
+            match o {
+                Some(n) => n + 1,
+                None => 0,
+            }
+        }
+    "#);
+    assert_eq!(result, 6);
+}
+
+// --- Ownership ---
+
+#[test]
+fn boxed_repeated_build_and_drop() {
+    // Each call builds and drops a whole tree; a double free or a leak of
+    // the handle would crash or exhaust the loop.
+    let result = run(r#"
+        enum Expr { Num(int), Add(Box<Expr>, Box<Expr>) }
+        fn eval(e: Expr) -> int {
+            match e {
+                Num(n) => n,
+                Add(a, b) => eval(a) + eval(b),
+            }
+        }
+        fn once(i: int) -> int {
+            let e = Add(Num(i), Add(Num(1), Num(1)))
+            eval(e)
+        }
+        fn main() -> int {
+            let mut total = 0
+            let mut i = 0
+            while i < 1000 {
+                total = total + once(i)
+                i = i + 1
+            }
+            total
+        }
+    "#);
+    assert_eq!(result, 501500);
+}
+
+#[test]
+fn boxed_moved_local_dropped_once() {
+    let result = run(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn head(l: List) -> int {
+            match l {
+                Nil => 0,
+                Cons(h, _) => h,
+            }
+        }
+        fn build() -> int {
+            let tail = Cons(2, Nil())
+            let l = Cons(1, tail)
+            head(l)
+        }
+        fn main() -> int {
+            build() + build()
+        }
+    "#);
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn boxed_local_from_binding_is_borrowed() {
+    // `rest` shares its boxes with `l`; only `l` drops them.
+    let result = run(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn second(l: List) -> int {
+            if let Cons(_, tail) = l {
+                let rest = tail
+                match rest {
+                    Nil => 0,
+                    Cons(h, _) => h,
+                }
+            } else {
+                0
+            }
+        }
+        fn build() -> int {
+            let l = Cons(1, Cons(2, Nil()))
+            let wrapped = Cons(0, l)
+            second(wrapped)
+        }
+        fn main() -> int {
+            build() + build() + build()
+        }
+    "#);
+    assert_eq!(result, 3);
+}
+
+// --- Errors ---
+
+#[test]
+fn boxed_unboxed_recursion_rejected() {
+    let err = expect_error(r#"
+        enum List { Nil, Cons(int, List) }
+        fn main() -> int { 0 }
+    "#);
+    assert!(err.contains("infinite size"), "got: {}", err);
+}
+
+#[test]
+fn boxed_box_arity_error() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let b = box(1, 2)
+            0
+        }
+    "#);
+    assert!(err.contains("box"), "got: {}", err);
+}
+
+#[test]
+fn boxed_use_after_move_rejected() {
+    // Both new owners would drop the same boxes.
+    let err = expect_error(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn main() -> int {
+            let a = Cons(1, Nil())
+            let b = a
+            let c = a
+            0
+        }
+    "#);
+    assert!(err.contains("line 6: Use of moved value 'a' (value moved at line 5)"), "got: {}", err);
+
+    let err = expect_error(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn main() -> int {
+            let a = Cons(1, Nil())
+            let b = Cons(2, a)
+            let c = Cons(3, a)
+            0
+        }
+    "#);
+    assert!(err.contains("line 6: Use of moved value 'a' (value moved at line 5)"), "got: {}", err);
+}
+
+#[test]
+fn boxed_move_inside_loop_rejected() {
+    let err = expect_error(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn main() -> int {
+            let a = Cons(1, Nil())
+            let mut i = 0
+            while i < 2 {
+                let b = Cons(i, a)
+                i = i + 1
+            }
+            0
+        }
+    "#);
+    assert!(err.contains("line 7: Cannot move 'a' inside a loop"), "got: {}", err);
+}
+
+#[test]
+fn boxed_reassigned_local_is_usable_again() {
+    let result = run(r#"
+        enum List { Nil, Cons(int, Box<List>) }
+        fn sum(l: List) -> int {
+            match l {
+                Nil => 0,
+                Cons(h, t) => h + sum(t),
+            }
+        }
+        fn main() -> int {
+            let mut a = Cons(1, Nil())
+            let b = Cons(2, a)
+            a = Cons(10, Nil())
+            sum(a) + sum(b)
+        }
+    "#);
+    assert_eq!(result, 13);
+}
@@ -0,0 +1,143 @@
+// tests/common/mod.rs
+//
+// Helpers shared by the backend test files: parse AHA! source, compile
+// and JIT-execute it, collect the compile error, emit LLVM IR, build a
+// module graph on disk and run the `aha-lang` binary. Each test file
+// pulls in what it needs with `mod common;`.
+
+#![allow(dead_code)]
+
+use aha_lang::ast::Program;
+use aha_lang::codegen::CodeGenerator;
+use aha_lang::compiler::Compiler;
+use aha_lang::lexer::Lexer;
+use aha_lang::parser::Parser;
+use inkwell::context::Context;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Parse, panicking on parser errors.
+pub fn parse(source: &str) -> Program {
+    let lexer = Lexer::new(source.to_string());
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        panic!("Parser errors: {:?}", parser.errors);
+    }
+    program
+}
+
+/// Parse only, returning the parser errors.
+pub fn parse_only(source: &str) -> Vec<String> {
+    let lexer = Lexer::new(source.to_string());
+    let mut parser = Parser::new(lexer);
+    let _ = parser.parse_program();
+    parser.errors
+}
+
+/// Compile source with the given overflow mode and call `f` with its
+/// code generator.
+pub fn with_codegen<R>(source: &str, overflow_checks: bool, f: impl FnOnce(&CodeGenerator) -> R) -> R {
+    let program = parse(source);
+    let context = Context::create();
+    let mut codegen = CodeGenerator::new(&context);
+    codegen.set_overflow_checks(overflow_checks);
+    codegen.compile(&program).expect("Codegen failed");
+    f(&codegen)
+}
+
+/// Compile and JIT-execute, returning the result.
+pub fn run(source: &str) -> i64 {
+    try_run(source).expect("JIT execution failed")
+}
+
+/// Compile and JIT-execute, returning the result or the panic message.
+pub fn try_run(source: &str) -> Result<i64, String> {
+    run_with(source, true)
+}
+
+/// Like `try_run`, with overflow checks on or off.
+pub fn run_with(source: &str, overflow_checks: bool) -> Result<i64, String> {
+    with_codegen(source, overflow_checks, |codegen| codegen.run_jit().map_err(|e| e.to_string()))
+}
+
+/// Compile and expect an error: the parser errors if parsing fails,
+/// otherwise the codegen error.
+pub fn expect_error(source: &str) -> String {
+    let lexer = Lexer::new(source.to_string());
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return format!("{:?}", parser.errors);
+    }
+    let context = Context::create();
+    let mut codegen = CodeGenerator::new(&context);
+    match codegen.compile(&program) {
+        Ok(()) => panic!("Expected compile error, but compilation succeeded"),
+        Err(e) => e,
+    }
+}
+
+/// Compile source and return the emitted LLVM IR as text.
+pub fn emit_ir(source: &str) -> String {
+    emit_ir_with(source, true)
+}
+
+/// Like `emit_ir`, with overflow checks on or off.
+pub fn emit_ir_with(source: &str, overflow_checks: bool) -> String {
+    with_codegen(source, overflow_checks, |codegen| codegen.get_llvm_ir())
+}
+
+/// Write `files` (name, source) into a fresh temp directory named after
+/// `dir_name` and return the path of the first one.
+pub fn write_files(dir_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aha_{}_{}", dir_name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    for (name, source) in files {
+        std::fs::write(dir.join(name), source).expect("write source");
+    }
+    dir.join(files[0].0)
+}
+
+/// Write a module graph whose first file is the root, compile the root
+/// with its imports and JIT-execute it.
+pub fn run_files(dir_name: &str, files: &[(&str, &str)]) -> i64 {
+    let root = write_files(dir_name, files);
+    let root = root.to_string_lossy().to_string();
+    let compiler = Compiler::new(vec![Compiler::parent_dir(&root)]);
+    let program = compiler.compile(&root);
+    let _ = std::fs::remove_dir_all(Compiler::parent_dir(&root));
+    let program = program.unwrap_or_else(|errors| panic!("Compilation failed: {:?}", errors));
+
+    let context = Context::create();
+    let mut codegen = CodeGenerator::new(&context);
+    codegen.compile(&program).expect("Codegen failed");
+    codegen.run_jit().expect("JIT execution failed")
+}
+
+/// What the `aha-lang` binary did: its exit code and output.
+pub struct BinaryRun {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run the compiler binary on `source`, saved as `main.aha`, with extra
+/// `args`: `aha-lang <subcommand> main.aha` when a subcommand (`test`,
+/// `bench`) is given, `aha-lang --file main.aha` otherwise.
+pub fn run_binary(name: &str, subcommand: Option<&str>, source: &str, args: &[&str]) -> BinaryRun {
+    let path = write_files(name, &[("main.aha", source)]);
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aha-lang"));
+    match subcommand {
+        Some(subcommand) => command.arg(subcommand),
+        None => command.arg("--file"),
+    };
+    let output = command.arg(&path).args(args).output().expect("run aha-lang");
+    let _ = std::fs::remove_dir_all(Compiler::parent_dir(&path.to_string_lossy()));
+    BinaryRun {
+        code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    }
+}
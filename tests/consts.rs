@@ -0,0 +1,149 @@
+// tests/consts.rs
+//
+// BACKEND TESTS — module-level `const` items. Initializers are evaluated
+// at compile time (arithmetic, string concatenation, other constants,
+// calls to pure functions) and inlined as LLVM constants in every
+// function; `pub const` is exported through `use`.
+
+mod common;
+
+use common::{emit_ir, expect_error, run, run_files};
+
+// --- Values ---
+
+#[test]
+fn const_int_arithmetic_in_any_order() {
+    assert_eq!(run("const MAX: int = 64 * 1024;\nMAX"), 65536);
+    let result = run(r#"
+        const TOTAL = PART * 3
+        const PART = 7
+        TOTAL
+    "#);
+    assert_eq!(result, 21);
+}
+
+#[test]
+fn const_used_in_functions() {
+    let result = run(r#"
+        const LIMIT: int = 10
+        fn clamp(x) -> int { if x > LIMIT { LIMIT } else { x } }
+        fn double_limit() -> int { LIMIT * 2 }
+        clamp(50) + clamp(3) + double_limit()
+    "#);
+    assert_eq!(result, 33);
+}
+
+#[test]
+fn const_string_bool_and_negative() {
+    let result = run(r#"
+        const GREETING: string = "hello" + ", " + "world"
+        const DEBUG: bool = 2 > 3
+        const OFFSET = -5
+        fn shout() -> string { GREETING + "!" }
+        if DEBUG { 0 } else { len(shout()) * 100 + OFFSET }
+    "#);
+    assert_eq!(result, 1295);
+}
+
+#[test]
+fn const_logic_short_circuits() {
+    // The right side would divide by zero or call an impure function;
+    // it is never evaluated.
+    let result = run(r#"
+        const D = 0
+        const SAFE = D != 0 && 10 / D > 1
+        const ANY = D == 0 || print(1)
+        const BOTH = 1 && 2 > 1
+        SAFE * 100 + ANY * 10 + BOTH
+    "#);
+    assert_eq!(result, 11);
+}
+
+#[test]
+fn const_calls_pure_function() {
+    let result = run(r#"
+        fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+        fn sum_to(n) -> int {
+            let mut total = 0
+            for i in 0..n + 1 {
+                total = total + i
+            }
+            total
+        }
+        const F: int = fib(15)
+        const S: int = sum_to(10) + max(1, 2)
+        F + S
+    "#);
+    assert_eq!(result, 610 + 57);
+}
+
+#[test]
+fn const_is_emitted_as_llvm_constant() {
+    let ir = emit_ir(r#"
+        fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+        const F = fib(10)
+        fn get() -> int { F }
+        get()
+    "#);
+    assert!(ir.contains("ret i64 55"), "IR:\n{}", ir);
+}
+
+#[test]
+fn local_shadows_const() {
+    assert_eq!(run("const X = 1\nfn f() -> int {\n  let X = 5\n  X\n}\nf() + X"), 6);
+}
+
+// --- Errors ---
+
+#[test]
+fn const_impure_call_is_error() {
+    let err = expect_error("const X = print(1)\nX");
+    assert_eq!(err, "line 1: Constant 'X': call to 'print' is not allowed in a constant expression");
+}
+
+#[test]
+fn const_cycle_is_error() {
+    let err = expect_error("const A = B + 1\nconst B = A + 1\nA");
+    assert!(err.contains("line 1: Constant 'A': cycle through constant 'A'"), "got: {}", err);
+    assert!(err.contains("line 2: Constant 'B': cycle through constant 'B'"), "got: {}", err);
+}
+
+#[test]
+fn const_evaluation_errors() {
+    let err = expect_error("const A = 1 / 0\nconst B: int = \"x\"\nconst C = A + 1\n0");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 1: Constant 'A': division by zero",
+        "line 2: Constant 'B': declared as Int but the value is String",
+        "line 3: Constant 'C': uses constant 'A', which is invalid",
+    ]);
+}
+
+#[test]
+fn const_of_unsupported_type_is_error() {
+    // The annotation parses like a `let` hint; only the type is rejected.
+    let err = expect_error("const P: (int, int) = (1, 2)\n0");
+    assert_eq!(err, "line 1: Constant 'P': const of type (Int, Int) is not supported");
+}
+
+#[test]
+fn const_assignment_and_local_const_are_errors() {
+    let err = expect_error("const X = 1\nX = 2\nfn f() -> int {\n  const Y = 3\n  Y\n}\nf()");
+    assert!(err.contains("line 2: Cannot assign to constant 'X'"), "got: {}", err);
+    assert!(err.contains("line 4: Constant 'Y' must be declared at module level"), "got: {}", err);
+}
+
+// --- Modules ---
+
+#[test]
+fn pub_const_is_exported() {
+    let config = r#"
+pub const PORT: int = 8000 + 80
+pub fn port_plus(n) -> int { PORT + n }
+"#;
+    let main = r#"
+use "config"
+PORT + port_plus(1)
+"#;
+    assert_eq!(run_files("consts_pub", &[("main.aha", main), ("config.aha", config)]), 8080 + 8081);
+}
@@ -0,0 +1,187 @@
+// tests/destructuring.rs
+//
+// BACKEND TESTS — nested and struct destructuring patterns. Covers
+// recursive enum sub-patterns, qualified variant paths, struct patterns in
+// match arms, `let Point { x, y } = p` and destructured fn parameters.
+
+mod common;
+
+use common::{expect_error, parse_only, run};
+
+// --- Parsing ---
+
+#[test]
+fn destructuring_parse_diagnostic() {
+    let errors = parse_only(r#"
+        struct Point { x: int, y: int }
+        enum Inner { A(int), B }
+        enum Outer { X(Inner), Y }
+        fn area(Point { x, y }: Point) -> int { x * y }
+        fn main() -> int {
+            let Point { x, y: py } = Point { x: 1, y: 2 }
+            let o = X(A(5))
+            match o {
+                Outer::X(Inner::A(v)) => v,
+                X(B) => 1,
+                _ => 0,
+            }
+        }
+    "#);
+    assert!(errors.is_empty(), "Destructuring parse errors: {:?}", errors);
+}
+
+// --- Nested enum patterns ---
+
+#[test]
+fn nested_enum_pattern_binds_inner_payload() {
+    let result = run(r#"
+        enum Inner { A(int), B }
+        enum Outer { X(Inner), Y }
+        fn f(o: Outer) -> int {
+            match o {
+                X(A(v)) => v,
+                X(B) => 100,
+                Y => 200,
+            }
+        }
+        fn main() -> int { f(X(A(7))) + f(X(B())) + f(Y()) }
+    "#);
+    assert_eq!(result, 307);
+}
+
+#[test]
+fn qualified_variant_paths_in_patterns() {
+    let result = run(r#"
+        enum Inner { A(int), B }
+        enum Outer { X(Inner), Y }
+        fn main() -> int {
+            let o = X(A(9))
+            match o {
+                Outer::X(Inner::A(v)) => v,
+                Outer::X(Inner::B) => 1,
+                Outer::Y => 2,
+            }
+        }
+    "#);
+    assert_eq!(result, 9);
+}
+
+#[test]
+fn literal_inside_variant_pattern() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn f(o: Option) -> int {
+            match o {
+                Some(0) => 10,
+                Some(1..=9) => 20,
+                Some(n) => n,
+                None => 0,
+            }
+        }
+        fn main() -> int { f(Some(0)) + f(Some(5)) + f(Some(100)) + f(None()) }
+    "#);
+    assert_eq!(result, 130);
+}
+
+#[test]
+fn wrong_enum_qualifier_rejected() {
+    let err = expect_error(r#"
+        enum Inner { A(int), B }
+        enum Outer { X(Inner), Y }
+        fn main() -> int {
+            let o = Y()
+            match o {
+                Inner::B => 1,
+                _ => 0,
+            }
+        }
+    "#);
+    assert!(err.contains("belongs to enum 'Inner'"), "got: {}", err);
+}
+
+#[test]
+fn variant_pattern_arity_mismatch() {
+    let err = expect_error(r#"
+        enum Pair { Make(int, int) }
+        fn main() -> int {
+            let p = Make(1, 2)
+            match p {
+                Make(a) => a,
+            }
+        }
+    "#);
+    assert!(err.contains("expects 2 fields, got 1"), "got: {}", err);
+}
+
+// --- Struct patterns ---
+
+#[test]
+fn match_struct_pattern_with_literal_field() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn quadrant(p: Point) -> int {
+            match p {
+                Point { x: 0, y: 0 } => 0,
+                Point { x: 0, y } => 100 + y,
+                Point { x, y: 0 } => 200 + x,
+                _ => 9,
+            }
+        }
+        fn main() -> int {
+            quadrant(Point { x: 0, y: 0 }) + quadrant(Point { x: 0, y: 5 }) + quadrant(Point { x: 3, y: 0 })
+        }
+    "#);
+    assert_eq!(result, 308);
+}
+
+#[test]
+fn let_struct_destructuring() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct User { name: string, age: int }
+        fn main() -> int {
+            let p = Point { x: 6, y: 7 }
+            let Point { x, y } = p
+            let Point { x: a, y: b } = Point { x: 50, y: 8 }
+            let User { name, age } = User { name: "ada", age: 36 }
+            x * y * 1000 + (a - b) * 100 + len(name) + age
+        }
+    "#);
+    assert_eq!(result, 46239);
+}
+
+#[test]
+fn fn_param_destructuring() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn area(Point { x, y }: Point) -> int { x * y }
+        fn main() -> int {
+            area(Point { x: 5, y: 8 })
+        }
+    "#);
+    assert_eq!(result, 40);
+}
+
+#[test]
+fn fn_param_destructuring_without_hint() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn sum(Point { x, y }, k: int) -> int { x + y + k }
+        fn main() -> int {
+            sum(Point { x: 1, y: 2 }, 3)
+        }
+    "#);
+    assert_eq!(result, 6);
+}
+
+#[test]
+fn let_refutable_pattern_rejected() {
+    let err = expect_error(r#"
+        struct Point { x: int, y: int }
+        fn main() -> int {
+            let Point { x: 0, y } = Point { x: 1, y: 2 }
+            y
+        }
+    "#);
+    assert!(err.contains("Refutable pattern"), "got: {}", err);
+}
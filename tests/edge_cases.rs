@@ -397,8 +397,8 @@ fn test_parser_error_struct_missing_name() {
 
 #[test]
 fn test_type_array_of_array() {
-    let nested = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int))));
-    assert_eq!(format!("{}", nested), "[[Int]]");
+    let nested = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int), 3)), 2);
+    assert_eq!(format!("{}", nested), "[[Int; 3]; 2]");
 }
 
 #[test]
@@ -436,7 +436,7 @@ fn test_type_check_bool_less_than_error() {
 
 #[test]
 fn test_type_check_array_arithmetic_error() {
-    let arr = AhaType::Array(Box::new(AhaType::Int));
+    let arr = AhaType::Array(Box::new(AhaType::Int), 4);
     let result = arr.check_binary_op("+", &arr);
     assert!(result.is_err());
 }
@@ -468,7 +468,7 @@ fn test_type_is_numeric_excludes_string() {
 
 #[test]
 fn test_type_equality_nested() {
-    let a1 = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int))));
-    let a2 = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int))));
+    let a1 = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int), 2)), 2);
+    let a2 = AhaType::Array(Box::new(AhaType::Array(Box::new(AhaType::Int), 2)), 2);
     assert_eq!(a1, a2);
 }
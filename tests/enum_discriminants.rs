@@ -0,0 +1,141 @@
+// tests/enum_discriminants.rs
+//
+// BACKEND TESTS — C-like enums with explicit discriminants. `Load = 0x10`
+// fixes a variant's tag, later variants count on from it, `op as int`
+// reads the tag back, `Opcode::from_int(x)` checks an int against the
+// discriminants and returns an Option, and `#[repr(u8)]` narrows the tag.
+
+mod common;
+
+use common::{emit_ir, expect_error, run};
+
+// --- Discriminants and `as int` ---
+
+#[test]
+fn explicit_and_implicit_discriminants() {
+    let result = run(r#"
+        enum Opcode { Nop = 0, Load = 0x10, Store = 0x11 }
+        Opcode::Nop as int + Opcode::Load as int * 100 + Opcode::Store as int
+    "#);
+    assert_eq!(result, 1617);
+
+    // Variants without a value count on from the previous one.
+    let result = run(r#"
+        enum Level { Low = 10, Mid, High, Top = 100, Over }
+        Level::Mid as int + Level::High as int + Level::Over as int
+    "#);
+    assert_eq!(result, 124);
+}
+
+#[test]
+fn plain_enum_converts_in_declaration_order() {
+    let result = run(r#"
+        enum Color { Red, Green, Blue }
+        fn main() -> int {
+            let c = Color::Blue
+            c as int * 10 + Green() as int
+        }
+    "#);
+    assert_eq!(result, 21);
+}
+
+#[test]
+fn match_on_explicit_discriminants() {
+    let result = run(r#"
+        enum Status { Ok = 200, NotFound = 404, Teapot = 418 }
+        fn score(s: Status) -> int {
+            match s { Ok => 1, NotFound => 2, Teapot => 3 }
+        }
+        score(Status::Teapot) * 10 + score(Status::Ok)
+    "#);
+    assert_eq!(result, 31);
+}
+
+#[test]
+fn negative_discriminants() {
+    let result = run(r#"
+        enum Delta { Down = -1, Same, Up }
+        fn step(d: Delta) -> int { d as int }
+        step(Delta::Down) * 100 + step(Delta::Same) * 10 + step(Delta::Up)
+    "#);
+    assert_eq!(result, -99);
+}
+
+// --- from_int ---
+
+#[test]
+fn from_int_round_trips() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        enum Opcode { Nop = 0, Load = 0x10, Store = 0x11 }
+        fn decode(x: int) -> int {
+            match Opcode::from_int(x) {
+                Some(op) => op as int,
+                None => -1,
+            }
+        }
+        fn main() -> int {
+            decode(17) * 1000 + decode(0) * 100 + decode(5)
+        }
+    "#);
+    assert_eq!(result, 16999);
+
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        enum Status { Ok = 200, NotFound = 404 }
+        fn main() -> int {
+            let s = Status::from_int(404)
+            if let Some(NotFound) = s { 1 } else { 0 }
+        }
+    "#);
+    assert_eq!(result, 1);
+}
+
+// --- #[repr] ---
+
+#[test]
+fn repr_u8_narrows_the_tag() {
+    let ir = emit_ir(r#"
+        #[repr(u8)]
+        enum Opcode { Nop, Load = 0x10, Halt = 0xFF }
+        fn code(op: Opcode) -> int { op as int }
+        fn main() -> int { code(Opcode::Halt) }
+    "#);
+    assert!(ir.contains("define i64 @code({ i8 } %0)"), "got:\n{}", ir);
+}
+
+#[test]
+fn repr_u8_values_read_back_unsigned() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        #[repr(u8)]
+        enum Opcode { Nop, Load = 0x10, Halt = 0xFF }
+        fn code(x: int) -> int {
+            match Opcode::from_int(x) { Some(op) => op as int, None => 0 }
+        }
+        fn main() -> int { Opcode::Halt as int * 1000 + code(16) + code(256) }
+    "#);
+    assert_eq!(result, 255016);
+}
+
+// --- Errors ---
+
+#[test]
+fn duplicate_or_oversized_discriminant_is_error() {
+    let err = expect_error("enum Opcode { Nop = 1, Load = 0, Store }\n0");
+    assert_eq!(err, "Enum 'Opcode' gives discriminant 1 to both 'Nop' and 'Store'");
+    let err = expect_error("#[repr(u8)]\nenum Opcode { Nop, Wide = 300 }\n0");
+    assert_eq!(err, "Discriminant 300 of 'Opcode::Wide' does not fit #[repr(u8)]");
+}
+
+#[test]
+fn cast_of_payload_enum_is_error() {
+    let err = expect_error("enum Shape { Dot, Circle(int) }\nlet s = Dot()\ns as int");
+    assert_eq!(err, "line 3: Cannot cast Shape to int: 'Shape::Circle' carries a payload");
+}
+
+#[test]
+fn from_int_without_option_is_error() {
+    let err = expect_error("enum Opcode { Nop, Load }\nfn f() -> int { match Opcode::from_int(1) { _ => 0 } }\nf()");
+    assert_eq!(err, "`Opcode::from_int` returns Option<Opcode>: declare `enum Option<T> { Some(T), None }`");
+}
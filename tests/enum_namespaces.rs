@@ -0,0 +1,153 @@
+// tests/enum_namespaces.rs
+//
+// BACKEND TESTS — per-enum variant namespaces. `Color::Red` and
+// `Shape::Circle(3)` construct and match a variant of a named enum. A bare
+// variant name that several enums declare is an error unless one of them
+// is opened with `use Color::*`; patterns resolve bare names against the
+// matched value's enum.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Qualified construction and patterns ---
+
+#[test]
+fn qualified_unit_variants_in_match_and_let() {
+    let result = run(r#"
+        enum Color { Red, Green, Blue }
+        fn score(c: Color) -> int {
+            match c {
+                Color::Red => 1,
+                Color::Green => 2,
+                Color::Blue => 3,
+            }
+        }
+        score(Color::Green) * 10 + score(Color::Blue)
+    "#);
+    assert_eq!(result, 23);
+
+    let result = run(r#"
+        enum Color { Red, Green }
+        fn main() -> int {
+            let c = Color::Red
+            let d: Color = Color::Green
+            match d { Color::Red => 1, Color::Green => 2 } + match c { Color::Red => 10, Color::Green => 20 }
+        }
+    "#);
+    assert_eq!(result, 12);
+}
+
+#[test]
+fn qualified_tuple_variants() {
+    let result = run(r#"
+        enum Shape { Circle(int), Square(int) }
+        fn area(s: Shape) -> int {
+            match s {
+                Shape::Circle(r) => 3 * r * r,
+                Shape::Square(a) => a * a,
+            }
+        }
+        area(Shape::Circle(3)) + area(Shape::Square(2))
+    "#);
+    assert_eq!(result, 31);
+}
+
+#[test]
+fn qualified_generic_enum_variants() {
+    let result = run(r#"
+        enum Maybe<T> { Just(T), Nothing }
+        fn get(m: Maybe<int>) -> int {
+            match m {
+                Maybe::Just(x) => x,
+                Maybe::Nothing => 0,
+            }
+        }
+        get(Maybe::Just(42)) + get(Maybe::Nothing)
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn unique_bare_variant_still_works() {
+    let result = run(r#"
+        enum Shape { Circle(int), Square(int) }
+        fn side(s: Shape) -> int { match s { Circle(r) => r, Square(a) => a } }
+        side(Square(7)) + side(Shape::Circle(1))
+    "#);
+    assert_eq!(result, 8);
+}
+
+// --- Shared variant names ---
+
+#[test]
+fn enums_sharing_variant_names() {
+    let result = run(r#"
+        enum Color { Red, Green, Blue }
+        enum Light { Red, Amber, Green }
+        fn score(c: Color) -> int {
+            match c { Color::Red => 1, Color::Green => 2, Color::Blue => 3 }
+        }
+        fn wait(l: Light) -> int {
+            match l { Light::Red => 30, Light::Amber => 5, Light::Green => 0 }
+        }
+        score(Color::Green) * 100 + wait(Light::Red)
+    "#);
+    assert_eq!(result, 230);
+}
+
+#[test]
+fn bare_pattern_resolves_against_scrutinee() {
+    let result = run(r#"
+        enum Reply { Ok(int), Error }
+        enum Status { Error, Done }
+        fn code(r: Reply) -> int { match r { Ok(n) => n, Error => 99 } }
+        code(Reply::Error) + code(Ok(1))
+    "#);
+    assert_eq!(result, 100);
+}
+
+#[test]
+fn use_opens_enum_for_bare_names() {
+    let result = run(r#"
+        enum Color { Red, Green }
+        enum Light { Red, Amber }
+        use Light::*
+        fn wait(l: Light) -> int { match l { Red => 30, Amber => 5 } }
+        wait(Red()) + wait(Amber())
+    "#);
+    assert_eq!(result, 35);
+}
+
+#[test]
+fn ambiguous_bare_variant_is_error() {
+    let err = expect_error("enum Color { Red, Green }\nenum Light { Red, Amber }\nlet c = Red()\n0");
+    assert_eq!(
+        err,
+        "line 3: Variant 'Red' is ambiguous: it belongs to enums 'Color', 'Light'; write Color::Red or add `use Color::*`"
+    );
+    let err = expect_error("enum Color { Red }\nenum Light { Red }\nuse Color::*\nuse Light::*\nRed()");
+    assert_eq!(
+        err,
+        "line 5: Variant 'Red' is ambiguous: it belongs to enums 'Color', 'Light'; write Color::Red"
+    );
+}
+
+#[test]
+fn unknown_enum_or_variant_is_error() {
+    let err = expect_error("enum Color { Red, Green }\nlet c = Color::Purple\n0");
+    assert_eq!(err, "line 2: Enum 'Color' has no variant 'Purple'");
+    let err = expect_error("use Colr::*\n0");
+    assert_eq!(err, "line 1: Unknown enum 'Colr' in `use Colr::*`");
+}
+
+#[test]
+fn pattern_of_other_enum_is_error() {
+    let err = expect_error(r#"
+enum Color { Red, Green }
+enum Light { Red, Amber }
+fn f(c: Color) -> int { match c { Light::Red => 1, _ => 0 } }
+f(Color::Red)
+"#);
+    assert_eq!(err, "Pattern 'Light::Red' belongs to enum 'Light', but the value is Color");
+}
@@ -0,0 +1,153 @@
+// tests/enum_payloads.rs
+//
+// BACKEND TESTS — enum payloads of any type. Strings, structs, tuples and
+// nested enums are carried with their real layout: an enum is an i64 tag
+// plus a union of i64 words sized to its largest variant, and match,
+// if-let and while-let rebuild each payload field with its declared type.
+
+mod common;
+
+use common::{emit_ir, expect_error, run};
+
+// --- Payload types ---
+
+#[test]
+fn string_payload() {
+    let result = run(r#"
+        enum Event { Quit, Message(string) }
+        fn size(e: Event) -> int { match e { Quit => 0, Message(s) => len(s) } }
+        fn main() -> int { size(Message("hello")) + size(Quit()) }
+    "#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn struct_and_mixed_payload_fields() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        enum Event { Tagged(string, Point, int), Resize(int, int) }
+        fn score(e: Event) -> int {
+            match e {
+                Tagged(label, p, n) => len(label) + p.y + n,
+                Resize(w, h) => w * h,
+            }
+        }
+        fn main() -> int { score(Tagged("ab", Point { x: 1, y: 7 }, 100)) + score(Resize(2, 5)) }
+    "#);
+    assert_eq!(result, 119);
+
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        enum Event { Quit, Moved(Point) }
+        fn code(e: Event) -> int { match e { Quit => 0, Moved(p) => p.x * 10 + p.y } }
+        fn main() -> int { code(Moved(Point { x: 3, y: 4 })) }
+    "#);
+    assert_eq!(result, 34);
+}
+
+#[test]
+fn tuple_payload() {
+    let result = run(r#"
+        enum Pair { Two((int, string)), Zero }
+        fn f(p: Pair) -> int { match p { Two((n, s)) => n + len(s), Zero => 0 } }
+        fn main() -> int { f(Two((40, "ab"))) }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn nested_enum_next_to_string() {
+    let result = run(r#"
+        enum Shape { Circle(int), Rect(int, int) }
+        enum Drawing { Single(Shape, string), Empty }
+        fn area(s: Shape) -> int { match s { Circle(r) => 3 * r * r, Rect(w, h) => w * h } }
+        fn total(d: Drawing) -> int { match d { Single(s, name) => area(s) + len(name), Empty => 0 } }
+        fn main() -> int { total(Single(Rect(2, 3), "box")) + total(Empty()) }
+    "#);
+    assert_eq!(result, 9);
+}
+
+#[test]
+fn boxed_tree_with_string_leaves() {
+    let result = run(r#"
+        enum Expr { Num(int), Name(string), Add(Box<Expr>, Box<Expr>) }
+        fn eval(e: Expr) -> int {
+            match e { Num(n) => n, Name(s) => len(s), Add(a, b) => eval(a) + eval(b) }
+        }
+        fn main() -> int { eval(Add(Name("four"), Add(Num(30), Num(8)))) }
+    "#);
+    assert_eq!(result, 42);
+}
+
+// --- Generic enums ---
+
+#[test]
+fn generic_enum_with_string_and_struct_payloads() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn main() -> int {
+            let a: Option<string> = Some("hey")
+            let n = match a { Some(s) => len(s), None => 0 }
+            if let Some(t) = a { n + len(t) } else { 0 }
+        }
+    "#);
+    assert_eq!(result, 6);
+
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        enum Option<T> { Some(T), None }
+        fn first(p: Option<Point>) -> int { match p { Some(q) => q.x + q.y, None => 0 } }
+        fn main() -> int { first(Some(Point { x: 20, y: 22 })) }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn string_payload_built_in_loop() {
+    let result = run(r#"
+        enum Msg { Text(string), Ping }
+        fn main() -> int {
+            let mut total = 0
+            let mut i = 0
+            while i < 100 {
+                let m = Text("abc")
+                total = total + match m { Text(s) => len(s), Ping => 0 }
+                i = i + 1
+            }
+            total
+        }
+    "#);
+    assert_eq!(result, 300);
+}
+
+// --- Layout ---
+
+#[test]
+fn union_is_sized_to_largest_variant() {
+    let ir = emit_ir(r#"
+        enum Msg { Ping, Text(string), Move(int, int, int) }
+        fn kind(m: Msg) -> int { match m { Ping => 0, Text(s) => 1, Move(a, b, c) => 2 } }
+        fn main() -> int { kind(Ping()) }
+    "#);
+    // Tag plus three words: Move's three ints outsize Text's {ptr, len}.
+    assert!(ir.contains("define i64 @kind({ i64, i64, i64, i64 } %0)"), "got:\n{}", ir);
+}
+
+// --- Errors ---
+
+#[test]
+fn wrong_or_unknown_payload_type_is_error() {
+    let err = expect_error("enum Event { Quit, Message(string) }\nlet e = Message(5)\n0");
+    assert_eq!(
+        err,
+        "line 2: Enum variant 'Event::Message' arg 0 expects String, got Int (signature: Event::Message(string))"
+    );
+    let err = expect_error("enum E { A(Pointt) }\n0");
+    assert_eq!(err, "Unknown type 'Pointt' in payload of 'E::A'");
+}
+
+#[test]
+fn recursion_through_struct_is_error() {
+    let err = expect_error("enum E { A(S), B }\nstruct S { e: E, n: int }\n0");
+    assert_eq!(err, "Recursive struct 'S' has infinite size: wrap the recursive field in Box<S>");
+}
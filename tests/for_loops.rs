@@ -0,0 +1,194 @@
+// tests/for_loops.rs
+//
+// BACKEND TESTS — `for` over collections. Besides `start..end`, a `for`
+// loop walks a `List<T>`, a fixed-size array, the bytes of a `String`
+// (as Int, like `char_at`) and any `Map<K, V>`, whose entries are
+// `(key, value)` tuples that `for (k, v) in m` destructures.
+
+mod common;
+
+use aha_lang::ast::{Expression, Statement};
+use common::{expect_error, parse, run};
+
+// --- Lists ---
+
+#[test]
+fn for_over_int_and_string_lists() {
+    assert_eq!(run(r#"
+        let xs = list_new()
+        list_push(xs, 3)
+        list_push(xs, 4)
+        list_push(xs, 5)
+        let mut weighted = 0
+        let mut place = 1
+        for x in xs {
+            weighted = weighted + x * place
+            place = place * 10
+        }
+        weighted
+    "#), 543);
+
+    assert_eq!(run(r#"
+        let words = list_new_string()
+        list_push(words, "ab")
+        list_push(words, "cde")
+        let mut joined = ""
+        for w in words { joined = joined + w + "," }
+        len(joined)
+    "#), 7);
+}
+
+#[test]
+fn for_over_list_of_tuples_destructures() {
+    assert_eq!(run(r#"
+        fn main() -> int {
+            let pairs: List<(int, string)> = list_new()
+            list_push(pairs, (2, "xy"))
+            list_push(pairs, (3, "abc"))
+            let mut total = 0
+            for (n, s) in pairs { total = total + n * len(s) }
+            total
+        }
+    "#), 13);
+}
+
+#[test]
+fn break_continue_and_empty_list() {
+    assert_eq!(run(r#"
+        let xs = list_new()
+        let mut i = 0
+        while i < 10 {
+            list_push(xs, i)
+            i = i + 1
+        }
+        let mut odd_sum = 0
+        for x in xs {
+            if x % 2 == 0 { continue }
+            if x > 7 { break }
+            odd_sum = odd_sum + x
+        }
+        let empty = list_new()
+        for x in empty { odd_sum = odd_sum + 1000 }
+        odd_sum
+    "#), 16);
+}
+
+#[test]
+fn pushing_during_iteration_visits_new_elements() {
+    assert_eq!(run(r#"
+        let xs = list_new()
+        list_push(xs, 1)
+        let mut visits = 0
+        for x in xs {
+            visits = visits + 1
+            if x < 4 { list_push(xs, x + 1) }
+        }
+        visits
+    "#), 4);
+}
+
+// --- Arrays and strings ---
+
+#[test]
+fn for_over_array() {
+    assert_eq!(run(r#"
+        fn main() -> int {
+            let a: [int; 4] = [1, 2, 3, 4]
+            let mut product = 1
+            for x in a { product = product * x }
+            let names = ["a", "bb", "ccc"]
+            let mut total = 0
+            for n in names { total = total + len(n) }
+            product * 10 + total
+        }
+    "#), 246);
+}
+
+#[test]
+fn for_over_string_bytes() {
+    assert_eq!(run(r#"
+        let mut digits = 0
+        let mut sum = 0
+        for c in "a1b22" {
+            if c >= 48 && c <= 57 {
+                digits = digits + 1
+                sum = sum + c - 48
+            }
+        }
+        digits * 10 + sum
+    "#), 35);
+}
+
+// --- Maps ---
+
+#[test]
+fn for_over_int_map() {
+    assert_eq!(run(r#"
+        let m = map_new()
+        map_set(m, 1, 10)
+        map_set(m, 2, 20)
+        map_set(m, 3, 30)
+        map_remove(m, 2)
+        let mut total = 0
+        for (k, v) in m { total = total + k * v }
+        total
+    "#), 100);
+}
+
+#[test]
+fn for_over_string_keyed_and_string_valued_maps() {
+    assert_eq!(run(r#"
+        let by_name = map_string_key_new()
+        map_string_key_set(by_name, "one", 1)
+        map_string_key_set(by_name, "three", 3)
+        let names = map_string_val_new()
+        map_string_val_set(names, 7, "seven")
+        let both = map_strings_new()
+        map_strings_set(both, "k", "vv")
+        let mut total = 0
+        for (name, n) in by_name { total = total + len(name) * n }
+        for (n, name) in names { total = total + n * 100 + len(name) * 1000 }
+        for (k, v) in both { total = total + len(k) * 10000 + len(v) * 100000 }
+        total
+    "#), 215718);
+}
+
+#[test]
+fn map_entry_is_a_tuple() {
+    assert_eq!(run(r#"
+        let m = map_new()
+        let mut i = 0
+        while i < 20 {
+            map_set(m, i, i * i)
+            i = i + 1
+        }
+        let mut count = 0
+        let mut squares = 0
+        for entry in m {
+            count = count + 1
+            if entry.0 % 5 == 0 { squares = squares + entry.1 }
+        }
+        count * 1000 + squares
+    "#), 20350);
+}
+
+// --- Parsing and errors ---
+
+#[test]
+fn parse_for_pattern_desugars_to_let() {
+    let program = parse("for (k, v) in m { k }");
+    let Statement::Expression(stmt) = &program.statements[0] else { panic!("Expected expression") };
+    let Expression::For(for_expr) = &stmt.expression else { panic!("Expected For expression") };
+    assert_eq!(for_expr.variable.value, "__for_item");
+    assert_eq!(for_expr.line, 1);
+    assert!(matches!(&for_expr.body.statements[0], Statement::LetPattern(_)));
+}
+
+#[test]
+fn non_iterable_or_mismatched_pattern_is_error() {
+    let err = expect_error("let n = 5\nfor x in n { x }\n0");
+    assert_eq!(err, "line 2: Cannot iterate over a value of type Int: for loops take a range, List, array, String or Map");
+    let err = expect_error("let xs = list_new()\nfor (a, b) in xs { a }\n0");
+    assert_eq!(err, "line 2: Pattern ('a', 'b') does not match the elements of List<Int>");
+}
+
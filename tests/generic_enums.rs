@@ -0,0 +1,184 @@
+// tests/generic_enums.rs
+//
+// BACKEND TESTS — generic enums. `enum Option<T> { Some(T), None }` is
+// monomorphized per type-argument list: each instance (`Option<Int>`) gets
+// its own layout, picked from a constructor's argument types or from the
+// expected type (`let` annotation, return type).
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Inference from constructors ---
+
+#[test]
+fn generic_enum_inferred_from_constructor() {
+    let result = run(r#"
+        enum Tree<T> { Leaf, Node(T) }
+        fn main() -> int {
+            let t = Node(42)
+            match t {
+                Node(v) => v,
+                Leaf => 0,
+            }
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn generic_enum_two_type_params() {
+    let result = run(r#"
+        enum Either<L, R> { Left(L), Right(R) }
+        fn main() -> int {
+            let e: Either<int, bool> = Right(true)
+            match e {
+                Left(n) => n,
+                Right(b) => if b { 7 } else { 8 },
+            }
+        }
+    "#);
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn generic_enum_unit_variant_from_context() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn nothing() -> Option<bool> {
+            return None()
+        }
+        fn main() -> int {
+            let o: Option<bool> = None()
+            let annotated = match o {
+                Some(_) => 1,
+                None => 2,
+            }
+            let returned = if let Some(_) = nothing() { 1 } else { 2 }
+            annotated * 10 + returned
+        }
+    "#);
+    assert_eq!(result, 22);
+}
+
+#[test]
+fn generic_enum_holds_enum_instance() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn main() -> int {
+            let o: Option<Option<int>> = Some(Some(5))
+            match o {
+                Some(Some(n)) => n,
+                Some(None) => 1,
+                None => 0,
+            }
+        }
+    "#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn generic_enum_qualified_pattern() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn main() -> int {
+            let o = Some(9)
+            match o {
+                Option::Some(n) => n,
+                Option::None => 0,
+            }
+        }
+    "#);
+    assert_eq!(result, 9);
+}
+
+// --- Functions ---
+
+#[test]
+fn generic_enum_fn_param_and_return() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn find(limit: int) -> Option<int> {
+            if limit > 3 { Some(limit * 2) } else { None() }
+        }
+        fn get(o: Option<int>) -> int {
+            match o {
+                Some(n) => n,
+                None => -1,
+            }
+        }
+        fn main() -> int {
+            get(find(5)) + get(find(1))
+        }
+    "#);
+    assert_eq!(result, 9);
+}
+
+#[test]
+fn generic_fn_binds_through_enum() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn unwrap_or<T>(o: Option<T>, fallback: T) -> T {
+            match o {
+                Some(v) => v,
+                None => fallback,
+            }
+        }
+        fn main() -> int {
+            unwrap_or(Some(40), 0) + unwrap_or(None(), 2)
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn generic_fn_returns_generic_enum() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn wrap<T>(v: T) -> Option<T> { Some(v) }
+        fn main() -> int {
+            match wrap(true) {
+                Some(b) => if b { 3 } else { 4 },
+                None => 0,
+            }
+        }
+    "#);
+    assert_eq!(result, 3);
+}
+
+// --- Errors ---
+
+#[test]
+fn generic_enum_wrong_arity_hint() {
+    let err = expect_error(r#"
+        enum Option<T> { Some(T), None }
+        fn main() -> int {
+            let o: Option<int, int> = Some(1)
+            0
+        }
+    "#);
+    assert!(err.contains("expects 1 type argument,"), "got: {}", err);
+}
+
+#[test]
+fn generic_enum_payload_mismatch() {
+    let err = expect_error(r#"
+        enum Color { Red, Blue }
+        enum Tree<T> { Leaf, Node(T) }
+        fn main() -> int {
+            let t: Tree<Color> = Node(5)
+            0
+        }
+    "#);
+    assert!(err.contains("expects Color, got Int"), "got: {}", err);
+}
+
+#[test]
+fn generic_enum_missing_type_arguments() {
+    let err = expect_error(r#"
+        enum Option<T> { Some(T), None }
+        fn get(o: Option) -> int { 0 }
+        fn main() -> int { get(Some(1)) }
+    "#);
+    assert!(err.contains("needs type arguments"), "got: {}", err);
+}
@@ -0,0 +1,159 @@
+// tests/generic_structs.rs
+//
+// BACKEND TESTS — generic structs. `struct Pair<A, B> { ... }` is
+// monomorphized per type-argument list: each instance (`Pair<Int, String>`)
+// gets its own struct layout, picked from a literal's field types or from an
+// explicit `Pair<int, string>` hint.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Inference from literals ---
+
+#[test]
+fn generic_struct_inferred_from_literal() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn main() -> int {
+            let p = Pair { first: 40, second: "ab" }
+            let q = Pair { first: "hello", second: 7 }
+            (p.first + len(p.second)) * 100 + len(q.first) + q.second
+        }
+    "#);
+    assert_eq!(result, 4212);
+}
+
+#[test]
+fn generic_struct_holds_struct() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Tagged<T> { tag: int, item: T }
+        fn main() -> int {
+            let t = Tagged { tag: 1, item: Point { x: 20, y: 21 } }
+            t.tag + t.item.x + t.item.y
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+// --- Explicit hints ---
+
+#[test]
+fn generic_struct_let_annotation() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn main() -> int {
+            let p: Pair<int, string> = Pair { first: 3, second: "four" }
+            p.first + len(p.second)
+        }
+    "#);
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn generic_struct_fn_param_and_return() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn swap(p: Pair<int, string>) -> Pair<string, int> {
+            Pair { first: p.second, second: p.first }
+        }
+        fn main() -> int {
+            let q = swap(Pair { first: 10, second: "xyz" })
+            len(q.first) + q.second
+        }
+    "#);
+    assert_eq!(result, 13);
+}
+
+#[test]
+fn generic_struct_list_field() {
+    let result = run(r#"
+        struct Stack<T> { items: List<T> }
+        fn main() -> int {
+            let s = Stack { items: list_new_string() }
+            list_push(s.items, "ab")
+            list_push(s.items, "cde")
+            list_len(s.items) * 10 + len(list_get(s.items, 1))
+        }
+    "#);
+    assert_eq!(result, 23);
+}
+
+#[test]
+fn generic_struct_nested_instance_in_field() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        struct Wrap<T> { inner: Pair<T, int> }
+        fn main() -> int {
+            let w: Wrap<string> = Wrap { inner: Pair { first: "abc", second: 5 } }
+            len(w.inner.first) + w.inner.second
+        }
+    "#);
+    assert_eq!(result, 8);
+}
+
+// --- Generic functions over generic structs ---
+
+#[test]
+fn generic_fn_binds_through_struct() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn second<A, B>(p: Pair<A, B>) -> B { p.second }
+        fn main() -> int {
+            let p = Pair { first: 1, second: "hello" }
+            len(second(p)) + second(Pair { first: "x", second: 30 })
+        }
+    "#);
+    assert_eq!(result, 35);
+}
+
+// --- Patterns ---
+
+#[test]
+fn generic_struct_destructuring() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn main() -> int {
+            let Pair { first, second } = Pair { first: 6, second: 7 }
+            first * second
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+// --- Errors ---
+
+#[test]
+fn generic_struct_wrong_arity_hint() {
+    let err = expect_error(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn main() -> int {
+            let p: Pair<int> = Pair { first: 1, second: 2 }
+            p.first
+        }
+    "#);
+    assert!(err.contains("expects 2 type arguments, got 1"), "got: {}", err);
+}
+
+#[test]
+fn generic_struct_hint_mismatch() {
+    let err = expect_error(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fn main() -> int {
+            let p: Pair<int, int> = Pair { first: 1, second: "two" }
+            p.first
+        }
+    "#);
+    assert!(err.contains("Type mismatch"), "got: {}", err);
+}
+
+#[test]
+fn generic_struct_missing_type_arguments() {
+    let err = expect_error(r#"
+        struct Box1<T> { value: T }
+        fn get(b: Box1) -> int { 0 }
+        fn main() -> int { get(Box1 { value: 1 }) }
+    "#);
+    assert!(err.contains("needs type arguments"), "got: {}", err);
+}
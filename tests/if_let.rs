@@ -0,0 +1,180 @@
+// tests/if_let.rs
+//
+// BACKEND TESTS — `if let` and `while let`. Both reuse the match pattern
+// lowering; `while let` re-evaluates its value each iteration and honours
+// `break` / `continue` through the loop stack.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- if let ---
+
+#[test]
+fn if_let_matches_and_binds() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn f(o: Option) -> int {
+            if let Some(v) = o { v + 1 } else { 7 }
+        }
+        fn main() -> int { f(Some(41)) * 10 + f(None()) }
+    "#);
+    assert_eq!(result, 427);
+}
+
+#[test]
+fn if_let_without_else() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn main() -> int {
+            let mut total = 0
+            let o = Some(5)
+            if let Some(v) = o { total = total + v }
+            let n = None()
+            if let Some(v) = n { total = total + 100 }
+            total
+        }
+    "#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn if_let_with_nested_pattern() {
+    let result = run(r#"
+        enum Inner { A(int), B }
+        enum Outer { X(Inner), Y }
+        fn f(o: Outer) -> int {
+            if let X(A(v)) = o { v } else { -1 }
+        }
+        fn main() -> int { f(X(A(10))) + f(X(B())) + f(Y()) }
+    "#);
+    assert_eq!(result, 8);
+}
+
+#[test]
+fn else_if_let_chain() {
+    let result = run(r#"
+        enum Shape { Circle(int), Square(int), Dot }
+        fn size(s: Shape) -> int {
+            if let Circle(r) = s { r * 3 } else if let Square(w) = s { w * w } else { 0 }
+        }
+        fn main() -> int { size(Circle(2)) + size(Square(3)) + size(Dot()) }
+    "#);
+    assert_eq!(result, 15);
+}
+
+#[test]
+fn if_let_binding_does_not_leak() {
+    let err = expect_error(r#"
+        enum Option { Some(int), None }
+        fn main() -> int {
+            let o = Some(1)
+            if let Some(v) = o { v } else { 0 }
+            v
+        }
+    "#);
+    assert!(err.contains("Variable 'v' not found"), "got: {}", err);
+}
+
+// --- while let ---
+
+#[test]
+fn while_let_loops_until_pattern_fails() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn next_below(i: int, n: int) -> Option {
+            if i < n { Some(i) } else { None() }
+        }
+        fn main() -> int {
+            let mut i = 0
+            let mut sum = 0
+            while let Some(v) = next_below(i, 5) {
+                sum = sum + v
+                i = i + 1
+            }
+            sum
+        }
+    "#);
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn while_let_break_and_continue() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn next_below(i: int, n: int) -> Option {
+            if i < n { Some(i) } else { None() }
+        }
+        fn main() -> int {
+            let mut i = 0
+            let mut odd = 0
+            while let Some(v) = next_below(i, 100) {
+                if v == 10 { break }
+                i = i + 1
+                if v % 2 == 0 { continue }
+                odd = odd + 1
+            }
+            i * 100 + odd
+        }
+    "#);
+    assert_eq!(result, 1005);
+}
+
+#[test]
+fn while_let_with_literal_pattern() {
+    let result = run(r#"
+        fn main() -> int {
+            let mut n = 0
+            while let 0..=4 = n {
+                n = n + 1
+            }
+            n
+        }
+    "#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn function_ending_in_while_let_returns_no_value() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        static mut SEEN: int = 0
+        fn next_below(i: int, n: int) -> Option {
+            if i < n { Some(i) } else { None() }
+        }
+        fn drain(n: int) {
+            let mut i = 0
+            while let Some(v) = next_below(i, n) {
+                SEEN = SEEN + v
+                i = i + 1
+            }
+        }
+        fn main() -> int {
+            drain(4) * 100 + SEEN
+        }
+    "#);
+    assert_eq!(result, 6);
+}
+
+#[test]
+fn while_let_is_not_a_string_value() {
+    let err = expect_error(r#"
+        enum Option { Some(string), None }
+        fn first(o: Option) -> string {
+            while let Some(s) = o { return s }
+        }
+        first(Some("a"))
+    "#);
+    assert_eq!(err, "line 3: Function 'first' ends in a `while let` loop, which has no value, but its return type is String");
+}
+
+#[test]
+fn if_let_pattern_type_mismatch() {
+    let err = expect_error(r#"
+        enum Option { Some(int), None }
+        fn main() -> int {
+            if let Some(v) = 5 { v } else { 0 }
+        }
+    "#);
+    assert!(err.contains("cannot match a value of type Int"), "got: {}", err);
+}
@@ -97,15 +97,15 @@ fn test_not_nonzero() {
 
 #[test]
 fn test_variable_reassignment() {
-    let src = "let x = 10;\nx = 20;\nx = 30;\nx";
+    let src = "let mut x = 10;\nx = 20;\nx = 30;\nx";
     assert_eq!(run(src), 30);
 }
 
 #[test]
 fn test_variable_used_before_assignment_in_loop() {
     let src = r#"
-        let sum = 0;
-        let i = 0;
+        let mut sum = 0;
+        let mut i = 0;
         while i < 5 {
             sum = sum + i;
             i = i + 1;
@@ -158,10 +158,10 @@ fn test_variable_shadow_in_if_branch() {
 #[test]
 fn test_nested_while_loops() {
     let src = r#"
-        let result = 0;
-        let i = 0;
+        let mut result = 0;
+        let mut i = 0;
         while i < 3 {
-            let j = 0;
+            let mut j = 0;
             while j < 3 {
                 result = result + 1;
                 j = j + 1;
@@ -176,8 +176,8 @@ fn test_nested_while_loops() {
 #[test]
 fn test_if_inside_while() {
     let src = r#"
-        let sum = 0;
-        let i = 0;
+        let mut sum = 0;
+        let mut i = 0;
         while i < 10 {
             if i > 5 {
                 sum = sum + i;
@@ -194,7 +194,7 @@ fn test_while_with_break() {
     // Note: break is parsed but may not be fully implemented in codegen
     // This test verifies the parser handles it; codegen may need work
     let src = r#"
-        let i = 0;
+        let mut i = 0;
         while i < 100 {
             if i == 5 {
                 break
@@ -213,7 +213,7 @@ fn test_while_with_break() {
 #[test]
 fn test_for_loop_nested() {
     let src = r#"
-        let result = 0;
+        let mut result = 0;
         for i in 0..3 {
             for j in 0..3 {
                 result = result + 1;
@@ -227,7 +227,7 @@ fn test_for_loop_nested() {
 #[test]
 fn test_for_loop_with_accumulation() {
     let src = r#"
-        let product = 1;
+        let mut product = 1;
         for i in 1..6 {
             product = product * i;
         }
@@ -318,8 +318,8 @@ fn test_function_calls_function() {
 fn test_function_with_while_inside() {
     let src = r#"
         fn sum_to(n) {
-            let total = 0;
-            let i = 1;
+            let mut total = 0;
+            let mut i = 1;
             while i <= n {
                 total = total + i;
                 i = i + 1;
@@ -335,7 +335,7 @@ fn test_function_with_while_inside() {
 fn test_function_with_for_inside() {
     let src = r#"
         fn sum_range(start, end) {
-            let total = 0;
+            let mut total = 0;
             for i in start..end {
                 total = total + i;
             }
@@ -450,8 +450,8 @@ fn test_type_error_not_string() {
 #[test]
 fn test_large_while_loop() {
     let src = r#"
-        let sum = 0;
-        let i = 0;
+        let mut sum = 0;
+        let mut i = 0;
         while i < 1000 {
             sum = sum + i;
             i = i + 1;
@@ -465,7 +465,7 @@ fn test_large_while_loop() {
 #[test]
 fn test_large_for_loop() {
     let src = r#"
-        let sum = 0;
+        let mut sum = 0;
         for i in 0..1000 {
             sum = sum + i;
         }
@@ -548,8 +548,8 @@ fn test_complex_program_all_features() {
     // arithmetic, comparison, builtins
     let src = r#"
         fn compute(n) {
-            let total = 0;
-            let i = 0;
+            let mut total = 0;
+            let mut i = 0;
             while i < n {
                 if i > 0 {
                     total = total + abs(i);
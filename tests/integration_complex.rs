@@ -53,7 +53,7 @@ fn test_gcd_euclidean_iterative() {
     // Greatest Common Divisor via Euclidean algorithm (iterative)
     // mod: r = a - (a / b) * b
     let src = r#"
-        fn gcd(a, b) {
+        fn gcd(mut a, mut b) {
             while b > 0 {
                 let r = a - (a / b) * b;
                 a = b;
@@ -69,7 +69,7 @@ fn test_gcd_euclidean_iterative() {
 #[test]
 fn test_gcd_mutual_prime() {
     let src = r#"
-        fn gcd(a, b) {
+        fn gcd(mut a, mut b) {
             while b > 0 {
                 let r = a - (a / b) * b;
                 a = b;
@@ -85,7 +85,7 @@ fn test_gcd_mutual_prime() {
 #[test]
 fn test_gcd_large_numbers() {
     let src = r#"
-        fn gcd(a, b) {
+        fn gcd(mut a, mut b) {
             while b > 0 {
                 let r = a - (a / b) * b;
                 a = b;
@@ -103,8 +103,8 @@ fn test_power_iterative() {
     // Exponentiation by repeated multiplication
     let src = r#"
         fn power(base, exp) {
-            let result = 1;
-            let i = 0;
+            let mut result = 1;
+            let mut i = 0;
             while i < exp {
                 result = result * base;
                 i = i + 1;
@@ -120,8 +120,8 @@ fn test_power_iterative() {
 fn test_power_zero_exponent() {
     let src = r#"
         fn power(base, exp) {
-            let result = 1;
-            let i = 0;
+            let mut result = 1;
+            let mut i = 0;
             while i < exp {
                 result = result * base;
                 i = i + 1;
@@ -138,8 +138,8 @@ fn test_power_large() {
     // 3^15 = 14348907
     let src = r#"
         fn power(base, exp) {
-            let result = 1;
-            let i = 0;
+            let mut result = 1;
+            let mut i = 0;
             while i < exp {
                 result = result * base;
                 i = i + 1;
@@ -159,7 +159,7 @@ fn test_is_prime() {
             if n < 2 {
                 return 0;
             }
-            let i = 2;
+            let mut i = 2;
             while i * i <= n {
                 if n - (n / i) * i == 0 {
                     return 0;
@@ -180,7 +180,7 @@ fn test_is_not_prime() {
             if n < 2 {
                 return 0;
             }
-            let i = 2;
+            let mut i = 2;
             while i * i <= n {
                 if n - (n / i) * i == 0 {
                     return 0;
@@ -201,7 +201,7 @@ fn test_is_prime_small() {
             if n < 2 {
                 return 0;
             }
-            let i = 2;
+            let mut i = 2;
             while i * i <= n {
                 if n - (n / i) * i == 0 {
                     return 0;
@@ -223,7 +223,7 @@ fn test_count_primes_below_30() {
             if n < 2 {
                 return 0;
             }
-            let i = 2;
+            let mut i = 2;
             while i * i <= n {
                 if n - (n / i) * i == 0 {
                     return 0;
@@ -232,8 +232,8 @@ fn test_count_primes_below_30() {
             }
             return 1;
         }
-        let count = 0;
-        let n = 2;
+        let mut count = 0;
+        let mut n = 2;
         while n < 30 {
             count = count + is_prime(n);
             n = n + 1;
@@ -248,8 +248,8 @@ fn test_digit_sum() {
     // Sum of digits of 987654321 = 45
     // mod 10: n - (n / 10) * 10
     let src = r#"
-        fn digit_sum(n) {
-            let sum = 0;
+        fn digit_sum(mut n) {
+            let mut sum = 0;
             while n > 0 {
                 sum = sum + (n - (n / 10) * 10);
                 n = n / 10;
@@ -264,8 +264,8 @@ fn test_digit_sum() {
 #[test]
 fn test_digit_sum_zero() {
     let src = r#"
-        fn digit_sum(n) {
-            let sum = 0;
+        fn digit_sum(mut n) {
+            let mut sum = 0;
             while n > 0 {
                 sum = sum + (n - (n / 10) * 10);
                 n = n / 10;
@@ -281,8 +281,8 @@ fn test_digit_sum_zero() {
 fn test_reverse_number() {
     // Reverse digits of 12345 → 54321
     let src = r#"
-        fn reverse(n) {
-            let result = 0;
+        fn reverse(mut n) {
+            let mut result = 0;
             while n > 0 {
                 result = result * 10 + (n - (n / 10) * 10);
                 n = n / 10;
@@ -299,8 +299,8 @@ fn test_collatz_steps() {
     // Collatz sequence length from 27 → 111 steps
     // even: n % 2 == 0 → n - (n/2)*2 == 0
     let src = r#"
-        fn collatz(n) {
-            let steps = 0;
+        fn collatz(mut n) {
+            let mut steps = 0;
             while n > 1 {
                 if n - (n / 2) * 2 == 0 {
                     n = n / 2;
@@ -320,8 +320,8 @@ fn test_collatz_steps() {
 fn test_collatz_small() {
     // collatz(6): 6→3→10→5→16→8→4→2→1 = 8 steps
     let src = r#"
-        fn collatz(n) {
-            let steps = 0;
+        fn collatz(mut n) {
+            let mut steps = 0;
             while n > 1 {
                 if n - (n / 2) * 2 == 0 {
                     n = n / 2;
@@ -342,8 +342,8 @@ fn test_factorial_iterative() {
     // 10! = 3628800
     let src = r#"
         fn fact(n) {
-            let result = 1;
-            let i = 1;
+            let mut result = 1;
+            let mut i = 1;
             while i <= n {
                 result = result * i;
                 i = i + 1;
@@ -359,8 +359,8 @@ fn test_factorial_iterative() {
 fn test_factorial_small() {
     let src = r#"
         fn fact(n) {
-            let result = 1;
-            let i = 1;
+            let mut result = 1;
+            let mut i = 1;
             while i <= n {
                 result = result * i;
                 i = i + 1;
@@ -380,9 +380,9 @@ fn test_fibonacci_iterative() {
             if n <= 1 {
                 return n;
             }
-            let a = 0;
-            let b = 1;
-            let i = 2;
+            let mut a = 0;
+            let mut b = 1;
+            let mut i = 2;
             while i <= n {
                 let c = a + b;
                 a = b;
@@ -404,9 +404,9 @@ fn test_fibonacci_iterative_large() {
             if n <= 1 {
                 return n;
             }
-            let a = 0;
-            let b = 1;
-            let i = 2;
+            let mut a = 0;
+            let mut b = 1;
+            let mut i = 2;
             while i <= n {
                 let c = a + b;
                 a = b;
@@ -427,9 +427,9 @@ fn test_fibonacci_iterative_zero() {
             if n <= 1 {
                 return n;
             }
-            let a = 0;
-            let b = 1;
-            let i = 2;
+            let mut a = 0;
+            let mut b = 1;
+            let mut i = 2;
             while i <= n {
                 let c = a + b;
                 a = b;
@@ -447,7 +447,7 @@ fn test_fibonacci_iterative_zero() {
 fn test_sum_of_squares() {
     // Sum of squares 1..10 = 385
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..11 {
             total = total + (i * i);
         }
@@ -464,10 +464,10 @@ fn test_sum_of_squares() {
 fn test_multiplication_table_diagonal() {
     // Sum of diagonal of 5x5 multiplication table = 1+4+9+16+25 = 55
     let src = r#"
-        let diag = 0;
-        let i = 1;
+        let mut diag = 0;
+        let mut i = 1;
         while i <= 5 {
-            let j = 1;
+            let mut j = 1;
             while j <= 5 {
                 if i == j {
                     diag = diag + (i * j);
@@ -486,7 +486,7 @@ fn test_nested_loops_with_skip_condition() {
     // Sum of i*j where i != j for i,j in 1..4
     // i=1: 2+3=5, i=2: 2+6=8, i=3: 3+6=9 → total 22
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..4 {
             for j in 1..4 {
                 if i != j {
@@ -520,7 +520,7 @@ fn test_if_chain_score_bands() {
 fn test_triangle_pattern_sum() {
     // Row i contains i..5 sums: 15+14+12+9+5 = 55
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..6 {
             for j in i..6 {
                 total = total + j;
@@ -536,8 +536,8 @@ fn test_fizzbuzz_weighted_sum() {
     // For 1..15: div by 3 → +3, div by 5 → +5, div by 15 → +15
     // mod 15: i - (i/15)*15; mod 5: i - (i/5)*5; mod 3: i - (i/3)*3
     let src = r#"
-        let total = 0;
-        let i = 1;
+        let mut total = 0;
+        let mut i = 1;
         while i <= 15 {
             if i - (i / 15) * 15 == 0 {
                 total = total + 15;
@@ -563,10 +563,10 @@ fn test_accumulator_in_nested_function() {
     // Σ_{i=1..5} Σ_{j=1..i} i*j = 1+6+18+40+75 = 140
     let src = r#"
         fn sum_products(n) {
-            let total = 0;
-            let i = 1;
+            let mut total = 0;
+            let mut i = 1;
             while i <= n {
-                let j = 1;
+                let mut j = 1;
                 while j <= i {
                     total = total + (i * j);
                     j = j + 1;
@@ -585,7 +585,7 @@ fn test_triple_nested_loop() {
     // 3-level nested loop: sum of i*j*k for i,j,k in 1..4
     // = (sum 1..3)^3 = 6^3 = 216
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..4 {
             for j in 1..4 {
                 for k in 1..4 {
@@ -815,8 +815,8 @@ fn test_array_access_in_loop() {
     // Sum array elements via while loop
     let src = r#"
         let arr = [10, 20, 30, 40, 50];
-        let sum = 0;
-        let i = 0;
+        let mut sum = 0;
+        let mut i = 0;
         while i < 5 {
             sum = sum + arr[i];
             i = i + 1;
@@ -831,8 +831,8 @@ fn test_array_access_in_function() {
     // Pass array to function and sum elements
     let src = r#"
         fn array_sum(arr, size) {
-            let total = 0;
-            let i = 0;
+            let mut total = 0;
+            let mut i = 0;
             while i < size {
                 total = total + arr[i];
                 i = i + 1;
@@ -849,8 +849,8 @@ fn test_array_access_in_function() {
 fn test_array_find_max() {
     let src = r#"
         fn find_max(arr, size) {
-            let max = arr[0];
-            let i = 1;
+            let mut max = arr[0];
+            let mut i = 1;
             while i < size {
                 if arr[i] > max {
                     max = arr[i];
@@ -869,8 +869,8 @@ fn test_array_find_max() {
 fn test_array_find_min() {
     let src = r#"
         fn find_min(arr, size) {
-            let min_val = arr[0];
-            let i = 1;
+            let mut min_val = arr[0];
+            let mut i = 1;
             while i < size {
                 if arr[i] < min_val {
                     min_val = arr[i];
@@ -889,7 +889,7 @@ fn test_array_find_min() {
 fn test_array_linear_search() {
     let src = r#"
         fn search(arr, size, target) {
-            let i = 0;
+            let mut i = 0;
             while i < size {
                 if arr[i] == target {
                     return i;
@@ -908,7 +908,7 @@ fn test_array_linear_search() {
 fn test_array_linear_search_not_found() {
     let src = r#"
         fn search(arr, size, target) {
-            let i = 0;
+            let mut i = 0;
             while i < size {
                 if arr[i] == target {
                     return i;
@@ -970,8 +970,8 @@ fn test_complex_math_program() {
         }
 
         fn count_positives(nums, limit) {
-            let count = 0;
-            let i = 0;
+            let mut count = 0;
+            let mut i = 0;
             while i < limit {
                 if classify(nums[i]) == 1 {
                     count = count + 1;
@@ -993,9 +993,9 @@ fn test_complex_math_program() {
 fn test_alternating_series_sum() {
     // 1 - 3 + 5 - 7 + 9 - 11 + 13 = 7
     let src = r#"
-        let total = 0;
-        let sign = 1;
-        let i = 1;
+        let mut total = 0;
+        let mut sign = 1;
+        let mut i = 1;
         while i < 14 {
             if sign == 1 {
                 total = total + i;
@@ -1016,8 +1016,8 @@ fn test_bouncing_ball_simulation() {
     // 100→50→25→12→6→3→1→0 = 7 bounces until height == 0
     let src = r#"
         fn bounce_count(start) {
-            let height = start;
-            let count = 0;
+            let mut height = start;
+            let mut count = 0;
             while height > 0 {
                 height = height / 2;
                 count = count + 1;
@@ -1040,8 +1040,8 @@ fn test_sum_of_cubes() {
         fn cube(x) {
             square(x) * x
         }
-        let total = 0;
-        let i = 1;
+        let mut total = 0;
+        let mut i = 1;
         while i <= 10 {
             total = total + cube(i);
             i = i + 1;
@@ -1056,10 +1056,10 @@ fn test_deeply_nested_control_flow() {
     // for → if → while → if: 4 levels deep
     // mod 2: i - (i/2)*2
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..5 {
             if i - (i / 2) * 2 == 0 {
-                let j = 1;
+                let mut j = 1;
                 while j <= 3 {
                     if j == 2 {
                         total = total + i * j;
@@ -1078,15 +1078,15 @@ fn test_deeply_nested_control_flow() {
 fn test_two_function_sum() {
     let src = r#"
         fn sum_range(start, end) {
-            let total = 0;
+            let mut total = 0;
             for i in start..end {
                 total = total + i;
             }
             return total;
         }
         fn sum_squares_iter(n) {
-            let total = 0;
-            let i = 1;
+            let mut total = 0;
+            let mut i = 1;
             while i <= n {
                 total = total + (i * i);
                 i = i + 1;
@@ -1107,8 +1107,8 @@ fn test_binary_search_iterative() {
             index
         }
         fn search(target, size) {
-            let lo = 0;
-            let hi = size - 1;
+            let mut lo = 0;
+            let mut hi = size - 1;
             while lo <= hi {
                 let mid = (lo + hi) / 2;
                 let val = get(mid);
@@ -1135,8 +1135,8 @@ fn test_binary_search_not_found() {
             index * 2
         }
         fn search(target, size) {
-            let lo = 0;
-            let hi = size - 1;
+            let mut lo = 0;
+            let mut hi = size - 1;
             while lo <= hi {
                 let mid = (lo + hi) / 2;
                 let val = get(mid);
@@ -1206,7 +1206,7 @@ fn test_median_another_set() {
 fn test_arithmetic_progression_sum() {
     // Sum of AP: 5 + 10 + 15 + ... + 50 = 5*(1+2+...+10) = 5*55 = 275
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..11 {
             total = total + i * 5;
         }
@@ -1222,8 +1222,8 @@ fn test_newton_sqrt_approximation() {
     // Converges to ~31 for sqrt(1000)
     let src = r#"
         fn isqrt(n) {
-            let x = n;
-            let y = (x + n / x) / 2;
+            let mut x = n;
+            let mut y = (x + n / x) / 2;
             while y < x {
                 x = y;
                 y = (x + n / x) / 2;
@@ -1240,8 +1240,8 @@ fn test_newton_sqrt_approximation() {
 fn test_newton_sqrt_perfect() {
     let src = r#"
         fn isqrt(n) {
-            let x = n;
-            let y = (x + n / x) / 2;
+            let mut x = n;
+            let mut y = (x + n / x) / 2;
             while y < x {
                 x = y;
                 y = (x + n / x) / 2;
@@ -1260,7 +1260,7 @@ fn test_egg_drop_simulation() {
     // Answer: 14 (smallest n where n(n+1)/2 >= 100)
     let src = r#"
         fn min_drops(floors) {
-            let n = 1;
+            let mut n = 1;
             while n * (n + 1) / 2 < floors {
                 n = n + 1;
             }
@@ -1281,8 +1281,8 @@ fn test_compound_interest() {
     // P = 1000, rate = 5%, 10 years
     // P = P + P/20 each year
     let src = r#"
-        fn compound(principal, years) {
-            let i = 0;
+        fn compound(mut principal, years) {
+            let mut i = 0;
             while i < years {
                 principal = principal + principal / 20;
                 i = i + 1;
@@ -1306,7 +1306,7 @@ fn test_largest_prime_factor_check() {
             if n < 2 {
                 return 0;
             }
-            let i = 2;
+            let mut i = 2;
             while i * i <= n {
                 if n - (n / i) * i == 0 {
                     return 0;
@@ -1349,8 +1349,8 @@ fn test_modulo_precedence() {
 fn test_modulo_in_loop() {
     // Sum of even numbers 1..10 = 2+4+6+8+10 = 30
     let src = r#"
-        let total = 0;
-        let i = 1;
+        let mut total = 0;
+        let mut i = 1;
         while i <= 10 {
             if i % 2 == 0 {
                 total = total + i;
@@ -1377,7 +1377,7 @@ fn test_modulo_in_function() {
 fn test_modulo_gcd_clean() {
     // Now GCD can use % directly instead of a - (a/b)*b
     let src = r#"
-        fn gcd(a, b) {
+        fn gcd(mut a, mut b) {
             while b > 0 {
                 let r = a % b;
                 a = b;
@@ -1445,8 +1445,8 @@ fn test_logical_combined_conditions() {
 fn test_logical_in_loop() {
     // Count numbers 1..20 that are divisible by 3 AND 5 (i.e., 15)
     let src = r#"
-        let count = 0;
-        let i = 1;
+        let mut count = 0;
+        let mut i = 1;
         while i <= 20 {
             if i % 3 == 0 && i % 5 == 0 {
                 count = count + 1;
@@ -1474,7 +1474,7 @@ fn test_logical_priority_over_comparison() {
 #[test]
 fn test_break_while_loop() {
     let src = r#"
-        let i = 0;
+        let mut i = 0;
         while i < 100 {
             if i == 5 {
                 break;
@@ -1489,7 +1489,7 @@ fn test_break_while_loop() {
 #[test]
 fn test_break_for_loop() {
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 0..100 {
             if i == 10 {
                 break;
@@ -1505,8 +1505,8 @@ fn test_break_for_loop() {
 fn test_continue_while_loop() {
     // Sum of numbers 1..10 skipping evens: 1+3+5+7+9 = 25
     let src = r#"
-        let total = 0;
-        let i = 0;
+        let mut total = 0;
+        let mut i = 0;
         while i < 10 {
             i = i + 1;
             if i % 2 == 0 {
@@ -1523,7 +1523,7 @@ fn test_continue_while_loop() {
 fn test_continue_for_loop() {
     // Sum of squares of odds 1..10: 1+9+25+49+81 = 165
     let src = r#"
-        let total = 0;
+        let mut total = 0;
         for i in 1..11 {
             if i % 2 == 0 {
                 continue;
@@ -1539,10 +1539,10 @@ fn test_continue_for_loop() {
 fn test_break_nested_loop() {
     // Break only exits the inner loop
     let src = r#"
-        let count = 0;
-        let i = 0;
+        let mut count = 0;
+        let mut i = 0;
         while i < 3 {
-            let j = 0;
+            let mut j = 0;
             while j < 100 {
                 if j == 2 {
                     break;
@@ -1561,8 +1561,8 @@ fn test_break_nested_loop() {
 fn test_break_and_continue_combined() {
     // Collatz-like: find first power of 2 above 100 via continue/break
     let src = r#"
-        let n = 1;
-        let found = 0;
+        let mut n = 1;
+        let mut found = 0;
         while true {
             if n < 100 {
                 n = n * 2;
@@ -194,8 +194,8 @@ fn test_function_two_params() {
 fn test_while_loop_basic() {
     // Sum 1..5 using while
     let src = r#"
-        let sum = 0;
-        let i = 1;
+        let mut sum = 0;
+        let mut i = 1;
         while i <= 5 {
             sum = sum + i;
             i = i + 1;
@@ -212,7 +212,7 @@ fn test_while_loop_basic() {
 #[test]
 fn test_for_loop_sum() {
     let src = r#"
-        let sum = 0;
+        let mut sum = 0;
         for i in 0..5 {
             sum = sum + i;
         }
@@ -37,6 +37,23 @@ fn run(source: &str) -> i64 {
     codegen.run_jit().expect("JIT execution failed")
 }
 
+/// Helper: compile and JIT-execute AHA! source, returning the result or
+/// the panic message.
+fn run_result(source: &str) -> Result<i64, String> {
+    let lexer = Lexer::new(source.to_string());
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+
+    if !parser.errors.is_empty() {
+        panic!("Parser errors: {:?}", parser.errors);
+    }
+
+    let context = Context::create();
+    let mut codegen = CodeGenerator::new(&context);
+    codegen.compile(&program).expect("Codegen failed");
+    codegen.run_jit().map_err(|e| e.to_string())
+}
+
 /// Helper: compile source and return the emitted LLVM IR as text.
 fn emit_ir(source: &str) -> String {
     let lexer = Lexer::new(source.to_string());
@@ -121,7 +138,7 @@ fn test_list_index_read() {
 #[test]
 fn test_list_index_write() {
     let result = run(
-        "let xs = list_new();
+        "let mut xs = list_new();
          list_push(xs, 5);
          xs[0] = 99;
          xs[0]"
@@ -132,7 +149,7 @@ fn test_list_index_write() {
 #[test]
 fn test_list_index_write_then_read_via_get() {
     let result = run(
-        "let xs = list_new();
+        "let mut xs = list_new();
          list_push(xs, 1);
          list_push(xs, 2);
          xs[1] = 42;
@@ -142,9 +159,9 @@ fn test_list_index_write_then_read_via_get() {
 }
 
 #[test]
-fn test_list_out_of_bounds_returns_zero() {
-    let result = run("let xs = list_new(); list_get(xs, 0)");
-    assert_eq!(result, 0);
+fn test_list_out_of_bounds_panics() {
+    let err = run_result("let xs = list_new(); list_get(xs, 0)").unwrap_err();
+    assert_eq!(err, "panicked at line 1: index out of bounds: the index is 0 but the length is 0");
 }
 
 #[test]
@@ -152,13 +169,13 @@ fn test_list_in_loop() {
     // Build 0..5 then sum via index reads.
     let result = run(
         "let xs = list_new();
-         let i = 0;
+         let mut i = 0;
          while i < 5 {
              list_push(xs, i);
              i = i + 1;
          }
-         let sum = 0;
-         let j = 0;
+         let mut sum = 0;
+         let mut j = 0;
          while j < 5 {
              sum = sum + xs[j];
              j = j + 1;
@@ -214,7 +231,7 @@ fn test_list_string_index_read() {
 #[test]
 fn test_list_string_index_write() {
     let result = run(
-        "let xs = list_new_string();
+        "let mut xs = list_new_string();
          list_push(xs, \"old\");
          xs[0] = \"new\";
          len(xs[0])"
@@ -211,3 +211,23 @@ fn map_chained_set_get() {
         let c = map_get(m2, 3)
         a + b + c"#), 60);
 }
+
+#[test]
+fn map_keeps_every_key_when_growing() {
+    assert_eq!(run(r#"let m = map_new()
+        let s = map_string_key_new()
+        let mut i = 0
+        while i < 100 {
+            map_set(m, i * 7, i)
+            map_string_key_set(s, "k" + int_to_string(i), i)
+            i = i + 1
+        }
+        let mut found = 0
+        i = 0
+        while i < 100 {
+            if map_get(m, i * 7) == i { found = found + 1 }
+            if map_string_key_get(s, "k" + int_to_string(i)) == i { found = found + 1 }
+            i = i + 1
+        }
+        found * 1000 + map_len(m)"#), 200100);
+}
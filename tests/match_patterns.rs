@@ -0,0 +1,250 @@
+// tests/match_patterns.rs
+//
+// BACKEND TESTS — literal, range, alternative and binding patterns plus
+// `if` guards in match arms. Constant-only arms lower to a switch; ranges,
+// strings and guards lower to a compare chain.
+
+mod common;
+
+use common::{expect_error, parse_only, run};
+
+// --- Parsing ---
+
+#[test]
+fn match_patterns_parse_diagnostic() {
+    let errors = parse_only(r#"
+        fn main() -> int {
+            let x = 5
+            match x {
+                0 => 0,
+                -1 => 1,
+                1..=9 => 2,
+                10 | 20 | 30 => 3,
+                n if n > 100 => 4,
+                _ => 5,
+            }
+        }
+    "#);
+    assert!(errors.is_empty(), "Pattern parse errors: {:?}", errors);
+}
+
+#[test]
+fn match_exclusive_range_pattern_rejected() {
+    let errors = parse_only(r#"
+        fn main() -> int {
+            match 3 { 1..5 => 1, _ => 0 }
+        }
+    "#);
+    assert!(errors.iter().any(|e| e.contains("..=")), "errors: {:?}", errors);
+}
+
+// --- Integer literals ---
+
+#[test]
+fn match_int_literal() {
+    let result = run(r#"
+        fn classify(x: int) -> int {
+            match x {
+                0 => 100,
+                1 => 200,
+                -1 => 300,
+                _ => 400,
+            }
+        }
+        fn main() -> int {
+            classify(0) + classify(1) + classify(-1) + classify(7)
+        }
+    "#);
+    assert_eq!(result, 1000);
+}
+
+#[test]
+fn match_int_minimum_literal() {
+    let result = run(r#"
+        fn classify(x: int) -> int {
+            match x {
+                -9223372036854775808 => 1,
+                -9223372036854775807..=-1 => 2,
+                _ => 3,
+            }
+        }
+        fn main() -> int {
+            let min = -9223372036854775807 - 1
+            classify(min) * 100 + classify(min + 1) * 10 + classify(0)
+        }
+    "#);
+    assert_eq!(result, 123);
+}
+
+#[test]
+fn match_int_range() {
+    let result = run(r#"
+        fn bucket(x: int) -> int {
+            match x {
+                0 => 0,
+                1..=9 => 1,
+                10..=99 => 2,
+                _ => 3,
+            }
+        }
+        fn main() -> int {
+            bucket(0) * 1000 + bucket(9) * 100 + bucket(42) * 10 + bucket(500)
+        }
+    "#);
+    assert_eq!(result, 123);
+}
+
+#[test]
+fn match_int_alternatives() {
+    let result = run(r#"
+        fn f(x: int) -> int {
+            match x {
+                6 | 7 => 1,
+                0 | 50..=60 => 2,
+                _ => 0,
+            }
+        }
+        fn main() -> int { f(6) + f(7) + f(3) + f(0) * 10 + f(55) * 100 + f(61) * 1000 }
+    "#);
+    assert_eq!(result, 222);
+}
+
+// --- Strings ---
+
+#[test]
+fn match_string_literal() {
+    let result = run(r#"
+        fn code(cmd: string) -> int {
+            match cmd {
+                "start" => 1,
+                "stop" => 2,
+                _ => 0,
+            }
+        }
+        fn main() -> int { code("start") * 100 + code("stop") * 10 + code("pause") }
+    "#);
+    assert_eq!(result, 120);
+}
+
+// --- Bindings and guards ---
+
+#[test]
+fn match_binding_pattern() {
+    let result = run(r#"
+        fn main() -> int {
+            let x = 21
+            match x {
+                0 => 0,
+                n => n * 2,
+            }
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn match_guard_falls_through() {
+    let result = run(r#"
+        fn sign(x: int) -> int {
+            match x {
+                n if n < 0 => -1,
+                0 => 0,
+                _ => 1,
+            }
+        }
+        fn main() -> int { sign(-5) * 100 + sign(0) * 10 + sign(8) }
+    "#);
+    assert_eq!(result, -99);
+}
+
+#[test]
+fn match_guard_on_enum_arm() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        fn describe(o: Option) -> int {
+            match o {
+                Some(v) if v > 10 => 2,
+                Some(v) => 1,
+                None => 0,
+            }
+        }
+        fn main() -> int {
+            describe(Some(50)) * 100 + describe(Some(3)) * 10 + describe(None())
+        }
+    "#);
+    assert_eq!(result, 210);
+}
+
+#[test]
+fn match_enum_alternatives() {
+    let result = run(r#"
+        enum Day { Mon, Tue, Wed, Thu, Fri, Sat, Sun }
+        fn weekend(d: Day) -> int {
+            match d {
+                Sat | Sun => 1,
+                _ => 0,
+            }
+        }
+        fn main() -> int { weekend(Sun()) + weekend(Sat()) + weekend(Wed()) }
+    "#);
+    assert_eq!(result, 2);
+}
+
+// --- Errors ---
+
+#[test]
+fn match_int_requires_catch_all() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let x = 3
+            match x {
+                1 => 10,
+                2 => 20,
+            }
+        }
+    "#);
+    assert!(err.contains("Non-exhaustive"), "got: {}", err);
+}
+
+#[test]
+fn match_pattern_type_mismatch() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let s = "hi"
+            match s {
+                1 => 10,
+                _ => 0,
+            }
+        }
+    "#);
+    assert!(err.contains("cannot match a value of type String"), "got: {}", err);
+}
+
+#[test]
+fn match_binding_in_alternative_rejected() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let x = 3
+            match x {
+                1 | n => n,
+                _ => 0,
+            }
+        }
+    "#);
+    assert!(err.contains("not allowed in `|` patterns"), "got: {}", err);
+}
+
+#[test]
+fn match_guarded_enum_arm_is_not_exhaustive() {
+    let err = expect_error(r#"
+        enum Option { Some(int), None }
+        fn f(o: Option) -> int {
+            match o {
+                None => 0,
+                Some(v) if v > 0 => v,
+            }
+        }
+        fn main() -> int { f(Some(-1)) }
+    "#);
+    assert_eq!(err, "line 4: Non-exhaustive match on Option: 'Some' not covered by an unguarded arm; add it or a `_` arm");
+}
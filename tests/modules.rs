@@ -274,7 +274,7 @@ fn try_compile_with_files(
             let context = Context::create();
             let mut codegen = CodeGenerator::new(&context);
             match codegen.compile(&program) {
-                Ok(()) => codegen.run_jit().map_err(|e| e),
+                Ok(()) => codegen.run_jit().map_err(|e| e.to_string()),
                 Err(e) => Err(e),
             }
         }
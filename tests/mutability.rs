@@ -0,0 +1,181 @@
+// tests/mutability.rs
+//
+// TYPECK TESTS — immutable-by-default bindings. `let` bindings,
+// parameters and pattern bindings are immutable; `let mut`,
+// `fn f(mut x)` and `mut` in a pattern opt in. Assigning to an immutable variable, or to a
+// field or element through one, is a compile error that points at the
+// original binding.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Variables ---
+
+#[test]
+fn mut_binding_can_be_reassigned() {
+    let result = run(r#"
+        let mut total = 0
+        let mut i = 1
+        while i <= 4 {
+            total = total + i
+            i = i + 1
+        }
+        total
+    "#);
+    assert_eq!(result, 10);
+    assert_eq!(run("let mut x: int = 5\nx = x * 2\nx"), 10);
+}
+
+#[test]
+fn immutable_binding_reassignment_is_error() {
+    let err = expect_error("let x = 1\nlet y = 2\nx = 3\nx");
+    assert_eq!(err, "line 3: Cannot assign twice to immutable variable 'x' (declared at line 1; use 'let mut x')");
+}
+
+#[test]
+fn shadowing_is_not_mutation() {
+    assert_eq!(run("let x = 1\nlet x = x + 1\nx"), 2);
+    let result = run(r#"
+        let x = 1
+        if true {
+            let mut x = 5
+            x = x + 1
+        }
+        x
+    "#);
+    assert_eq!(result, 1);
+}
+
+#[test]
+fn mut_binding_in_inner_scope_is_checked_there() {
+    let err = expect_error(r#"
+        let mut x = 1
+        if true {
+            let x = 5
+            x = 6
+        }
+        x
+    "#);
+    assert!(err.starts_with("line 5: Cannot assign twice to immutable variable 'x' (declared at line 4;"), "got: {}", err);
+}
+
+// --- Fields and elements ---
+
+#[test]
+fn field_mutation_needs_mut_binding() {
+    let err = expect_error(r#"
+        struct Point { x: int, y: int }
+        let p = Point { x: 1, y: 2 }
+        p.x = 10
+        p.x
+    "#);
+    assert_eq!(err, "line 4: Cannot assign to field 'x' of immutable variable 'p' (declared at line 3; use 'let mut p')");
+}
+
+#[test]
+fn field_mutation_through_mut_binding() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        let mut p = Point { x: 1, y: 2 }
+        p.x = 10
+        p.x + p.y
+    "#);
+    assert_eq!(result, 12);
+}
+
+#[test]
+fn list_element_write_needs_mut_binding() {
+    let err = expect_error(r#"
+        let xs = list_new()
+        list_push(xs, 1)
+        xs[0] = 5
+    "#);
+    assert!(err.contains("Cannot assign to an element of immutable variable 'xs'"), "got: {}", err);
+}
+
+// --- Parameters and patterns ---
+
+#[test]
+fn mut_parameter_can_be_reassigned() {
+    let result = run(r#"
+        fn countdown(mut n) -> int {
+            let mut steps = 0
+            while n > 0 {
+                n = n - 1
+                steps = steps + 1
+            }
+            steps
+        }
+        countdown(7)
+    "#);
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn parameter_reassignment_is_error() {
+    let err = expect_error("fn f(n: int) -> int {\n  n = n + 1\n  n\n}\nf(1)");
+    assert_eq!(err, "line 2: Cannot assign twice to immutable variable 'n' (a parameter; declare it as 'mut n')");
+}
+
+#[test]
+fn for_variable_is_immutable() {
+    let err = expect_error(r#"
+        let mut sum = 0
+        for i in 0..3 {
+            i = 10
+            sum = sum + i
+        }
+        sum
+    "#);
+    assert!(err.contains("Cannot assign twice to immutable variable 'i' (bound by a pattern or loop)"), "got: {}", err);
+}
+
+#[test]
+fn let_mut_pattern_makes_every_binding_mutable() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn main() -> int {
+            let mut (a, b) = (1, 2)
+            a = a + 10
+            b = b * 3
+            let mut Point { x, y } = Point { x: 4, y: 5 }
+            x = x * 2
+            y = y - 5
+            a * 10 + b + x * 1000 + y
+        }
+    "#);
+    assert_eq!(result, 8116);
+}
+
+#[test]
+fn mut_marks_single_pattern_bindings() {
+    let result = run(r#"
+        enum Option { Some(int), None }
+        struct Point { x: int, y: int }
+        fn main() -> int {
+            let (mut a, b) = (1, 2)
+            a = a + b
+            let Point { mut x, y } = Point { x: 3, y: 4 }
+            x = x + y
+            let mut bumped = 0
+            if let Some(mut n) = Some(5) {
+                n = n + 1
+                bumped = n
+            }
+            a * 100 + x * 10 + bumped
+        }
+    "#);
+    assert_eq!(result, 376);
+
+    let err = expect_error("let (mut a, b) = (1, 2)\na = 5\nb = 3\na + b");
+    assert_eq!(err, "line 3: Cannot assign twice to immutable variable 'b' (bound by a pattern or loop)");
+}
+
+#[test]
+fn every_immutable_assignment_is_reported() {
+    let err = expect_error("let a = 1\nlet b = 2\na = 3\nb = 4\na + b");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines.len(), 2, "got: {}", err);
+    assert!(lines[0].starts_with("line 3:") && lines[1].starts_with("line 4:"), "got: {}", err);
+}
@@ -0,0 +1,116 @@
+// tests/overflow.rs
+//
+// BACKEND TESTS — checked integer arithmetic. With overflow checks on (the
+// default; `--release` turns them off, `--overflow-checks` forces them)
+// Int `+`, `-`, `*` and negation lower to LLVM overflow intrinsics and
+// panic with the source line on overflow. `Int` minimum divided by -1,
+// or its remainder by -1, always panics. `wrapping_add`, `saturating_add`
+// and `checked_add` pick a behaviour explicitly.
+
+mod common;
+
+use common::{emit_ir_with, expect_error, run_binary, run_with, try_run};
+
+const MAX: &str = "fn max_int() -> int { 9223372036854775807 }\n";
+
+// --- Checked operators ---
+
+#[test]
+fn add_and_subtract_overflow_panic() {
+    let err = try_run(&format!("{}let x = max_int()\nx + 1", MAX)).unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to add with overflow");
+    let err = try_run(&format!("{}let low = 0 - max_int() - 1\nlow - 1", MAX)).unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to subtract with overflow");
+}
+
+#[test]
+fn multiply_and_negate_overflow_panic() {
+    let err = try_run(&format!("{}fn double(n: int) -> int {{\n    n * 2\n}}\ndouble(max_int())", MAX)).unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to multiply with overflow");
+    let err = try_run(&format!("{}fn neg(n: int) -> int {{\n    -n\n}}\nneg(0 - max_int() - 1)", MAX)).unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to negate with overflow");
+}
+
+#[test]
+fn divide_min_by_minus_one_panics() {
+    let err = run_with(&format!("{}let low = 0 - max_int() - 1\nlet d = 0 - 1\nlow / d", MAX), false).unwrap_err();
+    assert_eq!(err, "panicked at line 4: attempt to divide with overflow");
+}
+
+#[test]
+fn remainder_min_by_minus_one_panics() {
+    let err = try_run(&format!("{}let low = 0 - max_int() - 1\nlow % -1", MAX)).unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to calculate the remainder with overflow");
+    let result = try_run(&format!("{}let low = 0 - max_int() - 1\nlet half = low / 2\nhalf / 1000000000000 * 10 + low % 3", MAX));
+    assert_eq!(result, Ok(-46116862));
+}
+
+#[test]
+fn arithmetic_in_range_is_unchanged() {
+    let result = try_run(&format!("{}(max_int() - 7) + 7 - max_int() + 6 * 7 - -1", MAX));
+    assert_eq!(result, Ok(43));
+}
+
+#[test]
+fn unchecked_mode_wraps() {
+    let result = run_with(&format!("{}let x = max_int() + 1\nx + max_int()", MAX), false);
+    assert_eq!(result, Ok(-1));
+}
+
+#[test]
+fn ir_uses_overflow_intrinsics_only_when_checked() {
+    let source = "fn f(a: int, b: int) -> int { a * b + a - b }\nf(2, 3)";
+    let checked = emit_ir_with(source, true);
+    assert!(checked.contains("@llvm.smul.with.overflow.i64"));
+    assert!(checked.contains("@llvm.sadd.with.overflow.i64"));
+    assert!(checked.contains("@llvm.ssub.with.overflow.i64"));
+    assert!(!emit_ir_with(source, false).contains("with.overflow"));
+}
+
+// --- Explicit builtins ---
+
+#[test]
+fn wrapping_add_wraps_in_checked_mode() {
+    let result = try_run(&format!("{}wrapping_add(max_int(), 1) + max_int()", MAX));
+    assert_eq!(result, Ok(-1));
+}
+
+#[test]
+fn saturating_add_clamps() {
+    let result = try_run(&format!(
+        "{}let low = 0 - max_int() - 1\nlet a = saturating_add(max_int(), 5) - max_int()\nlet b = saturating_add(low, 0 - 5) - low\na * 10 + b + saturating_add(2, 3) * 100",
+        MAX
+    ));
+    assert_eq!(result, Ok(500));
+}
+
+#[test]
+fn checked_add_returns_option() {
+    let result = try_run(&format!(r#"
+        enum Option<T> {{ Some(T), None }}
+        {}
+        fn main() -> int {{
+            let over = match checked_add(max_int(), 1) {{ Some(x) => x, None => 7 }}
+            over * 100 + unwrap(checked_add(40, 2))
+        }}
+    "#, MAX));
+    assert_eq!(result, Ok(742));
+}
+
+#[test]
+fn checked_add_needs_declared_option() {
+    let err = expect_error("checked_add(1, 2)\n0");
+    assert_eq!(err, "`checked_add` returns Option<Int>: declare `enum Option<T> { Some(T), None }`");
+}
+
+// --- CLI ---
+
+#[test]
+fn release_flag_wraps_and_overflow_checks_flag_restores() {
+    let source = format!("{}max_int() + 1\n", MAX);
+    let release = run_binary("overflow_release", None, &source, &["--release"]);
+    assert_eq!(release.code, Some(0));
+    assert!(release.stdout.contains("Result: -9223372036854775808"), "got:\n{}", release.stdout);
+    assert_eq!(run_binary("overflow_forced", None, &source, &["--release", "--overflow-checks"]).code, Some(101));
+    assert_eq!(run_binary("overflow_default", None, &source, &[]).code, Some(101));
+}
@@ -0,0 +1,201 @@
+// tests/panics.rs
+//
+// BACKEND TESTS — runtime panics. `panic("msg")`, integer division or
+// remainder by zero, an out-of-range list or array index and `unwrap` on
+// `None`/`Err` print the message, its source line and a backtrace of AHA!
+// functions to stderr. A panic in an actor's handler is raised again in
+// the thread waiting in `call`. Under the JIT `run_jit` returns the
+// message as an `Err`; the compiler binary and native executables exit
+// with status 101.
+
+mod common;
+
+use std::process::Command;
+use common::{expect_error, run_binary, try_run};
+
+// --- panic() ---
+
+#[test]
+fn panic_returns_err_with_its_message() {
+    let err = try_run("let x = 1\npanic(\"something broke\")\n0").unwrap_err();
+    assert_eq!(err, "panicked at line 2: something broke");
+
+    let err = try_run(r#"
+        fn check(n: int) -> int {
+            if n < 0 {
+                panic("negative input: " + int_to_string(n))
+            }
+            n
+        }
+        check(0 - 3)
+    "#).unwrap_err();
+    assert_eq!(err, "panicked at line 4: negative input: -3");
+}
+
+#[test]
+fn panic_in_untaken_branch_does_not_fire() {
+    let result = try_run(r#"
+        fn always() -> int { panic("unreachable") }
+        fn half(n: int) -> int {
+            if n % 2 == 1 { panic("odd") } else { n / 2 }
+        }
+        fn main() -> int {
+            let mut i = 0
+            while i < 3 {
+                if i == 5 { always() }
+                i = i + 1
+            }
+            half(10) + i
+        }
+    "#);
+    assert_eq!(result, Ok(8));
+}
+
+#[test]
+fn panic_message_must_be_a_string() {
+    let err = expect_error("panic(42)");
+    assert_eq!(err, "line 1: Builtin 'panic' argument 'message' expects String, got Int (signature: panic(message: String) -> Never)");
+}
+
+// --- Compiler-inserted checks ---
+
+#[test]
+fn division_and_remainder_by_zero_panic() {
+    let err = try_run("fn div(a: int, b: int) -> int { a / b }\ndiv(7, 0)").unwrap_err();
+    assert_eq!(err, "panicked at line 1: attempt to divide by zero");
+
+    let err = try_run("let a = 7\nlet b = a - 7\na % b").unwrap_err();
+    assert_eq!(err, "panicked at line 3: attempt to calculate the remainder with a divisor of zero");
+}
+
+#[test]
+fn list_read_and_write_out_of_bounds_panic() {
+    let err = try_run(r#"
+        let xs = list_new()
+        list_push(xs, 10)
+        list_push(xs, 20)
+        xs[2]
+    "#).unwrap_err();
+    assert_eq!(err, "panicked at line 5: index out of bounds: the index is 2 but the length is 2");
+
+    let err = try_run("let mut xs = list_new()\nlist_push(xs, 1)\nxs[4] = 9\n0").unwrap_err();
+    assert_eq!(err, "panicked at line 3: index out of bounds: the index is 4 but the length is 1");
+}
+
+#[test]
+fn unmatched_enum_value_panics() {
+    let err = try_run(r#"
+        enum Option { Some(int), None }
+        fn f(o: Option) -> int {
+            match o {
+                Some(0) => 1,
+                None => 0,
+            }
+        }
+        fn main() -> int { f(Some(5)) }
+    "#).unwrap_err();
+    assert_eq!(err, "panicked at line 4: no match arm matched the value");
+}
+
+// --- unwrap ---
+
+#[test]
+fn unwrap_some_and_ok() {
+    let result = try_run(r#"
+        enum Option { Some(int), None }
+        enum Outcome { Ok(string), Err(int) }
+        fn find(n: int) -> Option { if n > 0 { Some(n * 10) } else { Option::None } }
+        fn main() -> int {
+            let name: Outcome = Ok("abc")
+            unwrap(find(4)) + len(unwrap(name))
+        }
+    "#);
+    assert_eq!(result, Ok(43));
+}
+
+#[test]
+fn unwrap_none_and_err_panic() {
+    let none = try_run(r#"
+        enum Option { Some(int), None }
+        let o: Option = Option::None
+        unwrap(o)
+    "#).unwrap_err();
+    assert_eq!(none, "panicked at line 4: called `unwrap` on a `None` value");
+
+    let err = try_run(r#"
+        enum Outcome { Ok(int), Err(string) }
+        let r: Outcome = Err("bad")
+        unwrap(r)
+    "#).unwrap_err();
+    assert_eq!(err, "panicked at line 4: called `unwrap` on an `Err` value");
+}
+
+#[test]
+fn unwrap_needs_option_or_result() {
+    let err = expect_error("let n = 3\nunwrap(n)");
+    assert_eq!(err, "line 2: `unwrap` needs an Option or Result, got Int");
+}
+
+// --- Actors ---
+
+#[test]
+fn actor_panic_is_raised_in_the_caller() {
+    let source = |msg: i64| format!(r#"
+        actor Divider {{ id: int }}
+        fn handle(state, msg) -> int {{
+            if msg < 0 {{ panic("negative message") }}
+            100 / msg
+        }}
+        let d = spawn Divider {{ id: 1 }}
+        call(d, 4) + call(d, {})
+    "#, msg);
+    assert_eq!(try_run(&source(5)), Ok(45));
+    assert_eq!(try_run(&source(-1)).unwrap_err(), "panicked at line 4: negative message");
+    assert_eq!(try_run(&source(0)).unwrap_err(), "panicked at line 5: attempt to divide by zero");
+}
+
+#[test]
+fn call_on_a_dead_actor_panics() {
+    let err = try_run("let x = 1
+call(12345, 1)").unwrap_err();
+    assert_eq!(err, "panicked at line 2: called `call` on an actor that is not running");
+
+    // A panic while handling a `send` ends the actor.
+    let err = try_run(r#"
+        actor Worker { id: int }
+        fn handle(state, msg) -> int {
+            if msg == 0 { panic("stopped") }
+            msg
+        }
+        let w = spawn Worker { id: 1 }
+        send(w, 0)
+        call(w, 7)
+    "#).unwrap_err();
+    assert_eq!(err, "panicked at line 9: called `call` on an actor that is not running");
+}
+
+// --- Backtraces and exit codes ---
+
+#[test]
+fn binary_prints_backtrace_and_exits_101() {
+    let source = "fn inner(n: int) -> int {\n    100 / n\n}\nfn outer(n: int) -> int {\n    inner(n - 1)\n}\nfn done() -> int { 1 }\ndone()\nouter(1)\n";
+    let run = run_binary("panics_trace", None, source, &[]);
+    assert_eq!(run.code, Some(101));
+    assert!(
+        run.stderr.contains("panicked at line 2: attempt to divide by zero\nbacktrace:\n  0: inner\n  1: outer\n  2: main\n"),
+        "got:\n{}", run.stderr
+    );
+}
+
+#[test]
+fn native_executable_exits_101() {
+    let exe = std::env::temp_dir().join(format!("aha_panics_native_exe_{}", std::process::id()));
+    let exe_arg = exe.to_str().unwrap();
+    let build = run_binary("panics_native", None, "fn f() -> int {\n    panic(\"native\")\n}\nf()\n", &["--emit-exe", exe_arg]);
+    assert_eq!(build.code, Some(0), "build failed:\n{}", build.stderr);
+    let output = Command::new(&exe).output().expect("run native executable");
+    let _ = std::fs::remove_file(&exe);
+    assert_eq!(output.status.code(), Some(101));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("panicked at line 2: native\nbacktrace:\n  0: f\n  1: main\n"), "got:\n{}", stderr);
+}
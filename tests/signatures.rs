@@ -0,0 +1,139 @@
+// tests/signatures.rs
+//
+// TYPECK TESTS — arity and signature checking. Every call to a user
+// function, builtin or enum constructor is checked for its argument
+// count and (where the types are known) argument types before codegen;
+// the error shows the expected signature.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- User functions ---
+
+#[test]
+fn signature_user_fn_arity_shows_signature() {
+    let err = expect_error("fn area(w: int, h: int) -> int { w * h }\narea(3)");
+    assert_eq!(
+        err,
+        "line 2: Function 'area' expects 2 arguments, got 1 (signature: area(w: Int, h: Int) -> Int)"
+    );
+}
+
+#[test]
+fn signature_user_fn_arg_type() {
+    let err = expect_error(r#"
+        fn greet(name: string) -> int { len(name) }
+        greet(42)
+    "#);
+    assert!(err.contains("Function 'greet' argument 'name' expects String, got Int"), "got: {}", err);
+    assert!(err.contains("(signature: greet(name: String) -> Int)"), "got: {}", err);
+}
+
+#[test]
+fn signature_user_fn_struct_arg() {
+    let err = expect_error(r#"
+        struct P { x: int }
+        struct Q { x: int }
+        fn get(p: P) -> int { p.x }
+        get(Q { x: 1 })
+    "#);
+    assert!(err.contains("argument 'p' expects P, got Q"), "got: {}", err);
+}
+
+#[test]
+fn signature_bool_and_int_mix() {
+    let result = run(r#"
+        fn pick(flag: bool, n: int) -> int { if flag { n } else { 0 } }
+        pick(3 > 1, true) + pick(true, 4)
+    "#);
+    assert_eq!(result, 5);
+}
+
+// --- Builtins ---
+
+#[test]
+fn signature_builtin_arity() {
+    let err = expect_error(r#"string_sub("hello", 1)"#);
+    assert!(err.contains(
+        "Builtin 'string_sub' expects 3 arguments, got 2 (signature: string_sub(s: String, start: Int, len: Int) -> String)"
+    ), "got: {}", err);
+}
+
+#[test]
+fn signature_print_rejects_string() {
+    let err = expect_error(r#"print("hello")"#);
+    assert!(err.contains("Builtin 'print' argument 'value' expects Int, got String"), "got: {}", err);
+}
+
+#[test]
+fn signature_list_push_element_type() {
+    let err = expect_error(r#"
+        let xs = list_new_string()
+        list_push(xs, 5)
+    "#);
+    assert!(err.contains("Builtin 'list_push' argument 'value' expects String, got Int"), "got: {}", err);
+    assert!(err.contains("list_push(list: List<T>, value: T) -> List<T>"), "got: {}", err);
+
+    let err = expect_error("list_len(5)");
+    assert!(err.contains("Builtin 'list_len' argument 'list' expects List<T>, got Int"), "got: {}", err);
+}
+
+#[test]
+fn signature_map_builtins() {
+    let err = expect_error(r#"
+        let m = map_string_key_new()
+        map_get(m, 1)
+    "#);
+    assert!(err.contains("Builtin 'map_get' argument 'key' expects String, got Int"), "got: {}", err);
+
+    let err = expect_error(r#"
+        let m = map_new()
+        map_set(m, 1)
+    "#);
+    assert!(err.contains(
+        "Builtin 'map_set' expects 3 arguments, got 2 (signature: map_set(map: Map<K, V>, key: K, value: V) -> Map<K, V>)"
+    ), "got: {}", err);
+}
+
+// --- Enum constructors ---
+
+#[test]
+fn signature_enum_constructor_arity() {
+    let err = expect_error(r#"
+        enum Shape { Circle(int), Rect(int, int) }
+        let s = Rect(1)
+    "#);
+    assert!(err.contains(
+        "Enum variant 'Shape::Rect' expects 2 arguments, got 1 (signature: Shape::Rect(int, int))"
+    ), "got: {}", err);
+}
+
+#[test]
+fn signature_enum_payload_type() {
+    let err = expect_error(r#"
+        enum Shape { Circle(int), Rect(int, int) }
+        struct P { x: int }
+        let s = Circle(P { x: 1 })
+    "#);
+    assert!(err.contains("Enum variant 'Shape::Circle' arg 0 expects Int, got P"), "got: {}", err);
+}
+
+#[test]
+fn signature_generic_enum_constructor_arity() {
+    let err = expect_error(r#"
+        enum Option<T> { Some(T), None }
+        let o = Some(1, 2)
+    "#);
+    assert!(err.contains(
+        "Enum variant 'Option::Some' expects 1 argument, got 2 (signature: Option<T>::Some(T))"
+    ), "got: {}", err);
+}
+
+#[test]
+fn signature_all_call_errors_reported() {
+    let err = expect_error("fn f(a: int) -> int { a }\nf()\nabs(1, 2)\nf(\"x\")");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines.len(), 3, "got: {}", err);
+    assert!(lines[0].starts_with("line 2:") && lines[1].starts_with("line 3:") && lines[2].starts_with("line 4:"), "got: {}", err);
+}
@@ -0,0 +1,241 @@
+// tests/statics.rs
+//
+// BACKEND TESTS — module-level `static` globals. Statics are LLVM
+// globals with compile-time initializers, readable from every function;
+// `static mut` ones are writable too. Actor handlers run on their own
+// threads, so code reachable from `handle` may read immutable statics but
+// not touch `static mut` ones.
+
+mod common;
+
+use common::{emit_ir, expect_error, run};
+
+// --- Reading and writing ---
+
+#[test]
+fn static_read_from_function_and_shadowed() {
+    let result = run(r#"
+        static LIMIT: int = 40 + 2
+        fn limit() -> int { LIMIT }
+        limit() + LIMIT
+    "#);
+    assert_eq!(result, 84);
+
+    // A local of the same name shadows the static inside its function.
+    let result = run(r#"
+        static mut X: int = 1
+        fn f() -> int {
+            let mut X = 10
+            X = X + 1
+            X
+        }
+        f() + X
+    "#);
+    assert_eq!(result, 12);
+}
+
+#[test]
+fn static_mut_shared_between_functions_and_top_level() {
+    let result = run(r#"
+        static mut COUNTER: int = 0
+        fn bump() -> int {
+            COUNTER = COUNTER + 1
+            COUNTER
+        }
+        fn reset() -> int {
+            COUNTER = 0
+            0
+        }
+        bump()
+        bump()
+        reset()
+        bump()
+        bump()
+        bump()
+        COUNTER
+    "#);
+    assert_eq!(result, 3);
+
+    // Top-level code writes the same global the functions see.
+    let result = run(r#"
+        static mut TOTAL = 10
+        fn add(n) -> int {
+            TOTAL = TOTAL + n
+            TOTAL
+        }
+        TOTAL = 100
+        add(5)
+    "#);
+    assert_eq!(result, 105);
+}
+
+#[test]
+fn static_string() {
+    let result = run(r#"
+        static GREETING: string = "hello" + " world"
+        static mut LAST: string = "none"
+        fn remember(s: string) -> int {
+            let copy = s + "!"
+            LAST = copy
+            0
+        }
+        remember("abc")
+        len(GREETING) + len(LAST)
+    "#);
+    assert_eq!(result, 15);
+}
+
+#[test]
+fn static_string_reassignment_frees_old_value() {
+    let source = r#"
+        static mut LOG: string = "start"
+        fn log(s: string) { LOG = LOG + s }
+        fn snapshot() -> string { LOG }
+        fn main() -> int {
+            let first = LOG
+            let mut i = 0
+            while i < 1000 {
+                log("x")
+                i = i + 1
+            }
+            let before = snapshot()
+            LOG = "reset"
+            LOG = before
+            len(first) * 100000 + len(before) + len(LOG) + char_at(before, 1004)
+        }
+    "#;
+    // `first` and `before` are copies, so they outlive the buffers
+    // freed by later assignments.
+    assert_eq!(run(source), 500000 + 1005 + 1005 + 120);
+    let ir = emit_ir(source);
+    assert!(ir.contains("%static_to_free = select i1 %static_is_initial, i8* null"), "IR:\n{}", ir);
+}
+
+#[test]
+fn static_string_used_by_value_is_copied() {
+    let source = r#"
+        static mut NAME: string = "start"
+        struct Tag { name: string }
+        enum Label { Named(string), Blank }
+        fn keep(s: string) -> Tag { Tag { name: s } }
+        fn main() -> int {
+            NAME = "first" + "!"
+            let kept = keep(NAME)
+            let tag = Tag { name: NAME }
+            let label = Named(NAME)
+            let pair = (NAME, 1)
+            let mut names = list_new_string()
+            list_push(names, NAME)
+            let mut by_id = map_string_val_new()
+            map_string_val_set(by_id, 1, NAME)
+            let mut local = "local"
+            local = NAME
+            NAME = "XXXXX" + "Y"
+            let from_label = match label { Named(s) => s, Blank => "" }
+            char_at(kept.name, 0) + char_at(tag.name, 1) + char_at(from_label, 2)
+                + char_at(pair.0, 3) + char_at(list_get(names, 0), 4)
+                + char_at(map_string_val_get(by_id, 1), 5) + char_at(local, 0)
+        }
+    "#;
+    // Every use reads "first!", not the buffer reused after `NAME` freed it.
+    assert_eq!(run(source), 102 + 105 + 114 + 115 + 116 + 33 + 102);
+}
+
+#[test]
+fn static_initializer_uses_consts_and_pure_functions() {
+    let result = run(r#"
+        const BASE: int = 100
+        fn square(n) -> int { n * n }
+        static mut LEVEL: int = BASE + square(3)
+        fn level() -> int { LEVEL }
+        level()
+    "#);
+    assert_eq!(result, 109);
+}
+
+#[test]
+fn statics_are_llvm_globals() {
+    let ir = emit_ir("static mut HITS: int = 7\nstatic NAME: string = \"ab\"\nHITS");
+    assert!(ir.contains("@static.HITS = global i64 7"), "IR:\n{}", ir);
+    assert!(ir.contains("@static.NAME = constant"), "IR:\n{}", ir);
+}
+
+// --- Errors ---
+
+#[test]
+fn immutable_static_assignment_is_error() {
+    let err = expect_error("static X: int = 1\nfn f() -> int {\n  X = 2\n  X\n}\nf()");
+    assert_eq!(err, "line 3: Cannot assign to immutable static 'X' (declared at line 1; use 'static mut X')");
+}
+
+#[test]
+fn static_initializer_must_be_constant() {
+    let err = expect_error("static mut A: int = 1\nstatic B: int = A + 1\nstatic C = list_new()\n0");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 2: Static 'B': 'A' is not a constant",
+        "line 3: Static 'C': call to 'list_new' is not allowed in a constant expression",
+    ]);
+}
+
+#[test]
+fn static_inside_function_is_error() {
+    let err = expect_error("fn f() -> int {\n  static S = 1\n  S\n}\nf()");
+    assert!(err.contains("line 2: Static 'S' must be declared at module level"), "got: {}", err);
+}
+
+#[test]
+fn static_type_mismatch_is_error() {
+    let err = expect_error("static mut NAME: string = \"a\"\nfn f() -> int {\n  NAME = 5\n  0\n}\nf()");
+    assert!(err.contains("Static 'NAME' has type String, cannot assign Int"), "got: {}", err);
+}
+
+#[test]
+fn static_of_unsupported_type_is_error() {
+    let err = expect_error("static A: [int; 3] = [1, 2, 3]\nstatic mut P: (int, bool) = (1, true)\n0");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 1: Static 'A': static of type [Int; 3] is not supported",
+        "line 2: Static 'P': static of type (Int, Bool) is not supported",
+    ]);
+}
+
+// --- Actors ---
+
+#[test]
+fn actor_handler_reads_immutable_static() {
+    let result = run(r#"
+        static SCALE: int = 3
+        actor Scaler {
+            id: int
+        }
+        fn handle(state, msg) -> int {
+            msg * SCALE
+        }
+        let a = spawn Scaler { id: 1 }
+        call(a, 14)
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn actor_code_cannot_touch_static_mut() {
+    let err = expect_error(r#"
+        static mut HITS: int = 0
+        actor Counter {
+            id: int
+        }
+        fn record(n) -> int {
+            HITS = HITS + n
+            HITS
+        }
+        fn handle(state, msg) -> int {
+            record(msg)
+        }
+        let a = spawn Counter { id: 1 }
+        call(a, 1)
+    "#);
+    assert!(err.contains(
+        "line 6: Function 'record' runs on actor threads (called from 'handle') and cannot use static mut 'HITS'"
+    ), "got: {}", err);
+}
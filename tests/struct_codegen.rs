@@ -111,10 +111,11 @@ fn test_struct_literal_field_order_independent() {
 }
 
 #[test]
-fn test_struct_missing_field_defaults_to_zero() {
-    // A field omitted from the literal defaults to 0 (const_zero base).
-    let result = run("struct Point { x, y } let p = Point { x: 5 }; p.y");
-    assert_eq!(result, 0);
+fn test_struct_missing_field_is_error() {
+    // A field omitted from the literal, with no declared default, is an
+    // error instead of silently reading as 0.
+    let err = expect_codegen_error("struct Point { x, y } let p = Point { x: 5 }; p.y");
+    assert_eq!(err, "Missing field 'y' in 'Point' literal");
 }
 
 // =====================================================================
@@ -164,7 +165,7 @@ fn test_struct_field_in_if_condition_false() {
 #[test]
 fn test_struct_field_accumulated_in_loop() {
     let result = run(
-        "struct Step { by } let s = Step { by: 3 }; let total = 0; for i in 0..4 { total = total + s.by; } total"
+        "struct Step { by } let s = Step { by: 3 }; let mut total = 0; for i in 0..4 { total = total + s.by; } total"
     );
     assert_eq!(result, 12);
 }
@@ -248,11 +249,10 @@ fn test_typed_string_field_equality() {
 }
 
 #[test]
-fn test_missing_typed_string_field_defaults() {
-    // An omitted string field still zeroes out; reading it back yields
-    // an empty string (len 0).
-    let result = run("struct P { name: string, age: int } let p = P { age: 5 }; len(p.name)");
-    assert_eq!(result, 0);
+fn test_missing_typed_string_field_uses_default() {
+    // An omitted string field takes its declared default.
+    let result = run("struct P { name: string = \"anon\", age: int } let p = P { age: 5 }; len(p.name)");
+    assert_eq!(result, 4);
 }
 
 #[test]
@@ -317,44 +317,44 @@ fn test_field_access_on_non_struct_is_error() {
 
 #[test]
 fn test_mutate_int_field() {
-    let result = run("struct P { x, y } let p = P { x: 1, y: 2 }; p.x = 99; p.x");
+    let result = run("struct P { x, y } let mut p = P { x: 1, y: 2 }; p.x = 99; p.x");
     assert_eq!(result, 99);
 }
 
 #[test]
 fn test_mutate_int_field_and_read_other() {
-    let result = run("struct P { x, y } let p = P { x: 1, y: 2 }; p.x = 99; p.y");
+    let result = run("struct P { x, y } let mut p = P { x: 1, y: 2 }; p.x = 99; p.y");
     assert_eq!(result, 2);
 }
 
 #[test]
 fn test_mutate_then_arithmetic() {
-    let result = run("struct P { x, y } let p = P { x: 10, y: 20 }; p.x = 30; p.x + p.y");
+    let result = run("struct P { x, y } let mut p = P { x: 10, y: 20 }; p.x = 30; p.x + p.y");
     assert_eq!(result, 50);
 }
 
 #[test]
 fn test_double_mutation() {
-    let result = run("struct P { x, y } let p = P { x: 1, y: 2 }; p.x = 5; p.y = 6; p.x * p.y");
+    let result = run("struct P { x, y } let mut p = P { x: 1, y: 2 }; p.x = 5; p.y = 6; p.x * p.y");
     assert_eq!(result, 30);
 }
 
 #[test]
 fn test_mutate_string_field() {
-    let result = run("struct P { name: string } let p = P { name: \"hello\" }; p.name = \"world\"; len(p.name)");
+    let result = run("struct P { name: string } let mut p = P { name: \"hello\" }; p.name = \"world\"; len(p.name)");
     assert_eq!(result, 5);
 }
 
 #[test]
 fn test_mutate_string_field_equality() {
-    let result = run("struct P { name: string } let p = P { name: \"hello\" }; p.name = \"world\"; p.name == \"world\"");
+    let result = run("struct P { name: string } let mut p = P { name: \"hello\" }; p.name = \"world\"; p.name == \"world\"");
     assert_eq!(result, 1);
 }
 
 #[test]
 fn test_mutate_field_in_loop() {
     let result = run(
-        "struct P { x } let p = P { x: 0 }; for i in 0..3 { p.x = p.x + 1; } p.x"
+        "struct P { x } let mut p = P { x: 0 }; for i in 0..3 { p.x = p.x + 1; } p.x"
     );
     assert_eq!(result, 3);
 }
@@ -362,7 +362,7 @@ fn test_mutate_field_in_loop() {
 #[test]
 fn test_mutate_field_wrong_type_is_error() {
     let err = expect_codegen_error(
-        "struct P { name: string } let p = P { name: \"x\" }; p.name = 42; p.name"
+        "struct P { name: string } let mut p = P { name: \"x\" }; p.name = 42; p.name"
     );
     assert!(
         err.contains("expects a string"),
@@ -375,13 +375,13 @@ fn test_mutate_field_wrong_type_is_error() {
 fn test_plain_variable_assignment_still_works() {
     // Ensure the change to generic assignment target didn't break
     // plain x = value assignments.
-    let result = run("let x = 5; x = 10; x");
+    let result = run("let mut x = 5; x = 10; x");
     assert_eq!(result, 10);
 }
 
 #[test]
 fn test_plain_variable_mutate_in_loop() {
-    let result = run("let total = 0; for i in 0..5 { total = total + i; } total");
+    let result = run("let mut total = 0; for i in 0..5 { total = total + i; } total");
     assert_eq!(result, 10);
 }
 
@@ -404,7 +404,7 @@ fn test_struct_param_mutated_inside_function() {
     // Mutating a struct parameter inside a function writes back to the
     // function-local copy (by value), so the caller's copy is untouched.
     let result = run(
-        "struct Point { x, y } fn bump(p) { p.x = p.x + 1; p.x } let p = Point { x: 5, y: 0 }; bump(p) + p.x"
+        "struct Point { x, y } fn bump(mut p) { p.x = p.x + 1; p.x } let p = Point { x: 5, y: 0 }; bump(p) + p.x"
     );
     assert_eq!(result, 11);
 }
@@ -453,7 +453,7 @@ fn test_struct_returned_directly_from_param() {
 #[test]
 fn test_struct_return_with_mutation_inside() {
     let result = run(
-        "struct Point { x, y } fn shifted(p) { p.x = p.x + 100; p } let p = Point { x: 1, y: 2 }; let q = shifted(p); q.x - p.x"
+        "struct Point { x, y } fn shifted(mut p) { p.x = p.x + 100; p } let p = Point { x: 1, y: 2 }; let q = shifted(p); q.x - p.x"
     );
     assert_eq!(result, 100);
 }
@@ -462,7 +462,7 @@ fn test_struct_return_with_mutation_inside() {
 fn test_struct_return_then_field_mutation() {
     // The returned struct is stored in a let binding, then mutated.
     let result = run(
-        "struct Point { x, y } fn make(x, y) { Point { x: x, y: y } } let p = make(1, 2); p.x = 9; p.x + p.y"
+        "struct Point { x, y } fn make(x, y) { Point { x: x, y: y } } let mut p = make(1, 2); p.x = 9; p.x + p.y"
     );
     assert_eq!(result, 11);
 }
@@ -0,0 +1,207 @@
+// tests/struct_defaults.rs
+//
+// BACKEND TESTS — struct field defaults, struct update syntax and
+// missing-field errors. A literal (or `spawn`) must set every field that
+// has no declared default; `Config { retries: 5, ..base }` copies the
+// omitted fields from `base`.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Defaults ---
+
+#[test]
+fn omitted_fields_take_defaults() {
+    let result = run(r#"
+        struct Config {
+            retries: int = 3,
+            timeout: int = 30,
+            port: int
+        }
+        let c = Config { port: 8080 }
+        c.retries * 10000 + c.timeout * 100 + c.port - 8000
+    "#);
+    assert_eq!(result, 33080);
+}
+
+#[test]
+fn given_field_overrides_default() {
+    let result = run(r#"
+        struct Config {
+            retries: int = 3,
+            name: string = "default"
+        }
+        let c = Config { retries: 7, name: "prod" }
+        c.retries * 10 + len(c.name)
+    "#);
+    assert_eq!(result, 74);
+
+    // Fields with defaults may also be separated by newlines.
+    let result = run(r#"
+        struct User {
+            name: string = "guest"
+            level: int = 1
+        }
+        let u = User { level: 4 }
+        len(u.name) * u.level
+    "#);
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn default_expression_uses_consts() {
+    let result = run(r#"
+        const BASE: int = 100
+        struct Limits {
+            soft: int = BASE,
+            hard: int = BASE * 2 + 1
+        }
+        let l = Limits {}
+        l.soft + l.hard
+    "#);
+    assert_eq!(result, 301);
+}
+
+#[test]
+fn generic_struct_default() {
+    let result = run(r#"
+        struct Tagged<T> {
+            value: T,
+            tag: int = 9
+        }
+        let t = Tagged { value: "abc" }
+        len(t.value) + t.tag
+    "#);
+    assert_eq!(result, 12);
+}
+
+// --- Struct update syntax ---
+
+#[test]
+fn update_copies_omitted_fields() {
+    let result = run(r#"
+        struct Config {
+            retries: int,
+            timeout: int,
+            host: string
+        }
+        let base = Config { retries: 1, timeout: 50, host: "example" }
+        let c = Config { retries: 5, ..base }
+        c.retries * 1000 + c.timeout + len(c.host) + base.retries * 100000
+    "#);
+    assert_eq!(result, 105057);
+}
+
+#[test]
+fn update_from_function_result() {
+    let result = run(r#"
+        struct Point {
+            x: int,
+            y: int,
+            z: int = 0
+        }
+        fn origin() -> Point { Point { x: 0, y: 0 } }
+        let p = Point { y: 4, ..origin() }
+        let q = Point { z: 2, ..p }
+        q.x + q.y * 10 + q.z * 100
+    "#);
+    assert_eq!(result, 240);
+}
+
+#[test]
+fn update_of_generic_struct_keeps_instance() {
+    let result = run(r#"
+        struct Pair<A, B> {
+            first: A,
+            second: B
+        }
+        let p = Pair { first: 1, second: "hello" }
+        let q = Pair { first: 41, ..p }
+        q.first + len(q.second) - 5
+    "#);
+    assert_eq!(result, 41);
+}
+
+// --- Errors ---
+
+#[test]
+fn missing_fields_are_listed() {
+    let err = expect_error("struct Config { host: string, port: int, retries: int = 3 }\nlet c = Config {}\n0");
+    assert_eq!(err, "Missing fields 'host', 'port' in 'Config' literal");
+}
+
+#[test]
+fn unknown_or_repeated_field_is_error() {
+    let err = expect_error("struct Config { retries: int = 3 }\nlet c = Config { retry: 5 }\n0");
+    assert_eq!(err, "Struct 'Config' has no field 'retry'");
+    let err = expect_error("struct P { x: int }\nlet p = P { x: 1, x: 2 }\n0");
+    assert_eq!(err, "Field 'x' is set twice in 'P' literal");
+}
+
+#[test]
+fn update_base_of_wrong_type_is_error() {
+    let err = expect_error("struct A { x: int }\nstruct B { x: int }\nlet b = B { x: 1 }\nlet a = A { ..b }\n0");
+    assert_eq!(err, "Struct update base for 'A' has type B, expected A");
+}
+
+#[test]
+fn spawn_missing_field_is_error() {
+    let err = expect_error(r#"
+actor Counter {
+    count: int,
+    step: int = 1
+}
+fn handle(state, msg) -> int { msg }
+let c = spawn Counter { step: 2 }
+0
+"#);
+    assert_eq!(err, "Missing field 'count' in 'Counter' literal");
+}
+
+#[test]
+fn default_must_be_constant() {
+    let err = expect_error(r#"
+let limit = 5
+struct Window {
+    size: int = limit
+}
+fn make() -> Window {
+    let limit = 99
+    Window {}
+}
+make().size
+"#);
+    assert_eq!(err, "line 4: Default of field 'size' of 'Window': 'limit' is not a constant");
+
+    // Actor field defaults follow the same rule.
+    let err = expect_error(r#"
+fn start() -> int { 1 }
+actor Counter {
+    count: int = start() + print_count
+}
+0
+"#);
+    assert_eq!(err, "line 4: Default of field 'count' of 'Counter': 'print_count' is not a constant");
+}
+
+#[test]
+fn default_must_fit_field_type() {
+    let err = expect_error(r#"
+struct Config {
+    name: string = 3,
+    retries = "many"
+}
+struct Box<T> {
+    value: T = 0
+}
+0
+"#);
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 3: Default of field 'name' of 'Config': declared as String but the value is Int",
+        "line 4: Default of field 'retries' of 'Config': declared as Int but the value is String",
+        "line 7: Default of field 'value' of 'Box': a field of type 'T' cannot have a default (use int, bool or string)",
+    ]);
+}
+
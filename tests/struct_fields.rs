@@ -0,0 +1,211 @@
+// tests/struct_fields.rs
+//
+// BACKEND TESTS — struct fields of any type. Nested structs, enums, tuples,
+// lists, maps and generic struct instances are laid out by value with their
+// real LLVM types; chained access and assignment (`line.start.x = 3`) work
+// through any depth, and a struct that contains itself without a `Box` is
+// rejected.
+
+mod common;
+
+use common::{expect_error, run};
+
+// --- Nested structs ---
+
+#[test]
+fn nested_struct_chained_access_and_assignment() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Line { start: Point, end: Point }
+        let l = Line { start: Point { x: 1, y: 2 }, end: Point { x: 3, y: 4 } }
+        l.end.x * 10 + l.start.y
+    "#);
+    assert_eq!(result, 32);
+
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Line { start: Point, end: Point }
+        let mut l = Line { start: Point { x: 1, y: 2 }, end: Point { x: 3, y: 4 } }
+        l.start.x = 10
+        l.start.x + l.end.y
+    "#);
+    assert_eq!(result, 14);
+}
+
+#[test]
+fn three_level_assignment_in_function() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Rect { origin: Point, w: int }
+        struct Scene { main: Rect, id: int }
+        fn main() -> int {
+            let mut s = Scene { main: Rect { origin: Point { x: 1, y: 2 }, w: 5 }, id: 9 }
+            s.main.origin.y = 40
+            s.main.w = s.main.w + 1
+            s.main.origin.y + s.main.w - s.main.origin.x - s.id
+        }
+    "#);
+    assert_eq!(result, 36);
+}
+
+#[test]
+fn nested_struct_passed_and_returned() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Rect { origin: Point, w: int }
+        fn shift(r: Rect, dx: int) -> Rect {
+            let mut out = r
+            out.origin.x = out.origin.x + dx
+            out
+        }
+        let r = shift(Rect { origin: Point { x: 1, y: 2 }, w: 3 }, 10)
+        r.origin.x + r.w
+    "#);
+    assert_eq!(result, 14);
+}
+
+// --- Enum, tuple and collection fields ---
+
+#[test]
+fn enum_and_tuple_fields() {
+    let result = run(r#"
+        enum Shape { Circle(int), Square(int) }
+        struct Item { shape: Shape, tag: (int, string) }
+        fn size(i: Item) -> int {
+            match i.shape {
+                Circle(r) => r * 2,
+                Square(s) => s,
+            }
+        }
+        let i = Item { shape: Circle(5), tag: (7, "abc") }
+        size(i) + i.tag.0 + len(i.tag.1)
+    "#);
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn tuple_elements_are_assignable() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        struct Item { tag: (int, string), corners: (Point, Point) }
+        fn main() -> int {
+            let mut pair = (1, "a")
+            pair.0 = 40
+            pair.1 = "bcd"
+            let mut i = Item { tag: (0, ""), corners: (Point { x: 0, y: 0 }, Point { x: 0, y: 0 }) }
+            i.tag.0 = 100
+            i.corners.1.y = 7
+            pair.0 + len(pair.1) + i.tag.0 + i.corners.1.y * 1000
+        }
+    "#);
+    assert_eq!(result, 7143);
+}
+
+#[test]
+fn enum_field_with_boxed_payload() {
+    let result = run(r#"
+        enum Tree { Leaf(int), Node(Box<Tree>, Box<Tree>) }
+        struct Wrapper { root: Tree, depth: int }
+        fn sum(t: Tree) -> int {
+            match t {
+                Leaf(n) => n,
+                Node(a, b) => sum(a) + sum(b),
+            }
+        }
+        fn main() -> int {
+            let w = Wrapper { root: Node(Leaf(3), Node(Leaf(4), Leaf(5))), depth: 2 }
+            sum(w.root) + w.depth
+        }
+    "#);
+    assert_eq!(result, 14);
+}
+
+#[test]
+fn list_and_map_fields() {
+    let result = run(r#"
+        struct Inventory { items: List<string>, counts: Map<string, int> }
+        fn make() -> Inventory {
+            let items = list_push(list_push(list_new_string(), "apple"), "pear")
+            let counts = map_string_key_set(map_string_key_new(), "apple", 3)
+            Inventory { items: items, counts: counts }
+        }
+        fn main() -> int {
+            let inv = make()
+            list_len(inv.items) * 10 + map_string_key_get(inv.counts, "apple") + len(list_get(inv.items, 1))
+        }
+    "#);
+    assert_eq!(result, 27);
+
+    let result = run(r#"
+        struct Bag { xs: List<int> }
+        fn main() -> int {
+            let mut b = Bag { xs: list_new() }
+            let ys = list_push(list_push(list_new(), 20), 22)
+            b.xs = ys
+            list_get(b.xs, 0) + list_get(b.xs, 1)
+        }
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn generic_struct_instance_field() {
+    let result = run(r#"
+        struct Pair<A, B> { first: A, second: B }
+        struct Entry { key: Pair<int, string>, score: int }
+        let e = Entry { key: Pair { first: 4, second: "abc" }, score: 10 }
+        e.key.first + len(e.key.second) + e.score
+    "#);
+    assert_eq!(result, 17);
+}
+
+// --- Errors ---
+
+#[test]
+fn unknown_field_type_is_error() {
+    let err = expect_error("struct Config { mode: Modee }\n0");
+    assert_eq!(err, "Unknown type 'Modee' for field 'mode' of 'Config'");
+}
+
+#[test]
+fn recursive_struct_without_box_is_error() {
+    let err = expect_error("struct A { b: B }\nstruct B { a: A }\n0");
+    assert_eq!(err, "Recursive struct 'A' has infinite size: wrap the recursive field in Box<A>");
+}
+
+#[test]
+fn wrong_value_for_field_is_error() {
+    let err = expect_error("struct Bag { xs: List<int> }\nlet b = Bag { xs: 5 }\n0");
+    assert_eq!(err, "Field 'xs' of 'Bag' expects List<Int>, got Int");
+    let err = expect_error(r#"
+struct Point { x: int, y: int }
+struct Size { w: int, h: int }
+struct Rect { origin: Point, size: Size }
+let mut r = Rect { origin: Point { x: 1, y: 2 }, size: Size { w: 3, h: 4 } }
+r.origin = Size { w: 1, h: 2 }
+0
+"#);
+    assert_eq!(err, "Field 'origin' of 'Rect' expects Point, got Size");
+}
+
+#[test]
+fn assigning_through_a_non_place_is_error() {
+    let err = expect_error(r#"
+struct Point { x: int, y: int }
+fn origin() -> Point { Point { x: 0, y: 0 } }
+fn main() -> int {
+    origin().x = 3
+    0
+}
+"#);
+    assert_eq!(err, "line 5: Cannot assign through a temporary value; bind it with 'let mut' first");
+    let err = expect_error(r#"
+fn main() -> int {
+    let mut pairs: List<(int, int)> = list_new()
+    list_push(pairs, (1, 2))
+    pairs[0].1 = 3
+    0
+}
+"#);
+    assert_eq!(err, "line 5: Cannot assign through an element of List<(Int, Int)>; copy it into a 'let mut', change it and store it back");
+}
@@ -0,0 +1,238 @@
+// tests/test_runner.rs
+//
+// BACKEND TESTS — `aha test`. `test fn name() { ... }` declares a test;
+// `assert`, `assert_eq` and `assert_ne` fail it with a readable message.
+// The runner walks the module graph from a root file, runs each test in
+// a fresh JIT, filters by name and reports a summary; the CLI exits
+// non-zero when a test fails.
+
+mod common;
+
+use aha_lang::testing::{run_tests, TestReport};
+use common::{expect_error, parse_only, run_binary, with_codegen, write_files};
+
+/// Helper: compile source and run one of its tests.
+fn run_test(source: &str, name: &str) -> Result<(), String> {
+    with_codegen(source, true, |codegen| codegen.run_test(name))
+}
+
+/// Helper: run the tests of a module graph, collecting nothing live.
+fn run_graph(dir_name: &str, files: &[(&str, &str)], filters: &[&str]) -> TestReport {
+    let root = write_files(&format!("test_runner_{}", dir_name), files);
+    let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+    run_tests(root.to_str().unwrap(), &filters, |_| {}).expect("compile failed")
+}
+
+// --- Assertions ---
+
+#[test]
+fn passing_assertions() {
+    let result = run_test(r#"
+        fn square(n: int) -> int { n * n }
+        test fn squares() {
+            assert(square(3) == 9)
+            assert(true)
+            assert_eq(square(4), 16)
+            assert_ne("a" + "b", "ba")
+            assert_eq(true, true)
+        }
+    "#, "squares");
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn assert_failure_message() {
+    let err = run_test("test fn t() {\n    assert(1 > 2)\n}", "t").unwrap_err();
+    assert_eq!(err, "panicked at line 2: assertion failed");
+}
+
+#[test]
+fn assert_eq_failure_shows_both_values() {
+    let err = run_test("fn f() -> int { 3 }\ntest fn t() {\n    assert_eq(f(), 4)\n}", "t").unwrap_err();
+    assert_eq!(err, "panicked at line 3: assertion `left == right` failed\n  left: 3\n right: 4");
+
+    let strings = run_test("test fn t() {\n    assert_eq(\"ab\" + \"c\", \"abd\")\n}", "t").unwrap_err();
+    assert_eq!(strings, "panicked at line 2: assertion `left == right` failed\n  left: \"abc\"\n right: \"abd\"");
+    let bools = run_test("test fn t() {\n    assert_ne(false, false)\n}", "t").unwrap_err();
+    assert_eq!(bools, "panicked at line 2: assertion `left != right` failed\n  left: false\n right: false");
+
+    // A newtype shows its wrapped value.
+    let err = run_test("newtype Meters = int\ntest fn t() {\n    assert_eq(Meters(2) + Meters(3), Meters(6))\n}", "t").unwrap_err();
+    assert_eq!(err, "panicked at line 3: assertion `left == right` failed\n  left: 5\n right: 6");
+}
+
+#[test]
+fn assert_eq_compares_tuples_and_enums() {
+    let source = r#"
+        enum Shape { Dot, Rect(int, string), Pair((int, bool)) }
+        enum Option<T> { Some(T), None }
+        test fn equal() {
+            assert_eq((1, "a", true), (1, "a", true))
+            assert_ne((1, "a"), (1, "b"))
+            assert_eq(Shape::Rect(2, "x"), Shape::Rect(2, "x"))
+            assert_ne(Shape::Rect(2, "x"), Shape::Rect(2, "y"))
+            assert_ne(Shape::Dot, Shape::Pair((0, false)))
+            let none: Option<int> = None()
+            assert_ne(Some(3), none)
+        }
+        test fn tuple() {
+            assert_eq((1, "a"), (2, "a"))
+        }
+        test fn single() {
+            assert_eq((1,), (2,))
+        }
+        test fn payload() {
+            assert_eq(Shape::Rect(2, "x"), Shape::Pair((3, false)))
+        }
+        test fn unit() {
+            assert_ne(Shape::Dot, Shape::Dot)
+        }
+    "#;
+    assert_eq!(run_test(source, "equal"), Ok(()));
+    let failure = |name: &str| run_test(source, name).unwrap_err();
+    assert_eq!(failure("tuple"), "panicked at line 14: assertion `left == right` failed\n  left: (1, \"a\")\n right: (2, \"a\")");
+    assert_eq!(failure("single"), "panicked at line 17: assertion `left == right` failed\n  left: (1,)\n right: (2,)");
+    assert_eq!(failure("payload"), "panicked at line 20: assertion `left == right` failed\n  left: Rect(2, \"x\")\n right: Pair((3, false))");
+    assert_eq!(failure("unit"), "panicked at line 23: assertion `left != right` failed\n  left: Dot\n right: Dot");
+}
+
+#[test]
+fn assert_eq_compares_structs_lists_and_arrays() {
+    let source = r#"
+        struct Point { x: int, label: string }
+        struct Line { from: Point, to: Point }
+        fn ints(n: int) -> List<int> {
+            let l = list_new()
+            let mut i = 1
+            while i <= n {
+                list_push(l, i)
+                i = i + 1
+            }
+            l
+        }
+        test fn equal() {
+            assert_eq(Point { x: 1, label: "a" }, Point { x: 1, label: "a" })
+            assert_ne(Point { x: 1, label: "a" }, Point { x: 1, label: "b" })
+            let p = Point { x: 0, label: "o" }
+            assert_eq(Line { from: p, to: p }, Line { from: p, to: p })
+            assert_eq(ints(3), ints(3))
+            assert_ne(ints(3), ints(4))
+            assert_eq(list_new(), list_new())
+            assert_eq([1, 2, 3], [1, 2, 3])
+            assert_ne([(1, "a"), (2, "b")], [(1, "a"), (2, "c")])
+        }
+        test fn point() {
+            assert_eq(Point { x: 1, label: "a" }, Point { x: 2, label: "a" })
+        }
+        test fn list() {
+            assert_eq(ints(2), ints(3))
+        }
+        test fn array() {
+            assert_ne([[1, 2], [3, 4]], [[1, 2], [3, 4]])
+        }
+    "#;
+    assert_eq!(run_test(source, "equal"), Ok(()));
+    let failure = |name: &str| run_test(source, name).unwrap_err();
+    assert_eq!(failure("point"), "panicked at line 25: assertion `left == right` failed\n  left: Point { x: 1, label: \"a\" }\n right: Point { x: 2, label: \"a\" }");
+    assert_eq!(failure("list"), "panicked at line 28: assertion `left == right` failed\n  left: [1, 2]\n right: [1, 2, 3]");
+    assert_eq!(failure("array"), "panicked at line 31: assertion `left != right` failed\n  left: [[1, 2], [3, 4]]\n right: [[1, 2], [3, 4]]");
+}
+
+#[test]
+fn assert_eq_on_mismatched_types_is_error() {
+    let err = expect_error("test fn t() {\n    assert_eq(1, \"one\")\n}");
+    assert_eq!(err, "line 2: `assert_eq`: Cannot apply operator '==' to types Int and String");
+    let err = expect_error("test fn t() {\n    assert_ne((1, true), (1, 2))\n}");
+    assert_eq!(err, "line 2: `assert_ne`: Cannot apply operator '!=' to types (Int, Bool) and (Int, Int)");
+}
+
+// --- Test functions ---
+
+#[test]
+fn test_fn_with_parameters_is_parse_error() {
+    assert_eq!(
+        parse_only("test fn t(n: int) { assert(n) }"),
+        vec!["line 1: test fn 't' must be named and take no parameters or type parameters".to_string()]
+    );
+}
+
+#[test]
+fn test_is_an_ordinary_name_elsewhere() {
+    with_codegen("fn test(n: int) -> int { n + 1 }\nlet test_value = test(2)\ntest_value", true, |codegen| {
+        assert!(codegen.test_functions().is_empty());
+        assert_eq!(codegen.run_jit(), Ok(3));
+    });
+}
+
+// --- Runner ---
+
+#[test]
+fn runner_discovers_tests_across_modules() {
+    let report = run_graph("graph", &[
+        ("main.aha", "use \"math\"\ntest fn adds() {\n    assert_eq(add(2, 3), 5)\n}\nadd(1, 1)\n"),
+        ("math.aha", "use \"util\"\npub fn add(a: int, b: int) -> int { a + b }\nfn secret() -> int { 41 }\ntest fn secret_is_private() {\n    assert_eq(secret() + one(), 42)\n}\n"),
+        ("util.aha", "pub fn one() -> int { 1 }\ntest fn one_is_one() {\n    assert_eq(one(), 1)\n}\n"),
+    ], &[]);
+    let names: Vec<&str> = report.outcomes.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["main::adds", "math::secret_is_private", "util::one_is_one"]);
+    assert!(report.success());
+}
+
+#[test]
+fn each_test_runs_isolated() {
+    let report = run_graph("isolated", &[(
+        "main.aha",
+        "static mut COUNT: int = 0\ntest fn first() {\n    COUNT = COUNT + 1\n    assert_eq(COUNT, 1)\n}\ntest fn second() {\n    COUNT = COUNT + 1\n    assert_eq(COUNT, 1)\n}\n",
+    )], &[]);
+    assert_eq!(report.passed(), 2);
+}
+
+#[test]
+fn failures_are_reported_and_do_not_stop_the_run() {
+    let report = run_graph("failures", &[(
+        "main.aha",
+        "test fn bad() {\n    assert_eq(1, 2)\n}\ntest fn divides() {\n    let z = 0\n    assert(10 / z)\n}\ntest fn good() {\n    assert(1)\n}\n",
+    )], &[]);
+    assert_eq!((report.passed(), report.failed()), (1, 2));
+    assert_eq!(report.outcomes[1].result, Err("panicked at line 6: attempt to divide by zero".to_string()));
+    assert!(report.outcomes[2].result.is_ok());
+}
+
+#[test]
+fn actor_panic_fails_only_its_test() {
+    let report = run_graph("actor_panic", &[(
+        "main.aha",
+        "actor Checker { id: int }\nfn handle(state, msg) -> int {\n    assert(msg > 0)\n    msg\n}\ntest fn rejects_zero() {\n    let c = spawn Checker { id: 1 }\n    assert_eq(call(c, 0), 0)\n}\ntest fn accepts_one() {\n    let c = spawn Checker { id: 2 }\n    assert_eq(call(c, 1), 1)\n}\n",
+    )], &[]);
+    assert_eq!((report.passed(), report.failed()), (1, 1));
+    assert_eq!(report.outcomes[0].result, Err("panicked at line 3: assertion failed".to_string()));
+}
+
+#[test]
+fn filters_select_tests_by_name() {
+    let report = run_graph("filters", &[(
+        "main.aha",
+        "test fn parse_int() { assert(1) }\ntest fn parse_str() { assert(1) }\ntest fn render() { assert(1) }\n",
+    )], &["parse", "main::render_"]);
+    let names: Vec<&str> = report.outcomes.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["main::parse_int", "main::parse_str"]);
+    assert_eq!(report.filtered_out, 1);
+}
+
+#[test]
+fn cli_prints_summary_and_exit_code() {
+    let source = "test fn good() {\n    assert_eq(2 + 2, 4)\n}\ntest fn bad() {\n    assert_ne(3, 3)\n}\n";
+    let run = |filters: &[&str]| {
+        let run = run_binary("test_runner_cli", Some("test"), source, filters);
+        assert!(!run.stderr.contains("panicked"), "the runner reports failures once, got:\n{}", run.stderr);
+        (run.code, run.stdout)
+    };
+    let (code, stdout) = run(&[]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains("test main::good ... ok\ntest main::bad ... FAILED\n"), "got:\n{}", stdout);
+    assert!(stdout.contains("---- main::bad ----\npanicked at line 5: assertion `left != right` failed\n  left: 3\n right: 3\n"), "got:\n{}", stdout);
+    assert!(stdout.contains("test result: FAILED. 1 passed; 1 failed; 0 filtered out"), "got:\n{}", stdout);
+    let (code, stdout) = run(&["good"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("test result: ok. 1 passed; 0 failed; 1 filtered out"), "got:\n{}", stdout);
+}
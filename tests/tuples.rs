@@ -0,0 +1,226 @@
+// tests/tuples.rs
+//
+// BACKEND TESTS — tuple types. Tuples are laid out as anonymous LLVM
+// structs and passed by value; inside List/Map slots they are boxed on the
+// heap. Covers literals, `(int, string)` hints, `.0` access, destructuring
+// and multiple return values.
+
+mod common;
+
+use common::{emit_ir, expect_error, parse, parse_only, run};
+
+// --- Parsing ---
+
+#[test]
+fn tuples_parse_diagnostic() {
+    let errors = parse_only(r#"
+        fn swap(p: (int, string)) -> (string, int) { (p.1, p.0) }
+        fn main() -> int {
+            let t: (int, (int, int)) = (1, (2, 3))
+            let (a, b) = t
+            let one = (5,)
+            a + b.1 + one.0 + (4)
+        }
+    "#);
+    assert!(errors.is_empty(), "Tuple parse errors: {:?}", errors);
+}
+
+#[test]
+fn paren_on_next_line_starts_a_statement() {
+    // A call's '(' must stay on the callee's line; once open, the
+    // arguments may span lines.
+    let statements = |source: &str| parse(source).statements.len();
+    assert_eq!(statements("f\n(1, 2)"), 2);
+    assert_eq!(statements("f\n(1)"), 2);
+    assert_eq!(statements("f(\n1,\n2\n)"), 1);
+
+    let result = run(r#"
+        fn add(a: int, b: int) -> int { a + b }
+        fn pair(n: int) -> (int, int) {
+            let m = add(
+                n,
+                1
+            )
+            (m, n)
+        }
+        let (a, b) = pair(4)
+        (a * 10 + b) * 2
+    "#);
+    assert_eq!(result, 108);
+}
+
+// --- Literals and access ---
+
+#[test]
+fn tuple_element_access() {
+    let result = run(r#"
+        fn main() -> int {
+            let t = (40, 2)
+            let nested = (1, (20, 300))
+            t.0 + t.1 + nested.1.0 + nested.1.1
+        }
+    "#);
+    assert_eq!(result, 362);
+}
+
+#[test]
+fn tuple_mixed_element_types() {
+    let result = run(r#"
+        fn main() -> int {
+            let t: (int, string, bool) = (7, "abc", true)
+            if t.2 { t.0 + len(t.1) } else { 0 }
+        }
+    "#);
+    assert_eq!(result, 10);
+}
+
+// --- Functions ---
+
+#[test]
+fn function_returns_tuple() {
+    let result = run(r#"
+        fn divmod(a: int, b: int) -> (int, int) {
+            let q = a / b
+            let r = a % b
+            (q, r)
+        }
+        fn main() -> int {
+            let (q, r) = divmod(17, 5)
+            q * 10 + r
+        }
+    "#);
+    assert_eq!(result, 32);
+}
+
+#[test]
+fn function_takes_tuple_param() {
+    let result = run(r#"
+        fn sum(p: (int, int)) -> int { p.0 + p.1 }
+        fn area((w, h): (int, int)) -> int { w * h }
+        fn main() -> int { sum((19, 23)) * 100 + area((6, 7)) }
+    "#);
+    assert_eq!(result, 4242);
+}
+
+#[test]
+fn tuple_with_struct_element() {
+    let result = run(r#"
+        struct Point { x: int, y: int }
+        fn tag(p: Point) -> (string, Point) { ("pt", p) }
+        fn main() -> int {
+            let (name, p) = tag(Point { x: 4, y: 5 })
+            len(name) + p.x * p.y
+        }
+    "#);
+    assert_eq!(result, 22);
+}
+
+// --- Patterns ---
+
+#[test]
+fn match_on_tuple() {
+    let result = run(r#"
+        fn classify(p: (int, int)) -> int {
+            match p {
+                (0, 0) => 0,
+                (0, y) => y,
+                (x, 0) => x * 10,
+                _ => 99,
+            }
+        }
+        fn main() -> int {
+            classify((0, 0)) + classify((0, 3)) + classify((4, 0)) + classify((1, 1))
+        }
+    "#);
+    assert_eq!(result, 142);
+}
+
+// --- Collections ---
+
+#[test]
+fn list_of_tuples() {
+    let result = run(r#"
+        fn main() -> int {
+            let xs: List<(int, string)> = list_new()
+            list_push(xs, (1, "a"))
+            list_push(xs, (2, "bcd"))
+            let second = xs[1]
+            list_get(xs, 0).0 + second.0 * 10 + len(second.1)
+        }
+    "#);
+    assert_eq!(result, 24);
+}
+
+#[test]
+fn map_with_tuple_values() {
+    let result = run(r#"
+        fn main() -> int {
+            let m: Map<int, (int, int)> = map_new()
+            map_set(m, 1, (10, 20))
+            map_set(m, 2, (30, 40))
+            let (a, b) = map_get(m, 2)
+            a + b + map_get(m, 1).0
+        }
+    "#);
+    assert_eq!(result, 80);
+}
+
+#[test]
+fn tuple_boxes_are_freed_with_their_container() {
+    let source = r#"
+        fn fill(n: int) -> int {
+            let xs: List<(int, int)> = list_new()
+            let m: Map<int, (int, string)> = map_new()
+            let mut i = 0
+            while i < n {
+                list_push(xs, (i, i))
+                map_set(m, i % 3, (i, "v"))
+                i = i + 1
+            }
+            map_remove(m, 0)
+            let result = list_len(xs) + map_len(m) * 100 + map_get(m, 2).0
+            result
+        }
+        fn main() -> int {
+            let mut total = 0
+            let mut round = 0
+            while round < 200 {
+                total = total + fill(50)
+                round = round + 1
+            }
+            total
+        }
+    "#;
+    assert_eq!(run(source), 200 * (50 + 200 + 47));
+
+    // Overwritten and removed map values are freed in place; the rest
+    // go when the List and Map are cleaned up at the end of `fill`.
+    let ir = emit_ir(source);
+    assert!(ir.contains("define void @\"free_tuples.List<(Int, Int)>\""), "{}", ir);
+    assert!(ir.contains("define void @\"free_tuples.Map<Int, (Int, String)>\""), "{}", ir);
+    assert_eq!(ir.matches(" = inttoptr i64 %old_tuple").count(), 2);
+}
+
+// --- Errors ---
+
+#[test]
+fn tuple_index_out_of_range() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let t = (1, 2)
+            t.2
+        }
+    "#);
+    assert!(err.contains("out of range"), "got: {}", err);
+}
+
+#[test]
+fn tuple_pattern_arity_mismatch() {
+    let err = expect_error(r#"
+        fn main() -> int {
+            let (a, b, c) = (1, 2)
+            a
+        }
+    "#);
+    assert!(err.contains("expects 2 elements, got 3"), "got: {}", err);
+}
@@ -161,12 +161,12 @@ fn test_annotated_variable_in_function_call() {
 
 #[test]
 fn test_annotation_then_mutation() {
-    assert_eq!(run("let x: int = 5; x = x + 1; x"), 6);
+    assert_eq!(run("let mut x: int = 5; x = x + 1; x"), 6);
 }
 
 #[test]
 fn test_annotation_string_then_mutation() {
-    assert_eq!(run("let s: string = \"abc\"; s = s + \"d\"; len(s)"), 4);
+    assert_eq!(run("let mut s: string = \"abc\"; s = s + \"d\"; len(s)"), 4);
 }
 
 // --- Function return type annotations (fn f() -> T) ---
@@ -0,0 +1,227 @@
+// tests/typeck.rs
+//
+// TYPECK TESTS — the standalone type-checking pass. `check_program` runs
+// on the parsed Program before codegen: it settles function signatures
+// from hints and call sites, records expression types in a `TypeTable`,
+// and reports every type error with its line. `CodeGenerator::compile`
+// runs it first and surfaces its errors.
+
+mod common;
+
+use aha_lang::ast::{Expression, Program, Statement};
+use aha_lang::typeck::{check_program, TypeError, TypeTable};
+use aha_lang::AhaType;
+use common::{expect_error, parse, run};
+
+/// Helper: type-check, expecting success.
+fn check(program: &Program) -> TypeTable<'_> {
+    check_program(program).unwrap_or_else(|errors| panic!("Type errors: {:?}", errors))
+}
+
+/// Helper: type-check, expecting errors.
+fn check_errors(source: &str) -> Vec<TypeError> {
+    match check_program(&parse(source)) {
+        Ok(_) => panic!("Expected type errors, but the program checked"),
+        Err(errors) => errors,
+    }
+}
+
+// --- Signatures ---
+
+#[test]
+fn typeck_param_from_call_site() {
+    let program = parse(r#"
+        fn greet(name) { name + "!" }
+        greet("aha")
+    "#);
+    let table = check(&program);
+    assert_eq!(table.params["greet"], vec![Some(AhaType::String)]);
+    assert_eq!(table.returns["greet"], AhaType::String);
+}
+
+#[test]
+fn typeck_chained_calls_settle() {
+    // wrap's param is only known once twice's return type is.
+    let program = parse(r#"
+        fn wrap(x) { x }
+        fn twice(s) { s + s }
+        wrap(twice("ab"))
+    "#);
+    let table = check(&program);
+    assert_eq!(table.params["wrap"], vec![Some(AhaType::String)]);
+    assert_eq!(table.returns["wrap"], AhaType::String);
+}
+
+#[test]
+fn typeck_unused_param_stays_open() {
+    let program = parse("fn f(a, b: string) { 0 } 1");
+    let table = check(&program);
+    assert_eq!(table.params["f"], vec![None, Some(AhaType::String)]);
+}
+
+#[test]
+fn typeck_records_expression_types() {
+    let program = parse(r#"
+        struct P { name: string, age: int }
+        let p = P { name: "a", age: 3 }
+        let n = p.name
+        let pair = (p.age, n)
+    "#);
+    let table = check(&program);
+    let value = |i: usize| match &program.statements[i] {
+        Statement::Let(l) => &l.value,
+        other => panic!("expected let, got {:?}", other),
+    };
+    assert_eq!(table.expr_type(value(1)), Some(&AhaType::Struct("P".to_string())));
+    assert_eq!(table.expr_type(value(2)), Some(&AhaType::String));
+    assert_eq!(
+        table.expr_type(value(3)),
+        Some(&AhaType::Tuple(vec![AhaType::Int, AhaType::String]))
+    );
+}
+
+#[test]
+fn typeck_generic_bodies_not_recorded() {
+    let program = parse(r#"
+        fn id<T>(x: T) -> T { x }
+        id(1) + len(id("ab"))
+    "#);
+    let table = check(&program);
+    assert!(!table.params.contains_key("id"));
+    let Statement::Expression(es) = &program.statements[0] else { panic!() };
+    let Expression::Function(func) = &es.expression else { panic!() };
+    let Statement::Expression(body) = &func.body.statements[0] else { panic!() };
+    assert_eq!(table.expr_type(&body.expression), None);
+}
+
+#[test]
+fn typeck_generic_instances() {
+    let program = parse(r#"
+        enum Option<T> { Some(T), None }
+        struct Pair<A, B> { a: A, b: B }
+        fn wrap<T>(x: T) { Some(x) }
+        fn pair<A, B>(a: A, b: B) -> Pair<A, B> { Pair { a: a, b: b } }
+        fn empty() { None() }
+        pair(1, wrap("ab"))
+    "#);
+    let table = check(&program);
+    let option = |t: &str| AhaType::Enum(format!("Option<{}>", t));
+    assert_eq!(table.instance_returns["wrap_String"], option("String"));
+    assert_eq!(table.instance_returns["pair_Int_Option<String>"], AhaType::Struct("Pair<Int, Option<String>>".to_string()));
+    assert_eq!(table.returns["empty"], option("Int"));
+    let names: Vec<&str> = table.instances.iter().map(|(base, _)| base.as_str()).collect();
+    assert_eq!(names, ["Option", "Option", "Pair"]);
+    assert_eq!(table.instances[2].1, vec![AhaType::Int, option("String")]);
+}
+
+// --- Errors ---
+
+#[test]
+fn typeck_wrong_arg_count_with_line() {
+    let errors = check_errors("fn add(a, b) { a + b }\n\nadd(1, 2, 3)");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+    assert_eq!(errors[0].to_string(), "line 3: Function 'add' expects 2 arguments, got 3 (signature: add(a, b))");
+}
+
+#[test]
+fn typeck_conflicting_call_sites() {
+    let errors = check_errors("fn show(x) { x }\nshow(1)\nshow(\"a\")");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+    assert!(errors[0].message.contains("Conflicting types for parameter 'x' of 'show'"), "got: {}", errors[0]);
+}
+
+#[test]
+fn typeck_return_string_from_int_fn() {
+    let errors = check_errors("fn f() -> int {\n    \"text\"\n}\nf()");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 1);
+    assert!(errors[0].message.contains("'int' does not match actual return type 'String'"), "got: {}", errors[0]);
+}
+
+#[test]
+fn typeck_return_statement_line() {
+    let errors = check_errors(r#"fn f(flag: bool) -> string {
+        if flag {
+            return 7
+        }
+        "ok"
+    }
+    f(true)"#);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn typeck_reports_all_errors() {
+    let errors = check_errors("fn one(a) { a }\none()\nfn two() -> string { 2 }\none(1, 2)");
+    let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines.len(), 3, "got: {:?}", errors);
+    assert!(lines.contains(&2) && lines.contains(&3) && lines.contains(&4), "got: {:?}", errors);
+}
+
+#[test]
+fn typeck_generic_arity_still_checked() {
+    let errors = check_errors("fn id<T>(x: T) -> T { x }\nid(1, 2)");
+    assert_eq!(errors[0].to_string(), "line 2: Function 'id' expects 1 argument, got 2 (signature: id<T>(x: T) -> T)");
+}
+
+// --- Codegen integration ---
+
+#[test]
+fn typeck_errors_surface_from_compile() {
+    let err = expect_error("fn add(a, b) { a + b }\nadd(1)\nadd(\"x\", 2, 3)");
+    assert!(err.contains("line 2: Function 'add' expects 2 arguments, got 1"), "got: {}", err);
+    assert!(err.contains("line 3: Function 'add' expects 2 arguments, got 3 (signature: add(a, b))"), "got: {}", err);
+}
+
+#[test]
+fn typeck_signatures_drive_codegen() {
+    let result = run(r#"
+        fn wrap(x) { x }
+        fn twice(s) { s + s }
+        fn main() -> int {
+            len(wrap(twice("abc")))
+        }
+    "#);
+    assert_eq!(result, 6);
+}
+
+#[test]
+fn typeck_assignment_value_drives_codegen() {
+    let source = r#"
+        fn rename(s: string) {
+            let mut name = "a"
+            name = s
+        }
+        fn main() -> int { len(rename("abc")) }
+    "#;
+    let program = parse(source);
+    assert_eq!(check(&program).returns["rename"], AhaType::String);
+    assert_eq!(run(source), 3);
+}
+
+#[test]
+fn typeck_generic_instances_drive_codegen() {
+    let result = run(r#"
+        enum Option<T> { Some(T), None }
+        fn wrap<T>(x: T) { Some(x) }
+        fn empty() { None() }
+        fn size(o: Option<bool>) -> int {
+            match o { Some(b) => if b { 3 } else { 0 }, None => 100 }
+        }
+        fn main() -> int {
+            let n = match empty() { Some(v) => v, None => 10 }
+            size(wrap(true)) + n
+        }
+    "#);
+    assert_eq!(result, 13);
+}
+
+#[test]
+fn codegen_checks_generic_instance_returns() {
+    // Generic bodies are only typed per instance, so codegen catches this.
+    let err = expect_error("fn show<T>(x: T) -> string { x }\nlen(show(1))");
+    assert_eq!(err, "line 1: Function 'show' returns Int, but its return type is String");
+}
@@ -0,0 +1,139 @@
+// tests/typedefs.rs
+//
+// BACKEND TESTS — `type` aliases and `newtype`s. An alias is another name
+// for a type and is interchangeable with it; a newtype has its base type's
+// layout but is a distinct type: `Meters(5)` wraps, `m.0` unwraps, and
+// arithmetic only combines two values of the same newtype.
+
+mod common;
+
+use common::{expect_error, run, run_files};
+
+// --- Type aliases ---
+
+#[test]
+fn alias_in_let_signature_and_field() {
+    let result = run(r#"
+        type Count = int
+        fn double(n: Count) -> Count { n * 2 }
+        let c: Count = 21
+        double(c)
+    "#);
+    assert_eq!(result, 42);
+
+    let result = run(r#"
+        type Label = string
+        struct Tag {
+            label: Label,
+            weight: int
+        }
+        let t = Tag { label: "hello", weight: 3 }
+        len(t.label) + t.weight
+    "#);
+    assert_eq!(result, 8);
+}
+
+#[test]
+fn alias_of_generic_collection() {
+    let result = run(r#"
+        type Scores = Map<int, int>
+        fn total(m: Scores) -> int {
+            map_get(m, 1) + map_get(m, 2)
+        }
+        let m: Scores = map_new()
+        let m2 = map_set(m, 1, 40)
+        let m3 = map_set(m2, 2, 2)
+        total(m3)
+    "#);
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn alias_refers_to_later_alias() {
+    let result = run(r#"
+        type Rows = List<Row>
+        type Row = int
+        fn first(rows: Rows) -> Row { list_get(rows, 0) }
+        let xs: Rows = list_new()
+        let ys = list_push(xs, 7)
+        first(ys)
+    "#);
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn alias_cycle_is_error() {
+    let err = expect_error("type A = List<B>\ntype B = A\n0");
+    assert_eq!(err, "line 1: Type alias 'A' refers to itself");
+}
+
+// --- Newtypes ---
+
+#[test]
+fn newtype_wrap_and_unwrap() {
+    let result = run(r#"
+        newtype Meters = int
+        fn to_cm(m: Meters) -> int { m.0 * 100 }
+        let d = Meters(4)
+        to_cm(d) + d.0
+    "#);
+    assert_eq!(result, 404);
+}
+
+#[test]
+fn newtype_arithmetic_and_comparison() {
+    let result = run(r#"
+        newtype Meters = int
+        fn add(a: Meters, b: Meters) -> Meters { a + b }
+        let total = add(Meters(30), Meters(12)) * Meters(2) - Meters(40)
+        if total > Meters(40) { total.0 } else { 0 }
+    "#);
+    assert_eq!(result, 44);
+}
+
+#[test]
+fn newtype_of_string() {
+    let result = run(r#"
+        newtype Email = string
+        fn domain_len(e: Email) -> int { len(e.0) - 4 }
+        domain_len(Email("bob@x.io"))
+    "#);
+    assert_eq!(result, 4);
+}
+
+#[test]
+fn newtypes_are_not_interchangeable() {
+    let err = expect_error(r#"
+newtype Meters = int
+newtype Seconds = int
+fn run_for(t: Seconds) -> int { t.0 }
+run_for(Meters(3))
+"#);
+    assert!(err.contains("line 5") && err.contains("Meters") && err.contains("Seconds"), "got: {}", err);
+}
+
+#[test]
+fn newtype_is_not_its_base() {
+    let err = expect_error("newtype Meters = int\nlet d: Meters = 5\n0");
+    assert_eq!(err, "Type mismatch: variable 'd' annotated as 'Meters' but value has type 'Int'");
+    let err = expect_error("newtype Meters = int\nlet d = Meters(5)\nlet e = d + 1\n0");
+    assert_eq!(err, "Cannot apply operator '+' to types Meters and Int");
+}
+
+// --- Modules ---
+
+#[test]
+fn pub_alias_and_newtype_are_exported() {
+    let units = r#"
+pub type Amount = int
+pub newtype Cents = int
+pub fn cents(a: Amount) -> Cents { Cents(a * 100) }
+"#;
+    let main = r#"
+use "units"
+let a: Amount = 3
+let c = cents(a) + Cents(5)
+c.0
+"#;
+    assert_eq!(run_files("typedefs_pub", &[("main.aha", main), ("units.aha", units)]), 305);
+}
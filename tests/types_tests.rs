@@ -14,7 +14,7 @@ fn test_type_display() {
     assert_eq!(format!("{}", AhaType::Bool), "Bool");
     assert_eq!(format!("{}", AhaType::String), "String");
     assert_eq!(format!("{}", AhaType::Void), "Void");
-    assert_eq!(format!("{}", AhaType::Array(Box::new(AhaType::Int))), "[Int]");
+    assert_eq!(format!("{}", AhaType::Array(Box::new(AhaType::Int), 3)), "[Int; 3]");
 }
 
 // =====================================================================
@@ -169,11 +169,15 @@ fn test_type_equality() {
     assert_eq!(AhaType::Int, AhaType::Int);
     assert_ne!(AhaType::Int, AhaType::String);
     assert_eq!(
-        AhaType::Array(Box::new(AhaType::Int)),
-        AhaType::Array(Box::new(AhaType::Int))
+        AhaType::Array(Box::new(AhaType::Int), 3),
+        AhaType::Array(Box::new(AhaType::Int), 3)
     );
     assert_ne!(
-        AhaType::Array(Box::new(AhaType::Int)),
-        AhaType::Array(Box::new(AhaType::String))
+        AhaType::Array(Box::new(AhaType::Int), 3),
+        AhaType::Array(Box::new(AhaType::String), 3)
+    );
+    assert_ne!(
+        AhaType::Array(Box::new(AhaType::Int), 3),
+        AhaType::Array(Box::new(AhaType::Int), 4)
     );
 }
@@ -0,0 +1,172 @@
+// tests/units.rs
+//
+// BACKEND TESTS — units of measure on `int`. `int<m>`, `int<m/s>`, ...
+// are checked before codegen: `*` and `/` combine units, `+`, `-` and
+// comparisons need equal ones. Literals and plain `int` values carry no
+// unit. Units are erased in codegen, so quantities are plain i64s.
+
+mod common;
+
+use aha_lang::types::Unit;
+use common::{emit_ir, expect_error, run};
+
+// --- Unit algebra ---
+
+#[test]
+fn unit_parse_and_display() {
+    let speed = Unit::parse("m/s").unwrap();
+    let accel = Unit::parse("m/s/s").unwrap();
+    assert_eq!(speed.to_string(), "m/s");
+    assert_eq!(speed.div(&Unit::parse("s").unwrap()), accel);
+    assert_eq!(Unit::parse("m*m").unwrap().div(&Unit::parse("m").unwrap()).to_string(), "m");
+    assert_eq!(Unit::parse("1/s").unwrap().to_string(), "1/s");
+    assert_eq!(speed.div(&speed).to_string(), "1");
+    assert!(Unit::parse("m/").is_none());
+}
+
+// --- Checked programs ---
+
+#[test]
+fn same_units_add_and_scale() {
+    let result = run(r#"
+        let a: int<m> = 1200
+        let b: int<m> = 300
+        let total = a + b - 100
+        total
+    "#);
+    assert_eq!(result, 1400);
+
+    // Literals scale a quantity without changing its unit.
+    let result = run(r#"
+        fn climb(alt: int<m>) -> int<m> { alt * 2 + 10 }
+        let a: int<m> = 20
+        if climb(a) > 40 { 1 } else { 0 }
+    "#);
+    assert_eq!(result, 1);
+}
+
+#[test]
+fn division_produces_compound_unit() {
+    let result = run(r#"
+        fn speed(d: int<m>, t: int<s>) -> int<m/s> { d / t }
+        let dist: int<m> = 500
+        let time: int<s> = 20
+        let v = speed(dist, time)
+        let more: int<m/s> = 5
+        v + more
+    "#);
+    assert_eq!(result, 30);
+}
+
+#[test]
+fn multiplication_cancels_units() {
+    let result = run(r#"
+        fn distance(v: int<m/s>, t: int<s>) -> int<m> { v * t }
+        let start: int<m> = 100
+        let v: int<m/s> = 7
+        let t: int<s> = 6
+        start + distance(v, t)
+    "#);
+    assert_eq!(result, 142);
+}
+
+#[test]
+fn struct_fields_carry_units() {
+    let result = run(r#"
+        struct Telemetry {
+            altitude: int<m>,
+            velocity: int<m/s>
+        }
+        fn predict(t: Telemetry, secs: int<s>) -> int<m> {
+            t.altitude + t.velocity * secs
+        }
+        let t = Telemetry { altitude: 1000, velocity: 12 }
+        let secs: int<s> = 5
+        predict(t, secs)
+    "#);
+    assert_eq!(result, 1060);
+}
+
+#[test]
+fn units_have_no_runtime_cost() {
+    let ir = emit_ir("fn double(x: int<m>) -> int<m> { x + x }\nlet a: int<m> = 4\ndouble(a)");
+    assert!(ir.contains("define i64 @double(i64"), "IR:\n{}", ir);
+}
+
+// --- Errors ---
+
+#[test]
+fn mixing_different_units_is_error() {
+    let err = expect_error("let altitude_m: int<m> = 1000\nlet velocity_mps: int<m/s> = 30\nlet x = altitude_m +\n  velocity_mps\nx");
+    assert_eq!(err, "line 3: Cannot apply operator '+' to int<m> and int<m/s>: units differ");
+    let err = expect_error("let a: int<m> = 1\nlet b: int<s> = 2\nif a < b { 1 } else { 0 }");
+    assert_eq!(err, "line 3: Cannot apply operator '<' to int<m> and int<s>: units differ");
+}
+
+#[test]
+fn argument_unit_mismatch_is_error() {
+    let err = expect_error(r#"
+fn burn(t: int<s>) -> int { t as int }
+let d: int<m> = 5
+burn(d)
+"#);
+    assert_eq!(err, "line 4: Argument 1 of 'burn' has unit int<m>, expected int<s>");
+}
+
+#[test]
+fn return_unit_mismatch_is_error() {
+    let err = expect_error("fn speed(d: int<m>, t: int<s>) -> int<m/s> {\n  d * t\n}\nspeed(1, 1)");
+    assert_eq!(err, "line 1: Result of 'speed' has unit int<m*s>, expected int<m/s>");
+}
+
+#[test]
+fn let_and_assignment_unit_mismatch_are_errors() {
+    let err = expect_error(r#"
+let d: int<m> = 5
+let t: int<s> = 2
+let v: int<m/s> = d * t
+let mut total: int<m> = 0
+total = t
+0
+"#);
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 4: Value of 'v' has unit int<m*s>, expected int<m/s>",
+        "line 6: Value assigned to 'total' has unit int<s>, expected int<m>",
+    ]);
+}
+
+#[test]
+fn struct_field_unit_mismatch_is_error() {
+    let err = expect_error(r#"
+struct Telemetry {
+    altitude: int<m>
+}
+let v: int<m/s> = 3
+let t = Telemetry { altitude: v }
+0
+"#);
+    assert_eq!(err, "line 6: Field 'altitude' of 'Telemetry' has unit int<m/s>, expected int<m>");
+}
+
+#[test]
+fn plain_int_annotation_rejects_units() {
+    let err = expect_error(r#"
+fn report(n: int) -> int { n }
+fn height(alt: int<m>) -> int { alt }
+let alt: int<m> = 120
+let shown: int = alt
+report(alt)
+let mut last: int = 0
+last = alt
+let ok: int = alt as int + alt / alt
+ok
+"#);
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines, vec![
+        "line 3: Result of 'height' has unit int<m>, expected int; use `as int` to drop the unit",
+        "line 5: Value of 'shown' has unit int<m>, expected int; use `as int` to drop the unit",
+        "line 6: Argument 1 of 'report' has unit int<m>, expected int; use `as int` to drop the unit",
+        "line 8: Value assigned to 'last' has unit int<m>, expected int; use `as int` to drop the unit",
+    ]);
+}